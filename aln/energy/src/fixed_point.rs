@@ -0,0 +1,350 @@
+//! Exact rational compression of external minimal units.
+//!
+//! Every conversion is `amount * num / den` evaluated with a 256-bit
+//! intermediate product, so no precision is lost for any `u128` input.
+//! Source and target decimals are folded into the ratio up front, and the
+//! caller picks the rounding mode explicitly.
+
+/// Rounding applied to the final quotient.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    /// Round toward zero. This is the conservative default for minting.
+    Floor,
+    /// Round away from zero whenever a remainder exists.
+    Ceil,
+    /// Round to nearest, ties to the even quotient.
+    HalfEven,
+}
+
+/// An exact compression factor `num / den`. The fields are public for
+/// `const` factors; the compression functions reject `den == 0`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ratio {
+    pub num: u128,
+    pub den: u128,
+}
+
+impl Ratio {
+    /// Returns `None` if `den` is zero.
+    pub const fn new(num: u128, den: u128) -> Option<Self> {
+        if den == 0 {
+            None
+        } else {
+            Some(Self { num, den })
+        }
+    }
+
+    /// `1 / 10^exp`, the shape of every canonical ALN compression factor.
+    pub const fn inverse_pow10(exp: u32) -> Self {
+        Self { num: 1, den: 10u128.pow(exp) }
+    }
+
+    /// Basis points, e.g. `from_bps(7_500)` is 0.75.
+    pub const fn from_bps(bps: u32) -> Self {
+        Self { num: bps as u128, den: 10_000 }
+    }
+}
+
+/// Decimals of the source asset and of the ALN target unit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecimalsProfile {
+    pub src_decimals: u8,
+    pub aln_decimals: u8,
+}
+
+impl DecimalsProfile {
+    pub const fn new(src_decimals: u8, aln_decimals: u8) -> Self {
+        Self { src_decimals, aln_decimals }
+    }
+
+    /// Same decimals on both sides: the ratio is applied to minimal units as-is.
+    pub const IDENTITY: Self = Self::new(0, 0);
+    /// Cosmos-style 6-decimal denoms (uatom, ukuji, uosmo) into 6-decimal ALN units.
+    pub const COSMOS_6: Self = Self::new(6, 6);
+    /// 18-decimal EVM tokens into 6-decimal ALN units.
+    pub const EVM_18: Self = Self::new(18, 6);
+}
+
+/// Failure modes of the exact compression path.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompressError {
+    /// Folding the decimals into the ratio overflowed `u128`.
+    DecimalsOutOfRange,
+    /// The ratio's denominator is zero.
+    ZeroDenominator,
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Reduce `num / den` and apply up to `exp` factors of ten to `side`
+/// (`true` scales the numerator), cancelling each against the other side
+/// where it divides evenly. Returns the ratio and the factors of ten that
+/// could not be applied without overflowing.
+fn fold_pow10(num: u128, den: u128, exp: u32, scale_num: bool) -> (u128, u128, u32) {
+    let g = gcd(num, den).max(1);
+    let (mut num, mut den) = (num / g, den / g);
+    let mut left = exp;
+    while left > 0 {
+        let (grow, shrink) = if scale_num { (&mut num, &mut den) } else { (&mut den, &mut num) };
+        if *shrink % 10 == 0 {
+            *shrink /= 10;
+        } else if let Some(v) = grow.checked_mul(10) {
+            *grow = v;
+        } else {
+            break;
+        }
+        left -= 1;
+    }
+    (num, den, left)
+}
+
+/// Fold the decimals profile into a single ratio:
+/// `num * 10^aln / (den * 10^src)`, reduced to lowest terms. Fails only if
+/// the denominator is zero or the reduced ratio still does not fit in `u128`.
+pub fn effective_ratio(ratio: Ratio, profile: DecimalsProfile) -> Result<Ratio, CompressError> {
+    if ratio.den == 0 {
+        return Err(CompressError::ZeroDenominator);
+    }
+    let src = profile.src_decimals as u32;
+    let aln = profile.aln_decimals as u32;
+    let (num, den, left) = fold_pow10(ratio.num, ratio.den, aln.abs_diff(src), aln >= src);
+    if left > 0 {
+        return Err(CompressError::DecimalsOutOfRange);
+    }
+    Ok(Ratio { num, den })
+}
+
+/// Exact `amount * ratio` under `profile`, rounded by `mode` and saturated at `cap`.
+///
+/// When the source has more decimals than fit in the denominator, the
+/// remaining powers of ten are divided out of the exact quotient afterwards,
+/// so the result stays exact. Only a numerator overflow (far more ALN than
+/// source decimals) is reported as `DecimalsOutOfRange`; a zero denominator
+/// is `ZeroDenominator`.
+pub fn compress_exact(
+    amount: u128,
+    ratio: Ratio,
+    profile: DecimalsProfile,
+    mode: RoundingMode,
+    cap: u128,
+) -> Result<u128, CompressError> {
+    if ratio.den == 0 {
+        return Err(CompressError::ZeroDenominator);
+    }
+    let src = profile.src_decimals as u32;
+    let aln = profile.aln_decimals as u32;
+    let (num, den, left) = fold_pow10(ratio.num, ratio.den, aln.abs_diff(src), aln >= src);
+    if left == 0 {
+        return Ok(mul_div(amount, num, den, mode).map_or(cap, |q| q.min(cap)));
+    }
+    if aln >= src {
+        return Err(CompressError::DecimalsOutOfRange);
+    }
+    // amount * num / den / 10^left: floor the first division, keep whether
+    // it was inexact, and round once at the end.
+    let Some((q, r)) = mul_div_rem(amount, num, den) else {
+        return Ok(cap);
+    };
+    Ok(div_pow10(q, r != 0, left, mode).min(cap))
+}
+
+/// Round `(q + f) / 10^exp` where `f` is an unknown fraction in [0, 1) that
+/// is non-zero iff `inexact`.
+fn div_pow10(q: u128, inexact: bool, exp: u32, mode: RoundingMode) -> u128 {
+    let Some(p) = 10u128.checked_pow(exp) else {
+        // q < u128::MAX < 10^exp / 2: the value is below one half.
+        return match mode {
+            RoundingMode::Ceil if q != 0 || inexact => 1,
+            _ => 0,
+        };
+    };
+    let (qq, rr) = (q / p, q % p);
+    let half = p / 2; // p is a multiple of 10, so exact.
+    let round_up = match mode {
+        RoundingMode::Floor => false,
+        RoundingMode::Ceil => rr != 0 || inexact,
+        RoundingMode::HalfEven => {
+            rr > half || (rr == half && (inexact || qq & 1 == 1))
+        }
+    };
+    // qq <= u128::MAX / 10, so the increment cannot overflow.
+    qq + round_up as u128
+}
+
+/// `a * b / d` with a 256-bit intermediate. Returns `None` when `d` is zero
+/// or the rounded quotient does not fit in `u128`.
+pub fn mul_div(a: u128, b: u128, d: u128, mode: RoundingMode) -> Option<u128> {
+    let (q, r) = mul_div_rem(a, b, d)?;
+    let round_up = match mode {
+        RoundingMode::Floor => false,
+        RoundingMode::Ceil => r != 0,
+        RoundingMode::HalfEven => {
+            // Compare 2r against d without overflowing.
+            let half = d - r;
+            r > half || (r == half && q & 1 == 1)
+        }
+    };
+    if round_up {
+        q.checked_add(1)
+    } else {
+        Some(q)
+    }
+}
+
+/// Floored quotient and remainder of `a * b / d`; `None` when `d` is zero or
+/// the quotient does not fit in `u128`.
+fn mul_div_rem(a: u128, b: u128, d: u128) -> Option<(u128, u128)> {
+    if d == 0 {
+        return None;
+    }
+    let (hi, lo) = widening_mul(a, b);
+    if hi >= d {
+        return None;
+    }
+    Some(div_wide(hi, lo, d))
+}
+
+/// Full 128x128 -> 256-bit product as `(hi, lo)`.
+fn widening_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+
+    let ll = a_lo * b_lo;
+    let lh = a_lo * b_hi;
+    let hl = a_hi * b_lo;
+    let hh = a_hi * b_hi;
+
+    let mid = (ll >> 64) + (lh & MASK) + (hl & MASK);
+    let lo = (ll & MASK) | (mid << 64);
+    let hi = hh + (lh >> 64) + (hl >> 64) + (mid >> 64);
+    (hi, lo)
+}
+
+/// Divide the 256-bit value `(hi, lo)` by `d`, assuming `hi < d` so the
+/// quotient fits in `u128`. Plain restoring long division, one bit at a time.
+fn div_wide(hi: u128, lo: u128, d: u128) -> (u128, u128) {
+    let mut rem = hi;
+    let mut q = 0u128;
+    for i in (0..128).rev() {
+        let carry = rem >> 127;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        q <<= 1;
+        if carry == 1 || rem >= d {
+            rem = rem.wrapping_sub(d);
+            q |= 1;
+        }
+    }
+    (q, rem)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn mul_div_matches_native_for_small_values() {
+        assert_eq!(mul_div(10, 3, 4, RoundingMode::Floor), Some(7));
+        assert_eq!(mul_div(10, 3, 4, RoundingMode::Ceil), Some(8));
+        assert_eq!(mul_div(10, 3, 4, RoundingMode::HalfEven), Some(8)); // 7.5 -> 8
+        assert_eq!(mul_div(9, 5, 10, RoundingMode::HalfEven), Some(4)); // 4.5 -> 4
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, RoundingMode::Floor), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 1, RoundingMode::Floor), None);
+        assert_eq!(mul_div(1, 1, 0, RoundingMode::Floor), None);
+    }
+
+    #[test]
+    fn oversized_denominator_stays_exact() {
+        // den * 10^18 overflows u128; the tail powers of ten are divided out later.
+        let ratio = Ratio::new(3, 10u128.pow(29)).unwrap();
+        let p = DecimalsProfile::new(18, 0);
+        assert!(effective_ratio(ratio, p).is_err());
+        let a = 10u128.pow(38);
+        // 1e38 * 3 / 1e47 = 3e-9
+        assert_eq!(compress_exact(a, ratio, p, RoundingMode::Floor, u128::MAX), Ok(0));
+        assert_eq!(compress_exact(a, ratio, p, RoundingMode::Ceil, u128::MAX), Ok(1));
+        // Ties after the deferred division still go to even, and a dropped
+        // remainder breaks the tie upward.
+        assert_eq!(div_pow10(5, false, 1, RoundingMode::HalfEven), 0);
+        assert_eq!(div_pow10(15, false, 1, RoundingMode::HalfEven), 2);
+        assert_eq!(div_pow10(5, true, 1, RoundingMode::HalfEven), 1);
+        assert_eq!(div_pow10(u128::MAX, false, 39, RoundingMode::Ceil), 1);
+        assert_eq!(div_pow10(u128::MAX, true, 39, RoundingMode::HalfEven), 0);
+        // Cancelling common powers of ten keeps it in range: 1e9/1e29 with 18
+        // source decimals is 1/1e38.
+        let reducible = Ratio::new(10u128.pow(9), 10u128.pow(29)).unwrap();
+        assert_eq!(effective_ratio(reducible, p), Ok(Ratio { num: 1, den: 10u128.pow(38) }));
+    }
+
+    #[test]
+    fn decimals_fold_into_ratio() {
+        // Same shape as the orphan scanner: 1,010,000 * 1e6 at 1e-12 with 6/6 decimals.
+        let b = 1_010_000u128 * 1_000_000u128;
+        let c_e = Ratio::inverse_pow10(12);
+        let c_s = Ratio::new(5, 10u128.pow(13)).unwrap();
+        let p = DecimalsProfile::COSMOS_6;
+        assert_eq!(compress_exact(b, c_e, p, RoundingMode::Floor, u128::MAX), Ok(1));
+        assert_eq!(compress_exact(b, c_s, p, RoundingMode::Floor, u128::MAX), Ok(0));
+        assert_eq!(
+            compress_exact(1, Ratio::new(1, 1).unwrap(), DecimalsProfile::new(0, 60), RoundingMode::Floor, 1),
+            Err(CompressError::DecimalsOutOfRange)
+        );
+    }
+
+    #[test]
+    fn zero_denominator_is_rejected_not_saturated() {
+        let zero = Ratio { num: 1, den: 0 };
+        for amount in [0, 1, u128::MAX] {
+            assert_eq!(
+                compress_exact(amount, zero, DecimalsProfile::COSMOS_6, RoundingMode::Floor, u128::MAX),
+                Err(CompressError::ZeroDenominator)
+            );
+        }
+        assert_eq!(effective_ratio(zero, DecimalsProfile::EVM_18), Err(CompressError::ZeroDenominator));
+    }
+
+    proptest! {
+        #[test]
+        fn floor_le_half_even_le_ceil(a in any::<u128>(), n in 1u128..=u64::MAX as u128, d in 1u128..=u64::MAX as u128) {
+            let f = mul_div(a, n, d, RoundingMode::Floor);
+            let h = mul_div(a, n, d, RoundingMode::HalfEven);
+            let c = mul_div(a, n, d, RoundingMode::Ceil);
+            if let (Some(f), Some(h), Some(c)) = (f, h, c) {
+                prop_assert!(f <= h && h <= c && c - f <= 1);
+            }
+        }
+
+        #[test]
+        fn exact_against_u128_when_product_fits(a in any::<u64>(), n in any::<u64>(), d in 1u64..) {
+            let (a, n, d) = (a as u128, n as u128, d as u128);
+            prop_assert_eq!(mul_div(a, n, d, RoundingMode::Floor), Some(a * n / d));
+        }
+
+        #[test]
+        fn compress_exact_monotone_and_capped(
+            a in any::<u128>(),
+            b in any::<u128>(),
+            num in 1u128..1_000_000,
+            den_exp in 0u32..30,
+            src in 0u8..19,
+            aln in 0u8..19,
+            cap in any::<u128>(),
+        ) {
+            let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+            let ratio = Ratio::new(num, 10u128.pow(den_exp)).unwrap();
+            let profile = DecimalsProfile::new(src, aln);
+            for mode in [RoundingMode::Floor, RoundingMode::Ceil, RoundingMode::HalfEven] {
+                let x = compress_exact(lo, ratio, profile, mode, cap).unwrap();
+                let y = compress_exact(hi, ratio, profile, mode, cap).unwrap();
+                prop_assert!(x <= y, "monotone");
+                prop_assert!(y <= cap, "cap respected");
+            }
+        }
+    }
+}
@@ -1,23 +1,23 @@
 //! ALN energy compression utilities
 //!
-//! Provides `to_u128_floor` and `compress_asset` as the canonical implementation for
-//! mapping external minimal units into AU.ET / CSP fixed‑point internal units.
+//! Provides `compress_asset` as the canonical implementation for mapping
+//! external minimal units into AU.ET / CSP fixed‑point internal units. All
+//! arithmetic is exact; see [`fixed_point`] for the rational core shared by
+//! the scanner tooling and the UBS energy mapping.
 
-pub const C_E: f64 = 1e-12;
-pub const C_S: f64 = 5e-13;
+pub mod fixed_point;
+
+pub use fixed_point::{
+    compress_exact, mul_div, CompressError, DecimalsProfile, Ratio, RoundingMode,
+};
+
+/// AU.ET compression factor, 1e-12.
+pub const C_E: Ratio = Ratio::inverse_pow10(12);
+/// CSP compression factor, 5e-13.
+pub const C_S: Ratio = Ratio { num: 5, den: 10_000_000_000_000 };
 pub const AE_CAP: u128 = 10_000_000_000_000u128;
 pub const CSP_CAP: u128 = 5_000_000_000_000u128;
 
-#[inline]
-pub fn to_u128_floor(x: f64) -> u128 {
-    if x.is_nan() || x <= 0.0 {
-        0u128
-    } else {
-        let f = x.floor();
-        if !f.is_finite() || f <= 0.0 { 0u128 } else { f as u128 }
-    }
-}
-
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct EnergyVector {
     pub au_et: u128,
@@ -25,17 +25,23 @@ pub struct EnergyVector {
 }
 
 /// Compress an external asset amount (minimal units) into an `EnergyVector`.
-/// The result saturates at the configured global caps.
+/// The result is floored and saturates at the configured global caps.
 pub fn compress_asset(a_src_min_unit: u128) -> EnergyVector {
-    // Convert to f64 for fixed-point multiplication using canonical constants.
-    let src = a_src_min_unit as f64;
-    let au = to_u128_floor(src * C_E);
-    let cs = to_u128_floor(src * C_S);
+    compress_asset_with(a_src_min_unit, DecimalsProfile::IDENTITY, RoundingMode::Floor)
+        .expect("identity profile never overflows")
+}
 
-    EnergyVector {
-        au_et: au.min(AE_CAP),
-        csp: cs.min(CSP_CAP),
-    }
+/// Compress an amount under an explicit per-asset decimals profile and
+/// rounding mode, using the canonical `C_E` / `C_S` factors and caps.
+pub fn compress_asset_with(
+    a_src_min_unit: u128,
+    profile: DecimalsProfile,
+    mode: RoundingMode,
+) -> Result<EnergyVector, CompressError> {
+    Ok(EnergyVector {
+        au_et: compress_exact(a_src_min_unit, C_E, profile, mode, AE_CAP)?,
+        csp: compress_exact(a_src_min_unit, C_S, profile, mode, CSP_CAP)?,
+    })
 }
 
 #[cfg(test)]
@@ -51,14 +57,17 @@ mod tests {
         assert_eq!(e.au_et, 1);
         // 0 maps to zero
         assert_eq!(compress_asset(0u128), EnergyVector { au_et: 0, csp: 0 });
+        // Above 2^53 the old f64 path drifted; the exact path does not.
+        let big = 9_999_999_999_999_999_999_999_999u128;
+        assert_eq!(compress_asset(big).au_et, 9_999_999_999_999);
+        assert_eq!(compress_asset(3_000_000_000_000).csp, 1);
+        assert_eq!(compress_asset(u128::MAX), EnergyVector { au_et: AE_CAP, csp: CSP_CAP });
     }
 
     proptest! {
         #[test]
-        fn monotonicity_and_caps(a in any::<u64>(), b in any::<u64>()) {
-            let a = a as u128;
-            let b = b as u128;
-            prop_assume!(a <= b);
+        fn monotonicity_and_caps(a in any::<u128>(), b in any::<u128>()) {
+            let (a, b) = if a <= b { (a, b) } else { (b, a) };
             let ea = compress_asset(a);
             let eb = compress_asset(b);
             prop_assert!(ea.au_et <= eb.au_et, "au monotonic");
@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+
+/// One hard gate in the governor. Gates run in `GovernorConfig::gate_order`;
+/// the first one that trips decides the outcome.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GateKind {
+    Consent,
+    Lifeforce,
+    Eco,
+    Risk,
+    ActionRate,
+    IdentityDrift,
+}
+
+/// Why the governor settled on a level. Every hard gate has exactly one reason.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum DecisionReason {
+    HostConsentMissing,
+    LifeforceTooLow,
+    EcoBudgetExceeded,
+    RiskTooHigh,
+    TooManyActionsRecently,
+    IdentityDriftLimit,
+    #[default]
+    WithinAllBudgets,
+}
+
+impl GateKind {
+    pub fn reason(self) -> DecisionReason {
+        match self {
+            GateKind::Consent => DecisionReason::HostConsentMissing,
+            GateKind::Lifeforce => DecisionReason::LifeforceTooLow,
+            GateKind::Eco => DecisionReason::EcoBudgetExceeded,
+            GateKind::Risk => DecisionReason::RiskTooHigh,
+            GateKind::ActionRate => DecisionReason::TooManyActionsRecently,
+            GateKind::IdentityDrift => DecisionReason::IdentityDriftLimit,
+        }
+    }
+}
+
+/// Autonomy level granted when each gate trips.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DowngradeLevels {
+    pub consent: f32,
+    pub lifeforce: f32,
+    pub eco: f32,
+    pub risk: f32,
+    pub action_rate: f32,
+    pub identity_drift: f32,
+}
+
+impl Default for DowngradeLevels {
+    fn default() -> Self {
+        Self {
+            consent: 0.0,
+            lifeforce: 0.0,
+            eco: 0.2,
+            risk: 0.0,
+            action_rate: 0.1,
+            identity_drift: 0.0,
+        }
+    }
+}
+
+impl DowngradeLevels {
+    pub fn for_gate(&self, gate: GateKind) -> f32 {
+        match gate {
+            GateKind::Consent => self.consent,
+            GateKind::Lifeforce => self.lifeforce,
+            GateKind::Eco => self.eco,
+            GateKind::Risk => self.risk,
+            GateKind::ActionRate => self.action_rate,
+            GateKind::IdentityDrift => self.identity_drift,
+        }
+    }
+}
+
+/// How the [-1, 1] reward signal feeds the assist ramp.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RewardMapping {
+    /// (r + 1) / 2: neutral reward still contributes half its weight.
+    Shifted,
+    /// max(r, 0): only positive reward contributes.
+    PositiveOnly,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GovernorConfig {
+    pub gate_order: Vec<GateKind>,
+    pub levels: DowngradeLevels,
+    pub reward_mapping: RewardMapping,
+    /// Eco above this fraction of budget (but within it) scales autonomy by
+    /// `eco_soft_penalty`.
+    pub eco_soft_fraction: f32,
+    pub eco_soft_penalty: f32,
+    /// Risk above which an in-budget decision routes to FewShot.
+    pub few_shot_risk: f32,
+    /// Gate downgrades above this level are labelled Hybrid; `None` keeps
+    /// every gated decision ZeroShot.
+    pub hybrid_min_level: Option<f32>,
+}
+
+impl GovernorConfig {
+    /// Historical `AutonomyGovernor` behaviour.
+    pub fn autonomy_core() -> Self {
+        Self {
+            gate_order: vec![
+                GateKind::Consent,
+                GateKind::Lifeforce,
+                GateKind::Eco,
+                GateKind::Risk,
+                GateKind::ActionRate,
+                GateKind::IdentityDrift,
+            ],
+            levels: DowngradeLevels::default(),
+            reward_mapping: RewardMapping::Shifted,
+            eco_soft_fraction: 0.7,
+            eco_soft_penalty: 0.7,
+            few_shot_risk: 0.7,
+            hybrid_min_level: None,
+        }
+    }
+
+    /// Historical `CompanionAutonomyGovernor` behaviour: risk is checked before
+    /// eco, and only positive reward lifts the ramp.
+    pub fn companion() -> Self {
+        Self {
+            gate_order: vec![
+                GateKind::Consent,
+                GateKind::Lifeforce,
+                GateKind::Risk,
+                GateKind::Eco,
+                GateKind::ActionRate,
+                GateKind::IdentityDrift,
+            ],
+            reward_mapping: RewardMapping::PositiveOnly,
+            hybrid_min_level: Some(0.3),
+            ..Self::autonomy_core()
+        }
+    }
+}
+
+/// Budgets the gates compare against.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GovernorLimits {
+    pub max_eco_energy_nj_per_minute: f32,
+    pub max_autonomous_actions_per_minute: u32,
+    pub max_risk_score: f32,
+    pub min_lifeforce_scalar: f32,
+    pub max_identity_drift_per_day: f32,
+}
+
+/// Signals for one decision, already collapsed by the caller (a single trace
+/// or a biomarker aggregation window).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GovernorInputs {
+    pub consent_active: bool,
+    pub lifeforce_scalar: f32,
+    pub eco_energy_nj: f32,
+    pub risk: f32,
+    pub actions_last_minute: u32,
+    pub identity_drift_today: f32,
+    pub stress: f32,
+    pub fatigue: f32,
+    pub reward: f32,
+    pub safety: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GovernorOutcome {
+    pub autonomy_level: f32,
+    pub shot_level_label: String,
+    pub reason: DecisionReason,
+    /// Gate that decided, or `None` when every gate passed.
+    pub gate: Option<GateKind>,
+}
+
+fn trips(gate: GateKind, limits: &GovernorLimits, inputs: &GovernorInputs) -> bool {
+    match gate {
+        GateKind::Consent => !inputs.consent_active,
+        GateKind::Lifeforce => inputs.lifeforce_scalar < limits.min_lifeforce_scalar,
+        GateKind::Eco => inputs.eco_energy_nj > limits.max_eco_energy_nj_per_minute,
+        GateKind::Risk => inputs.risk > limits.max_risk_score,
+        GateKind::ActionRate => {
+            inputs.actions_last_minute >= limits.max_autonomous_actions_per_minute
+        }
+        GateKind::IdentityDrift => inputs.identity_drift_today > limits.max_identity_drift_per_day,
+    }
+}
+
+/// Run the hard gates in order, then the smooth assist ramp.
+pub fn decide(
+    config: &GovernorConfig,
+    limits: &GovernorLimits,
+    inputs: &GovernorInputs,
+) -> GovernorOutcome {
+    if let Some(gate) = config
+        .gate_order
+        .iter()
+        .copied()
+        .find(|g| trips(*g, limits, inputs))
+    {
+        let level = config.levels.for_gate(gate).clamp(0.0, 1.0);
+        let hybrid = config.hybrid_min_level.is_some_and(|min| level > min);
+        return GovernorOutcome {
+            autonomy_level: level,
+            shot_level_label: if hybrid { "Hybrid" } else { "ZeroShot" }.into(),
+            reason: gate.reason(),
+            gate: Some(gate),
+        };
+    }
+
+    let reward = match config.reward_mapping {
+        RewardMapping::Shifted => (inputs.reward + 1.0) / 2.0,
+        RewardMapping::PositiveOnly => inputs.reward.max(0.0),
+    };
+    let mut autonomy = (0.4 * (1.0 - inputs.stress).max(0.0)
+        + 0.2 * (1.0 - inputs.fatigue).max(0.0)
+        + 0.2 * reward
+        + 0.2 * inputs.safety.max(0.0))
+    .clamp(0.0, 1.0);
+
+    if inputs.eco_energy_nj > config.eco_soft_fraction * limits.max_eco_energy_nj_per_minute {
+        autonomy *= config.eco_soft_penalty;
+    }
+
+    let shot = if inputs.risk > config.few_shot_risk {
+        "FewShot"
+    } else {
+        "ZeroShot"
+    };
+
+    GovernorOutcome {
+        autonomy_level: autonomy,
+        shot_level_label: shot.into(),
+        reason: DecisionReason::WithinAllBudgets,
+        gate: None,
+    }
+}
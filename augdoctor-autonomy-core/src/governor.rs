@@ -1,3 +1,4 @@
+use crate::gates::{self, DecisionReason, GovernorConfig, GovernorInputs, GovernorLimits};
 use crate::trace::AutonomyTraceAttributes;
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +17,20 @@ pub struct AutonomyProfile {
 pub struct AutonomyDecision {
     pub autonomy_level: f32,
     pub shot_level_label: String,
+    #[serde(default)]
+    pub reason: DecisionReason,
+}
+
+impl AutonomyProfile {
+    pub fn limits(&self) -> GovernorLimits {
+        GovernorLimits {
+            max_eco_energy_nj_per_minute: self.max_eco_energy_nj_per_minute,
+            max_autonomous_actions_per_minute: self.max_autonomous_actions_per_minute,
+            max_risk_score: self.max_risk_score,
+            min_lifeforce_scalar: self.min_lifeforce_scalar,
+            max_identity_drift_per_day: self.max_identity_drift_per_day,
+        }
+    }
 }
 
 pub struct AutonomyGovernor;
@@ -37,70 +52,112 @@ impl AutonomyGovernor {
             "lifeforce_scalar must be normalized"
         );
 
-        // 1. Identity / consent (upstream identity header already checked in inner-ledger).[file:41]
-        if !host_consent_active {
-            return AutonomyDecision {
-                autonomy_level: 0.0,
-                shot_level_label: "ZeroShot".into(),
-            };
-        }
+        // Identity is checked upstream in inner-ledger; the shared gate order
+        // is consent → lifeforce → eco → risk → SCALE/turns.[file:41][file:42]
+        let outcome = gates::decide(
+            &GovernorConfig::autonomy_core(),
+            &profile.limits(),
+            &GovernorInputs {
+                consent_active: host_consent_active,
+                lifeforce_scalar: trace.lifeforce_scalar,
+                eco_energy_nj: trace.eco_energy_nj,
+                risk: trace.risk,
+                actions_last_minute: trace.actions_last_minute,
+                identity_drift_today: trace.identity_drift_today,
+                stress: trace.stress,
+                fatigue: trace.fatigue,
+                reward: trace.reward,
+                safety: trace.safety,
+            },
+        );
 
-        // 2. Lifeforce (hard veto).[file:42][file:41]
-        if trace.lifeforce_scalar < profile.min_lifeforce_scalar {
-            return AutonomyDecision {
-                autonomy_level: 0.0,
-                shot_level_label: "ZeroShot".into(),
-            };
+        AutonomyDecision {
+            autonomy_level: outcome.autonomy_level,
+            shot_level_label: outcome.shot_level_label,
+            reason: outcome.reason,
         }
+    }
+}
 
-        // 3. Eco budget.[file:42]
-        if trace.eco_energy_nj > profile.max_eco_energy_nj_per_minute {
-            return AutonomyDecision {
-                autonomy_level: 0.2,
-                shot_level_label: "ZeroShot".into(),
-            };
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::calm_trace;
 
-        // 4. Risk.[file:41]
-        if trace.risk > profile.max_risk_score {
-            return AutonomyDecision {
-                autonomy_level: 0.0,
-                shot_level_label: "ZeroShot".into(),
-            };
+    fn profile() -> AutonomyProfile {
+        AutonomyProfile {
+            profile_id: "test".into(),
+            host_id: "host".into(),
+            max_eco_energy_nj_per_minute: 5.0,
+            max_autonomous_actions_per_minute: 10,
+            max_risk_score: 0.65,
+            min_lifeforce_scalar: 0.35,
+            max_identity_drift_per_day: 0.15,
         }
+    }
 
-        // 5. SCALE / turns (approximated by actions_last_minute and identity drift).[file:42][file:41]
-        if trace.actions_last_minute >= profile.max_autonomous_actions_per_minute {
-            return AutonomyDecision {
-                autonomy_level: 0.1,
-                shot_level_label: "ZeroShot".into(),
-            };
-        }
-        if trace.identity_drift_today > profile.max_identity_drift_per_day {
-            return AutonomyDecision {
-                autonomy_level: 0.0,
-                shot_level_label: "ZeroShot".into(),
-            };
+    #[test]
+    fn trace_signals_reach_their_gates() {
+        let cases = [
+            (
+                AutonomyTraceAttributes {
+                    lifeforce_scalar: 0.2,
+                    ..calm_trace()
+                },
+                DecisionReason::LifeforceTooLow,
+            ),
+            (
+                AutonomyTraceAttributes {
+                    eco_energy_nj: 6.0,
+                    ..calm_trace()
+                },
+                DecisionReason::EcoBudgetExceeded,
+            ),
+            (
+                AutonomyTraceAttributes {
+                    actions_last_minute: 10,
+                    ..calm_trace()
+                },
+                DecisionReason::TooManyActionsRecently,
+            ),
+            (
+                AutonomyTraceAttributes {
+                    identity_drift_today: 0.3,
+                    ..calm_trace()
+                },
+                DecisionReason::IdentityDriftLimit,
+            ),
+        ];
+        for (trace, reason) in cases {
+            assert_eq!(
+                AutonomyGovernor::decide(&profile(), &trace, true).reason,
+                reason
+            );
         }
+        let denied = AutonomyGovernor::decide(&profile(), &calm_trace(), false);
+        assert_eq!(denied.reason, DecisionReason::HostConsentMissing);
+        assert_eq!(denied.autonomy_level, 0.0);
+    }
 
-        // Smooth assist ramp (Lipschitz-bounded).[file:41]
-        let mut autonomy = {
-            let s = (1.0 - trace.stress).max(0.0);
-            let f = (1.0 - trace.fatigue).max(0.0);
-            let r = (trace.reward + 1.0) / 2.0; // [-1,1] → [0,1]
-            let sa = trace.safety.max(0.0);
-            (0.4 * s + 0.2 * f + 0.2 * r + 0.2 * sa).clamp(0.0, 1.0)
-        };
-
-        if trace.eco_energy_nj > 0.7 * profile.max_eco_energy_nj_per_minute {
-            autonomy *= 0.7;
-        }
+    #[test]
+    fn ramp_reads_trace_biomarkers() {
+        let d = AutonomyGovernor::decide(&profile(), &calm_trace(), true);
+        assert_eq!(d.reason, DecisionReason::WithinAllBudgets);
+        assert!((d.autonomy_level - 0.83).abs() < 1e-6);
 
-        let shot = if trace.risk > 0.7 { "FewShot" } else { "ZeroShot" };
+        let tired = AutonomyTraceAttributes {
+            fatigue: 0.6,
+            ..calm_trace()
+        };
+        let d = AutonomyGovernor::decide(&profile(), &tired, true);
+        assert!((d.autonomy_level - 0.73).abs() < 1e-6);
+    }
 
-        AutonomyDecision {
-            autonomy_level: autonomy,
-            shot_level_label: shot.into(),
-        }
+    #[test]
+    fn decisions_without_reason_deserialize_as_within_budgets() {
+        let d: AutonomyDecision =
+            serde_json::from_str(r#"{"autonomy_level":0.4,"shot_level_label":"ZeroShot"}"#)
+                .unwrap();
+        assert_eq!(d.reason, DecisionReason::WithinAllBudgets);
     }
 }
@@ -1,5 +1,49 @@
 pub mod trace;
+pub mod gates;
 pub mod governor;
+pub mod monitor;
+pub mod state;
 
 pub use trace::AutonomyTraceAttributes;
+pub use gates::{
+    DecisionReason, DowngradeLevels, GateKind, GovernorConfig, GovernorInputs, GovernorLimits,
+    GovernorOutcome, RewardMapping,
+};
 pub use governor::{AutonomyDecision, AutonomyGovernor, AutonomyProfile};
+pub use state::{
+    ActionEvent, AutonomyCounters, AutonomyStateTracker, Clock, SimulatedClock, SnapshotError,
+    SystemClock, TrackerSnapshot,
+};
+pub use monitor::{check_traces, LtlMonitor, MonitorSpec, Violation, ViolationKind};
+
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::trace::{AutonomyConstraint, AutonomyTraceAttributes};
+
+    /// Calm, in-budget trace shared by the unit tests; override fields with
+    /// struct update syntax.
+    pub fn calm_trace() -> AutonomyTraceAttributes {
+        AutonomyTraceAttributes {
+            schemaversion: "autonomy-trace.v1".into(),
+            host_id: "host".into(),
+            session_id: "s1".into(),
+            environment_id: "env".into(),
+            plane: "chat.only".into(),
+            stress: 0.2,
+            fatigue: 0.1,
+            reward: 0.5,
+            safety: 0.9,
+            lifeforce_scalar: 0.8,
+            eco_energy_nj: 1.0,
+            risk: 0.2,
+            actions_last_minute: 1,
+            identity_drift_today: 0.05,
+            decision_autonomy_level: 0.0,
+            decision_shot_level_label: "ZeroShot".into(),
+            constraint: AutonomyConstraint {
+                highest_risk_score: 0.2,
+                worst_lifeforce_scalar: 0.8,
+            },
+        }
+    }
+}
@@ -0,0 +1,536 @@
+//! Runtime verification of `AutonomyTraceAttributes` streams.
+//!
+//! Properties are finite-trace LTL fragments over per-trace predicates:
+//! `G(p)`, `G(trigger -> F[0,n] target)` and `G(trigger -> (hold U release))`.
+//! The monitor is incremental, so the same spec runs next to the governor in
+//! production and over recorded traces in offline replay.
+
+use crate::trace::AutonomyTraceAttributes;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::Path;
+
+pub const MONITOR_SPEC_VERSION: u32 = 1;
+const DEFAULT_CONTEXT: usize = 8;
+
+/// Numeric trace fields a predicate may compare.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TraceField {
+    #[serde(rename = "stress")]
+    Stress,
+    #[serde(rename = "fatigue")]
+    Fatigue,
+    #[serde(rename = "reward")]
+    Reward,
+    #[serde(rename = "safety")]
+    Safety,
+    #[serde(rename = "lifeforce_scalar")]
+    LifeforceScalar,
+    #[serde(rename = "eco_energy_nj")]
+    EcoEnergyNj,
+    #[serde(rename = "risk")]
+    Risk,
+    #[serde(rename = "actions_last_minute")]
+    ActionsLastMinute,
+    #[serde(rename = "identity_drift_today")]
+    IdentityDriftToday,
+    #[serde(rename = "decision_autonomy_level")]
+    DecisionAutonomyLevel,
+    #[serde(rename = "constraint.highest_risk_score")]
+    HighestRiskScore,
+    #[serde(rename = "constraint.worst_lifeforce_scalar")]
+    WorstLifeforceScalar,
+}
+
+impl TraceField {
+    fn read(self, t: &AutonomyTraceAttributes) -> f32 {
+        match self {
+            TraceField::Stress => t.stress,
+            TraceField::Fatigue => t.fatigue,
+            TraceField::Reward => t.reward,
+            TraceField::Safety => t.safety,
+            TraceField::LifeforceScalar => t.lifeforce_scalar,
+            TraceField::EcoEnergyNj => t.eco_energy_nj,
+            TraceField::Risk => t.risk,
+            TraceField::ActionsLastMinute => t.actions_last_minute as f32,
+            TraceField::IdentityDriftToday => t.identity_drift_today,
+            TraceField::DecisionAutonomyLevel => t.decision_autonomy_level,
+            TraceField::HighestRiskScore => t.constraint.highest_risk_score,
+            TraceField::WorstLifeforceScalar => t.constraint.worst_lifeforce_scalar,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CmpOp {
+    #[serde(rename = "<")]
+    Lt,
+    #[serde(rename = "<=")]
+    Le,
+    #[serde(rename = ">")]
+    Gt,
+    #[serde(rename = ">=")]
+    Ge,
+    #[serde(rename = "==")]
+    Eq,
+    #[serde(rename = "!=")]
+    Ne,
+}
+
+/// State formula evaluated on a single trace.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "pred", rename_all = "snake_case")]
+pub enum Predicate {
+    True,
+    Cmp {
+        field: TraceField,
+        op: CmpOp,
+        value: f32,
+    },
+    ShotLabel {
+        equals: String,
+    },
+    Plane {
+        equals: String,
+    },
+    Not {
+        arg: Box<Predicate>,
+    },
+    And {
+        args: Vec<Predicate>,
+    },
+    Or {
+        args: Vec<Predicate>,
+    },
+    Implies {
+        lhs: Box<Predicate>,
+        rhs: Box<Predicate>,
+    },
+}
+
+impl Predicate {
+    pub fn holds(&self, t: &AutonomyTraceAttributes) -> bool {
+        match self {
+            Predicate::True => true,
+            Predicate::Cmp { field, op, value } => {
+                let v = field.read(t);
+                match op {
+                    CmpOp::Lt => v < *value,
+                    CmpOp::Le => v <= *value,
+                    CmpOp::Gt => v > *value,
+                    CmpOp::Ge => v >= *value,
+                    CmpOp::Eq => v == *value,
+                    CmpOp::Ne => v != *value,
+                }
+            }
+            Predicate::ShotLabel { equals } => t.decision_shot_level_label == *equals,
+            Predicate::Plane { equals } => t.plane == *equals,
+            Predicate::Not { arg } => !arg.holds(t),
+            Predicate::And { args } => args.iter().all(|p| p.holds(t)),
+            Predicate::Or { args } => args.iter().any(|p| p.holds(t)),
+            Predicate::Implies { lhs, rhs } => !lhs.holds(t) || rhs.holds(t),
+        }
+    }
+}
+
+/// Temporal formula. Only the earliest open obligation is tracked: a later
+/// trigger is discharged by the same `target`/`release` and cannot miss its
+/// deadline first, so it is absorbed. Once an obligation is reported, the next
+/// trigger opens a fresh one.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum Formula {
+    /// G(holds)
+    Globally { holds: Predicate },
+    /// G(trigger -> F[0,within] target)
+    EventuallyWithin {
+        trigger: Predicate,
+        target: Predicate,
+        within: usize,
+    },
+    /// G(trigger -> (hold U release)), strong: `release` must occur, within
+    /// `within` steps when bounded.
+    Until {
+        trigger: Predicate,
+        hold: Predicate,
+        release: Predicate,
+        #[serde(default)]
+        within: Option<usize>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Property {
+    pub id: String,
+    #[serde(default)]
+    pub description: String,
+    pub formula: Formula,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MonitorSpec {
+    pub version: u32,
+    /// Traces kept before the current one when reporting a `Globally` violation.
+    #[serde(default = "default_context")]
+    pub context: usize,
+    pub properties: Vec<Property>,
+}
+
+fn default_context() -> usize {
+    DEFAULT_CONTEXT
+}
+
+#[derive(Debug)]
+pub enum MonitorSpecError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    UnsupportedVersion(u32),
+    DuplicateProperty(String),
+}
+
+impl fmt::Display for MonitorSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MonitorSpecError::Io(e) => write!(f, "monitor spec io error: {}", e),
+            MonitorSpecError::Parse(e) => write!(f, "monitor spec parse error: {}", e),
+            MonitorSpecError::UnsupportedVersion(v) => {
+                write!(f, "unsupported monitor spec version {}", v)
+            }
+            MonitorSpecError::DuplicateProperty(id) => write!(f, "duplicate property id {}", id),
+        }
+    }
+}
+
+impl std::error::Error for MonitorSpecError {}
+
+impl MonitorSpec {
+    pub fn from_json(raw: &str) -> Result<Self, MonitorSpecError> {
+        let spec: MonitorSpec = serde_json::from_str(raw).map_err(MonitorSpecError::Parse)?;
+        if spec.version != MONITOR_SPEC_VERSION {
+            return Err(MonitorSpecError::UnsupportedVersion(spec.version));
+        }
+        let mut seen = std::collections::HashSet::new();
+        for p in &spec.properties {
+            if !seen.insert(p.id.as_str()) {
+                return Err(MonitorSpecError::DuplicateProperty(p.id.clone()));
+            }
+        }
+        Ok(spec)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MonitorSpecError> {
+        let raw = std::fs::read_to_string(path).map_err(MonitorSpecError::Io)?;
+        Self::from_json(&raw)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ViolationKind {
+    /// A `Globally` predicate or an `Until` hold condition was false.
+    Falsified,
+    /// A bounded obligation ran out of steps.
+    DeadlineMissed,
+    /// The stream ended with an obligation still open.
+    OpenAtEndOfStream,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WindowEntry {
+    pub step: u64,
+    pub trace: AutonomyTraceAttributes,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Violation {
+    pub property_id: String,
+    pub kind: ViolationKind,
+    /// Step at which the violation was detected.
+    pub step: u64,
+    /// Step whose trigger opened the failed obligation, if any.
+    pub triggered_at: Option<u64>,
+    /// Offending traces, oldest first.
+    pub window: Vec<WindowEntry>,
+}
+
+struct PropertyState {
+    property: Property,
+    /// Trigger steps of the pending obligations, oldest first. A missed
+    /// deadline only closes the oldest, so later triggers stay armed. An
+    /// unbounded `Until` keeps just the earliest, since a later one cannot
+    /// fail separately.
+    open: VecDeque<u64>,
+}
+
+/// Incremental monitor over one trace stream.
+pub struct LtlMonitor {
+    context: usize,
+    properties: Vec<PropertyState>,
+    history: VecDeque<WindowEntry>,
+    capacity: usize,
+    next_step: u64,
+}
+
+impl LtlMonitor {
+    pub fn new(spec: MonitorSpec) -> Self {
+        let longest_bound = spec
+            .properties
+            .iter()
+            .filter_map(|p| match &p.formula {
+                Formula::EventuallyWithin { within, .. } => Some(*within),
+                Formula::Until { within, .. } => *within,
+                Formula::Globally { .. } => None,
+            })
+            .max()
+            .unwrap_or(0);
+        Self {
+            context: spec.context,
+            capacity: (spec.context + 1).max(longest_bound + 1),
+            properties: spec
+                .properties
+                .into_iter()
+                .map(|property| PropertyState {
+                    property,
+                    open: VecDeque::new(),
+                })
+                .collect(),
+            history: VecDeque::new(),
+            next_step: 0,
+        }
+    }
+
+    /// Feed the next trace; returns violations detected at this step.
+    pub fn observe(&mut self, trace: &AutonomyTraceAttributes) -> Vec<Violation> {
+        let step = self.next_step;
+        self.next_step += 1;
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+        self.history.push_back(WindowEntry {
+            step,
+            trace: trace.clone(),
+        });
+
+        let mut found = Vec::new();
+        for idx in 0..self.properties.len() {
+            let mut hits: Vec<(ViolationKind, Option<u64>)> = Vec::new();
+            let state = &mut self.properties[idx];
+            match &state.property.formula {
+                Formula::Globally { holds } => {
+                    if !holds.holds(trace) {
+                        hits.push((ViolationKind::Falsified, None));
+                    }
+                }
+                Formula::EventuallyWithin {
+                    trigger,
+                    target,
+                    within,
+                } => {
+                    if trigger.holds(trace) {
+                        state.open.push_back(step);
+                    }
+                    if target.holds(trace) {
+                        state.open.clear();
+                    }
+                    while let Some(start) = state.open.pop_front_if(|start| step - *start >= *within as u64) {
+                        hits.push((ViolationKind::DeadlineMissed, Some(start)));
+                    }
+                }
+                Formula::Until {
+                    trigger,
+                    hold,
+                    release,
+                    within,
+                } => {
+                    if trigger.holds(trace) && (within.is_some() || state.open.is_empty()) {
+                        state.open.push_back(step);
+                    }
+                    if release.holds(trace) {
+                        state.open.clear();
+                    } else if !hold.holds(trace) {
+                        // One failed hold breaks every pending obligation;
+                        // it is reported once, against the earliest.
+                        if let Some(&start) = state.open.front() {
+                            hits.push((ViolationKind::Falsified, Some(start)));
+                        }
+                        state.open.clear();
+                    } else if let Some(within) = within {
+                        while let Some(start) =
+                            state.open.pop_front_if(|start| step - *start >= *within as u64)
+                        {
+                            hits.push((ViolationKind::DeadlineMissed, Some(start)));
+                        }
+                    }
+                }
+            }
+            for (kind, triggered_at) in hits {
+                found.push(self.violation(idx, kind, step, triggered_at));
+            }
+        }
+        found
+    }
+
+    /// Close the stream: every open obligation becomes a violation.
+    pub fn finish(&mut self) -> Vec<Violation> {
+        let last = self.next_step.saturating_sub(1);
+        let mut found = Vec::new();
+        for idx in 0..self.properties.len() {
+            for start in std::mem::take(&mut self.properties[idx].open) {
+                found.push(self.violation(
+                    idx,
+                    ViolationKind::OpenAtEndOfStream,
+                    last,
+                    Some(start),
+                ));
+            }
+        }
+        found
+    }
+
+    fn violation(
+        &self,
+        idx: usize,
+        kind: ViolationKind,
+        step: u64,
+        triggered_at: Option<u64>,
+    ) -> Violation {
+        let from = triggered_at.unwrap_or(step.saturating_sub(self.context as u64));
+        Violation {
+            property_id: self.properties[idx].property.id.clone(),
+            kind,
+            step,
+            triggered_at,
+            window: self
+                .history
+                .iter()
+                .filter(|e| e.step >= from && e.step <= step)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Offline replay: run `spec` over a recorded stream, end-of-stream included.
+pub fn check_traces<'a>(
+    spec: &MonitorSpec,
+    traces: impl IntoIterator<Item = &'a AutonomyTraceAttributes>,
+) -> Vec<Violation> {
+    let mut monitor = LtlMonitor::new(spec.clone());
+    let mut violations: Vec<Violation> = traces
+        .into_iter()
+        .flat_map(|t| monitor.observe(t))
+        .collect();
+    violations.extend(monitor.finish());
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::calm_trace;
+
+    fn trace(risk: f32, autonomy: f32, eco: f32) -> AutonomyTraceAttributes {
+        let mut t = calm_trace();
+        t.risk = risk;
+        t.constraint.highest_risk_score = risk;
+        t.decision_autonomy_level = autonomy;
+        t.eco_energy_nj = eco;
+        t
+    }
+
+    fn shipped() -> MonitorSpec {
+        MonitorSpec::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../policies/autonomy-ltl.default.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn globally_reports_offending_window() {
+        let mut m = LtlMonitor::new(shipped());
+        assert!(m.observe(&trace(0.2, 0.9, 1.0)).is_empty());
+        let v: Vec<Violation> = m
+            .observe(&trace(0.9, 0.85, 1.0))
+            .into_iter()
+            .filter(|v| v.property_id == "risk-high-bounds-autonomy")
+            .collect();
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].kind, ViolationKind::Falsified);
+        assert_eq!(
+            v[0].window.iter().map(|e| e.step).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+    }
+
+    #[test]
+    fn eventually_within_and_until_track_obligations() {
+        let spec = shipped();
+        // Eco overrun must be followed by autonomy <= 0.2 within 2 steps.
+        let late = [
+            trace(0.2, 0.6, 6.0),
+            trace(0.2, 0.6, 1.0),
+            trace(0.2, 0.6, 1.0),
+        ];
+        let v = check_traces(&spec, &late);
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].kind, ViolationKind::DeadlineMissed);
+        assert_eq!(v[0].triggered_at, Some(0));
+        assert_eq!(v[0].window.len(), 3);
+
+        let recovered = [trace(0.2, 0.6, 6.0), trace(0.2, 0.2, 1.0)];
+        assert!(check_traces(&spec, &recovered).is_empty());
+
+        // Until: once risk exceeds the hard limit autonomy stays at zero until
+        // risk falls back; a stream ending mid-obligation is reported.
+        let mut high = trace(0.9, 0.0, 1.0);
+        high.constraint.highest_risk_score = 0.5;
+        let v = check_traces(&spec, &[high.clone(), trace(0.9, 0.3, 1.0)]);
+        assert!(v
+            .iter()
+            .any(|x| x.property_id == "risk-lockout" && x.kind == ViolationKind::Falsified));
+        let v = check_traces(&spec, &[high]);
+        assert_eq!(v[0].kind, ViolationKind::OpenAtEndOfStream);
+    }
+
+    #[test]
+    fn triggers_absorbed_before_a_missed_deadline_are_still_reported() {
+        // Eco overruns at steps 0 and 1, autonomy never drops: each trigger
+        // misses its own deadline.
+        let stream = [
+            trace(0.2, 0.6, 6.0),
+            trace(0.2, 0.6, 6.0),
+            trace(0.2, 0.6, 1.0),
+            trace(0.2, 0.6, 1.0),
+        ];
+        let missed: Vec<(u64, Option<u64>)> = check_traces(&shipped(), &stream)
+            .into_iter()
+            .filter(|v| v.property_id == "eco-overrun-downgrades")
+            .map(|v| {
+                assert_eq!(v.kind, ViolationKind::DeadlineMissed);
+                (v.step, v.triggered_at)
+            })
+            .collect();
+        assert_eq!(missed, vec![(2, Some(0)), (3, Some(1))]);
+    }
+
+    #[test]
+    fn unbounded_until_keeps_only_the_earliest_obligation() {
+        let mut m = LtlMonitor::new(shipped());
+        let mut high = trace(0.9, 0.0, 1.0);
+        high.constraint.highest_risk_score = 0.5;
+        for _ in 0..1_000 {
+            m.observe(&high);
+        }
+        assert!(m.properties.iter().all(|p| p.open.iter().all(|&start| start == 0)));
+
+        let v: Vec<Violation> = m
+            .finish()
+            .into_iter()
+            .filter(|v| v.property_id == "risk-lockout")
+            .collect();
+        assert_eq!(v.len(), 1);
+        assert_eq!(v[0].kind, ViolationKind::OpenAtEndOfStream);
+        assert_eq!(v[0].triggered_at, Some(0));
+    }
+}
@@ -0,0 +1,418 @@
+use crate::trace::AutonomyTraceAttributes;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+
+pub const DEFAULT_ACTION_WINDOW_MS: i64 = 60_000;
+pub const DAY_MS: i64 = 86_400_000;
+pub const TRACKER_SNAPSHOT_VERSION: u32 = 1;
+
+/// Additive smoothing for histogram bins missing on one side of the KL term.
+const KL_EPSILON: f64 = 1e-3;
+
+/// Source of "now" for window pruning and day rollover.
+pub trait Clock {
+    fn now_ms(&self) -> i64;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> i64 {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as i64)
+            .unwrap_or(0)
+    }
+}
+
+/// Manually driven clock for tests and replays. Clones share the same time.
+#[derive(Clone, Debug, Default)]
+pub struct SimulatedClock {
+    now: Arc<AtomicI64>,
+}
+
+impl SimulatedClock {
+    pub fn at(now_ms: i64) -> Self {
+        Self {
+            now: Arc::new(AtomicI64::new(now_ms)),
+        }
+    }
+
+    pub fn set(&self, now_ms: i64) {
+        self.now.store(now_ms, Ordering::SeqCst);
+    }
+
+    pub fn advance(&self, delta_ms: i64) {
+        self.now.fetch_add(delta_ms, Ordering::SeqCst);
+    }
+}
+
+impl Clock for SimulatedClock {
+    fn now_ms(&self) -> i64 {
+        self.now.load(Ordering::SeqCst)
+    }
+}
+
+/// One autonomous action taken on behalf of a host.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ActionEvent {
+    pub host_id: String,
+    pub at_ms: i64,
+    /// Behaviour label the drift histogram is built over ("suggest", "edit", ...).
+    pub kind: String,
+}
+
+/// Counters both governors expect precomputed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AutonomyCounters {
+    pub host_id: String,
+    pub profile_id: String,
+    pub actions_last_minute: u32,
+    pub identity_drift_today: f32,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HostAutonomyState {
+    pub profile_id: String,
+    /// Action timestamps inside the sliding window, oldest first.
+    pub action_times_ms: VecDeque<i64>,
+    /// UTC day index (`ms / DAY_MS`) the drift accumulator belongs to.
+    pub day: i64,
+    pub identity_drift_today: f64,
+    /// Behaviour histogram of the window closed by the last decision trace.
+    pub baseline_histogram: BTreeMap<String, u32>,
+    /// Actions since the last decision trace.
+    pub open_histogram: BTreeMap<String, u32>,
+    pub last_trace: Option<AutonomyTraceAttributes>,
+}
+
+impl HostAutonomyState {
+    fn roll_day(&mut self, now_ms: i64) {
+        let day = now_ms.div_euclid(DAY_MS);
+        if day > self.day {
+            self.day = day;
+            self.identity_drift_today = 0.0;
+        }
+    }
+
+    fn prune(&mut self, now_ms: i64, window_ms: i64) {
+        while self
+            .action_times_ms
+            .front()
+            .is_some_and(|t| *t <= now_ms - window_ms)
+        {
+            self.action_times_ms.pop_front();
+        }
+    }
+
+    fn actions_in_window(&self, now_ms: i64, window_ms: i64) -> u32 {
+        self.action_times_ms
+            .iter()
+            .filter(|t| **t > now_ms - window_ms && **t <= now_ms)
+            .count() as u32
+    }
+}
+
+/// KL(p || q) over the union of bins, both sides smoothed by `KL_EPSILON`.
+pub fn histogram_kl(p: &BTreeMap<String, u32>, q: &BTreeMap<String, u32>) -> f64 {
+    let keys: Vec<&String> = {
+        let mut k: Vec<&String> = p.keys().chain(q.keys()).collect();
+        k.sort();
+        k.dedup();
+        k
+    };
+    if keys.is_empty() {
+        return 0.0;
+    }
+    let total = |h: &BTreeMap<String, u32>| {
+        h.values().map(|v| *v as f64).sum::<f64>() + KL_EPSILON * keys.len() as f64
+    };
+    let (p_total, q_total) = (total(p), total(q));
+    keys.iter()
+        .map(|k| {
+            let pi = (p.get(*k).copied().unwrap_or(0) as f64 + KL_EPSILON) / p_total;
+            let qi = (q.get(*k).copied().unwrap_or(0) as f64 + KL_EPSILON) / q_total;
+            pi * (pi / qi).ln()
+        })
+        .sum::<f64>()
+        .max(0.0)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SnapshotError {
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::UnsupportedVersion(v) => {
+                write!(f, "unsupported tracker snapshot version {}", v)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrackerSnapshot {
+    pub version: u32,
+    pub window_ms: i64,
+    pub hosts: BTreeMap<String, HostAutonomyState>,
+}
+
+/// Per-host sliding-window action rates and daily identity drift.
+///
+/// Actions are counted in a sliding window ending at `clock.now_ms()`. Each
+/// decision trace closes the current behaviour window: the KL divergence of
+/// its action-kind histogram against the previous one is added to the day's
+/// drift. Drift resets at the UTC day boundary.
+pub struct AutonomyStateTracker<C: Clock = SystemClock> {
+    clock: C,
+    window_ms: i64,
+    hosts: BTreeMap<String, HostAutonomyState>,
+}
+
+impl<C: Clock> AutonomyStateTracker<C> {
+    pub fn new(clock: C) -> Self {
+        Self::with_window(clock, DEFAULT_ACTION_WINDOW_MS)
+    }
+
+    pub fn with_window(clock: C, window_ms: i64) -> Self {
+        Self {
+            clock,
+            window_ms: window_ms.max(1),
+            hosts: BTreeMap::new(),
+        }
+    }
+
+    pub fn register_host(&mut self, host_id: &str, profile_id: &str) {
+        let now = self.clock.now_ms();
+        let state = self.host_mut(host_id, now);
+        state.profile_id = profile_id.to_string();
+    }
+
+    fn host_mut(&mut self, host_id: &str, now_ms: i64) -> &mut HostAutonomyState {
+        let state = self
+            .hosts
+            .entry(host_id.to_string())
+            .or_insert_with(|| HostAutonomyState {
+                day: now_ms.div_euclid(DAY_MS),
+                ..HostAutonomyState::default()
+            });
+        state.roll_day(now_ms);
+        state
+    }
+
+    /// Count an action. Day rollover and pruning follow the tracker's clock,
+    /// never the event: a future-dated `at_ms` is clamped to now so it can
+    /// neither roll the day forward nor push earlier actions out of the window.
+    pub fn record_action(&mut self, event: &ActionEvent) {
+        let now = self.clock.now_ms();
+        let window_ms = self.window_ms;
+        let at_ms = event.at_ms.min(now);
+        let state = self.host_mut(&event.host_id, now);
+        // Keep timestamps ordered even when events arrive late.
+        let pos = state.action_times_ms.partition_point(|t| *t <= at_ms);
+        state.action_times_ms.insert(pos, at_ms);
+        state.prune(now, window_ms);
+        *state.open_histogram.entry(event.kind.clone()).or_insert(0) += 1;
+    }
+
+    /// Ingest a governor decision trace for its host. Closes the current
+    /// behaviour window and returns the drift it added.
+    pub fn record_trace(&mut self, trace: &AutonomyTraceAttributes) -> f32 {
+        let now = self.clock.now_ms();
+        let state = self.host_mut(&trace.host_id, now);
+        let mut added = 0.0;
+        if !state.open_histogram.is_empty() {
+            if !state.baseline_histogram.is_empty() {
+                added = histogram_kl(&state.open_histogram, &state.baseline_histogram);
+                state.identity_drift_today += added;
+            }
+            state.baseline_histogram = std::mem::take(&mut state.open_histogram);
+        }
+        state.last_trace = Some(trace.clone());
+        added as f32
+    }
+
+    pub fn counters(&mut self, host_id: &str) -> Option<AutonomyCounters> {
+        let now = self.clock.now_ms();
+        let window_ms = self.window_ms;
+        let state = self.hosts.get_mut(host_id)?;
+        state.roll_day(now);
+        state.prune(now, window_ms);
+        Some(AutonomyCounters {
+            host_id: host_id.to_string(),
+            profile_id: state.profile_id.clone(),
+            actions_last_minute: state.actions_in_window(now, window_ms),
+            identity_drift_today: state.identity_drift_today as f32,
+        })
+    }
+
+    /// Latest decision trace for the host with its counters refreshed to now,
+    /// ready for `AutonomyGovernor::decide`.
+    pub fn trace_attributes(&mut self, host_id: &str) -> Option<AutonomyTraceAttributes> {
+        let counters = self.counters(host_id)?;
+        let mut trace = self.hosts.get(host_id)?.last_trace.clone()?;
+        trace.actions_last_minute = counters.actions_last_minute;
+        trace.identity_drift_today = counters.identity_drift_today;
+        Some(trace)
+    }
+
+    pub fn snapshot(&self) -> TrackerSnapshot {
+        TrackerSnapshot {
+            version: TRACKER_SNAPSHOT_VERSION,
+            window_ms: self.window_ms,
+            hosts: self.hosts.clone(),
+        }
+    }
+
+    pub fn restore(clock: C, snapshot: TrackerSnapshot) -> Result<Self, SnapshotError> {
+        if snapshot.version != TRACKER_SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(snapshot.version));
+        }
+        Ok(Self {
+            clock,
+            window_ms: snapshot.window_ms.max(1),
+            hosts: snapshot.hosts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::calm_trace;
+
+    const DAY0: i64 = 20_000 * DAY_MS;
+
+    fn action(at_ms: i64, kind: &str) -> ActionEvent {
+        ActionEvent {
+            host_id: "host".into(),
+            at_ms,
+            kind: kind.into(),
+        }
+    }
+
+    /// No actions and no drift reported, so every count comes from the tracker.
+    fn trace() -> AutonomyTraceAttributes {
+        AutonomyTraceAttributes {
+            actions_last_minute: 0,
+            identity_drift_today: 0.0,
+            ..calm_trace()
+        }
+    }
+
+    #[test]
+    fn sliding_window_counts_recent_actions_only() {
+        let clock = SimulatedClock::at(DAY0);
+        let mut tracker = AutonomyStateTracker::new(clock.clone());
+        tracker.register_host("host", "companion-default");
+        for i in 0..5 {
+            clock.set(DAY0 + i * 20_000);
+            tracker.record_action(&action(DAY0 + i * 20_000, "suggest"));
+        }
+        clock.set(DAY0 + 80_000);
+        let c = tracker.counters("host").unwrap();
+        assert_eq!(c.actions_last_minute, 3);
+        assert_eq!(c.profile_id, "companion-default");
+
+        clock.advance(60_000);
+        assert_eq!(tracker.counters("host").unwrap().actions_last_minute, 0);
+    }
+
+    #[test]
+    fn drift_accumulates_per_trace_and_resets_at_day_boundary() {
+        let clock = SimulatedClock::at(DAY0 + 1_000);
+        let mut tracker = AutonomyStateTracker::new(clock.clone());
+
+        tracker.record_action(&action(DAY0 + 1_000, "suggest"));
+        tracker.record_action(&action(DAY0 + 2_000, "suggest"));
+        assert_eq!(tracker.record_trace(&trace()), 0.0);
+
+        // Same behaviour mix: no drift.
+        tracker.record_action(&action(DAY0 + 3_000, "suggest"));
+        assert!(tracker.record_trace(&trace()) < 1e-6);
+
+        // Shift to a new behaviour: drift grows.
+        tracker.record_action(&action(DAY0 + 4_000, "edit"));
+        tracker.record_action(&action(DAY0 + 5_000, "edit"));
+        let added = tracker.record_trace(&trace());
+        assert!(added > 1.0);
+        let drift = tracker.counters("host").unwrap().identity_drift_today;
+        assert!((drift - added).abs() < 1e-4);
+
+        clock.set(DAY0 + DAY_MS + 10);
+        assert_eq!(tracker.counters("host").unwrap().identity_drift_today, 0.0);
+    }
+
+    #[test]
+    fn snapshot_round_trips_and_feeds_the_governor() {
+        let clock = SimulatedClock::at(DAY0 + 30_000);
+        let mut tracker = AutonomyStateTracker::new(clock.clone());
+        for i in 0..12 {
+            tracker.record_action(&action(DAY0 + i * 1_000, "suggest"));
+        }
+        tracker.record_trace(&trace());
+
+        let raw = serde_json::to_string(&tracker.snapshot()).unwrap();
+        let mut restored =
+            AutonomyStateTracker::restore(clock.clone(), serde_json::from_str(&raw).unwrap())
+                .unwrap();
+        let attrs = restored.trace_attributes("host").unwrap();
+        assert_eq!(attrs.actions_last_minute, 12);
+
+        let profile = crate::AutonomyProfile {
+            profile_id: "test".into(),
+            host_id: "host".into(),
+            max_eco_energy_nj_per_minute: 5.0,
+            max_autonomous_actions_per_minute: 10,
+            max_risk_score: 0.65,
+            min_lifeforce_scalar: 0.35,
+            max_identity_drift_per_day: 0.15,
+        };
+        let d = crate::AutonomyGovernor::decide(&profile, &attrs, true);
+        assert_eq!(d.reason, crate::DecisionReason::TooManyActionsRecently);
+    }
+
+    #[test]
+    fn future_dated_action_cannot_roll_the_day_or_empty_the_window() {
+        let clock = SimulatedClock::at(DAY0 + 30_000);
+        let mut tracker = AutonomyStateTracker::new(clock.clone());
+        for i in 0..12 {
+            tracker.record_action(&action(
+                DAY0 + 20_000 + i * 500,
+                if i % 2 == 0 { "suggest" } else { "edit" },
+            ));
+        }
+        tracker.record_trace(&trace());
+        tracker.record_action(&action(DAY0 + 29_000, "edit"));
+        tracker.record_action(&action(DAY0 + 29_500, "edit"));
+        let drift = tracker.record_trace(&trace());
+        assert!(drift > 0.0);
+
+        tracker.record_action(&action(DAY0 + DAY_MS + 120_000, "suggest"));
+        let c = tracker.counters("host").unwrap();
+        assert_eq!(c.actions_last_minute, 15);
+        assert!((c.identity_drift_today - drift).abs() < 1e-6);
+    }
+
+    #[test]
+    fn restore_rejects_other_snapshot_versions() {
+        let tracker = AutonomyStateTracker::new(SimulatedClock::at(DAY0));
+        let mut snap = tracker.snapshot();
+        snap.version = TRACKER_SNAPSHOT_VERSION + 1;
+        assert_eq!(
+            AutonomyStateTracker::restore(SimulatedClock::at(DAY0), snap).err(),
+            Some(SnapshotError::UnsupportedVersion(
+                TRACKER_SNAPSHOT_VERSION + 1
+            ))
+        );
+    }
+}
@@ -1,8 +1,19 @@
-use augdoctor_autonomy_core::{AutonomyGovernor, AutonomyProfile, AutonomyTraceAttributes};
+use augdoctor_autonomy_core::gates::decide;
+use augdoctor_autonomy_core::{
+    AutonomyGovernor, AutonomyProfile, AutonomyTraceAttributes, DecisionReason, GateKind,
+    GovernorConfig, GovernorInputs, GovernorLimits,
+};
 
-fn golden_vectors() -> Vec<(AutonomyProfile, AutonomyTraceAttributes, bool, f32, &'static str)> {
+fn golden_vectors() -> Vec<(
+    AutonomyProfile,
+    AutonomyTraceAttributes,
+    bool,
+    f32,
+    &'static str,
+)> {
     vec![
         // calm, low eco, low risk → high autonomy, ZeroShot
+        // (0.4·0.8 + 0.2·0.9 + 0.2·0.75 + 0.2·0.9)
         (
             AutonomyProfile {
                 profile_id: "test".into(),
@@ -30,13 +41,13 @@ fn golden_vectors() -> Vec<(AutonomyProfile, AutonomyTraceAttributes, bool, f32,
                 identity_drift_today: 0.05,
                 decision_autonomy_level: 0.0,
                 decision_shot_level_label: "ZeroShot".into(),
-                constraint: crate::trace::AutonomyConstraint {
+                constraint: augdoctor_autonomy_core::trace::AutonomyConstraint {
                     highest_risk_score: 0.2,
                     worst_lifeforce_scalar: 0.8,
                 },
             },
             true,
-            0.7,
+            0.83,
             "ZeroShot",
         ),
         // high risk → autonomy 0.0
@@ -67,7 +78,7 @@ fn golden_vectors() -> Vec<(AutonomyProfile, AutonomyTraceAttributes, bool, f32,
                 identity_drift_today: 0.05,
                 decision_autonomy_level: 0.0,
                 decision_shot_level_label: "ZeroShot".into(),
-                constraint: crate::trace::AutonomyConstraint {
+                constraint: augdoctor_autonomy_core::trace::AutonomyConstraint {
                     highest_risk_score: 0.9,
                     worst_lifeforce_scalar: 0.6,
                 },
@@ -87,3 +98,314 @@ fn rust_reference_matches_golden_vectors() {
         assert_eq!(d.shot_level_label, expected_shot);
     }
 }
+
+// Differential suite: the historical `AutonomyGovernor` and
+// `CompanionAutonomyGovernor` behaviours expressed as the two shared configs.
+
+fn limits() -> GovernorLimits {
+    GovernorLimits {
+        max_eco_energy_nj_per_minute: 5.0,
+        max_autonomous_actions_per_minute: 10,
+        max_risk_score: 0.65,
+        min_lifeforce_scalar: 0.35,
+        max_identity_drift_per_day: 0.15,
+    }
+}
+
+fn calm() -> GovernorInputs {
+    GovernorInputs {
+        consent_active: true,
+        lifeforce_scalar: 0.8,
+        eco_energy_nj: 1.0,
+        risk: 0.2,
+        actions_last_minute: 1,
+        identity_drift_today: 0.05,
+        stress: 0.2,
+        fatigue: 0.1,
+        reward: 1.0,
+        safety: 0.9,
+    }
+}
+
+fn both(
+    inputs: &GovernorInputs,
+) -> (
+    augdoctor_autonomy_core::GovernorOutcome,
+    augdoctor_autonomy_core::GovernorOutcome,
+) {
+    (
+        decide(&GovernorConfig::autonomy_core(), &limits(), inputs),
+        decide(&GovernorConfig::companion(), &limits(), inputs),
+    )
+}
+
+#[test]
+fn single_gate_trips_agree() {
+    let cases: Vec<(GovernorInputs, GateKind, f32)> = vec![
+        (
+            GovernorInputs {
+                consent_active: false,
+                ..calm()
+            },
+            GateKind::Consent,
+            0.0,
+        ),
+        (
+            GovernorInputs {
+                lifeforce_scalar: 0.2,
+                ..calm()
+            },
+            GateKind::Lifeforce,
+            0.0,
+        ),
+        (
+            GovernorInputs {
+                eco_energy_nj: 6.0,
+                ..calm()
+            },
+            GateKind::Eco,
+            0.2,
+        ),
+        (
+            GovernorInputs {
+                risk: 0.9,
+                ..calm()
+            },
+            GateKind::Risk,
+            0.0,
+        ),
+        (
+            GovernorInputs {
+                actions_last_minute: 10,
+                ..calm()
+            },
+            GateKind::ActionRate,
+            0.1,
+        ),
+        (
+            GovernorInputs {
+                identity_drift_today: 0.3,
+                ..calm()
+            },
+            GateKind::IdentityDrift,
+            0.0,
+        ),
+    ];
+    for (inputs, gate, level) in cases {
+        let (core, companion) = both(&inputs);
+        assert_eq!(core, companion, "{:?}", gate);
+        assert_eq!(core.gate, Some(gate));
+        assert_eq!(core.reason, gate.reason());
+        assert!((core.autonomy_level - level).abs() < 1e-6);
+        assert_eq!(core.shot_level_label, "ZeroShot");
+    }
+}
+
+#[test]
+fn in_budget_ramp_agrees_for_non_negative_extremes() {
+    // (r + 1) / 2 and max(r, 0) coincide at r = 1.
+    let (core, companion) = both(&calm());
+    assert_eq!(core, companion);
+    assert_eq!(core.reason, DecisionReason::WithinAllBudgets);
+
+    // Soft eco penalty and FewShot routing are shared.
+    let warm = GovernorInputs {
+        eco_energy_nj: 4.0,
+        ..calm()
+    };
+    let (core, companion) = both(&warm);
+    assert_eq!(core, companion);
+    let (core, _) = both(&calm());
+    assert!((both(&warm).0.autonomy_level - core.autonomy_level * 0.7).abs() < 1e-6);
+}
+
+#[test]
+fn documented_divergences() {
+    // Gate order: with eco and risk both over budget the core governor reports
+    // the eco downgrade, the companion the risk denial.
+    let (core, companion) = both(&GovernorInputs {
+        eco_energy_nj: 6.0,
+        risk: 0.9,
+        ..calm()
+    });
+    assert_eq!(
+        (core.reason, core.autonomy_level),
+        (DecisionReason::EcoBudgetExceeded, 0.2)
+    );
+    assert_eq!(
+        (companion.reason, companion.autonomy_level),
+        (DecisionReason::RiskTooHigh, 0.0)
+    );
+
+    // Reward mapping: neutral reward still lifts the core ramp by 0.1.
+    let (core, companion) = both(&GovernorInputs {
+        reward: 0.0,
+        ..calm()
+    });
+    assert!((core.autonomy_level - companion.autonomy_level - 0.1).abs() < 1e-6);
+
+    // Hybrid labelling only exists in the companion config, and only for
+    // downgrade levels above 0.3, which neither historical table uses.
+    let mut companion_cfg = GovernorConfig::companion();
+    companion_cfg.levels.eco = 0.4;
+    let mut core_cfg = GovernorConfig::autonomy_core();
+    core_cfg.levels.eco = 0.4;
+    let eco = GovernorInputs {
+        eco_energy_nj: 6.0,
+        ..calm()
+    };
+    assert_eq!(
+        decide(&companion_cfg, &limits(), &eco).shot_level_label,
+        "Hybrid"
+    );
+    assert_eq!(
+        decide(&core_cfg, &limits(), &eco).shot_level_label,
+        "ZeroShot"
+    );
+}
+
+// Frozen copies of the two governors as they stood before the shared engine,
+// restated over `GovernorInputs`. Do not update these when the engine changes;
+// they pin the historical behaviour the configs above claim to reproduce.
+mod baseline {
+    use augdoctor_autonomy_core::{DecisionReason, GovernorInputs, GovernorLimits};
+
+    pub fn autonomy_core(p: &GovernorLimits, t: &GovernorInputs) -> (f32, &'static str) {
+        if !t.consent_active {
+            return (0.0, "ZeroShot");
+        }
+        if t.lifeforce_scalar < p.min_lifeforce_scalar {
+            return (0.0, "ZeroShot");
+        }
+        if t.eco_energy_nj > p.max_eco_energy_nj_per_minute {
+            return (0.2, "ZeroShot");
+        }
+        if t.risk > p.max_risk_score {
+            return (0.0, "ZeroShot");
+        }
+        if t.actions_last_minute >= p.max_autonomous_actions_per_minute {
+            return (0.1, "ZeroShot");
+        }
+        if t.identity_drift_today > p.max_identity_drift_per_day {
+            return (0.0, "ZeroShot");
+        }
+
+        let mut autonomy = {
+            let s = (1.0 - t.stress).max(0.0);
+            let f = (1.0 - t.fatigue).max(0.0);
+            let r = (t.reward + 1.0) / 2.0;
+            let sa = t.safety.max(0.0);
+            (0.4 * s + 0.2 * f + 0.2 * r + 0.2 * sa).clamp(0.0, 1.0)
+        };
+        if t.eco_energy_nj > 0.7 * p.max_eco_energy_nj_per_minute {
+            autonomy *= 0.7;
+        }
+        let shot = if t.risk > 0.7 { "FewShot" } else { "ZeroShot" };
+        (autonomy, shot)
+    }
+
+    /// `consent_active` stands for `host_consent_active ||
+    /// allow_self_tuning_with_consent`.
+    pub fn companion(p: &GovernorLimits, t: &GovernorInputs) -> (f32, String, DecisionReason) {
+        let deny = |reason| (0.0, "ZeroShot".to_string(), reason);
+        let downgrade = |level: f32, reason| {
+            let label = if level > 0.3 { "Hybrid" } else { "ZeroShot" };
+            (level.clamp(0.0, 1.0), label.to_string(), reason)
+        };
+        if !t.consent_active {
+            return deny(DecisionReason::HostConsentMissing);
+        }
+        if t.lifeforce_scalar < p.min_lifeforce_scalar {
+            return deny(DecisionReason::LifeforceTooLow);
+        }
+        if t.risk > p.max_risk_score {
+            return deny(DecisionReason::RiskTooHigh);
+        }
+        if t.eco_energy_nj > p.max_eco_energy_nj_per_minute {
+            return downgrade(0.2, DecisionReason::EcoBudgetExceeded);
+        }
+        if t.actions_last_minute >= p.max_autonomous_actions_per_minute {
+            return downgrade(0.1, DecisionReason::TooManyActionsRecently);
+        }
+        if t.identity_drift_today > p.max_identity_drift_per_day {
+            return downgrade(0.0, DecisionReason::IdentityDriftLimit);
+        }
+
+        let health = (1.0 - t.stress).max(0.0) * 0.4
+            + (1.0 - t.fatigue).max(0.0) * 0.2
+            + t.reward.max(0.0) * 0.2
+            + t.safety.max(0.0) * 0.2;
+        let mut autonomy = health.clamp(0.0, 1.0);
+        if t.eco_energy_nj > 0.7 * p.max_eco_energy_nj_per_minute {
+            autonomy *= 0.7;
+        }
+        let shot = if t.risk > 0.7 { "FewShot" } else { "ZeroShot" };
+        (autonomy, shot.to_string(), DecisionReason::WithinAllBudgets)
+    }
+}
+
+/// Every combination of in-budget, boundary and over-budget values for each
+/// signal, under the default limits and under a permissive risk budget that
+/// lets FewShot routing through.
+fn grid() -> Vec<(GovernorLimits, GovernorInputs)> {
+    let permissive = GovernorLimits {
+        max_risk_score: 0.9,
+        ..limits()
+    };
+    let mut out = Vec::new();
+    for lim in [limits(), permissive] {
+        for consent_active in [true, false] {
+            for lifeforce_scalar in [0.2, 0.35, 0.8] {
+                for eco_energy_nj in [1.0, 3.6, 5.0, 6.0] {
+                    for risk in [0.2, 0.65, 0.75, 0.95] {
+                        for actions_last_minute in [1, 10] {
+                            for identity_drift_today in [0.05, 0.3] {
+                                for (stress, fatigue) in [(0.2, 0.1), (1.4, 0.9)] {
+                                    for reward in [-1.0, 0.0, 0.5] {
+                                        out.push((
+                                            lim.clone(),
+                                            GovernorInputs {
+                                                consent_active,
+                                                lifeforce_scalar,
+                                                eco_energy_nj,
+                                                risk,
+                                                actions_last_minute,
+                                                identity_drift_today,
+                                                stress,
+                                                fatigue,
+                                                reward,
+                                                safety: 0.9,
+                                            },
+                                        ));
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+#[test]
+fn autonomy_core_config_matches_frozen_baseline() {
+    for (lim, inputs) in grid() {
+        let (level, shot) = baseline::autonomy_core(&lim, &inputs);
+        let got = decide(&GovernorConfig::autonomy_core(), &lim, &inputs);
+        assert!((got.autonomy_level - level).abs() < 1e-6, "{:?}", inputs);
+        assert_eq!(got.shot_level_label, shot, "{:?}", inputs);
+    }
+}
+
+#[test]
+fn companion_config_matches_frozen_baseline() {
+    for (lim, inputs) in grid() {
+        let (level, shot, reason) = baseline::companion(&lim, &inputs);
+        let got = decide(&GovernorConfig::companion(), &lim, &inputs);
+        assert!((got.autonomy_level - level).abs() < 1e-6, "{:?}", inputs);
+        assert_eq!(got.shot_level_label, shot, "{:?}", inputs);
+        assert_eq!(got.reason, reason, "{:?}", inputs);
+    }
+}
@@ -4,6 +4,8 @@ use crate::types::{
     AssistantAutonomyReason,
 };
 use crate::recorder::BiomarkerAggregation;
+use augdoctor_autonomy_core::AutonomyCounters;
+use augdoctor_autonomy_core::gates::{self, DecisionReason, GovernorConfig, GovernorInputs, GovernorLimits};
 use serde::{Deserialize, Serialize};
 
 /// State needed to track identity drift and action rates.
@@ -15,94 +17,208 @@ pub struct CompanionAutonomyState {
     pub identity_drift_today: f32, // e.g., KL accumulator
 }
 
+/// Counters maintained by `augdoctor_autonomy_core::AutonomyStateTracker`.
+impl From<AutonomyCounters> for CompanionAutonomyState {
+    fn from(c: AutonomyCounters) -> Self {
+        Self {
+            host_id: c.host_id,
+            profile_id: c.profile_id,
+            actions_last_minute: c.actions_last_minute,
+            identity_drift_today: c.identity_drift_today,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CompanionAutonomyGovernor;
 
+impl From<DecisionReason> for AssistantAutonomyReason {
+    fn from(reason: DecisionReason) -> Self {
+        match reason {
+            DecisionReason::HostConsentMissing => AssistantAutonomyReason::HostConsentMissing,
+            DecisionReason::LifeforceTooLow => AssistantAutonomyReason::LifeforceTooLow,
+            DecisionReason::EcoBudgetExceeded => AssistantAutonomyReason::EcoBudgetExceeded,
+            DecisionReason::RiskTooHigh => AssistantAutonomyReason::RiskTooHigh,
+            DecisionReason::TooManyActionsRecently => {
+                AssistantAutonomyReason::TooManyActionsRecently
+            }
+            DecisionReason::IdentityDriftLimit => AssistantAutonomyReason::IdentityDriftLimit,
+            DecisionReason::WithinAllBudgets => AssistantAutonomyReason::WithinAllBudgets,
+        }
+    }
+}
+
 impl CompanionAutonomyGovernor {
+    /// Shared gate engine with the companion ordering (risk before eco) and
+    /// ramp. A profile that allows self-tuning waives the consent gate.
     pub fn decide(
         profile: &AssistantAutonomyProfile,
         state: &CompanionAutonomyState,
         agg: &BiomarkerAggregation,
         host_consent_active: bool,
     ) -> AssistantAutonomyDecision {
-        // 1. Hard gates.
-        if !host_consent_active && !profile.allow_self_tuning_with_consent {
-            return Self::deny(profile, AssistantAutonomyReason::HostConsentMissing);
-        }
-        if agg.worst_lifeforce_scalar < profile.min_lifeforce_scalar {
-            return Self::deny(profile, AssistantAutonomyReason::LifeforceTooLow);
-        }
-        if agg.highest_risk_score > profile.max_risk_score {
-            return Self::deny(profile, AssistantAutonomyReason::RiskTooHigh);
-        }
-        if agg.avg_eco_energy_nj > profile.max_eco_energy_nj_per_minute {
-            return Self::downgrade(profile, 0.2, AssistantAutonomyReason::EcoBudgetExceeded);
+        let outcome = gates::decide(
+            &GovernorConfig::companion(),
+            &GovernorLimits {
+                max_eco_energy_nj_per_minute: profile.max_eco_energy_nj_per_minute,
+                max_autonomous_actions_per_minute: profile.max_autonomous_actions_per_minute,
+                max_risk_score: profile.max_risk_score,
+                min_lifeforce_scalar: profile.min_lifeforce_scalar,
+                max_identity_drift_per_day: profile.max_identity_drift_per_day,
+            },
+            &GovernorInputs {
+                consent_active: host_consent_active || profile.allow_self_tuning_with_consent,
+                lifeforce_scalar: agg.worst_lifeforce_scalar,
+                eco_energy_nj: agg.avg_eco_energy_nj,
+                risk: agg.highest_risk_score,
+                actions_last_minute: state.actions_last_minute,
+                identity_drift_today: state.identity_drift_today,
+                stress: agg.avg_stress,
+                fatigue: agg.avg_fatigue,
+                reward: agg.avg_reward,
+                safety: agg.avg_safety_margin,
+            },
+        );
+
+        AssistantAutonomyDecision {
+            profile_id: profile.profile_id.clone(),
+            host_id: profile.host_id.clone(),
+            autonomy_level: outcome.autonomy_level,
+            shot_level_label: outcome.shot_level_label,
+            may_act_without_explicit_confirm: outcome.autonomy_level > 0.5,
+            primary_reason: outcome.reason.into(),
         }
-        if state.actions_last_minute >= profile.max_autonomous_actions_per_minute {
-            return Self::downgrade(profile, 0.1, AssistantAutonomyReason::TooManyActionsRecently);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> AssistantAutonomyProfile {
+        AssistantAutonomyProfile {
+            profile_id: "companion-default".into(),
+            host_id: "did:host".into(),
+            max_eco_energy_nj_per_minute: 5.0,
+            max_autonomous_actions_per_minute: 10,
+            max_risk_score: 0.65,
+            min_lifeforce_scalar: 0.35,
+            max_identity_drift_per_day: 0.15,
+            allow_self_tuning_with_consent: false,
         }
-        if state.identity_drift_today > profile.max_identity_drift_per_day {
-            return Self::downgrade(profile, 0.0, AssistantAutonomyReason::IdentityDriftLimit);
+    }
+
+    fn state() -> CompanionAutonomyState {
+        CompanionAutonomyState {
+            host_id: "did:host".into(),
+            profile_id: "companion-default".into(),
+            actions_last_minute: 1,
+            identity_drift_today: 0.05,
         }
+    }
 
-        // 2. Soft shaping: more autonomy when biomarker envelope is healthy.
-        let stress = agg.avg_stress;
-        let fatigue = agg.avg_fatigue;
-        let reward = agg.avg_reward;
-        let safety = agg.avg_safety_margin;
+    fn calm() -> BiomarkerAggregation {
+        BiomarkerAggregation {
+            session_id: "s1".into(),
+            avg_stress: 0.2,
+            avg_fatigue: 0.1,
+            avg_cognitive_load: 0.3,
+            avg_eco_energy_nj: 1.0,
+            avg_reward: 0.5,
+            avg_safety_margin: 0.9,
+            worst_lifeforce_scalar: 0.8,
+            highest_risk_score: 0.2,
+        }
+    }
 
-        let health = (1.0 - stress).max(0.0) * 0.4
-            + (1.0 - fatigue).max(0.0) * 0.2
-            + reward.max(0.0) * 0.2
-            + safety.max(0.0) * 0.2;
+    #[test]
+    fn healthy_window_ramps_and_may_act() {
+        let d = CompanionAutonomyGovernor::decide(&profile(), &state(), &calm(), true);
+        assert!(matches!(
+            d.primary_reason,
+            AssistantAutonomyReason::WithinAllBudgets
+        ));
+        // 0.4·0.8 + 0.2·0.9 + 0.2·0.5 + 0.2·0.9
+        assert!((d.autonomy_level - 0.78).abs() < 1e-6);
+        assert_eq!(d.shot_level_label, "ZeroShot");
+        assert!(d.may_act_without_explicit_confirm);
+        assert_eq!(d.profile_id, "companion-default");
+        assert_eq!(d.host_id, "did:host");
+    }
 
-        let mut autonomy_level = health.clamp(0.0, 1.0);
+    #[test]
+    fn self_tuning_profile_waives_consent() {
+        let d = CompanionAutonomyGovernor::decide(&profile(), &state(), &calm(), false);
+        assert!(matches!(
+            d.primary_reason,
+            AssistantAutonomyReason::HostConsentMissing
+        ));
+        assert_eq!(d.autonomy_level, 0.0);
+        assert!(!d.may_act_without_explicit_confirm);
 
-        // 3. Apply small penalty if eco cost is high but still in-band.
-        if agg.avg_eco_energy_nj > 0.7 * profile.max_eco_energy_nj_per_minute {
-            autonomy_level *= 0.7;
-        }
+        let tuned = AssistantAutonomyProfile {
+            allow_self_tuning_with_consent: true,
+            ..profile()
+        };
+        let d = CompanionAutonomyGovernor::decide(&tuned, &state(), &calm(), false);
+        assert!(matches!(
+            d.primary_reason,
+            AssistantAutonomyReason::WithinAllBudgets
+        ));
+    }
 
-        // 4. Shot-level routing suggestion.
-        let shot_label = if agg.highest_risk_score > 0.7 {
-            "FewShot".to_string()
-        } else {
-            "ZeroShot".to_string()
+    #[test]
+    fn risk_is_gated_before_eco() {
+        let agg = BiomarkerAggregation {
+            avg_eco_energy_nj: 6.0,
+            highest_risk_score: 0.9,
+            ..calm()
         };
+        let d = CompanionAutonomyGovernor::decide(&profile(), &state(), &agg, true);
+        assert!(matches!(
+            d.primary_reason,
+            AssistantAutonomyReason::RiskTooHigh
+        ));
+        assert_eq!(d.autonomy_level, 0.0);
 
-        AssistantAutonomyDecision {
-            profile_id: profile.profile_id.clone(),
-            host_id: profile.host_id.clone(),
-            autonomy_level,
-            shot_level_label: shot_label,
-            may_act_without_explicit_confirm: autonomy_level > 0.5,
-            primary_reason: AssistantAutonomyReason::WithinAllBudgets,
-        }
+        let agg = BiomarkerAggregation {
+            avg_eco_energy_nj: 6.0,
+            ..calm()
+        };
+        let d = CompanionAutonomyGovernor::decide(&profile(), &state(), &agg, true);
+        assert!(matches!(
+            d.primary_reason,
+            AssistantAutonomyReason::EcoBudgetExceeded
+        ));
+        assert!((d.autonomy_level - 0.2).abs() < 1e-6);
+        assert_eq!(d.shot_level_label, "ZeroShot");
     }
 
-    fn deny(profile: &AssistantAutonomyProfile, reason: AssistantAutonomyReason) -> AssistantAutonomyDecision {
-        AssistantAutonomyDecision {
-            profile_id: profile.profile_id.clone(),
-            host_id: profile.host_id.clone(),
-            autonomy_level: 0.0,
-            shot_level_label: "ZeroShot".to_string(),
-            may_act_without_explicit_confirm: false,
-            primary_reason: reason,
-        }
-    }
+    #[test]
+    fn tracker_counters_drive_rate_and_drift_gates() {
+        let busy = CompanionAutonomyState::from(AutonomyCounters {
+            host_id: "did:host".into(),
+            profile_id: "companion-default".into(),
+            actions_last_minute: 10,
+            identity_drift_today: 0.05,
+        });
+        let d = CompanionAutonomyGovernor::decide(&profile(), &busy, &calm(), true);
+        assert!(matches!(
+            d.primary_reason,
+            AssistantAutonomyReason::TooManyActionsRecently
+        ));
+        assert!((d.autonomy_level - 0.1).abs() < 1e-6);
+        assert!(!d.may_act_without_explicit_confirm);
 
-    fn downgrade(
-        profile: &AssistantAutonomyProfile,
-        level: f32,
-        reason: AssistantAutonomyReason,
-    ) -> AssistantAutonomyDecision {
-        AssistantAutonomyDecision {
-            profile_id: profile.profile_id.clone(),
-            host_id: profile.host_id.clone(),
-            autonomy_level: level.clamp(0.0, 1.0),
-            shot_level_label: if level > 0.3 { "Hybrid".to_string() } else { "ZeroShot".to_string() },
-            may_act_without_explicit_confirm: level > 0.5,
-            primary_reason: reason,
-        }
+        let drifted = CompanionAutonomyState {
+            identity_drift_today: 0.3,
+            ..state()
+        };
+        let d = CompanionAutonomyGovernor::decide(&profile(), &drifted, &calm(), true);
+        assert!(matches!(
+            d.primary_reason,
+            AssistantAutonomyReason::IdentityDriftLimit
+        ));
+        assert_eq!(d.autonomy_level, 0.0);
     }
 }
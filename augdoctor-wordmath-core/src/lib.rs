@@ -1,5 +1,18 @@
 use serde::{Deserialize, Serialize};
 
+pub mod metrics;
+pub mod profiles;
+pub mod rewrite;
+pub mod rights_header;
+
+pub use metrics::{
+    claim_inflates_band, compare_metrics, compute_metrics, MetricDisagreement, MetricLexicon,
+    TaskTriad,
+};
+pub use profiles::{ThresholdConfigError, ThresholdProfiles};
+pub use rewrite::{RewriteEngine, RewriteLexicon, RewriteOutcome, RewriteTransform};
+pub use rights_header::{NeurorightsFlags, PromptRightsHeader, SocialImpactVector};
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PromptMetrics {
     pub repetition_y: f32,   // 0.0–1.0, higher = more repetition
@@ -35,7 +48,7 @@ impl PromptMetrics {
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct PromptBandThresholds {
     /// Minimum f for Green (knowledge‑admissible).
     pub green_min_f: f32,
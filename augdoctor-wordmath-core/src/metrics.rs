@@ -0,0 +1,364 @@
+//! Local, deterministic computation of `PromptMetrics`.
+//!
+//! Everything here is lexical: no model, no network, same text in gives the
+//! same five numbers out. The service classifies on these values and only uses
+//! client-supplied metrics to detect disagreement.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{classify_prompt, PromptBand, PromptBandThresholds, PromptMetrics};
+
+/// The three topic anchors a prompt is expected to stay on, e.g.
+/// `["cybernetics", "biophysical-blockchain", "quantum-learning"]`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TaskTriad {
+    pub anchors: [String; 3],
+}
+
+impl Default for TaskTriad {
+    fn default() -> Self {
+        Self {
+            anchors: [
+                "cybernetics".to_string(),
+                "biophysical-blockchain".to_string(),
+                "quantum-learning".to_string(),
+            ],
+        }
+    }
+}
+
+impl TaskTriad {
+    /// Anchor words as matching stems: lower-cased, split on non-alphanumerics,
+    /// cut to at most `stem_len` characters so "cybernetics" also matches
+    /// "cybernetic".
+    fn stems(&self, stem_len: usize) -> Vec<String> {
+        let mut stems: Vec<String> = self
+            .anchors
+            .iter()
+            .flat_map(|a| words(a))
+            .filter(|w| w.chars().count() >= 3)
+            .map(|w| w.chars().take(stem_len).collect())
+            .collect();
+        stems.sort();
+        stems.dedup();
+        stems
+    }
+}
+
+/// Lexicons and weights used by `compute_metrics`. All terms are matched
+/// against lower-cased words; multi-word terms are matched as phrases.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MetricLexicon {
+    /// n for n-gram repetition.
+    pub repetition_ngram: usize,
+    pub stopwords: Vec<String>,
+    pub stem_len: usize,
+    /// Share of content words on the triad at which drift reaches 0.
+    pub on_topic_target: f32,
+    pub toxic_terms: Vec<String>,
+    /// Toxicity added per toxic term occurrence.
+    pub toxicity_per_hit: f32,
+    pub kind_terms: Vec<String>,
+    pub harsh_terms: Vec<String>,
+    /// Kindness of a prompt with no kind or harsh markers.
+    pub kindness_baseline: f32,
+    pub kindness_per_kind: f32,
+    pub kindness_per_harsh: f32,
+    /// Evidentiality of a prompt with no hooks.
+    pub evidentiality_baseline: f32,
+    /// Number of hooks at which evidentiality reaches 1.0.
+    pub hooks_for_full: usize,
+}
+
+fn terms(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+impl Default for MetricLexicon {
+    fn default() -> Self {
+        Self {
+            repetition_ngram: 3,
+            stopwords: terms(&[
+                "a", "an", "the", "and", "or", "but", "of", "to", "in", "on", "for", "with", "by",
+                "at", "from", "as", "is", "are", "was", "be", "it", "this", "that", "i", "me",
+                "my", "we", "our", "you", "your", "please", "can", "could", "would",
+            ]),
+            stem_len: 6,
+            on_topic_target: 0.25,
+            toxic_terms: terms(&[
+                "idiot",
+                "stupid",
+                "moron",
+                "worthless",
+                "hate",
+                "destroy",
+                "kill",
+                "shut up",
+                "pathetic",
+                "dumb",
+            ]),
+            toxicity_per_hit: 0.15,
+            kind_terms: terms(&[
+                "please",
+                "thank",
+                "thanks",
+                "kindly",
+                "appreciate",
+                "could you",
+                "would you",
+                "help",
+            ]),
+            harsh_terms: terms(&["must", "demand", "immediately", "!"]),
+            kindness_baseline: 0.95,
+            kindness_per_kind: 0.05,
+            kindness_per_harsh: 0.05,
+            evidentiality_baseline: 0.75,
+            hooks_for_full: 1,
+        }
+    }
+}
+
+/// Lower-cased alphanumeric words of `text`.
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn count_term(text_lower: &str, word_list: &[String], term: &str) -> usize {
+    if term.chars().all(|c| c.is_alphanumeric()) {
+        word_list.iter().filter(|w| w.as_str() == term).count()
+    } else if term.contains(' ') {
+        let joined = format!(" {} ", word_list.join(" "));
+        joined.matches(&format!(" {} ", term)).count()
+    } else {
+        text_lower.matches(term).count()
+    }
+}
+
+fn count_terms(text_lower: &str, word_list: &[String], lexicon: &[String]) -> usize {
+    lexicon
+        .iter()
+        .map(|t| count_term(text_lower, word_list, t))
+        .sum()
+}
+
+/// Share of repeated n-grams: 1 - distinct/total. Texts shorter than one
+/// n-gram score 0.
+pub fn repetition(word_list: &[String], n: usize) -> f32 {
+    let n = n.max(1);
+    if word_list.len() < n {
+        return 0.0;
+    }
+    let grams: Vec<&[String]> = word_list.windows(n).collect();
+    let mut distinct = grams.clone();
+    distinct.sort();
+    distinct.dedup();
+    1.0 - distinct.len() as f32 / grams.len() as f32
+}
+
+/// Evidential hooks: DIDs (`did:<method>:<id>`) and hashes (optionally
+/// `0x`/`sha256:` prefixed hex of at least 16 digits).
+pub fn evidential_hooks(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|t| t.trim_matches(|c: char| !c.is_alphanumeric() && c != ':'))
+        .filter(|t| is_did(t) || is_hash(t))
+        .map(str::to_string)
+        .collect()
+}
+
+fn is_did(token: &str) -> bool {
+    let mut parts = token.splitn(3, ':');
+    parts.next() == Some("did")
+        && parts
+            .next()
+            .is_some_and(|m| !m.is_empty() && m.chars().all(|c| c.is_ascii_alphanumeric()))
+        && parts.next().is_some_and(|id| !id.is_empty())
+}
+
+/// A `0x`/`sha256:`-prefixed hex digest of at least 16 digits, or a bare
+/// SHA-256 (exactly 64 hex digits). Bare shorter runs are usually numbers.
+fn is_hash(token: &str) -> bool {
+    let lower = token.to_ascii_lowercase();
+    let all_hex = |s: &str| s.chars().all(|c| c.is_ascii_hexdigit());
+    match lower.strip_prefix("0x").or_else(|| lower.strip_prefix("sha256:")) {
+        Some(hex) => hex.len() >= 16 && all_hex(hex),
+        None => lower.len() == 64 && all_hex(&lower),
+    }
+}
+
+/// Compute all five metrics for `text` against `triad`.
+pub fn compute_metrics(text: &str, triad: &TaskTriad, lex: &MetricLexicon) -> PromptMetrics {
+    let lower = text.to_lowercase();
+    let word_list = words(text);
+
+    let repetition_y = repetition(&word_list, lex.repetition_ngram);
+
+    let content: Vec<&String> = word_list
+        .iter()
+        .filter(|w| !lex.stopwords.contains(w))
+        .collect();
+    let stems = triad.stems(lex.stem_len);
+    let on_topic = content
+        .iter()
+        .filter(|w| stems.iter().any(|s| w.starts_with(s.as_str())))
+        .count();
+    let drift_z = if content.is_empty() {
+        1.0
+    } else {
+        let share = on_topic as f32 / content.len() as f32;
+        (1.0 - share / lex.on_topic_target.max(f32::EPSILON)).clamp(0.0, 1.0)
+    };
+
+    let toxic = count_terms(&lower, &word_list, &lex.toxic_terms);
+    let toxicity_t = (toxic as f32 * lex.toxicity_per_hit).clamp(0.0, 1.0);
+
+    let kind = count_terms(&lower, &word_list, &lex.kind_terms);
+    let harsh = count_terms(&lower, &word_list, &lex.harsh_terms);
+    let kindness_k = (lex.kindness_baseline + kind as f32 * lex.kindness_per_kind
+        - harsh as f32 * lex.kindness_per_harsh)
+        .clamp(0.0, 1.0);
+
+    let hooks = evidential_hooks(text).len().min(lex.hooks_for_full.max(1));
+    let evidentiality_e = lex.evidentiality_baseline
+        + (1.0 - lex.evidentiality_baseline) * hooks as f32 / lex.hooks_for_full.max(1) as f32;
+
+    PromptMetrics {
+        repetition_y,
+        drift_z,
+        toxicity_t,
+        kindness_k,
+        evidentiality_e: evidentiality_e.clamp(0.0, 1.0),
+    }
+}
+
+/// One metric where the client's claim is further than the tolerance from
+/// what was computed locally.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct MetricDisagreement {
+    pub metric: String,
+    pub claimed: f32,
+    pub computed: f32,
+}
+
+/// Metrics whose claimed value differs from the computed one by more than
+/// `tolerance`.
+pub fn compare_metrics(
+    claimed: &PromptMetrics,
+    computed: &PromptMetrics,
+    tolerance: f32,
+) -> Vec<MetricDisagreement> {
+    [
+        ("repetition_y", claimed.repetition_y, computed.repetition_y),
+        ("drift_z", claimed.drift_z, computed.drift_z),
+        ("toxicity_t", claimed.toxicity_t, computed.toxicity_t),
+        ("kindness_k", claimed.kindness_k, computed.kindness_k),
+        (
+            "evidentiality_e",
+            claimed.evidentiality_e,
+            computed.evidentiality_e,
+        ),
+    ]
+    .into_iter()
+    .filter(|(_, c, l)| (c - l).abs() > tolerance)
+    .map(|(metric, claimed, computed)| MetricDisagreement {
+        metric: metric.to_string(),
+        claimed,
+        computed,
+    })
+    .collect()
+}
+
+fn band_rank(band: &PromptBand) -> u8 {
+    match band {
+        PromptBand::RedBlocked => 0,
+        PromptBand::AmberRewrite => 1,
+        PromptBand::GreenAdmit => 2,
+    }
+}
+
+/// True when the claimed metrics would land in a better band than the
+/// computed ones, i.e. the client is self-certifying upward.
+pub fn claim_inflates_band(
+    claimed: &PromptMetrics,
+    computed: &PromptMetrics,
+    th: &PromptBandThresholds,
+) -> bool {
+    let claimed_band = classify_prompt(claimed.clone(), th).band;
+    let computed_band = classify_prompt(computed.clone(), th).band;
+    band_rank(&claimed_band) > band_rank(&computed_band)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(text: &str) -> PromptMetrics {
+        compute_metrics(text, &TaskTriad::default(), &MetricLexicon::default())
+    }
+
+    #[test]
+    fn on_topic_prompt_with_hook_is_green_and_stable() {
+        let text = "Please explain how quantum learning schedules cybernetic implants \
+                    on a biophysical blockchain for did:aln:citizen-7";
+        let m = metrics(text);
+        assert_eq!(m.repetition_y, 0.0);
+        assert_eq!(m.drift_z, 0.0);
+        assert_eq!(m.toxicity_t, 0.0);
+        assert_eq!(m.evidentiality_e, 1.0);
+        let th = PromptBandThresholds::default();
+        assert_eq!(classify_prompt(m.clone(), &th).band, PromptBand::GreenAdmit);
+        assert_eq!(
+            serde_json::to_string(&m).unwrap(),
+            serde_json::to_string(&metrics(text)).unwrap()
+        );
+    }
+
+    #[test]
+    fn repetitive_hostile_off_topic_prompt_scores_badly() {
+        let m = metrics("you stupid idiot write a poem write a poem write a poem now!");
+        assert!(m.repetition_y > 0.3);
+        assert_eq!(m.drift_z, 1.0);
+        assert!(m.toxicity_t >= 0.3);
+        assert!(m.kindness_k < 0.95);
+        assert_eq!(m.evidentiality_e, 0.75);
+    }
+
+    #[test]
+    fn inflated_claims_are_detected() {
+        let computed = metrics("write a poem about cats");
+        let claimed = PromptMetrics {
+            repetition_y: 0.0,
+            drift_z: 0.0,
+            toxicity_t: 0.0,
+            kindness_k: 1.0,
+            evidentiality_e: 1.0,
+        };
+        let diffs = compare_metrics(&claimed, &computed, 0.1);
+        assert_eq!(
+            diffs.iter().map(|d| d.metric.as_str()).collect::<Vec<_>>(),
+            vec!["drift_z", "evidentiality_e"]
+        );
+        assert!(claim_inflates_band(
+            &claimed,
+            &computed,
+            &PromptBandThresholds::default()
+        ));
+        assert!(
+            evidential_hooks("anchor 0x9f3ab2c4d5e6f708 and sha256:deadbeefdeadbeef").len() == 2
+        );
+    }
+
+    #[test]
+    fn bare_numbers_are_not_hashes() {
+        assert!(is_hash(&"ab".repeat(32)));
+        assert!(is_hash("0x0000000000000000"));
+        assert!(!is_hash("0000000000000000"));
+        assert!(!is_hash("12345678901234567890"));
+        assert!(!is_hash(&"ab".repeat(33)));
+        assert!(!is_hash("0x12345"));
+        assert!(!is_hash("sha256:"));
+        assert!(evidential_hooks("order 0000000000000000 costs 12345678901234567890").is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+//! Per-`phoenix_profile` band thresholds and the rewrite lexicon, loaded from a
+//! versioned config file.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rewrite::RewriteLexicon;
+use crate::PromptBandThresholds;
+
+pub const THRESHOLD_CONFIG_SCHEMA: u32 = 1;
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ThresholdProfiles {
+    pub schema: u32,
+    /// Recorded with every gating decision so a past band can be re-derived.
+    pub version: String,
+    /// Used for any profile not listed in `profiles`.
+    pub default: PromptBandThresholds,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, PromptBandThresholds>,
+    /// Softenings and hook text for AmberRewrite suggestions; the built-in
+    /// lexicon when omitted.
+    #[serde(default)]
+    pub rewrite_lexicon: RewriteLexicon,
+}
+
+impl Default for ThresholdProfiles {
+    fn default() -> Self {
+        Self {
+            schema: THRESHOLD_CONFIG_SCHEMA,
+            version: "builtin".to_string(),
+            default: PromptBandThresholds::default(),
+            profiles: BTreeMap::new(),
+            rewrite_lexicon: RewriteLexicon::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ThresholdConfigError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    UnsupportedSchema(u32),
+    Invalid { profile: String, reason: String },
+    InvalidLexicon(String),
+}
+
+impl fmt::Display for ThresholdConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThresholdConfigError::Io(e) => write!(f, "threshold config io error: {}", e),
+            ThresholdConfigError::Parse(e) => write!(f, "threshold config parse error: {}", e),
+            ThresholdConfigError::UnsupportedSchema(v) => {
+                write!(f, "unsupported threshold config schema {}", v)
+            }
+            ThresholdConfigError::Invalid { profile, reason } => {
+                write!(
+                    f,
+                    "thresholds for profile {:?} invalid: {}",
+                    profile, reason
+                )
+            }
+            ThresholdConfigError::InvalidLexicon(reason) => {
+                write!(f, "rewrite lexicon invalid: {}", reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ThresholdConfigError {}
+
+impl PromptBandThresholds {
+    /// Amber must never be stricter than green: it needs at most green's
+    /// minimum f and tolerates at least green's toxicity. All values lie in
+    /// [0, 1].
+    pub fn validate(&self) -> Result<(), String> {
+        let values = [
+            ("green_min_f", self.green_min_f),
+            ("amber_min_f", self.amber_min_f),
+            ("green_max_toxicity", self.green_max_toxicity),
+            ("amber_max_toxicity", self.amber_max_toxicity),
+        ];
+        for (name, v) in values {
+            if !(0.0..=1.0).contains(&v) {
+                return Err(format!("{} = {} outside [0, 1]", name, v));
+            }
+        }
+        if self.amber_min_f > self.green_min_f {
+            return Err(format!(
+                "amber_min_f {} exceeds green_min_f {}",
+                self.amber_min_f, self.green_min_f
+            ));
+        }
+        if self.green_max_toxicity > self.amber_max_toxicity {
+            return Err(format!(
+                "green_max_toxicity {} exceeds amber_max_toxicity {}",
+                self.green_max_toxicity, self.amber_max_toxicity
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl ThresholdProfiles {
+    pub fn from_json(raw: &str) -> Result<Self, ThresholdConfigError> {
+        let cfg: ThresholdProfiles =
+            serde_json::from_str(raw).map_err(ThresholdConfigError::Parse)?;
+        cfg.validate()?;
+        Ok(cfg)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ThresholdConfigError> {
+        let raw = std::fs::read_to_string(path).map_err(ThresholdConfigError::Io)?;
+        Self::from_json(&raw)
+    }
+
+    pub fn validate(&self) -> Result<(), ThresholdConfigError> {
+        if self.schema != THRESHOLD_CONFIG_SCHEMA {
+            return Err(ThresholdConfigError::UnsupportedSchema(self.schema));
+        }
+        let all = std::iter::once(("default", &self.default))
+            .chain(self.profiles.iter().map(|(k, v)| (k.as_str(), v)));
+        for (profile, th) in all {
+            th.validate()
+                .map_err(|reason| ThresholdConfigError::Invalid {
+                    profile: profile.to_string(),
+                    reason,
+                })?;
+        }
+        self.rewrite_lexicon
+            .validate()
+            .map_err(ThresholdConfigError::InvalidLexicon)
+    }
+
+    pub fn for_profile(&self, phoenix_profile: &str) -> &PromptBandThresholds {
+        self.profiles.get(phoenix_profile).unwrap_or(&self.default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_config_loads_and_rejects_inverted_bands() {
+        let shipped = ThresholdProfiles::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../config/wordmath-thresholds.v1.json"
+        ))
+        .unwrap();
+        assert_eq!(
+            shipped.for_profile("unlisted"),
+            &PromptBandThresholds::default()
+        );
+
+        let mut bad = shipped.clone();
+        bad.profiles.insert(
+            "inverted".to_string(),
+            PromptBandThresholds {
+                green_min_f: 0.6,
+                amber_min_f: 0.7,
+                ..PromptBandThresholds::default()
+            },
+        );
+        assert!(matches!(
+            bad.validate(),
+            Err(ThresholdConfigError::Invalid { profile, .. }) if profile == "inverted"
+        ));
+    }
+
+    #[test]
+    fn rewrite_lexicon_is_read_from_config() {
+        let shipped = ThresholdProfiles::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../config/wordmath-thresholds.v1.json"
+        ))
+        .unwrap();
+        assert_eq!(shipped.rewrite_lexicon, RewriteLexicon::default());
+
+        let custom = ThresholdProfiles::from_json(
+            r#"{
+                "schema": 1,
+                "version": "test",
+                "default": {
+                    "green_min_f": 0.8,
+                    "amber_min_f": 0.7,
+                    "green_max_toxicity": 0.1,
+                    "amber_max_toxicity": 0.2
+                },
+                "rewrite_lexicon": {
+                    "softenings": [["asap", "soon"]],
+                    "hook_template": "(see {did})",
+                    "hook_placeholder": "[cite]"
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            custom.rewrite_lexicon.softenings,
+            vec![("asap".to_string(), "soon".to_string())]
+        );
+
+        let mut bad = custom.clone();
+        bad.rewrite_lexicon.hook_placeholder.clear();
+        assert!(matches!(
+            bad.validate(),
+            Err(ThresholdConfigError::InvalidLexicon(_))
+        ));
+    }
+}
@@ -0,0 +1,441 @@
+//! Rule-based rewriting for AmberRewrite prompts.
+//!
+//! The engine applies one targeted transform per step, re-scores the text with
+//! `classify_prompt` on locally computed metrics, and stops as soon as the band
+//! improves, no transform applies any more, or the step budget is spent.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::{compute_metrics, evidential_hooks, MetricLexicon, TaskTriad};
+use crate::{classify_prompt, PromptBand, PromptBandThresholds, PromptScore};
+
+/// Adversarial phrases and their softer replacements. Phrases are matched
+/// case-insensitively on word boundaries; an empty replacement deletes.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RewriteLexicon {
+    pub softenings: Vec<(String, String)>,
+    /// Appended when the prompt carries no evidential hook. `{did}` is replaced
+    /// by the requester DID; without one the placeholder is left for the author
+    /// to fill in and does not count as a hook.
+    pub hook_template: String,
+    pub hook_placeholder: String,
+}
+
+impl Default for RewriteLexicon {
+    fn default() -> Self {
+        let pairs = [
+            ("must", "should"),
+            ("demand", "request"),
+            ("immediately", "when possible"),
+            ("!", "."),
+            ("shut up", "pause"),
+            ("stupid", "unclear"),
+            ("dumb", "simple"),
+            ("idiot", ""),
+            ("moron", ""),
+            ("pathetic", "weak"),
+            ("worthless", "low-value"),
+            ("hate", "dislike"),
+            ("destroy", "retire"),
+            ("kill", "stop"),
+        ];
+        Self {
+            softenings: pairs
+                .iter()
+                .map(|(a, b)| (a.to_string(), b.to_string()))
+                .collect(),
+            hook_template: "(evidence: {did})".to_string(),
+            hook_placeholder: "[evidence-hook: add a DID or content hash]".to_string(),
+        }
+    }
+}
+
+impl RewriteLexicon {
+    /// An empty placeholder is contained in every text, which would silently
+    /// disable hook insertion.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.hook_placeholder.is_empty() {
+            return Err("hook_placeholder is empty".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RewriteTransform {
+    DedupRepeatedSpans,
+    SoftenAdversarial,
+    InsertEvidentialHook,
+}
+
+const TRANSFORM_ORDER: [RewriteTransform; 3] = [
+    RewriteTransform::DedupRepeatedSpans,
+    RewriteTransform::SoftenAdversarial,
+    RewriteTransform::InsertEvidentialHook,
+];
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AppliedTransform {
+    pub step: usize,
+    pub transform: RewriteTransform,
+    pub detail: String,
+}
+
+/// Score after each step; step 0 is the original text.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ScorePoint {
+    pub step: usize,
+    pub f: f32,
+    pub band: PromptBand,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RewriteOutcome {
+    pub text: String,
+    pub initial: PromptScore,
+    pub final_score: PromptScore,
+    pub improved: bool,
+    pub trajectory: Vec<ScorePoint>,
+    pub transforms: Vec<AppliedTransform>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RewriteEngine {
+    pub lexicon: RewriteLexicon,
+    pub metric_lexicon: MetricLexicon,
+    pub thresholds: PromptBandThresholds,
+    pub max_steps: usize,
+}
+
+impl Default for RewriteEngine {
+    fn default() -> Self {
+        Self {
+            lexicon: RewriteLexicon::default(),
+            metric_lexicon: MetricLexicon::default(),
+            thresholds: PromptBandThresholds::default(),
+            max_steps: 4,
+        }
+    }
+}
+
+fn band_rank(band: &PromptBand) -> u8 {
+    match band {
+        PromptBand::RedBlocked => 0,
+        PromptBand::AmberRewrite => 1,
+        PromptBand::GreenAdmit => 2,
+    }
+}
+
+impl RewriteEngine {
+    pub fn score(&self, text: &str, triad: &TaskTriad) -> PromptScore {
+        classify_prompt(
+            compute_metrics(text, triad, &self.metric_lexicon),
+            &self.thresholds,
+        )
+    }
+
+    pub fn rewrite(&self, text: &str, triad: &TaskTriad, did: Option<&str>) -> RewriteOutcome {
+        let initial = self.score(text, triad);
+        let mut current = text.to_string();
+        let mut score = initial.clone();
+        let mut trajectory = vec![ScorePoint {
+            step: 0,
+            f: score.f,
+            band: score.band.clone(),
+        }];
+        let mut transforms = Vec::new();
+
+        for step in 1..=self.max_steps {
+            if band_rank(&score.band) > band_rank(&initial.band) {
+                break;
+            }
+            let applied = TRANSFORM_ORDER.iter().find_map(|t| {
+                self.apply(*t, &current, did)
+                    .map(|(next, detail)| (*t, next, detail))
+            });
+            let Some((transform, next, detail)) = applied else {
+                break;
+            };
+            current = next;
+            score = self.score(&current, triad);
+            trajectory.push(ScorePoint {
+                step,
+                f: score.f,
+                band: score.band.clone(),
+            });
+            transforms.push(AppliedTransform {
+                step,
+                transform,
+                detail,
+            });
+        }
+
+        RewriteOutcome {
+            text: current,
+            improved: band_rank(&score.band) > band_rank(&initial.band),
+            initial,
+            final_score: score,
+            trajectory,
+            transforms,
+        }
+    }
+
+    /// New text and a description, or `None` when the transform has nothing to do.
+    fn apply(
+        &self,
+        transform: RewriteTransform,
+        text: &str,
+        did: Option<&str>,
+    ) -> Option<(String, String)> {
+        match transform {
+            RewriteTransform::DedupRepeatedSpans => {
+                let (next, removed) = dedup_repeated_spans(text);
+                (removed > 0).then(|| (next, format!("removed {} repeated span(s)", removed)))
+            }
+            RewriteTransform::SoftenAdversarial => {
+                let mut next = text.to_string();
+                let mut changed = Vec::new();
+                for (from, to) in &self.lexicon.softenings {
+                    let (replaced, n) = replace_phrase(&next, from, to);
+                    if n > 0 {
+                        next = replaced;
+                        changed.push(format!("{:?}->{:?} x{}", from, to, n));
+                    }
+                }
+                (!changed.is_empty()).then(|| (collapse_spaces(&next), changed.join(", ")))
+            }
+            RewriteTransform::InsertEvidentialHook => {
+                if !evidential_hooks(text).is_empty()
+                    || text.contains(&self.lexicon.hook_placeholder)
+                {
+                    return None;
+                }
+                let hook = match did.filter(|d| !evidential_hooks(d).is_empty()) {
+                    Some(d) => self.lexicon.hook_template.replace("{did}", d),
+                    None => self.lexicon.hook_placeholder.clone(),
+                };
+                if text.contains(&hook) {
+                    return None;
+                }
+                Some((
+                    format!("{} {}", text.trim_end(), hook),
+                    format!("appended {}", hook),
+                ))
+            }
+        }
+    }
+}
+
+fn normalize(token: &str) -> String {
+    token
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Longest input, in words, that `dedup_repeated_spans` will scan. Longer
+/// texts are returned unchanged.
+pub const MAX_DEDUP_TOKENS: usize = 2048;
+
+const HASH_MOD: u128 = (1 << 61) - 1;
+const HASH_BASE: u128 = 1_000_003;
+
+/// Polynomial prefix hashes over interned token ids, so any span compares in
+/// O(1).
+struct SpanHashes {
+    prefix: Vec<u128>,
+    pow: Vec<u128>,
+}
+
+impl SpanHashes {
+    fn new(ids: &[u64]) -> Self {
+        let mut prefix = Vec::with_capacity(ids.len() + 1);
+        let mut pow = Vec::with_capacity(ids.len() + 1);
+        prefix.push(0);
+        pow.push(1);
+        for (k, &id) in ids.iter().enumerate() {
+            prefix.push((prefix[k] * HASH_BASE + id as u128) % HASH_MOD);
+            pow.push(pow[k] * HASH_BASE % HASH_MOD);
+        }
+        Self { prefix, pow }
+    }
+
+    fn span(&self, start: usize, len: usize) -> u128 {
+        let shifted = self.prefix[start] * self.pow[len] % HASH_MOD;
+        (self.prefix[start + len] + HASH_MOD - shifted) % HASH_MOD
+    }
+}
+
+/// Remove immediately repeated word spans ("a poem a poem" -> "a poem"),
+/// longest spans first. Returns the text and the number of spans removed.
+///
+/// Tokens are normalised once and compared by rolling hash, so a scan is
+/// quadratic in the word count; inputs over `MAX_DEDUP_TOKENS` are skipped.
+pub fn dedup_repeated_spans(text: &str) -> (String, usize) {
+    let mut tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.len() > MAX_DEDUP_TOKENS {
+        return (text.to_string(), 0);
+    }
+    // 0 marks a token with no alphanumeric content.
+    let mut interned: HashMap<String, u64> = HashMap::new();
+    let mut ids: Vec<u64> = tokens
+        .iter()
+        .map(|t| {
+            let key = normalize(t);
+            if key.is_empty() {
+                return 0;
+            }
+            let next = interned.len() as u64 + 1;
+            *interned.entry(key).or_insert(next)
+        })
+        .collect();
+    let mut hashes = SpanHashes::new(&ids);
+    let mut removed = 0;
+    let mut n = ids.len() / 2;
+    while n >= 1 {
+        let mut i = 0;
+        while i + 2 * n <= ids.len() {
+            let repeated = hashes.span(i, n) == hashes.span(i + n, n)
+                && ids[i..i + n] == ids[i + n..i + 2 * n]
+                && ids[i..i + n].iter().any(|&id| id != 0);
+            if repeated {
+                tokens.drain(i + n..i + 2 * n);
+                ids.drain(i + n..i + 2 * n);
+                hashes = SpanHashes::new(&ids);
+                removed += 1;
+            } else {
+                i += 1;
+            }
+        }
+        n -= 1;
+    }
+    (tokens.join(" "), removed)
+}
+
+/// ASCII case-insensitive replacement. Alphanumeric phrases only match on word
+/// boundaries; punctuation phrases match anywhere.
+fn replace_phrase(text: &str, from: &str, to: &str) -> (String, usize) {
+    if from.is_empty() {
+        return (text.to_string(), 0);
+    }
+    let lower = text.to_ascii_lowercase();
+    let needle = from.to_ascii_lowercase();
+    let word_like = needle.chars().any(|c| c.is_alphanumeric());
+    let mut out = String::with_capacity(text.len());
+    let mut last = 0;
+    let mut count = 0;
+    for (start, _) in lower.match_indices(&needle) {
+        if start < last {
+            continue;
+        }
+        let end = start + needle.len();
+        let bounded = !word_like
+            || (!lower[..start]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_alphanumeric)
+                && !lower[end..]
+                    .chars()
+                    .next()
+                    .is_some_and(char::is_alphanumeric));
+        if bounded {
+            out.push_str(&text[last..start]);
+            out.push_str(to);
+            last = end;
+            count += 1;
+        }
+    }
+    out.push_str(&text[last..]);
+    (out, count)
+}
+
+fn collapse_spaces(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == ' ' && (out.ends_with(' ') || out.is_empty()) {
+            continue;
+        }
+        if matches!(c, '.' | ',') && out.ends_with(' ') {
+            out.pop();
+        }
+        out.push(c);
+    }
+    out.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedups_softens_and_hooks_until_band_improves() {
+        let engine = RewriteEngine::default();
+        let text = "Please explain quantum learning for cybernetic implants on a \
+                    biophysical blockchain biophysical blockchain";
+        let out = engine.rewrite(text, &TaskTriad::default(), Some("did:aln:citizen-7"));
+
+        assert_eq!(out.initial.band, PromptBand::AmberRewrite);
+        assert_eq!(out.final_score.band, PromptBand::GreenAdmit);
+        assert!(out.improved);
+        assert_eq!(
+            out.transforms
+                .iter()
+                .map(|t| t.transform)
+                .collect::<Vec<_>>(),
+            vec![
+                RewriteTransform::DedupRepeatedSpans,
+                RewriteTransform::InsertEvidentialHook
+            ]
+        );
+        assert_eq!(out.trajectory.len(), 3);
+        assert_eq!(
+            out.text,
+            "Please explain quantum learning for cybernetic implants on a biophysical \
+             blockchain (evidence: did:aln:citizen-7)"
+        );
+    }
+
+    #[test]
+    fn placeholder_hook_does_not_score_and_budget_is_respected() {
+        let engine = RewriteEngine {
+            max_steps: 1,
+            ..RewriteEngine::default()
+        };
+        let text = "You must explain quantum learning for cybernetic biophysical blockchain now!";
+        let out = engine.rewrite(text, &TaskTriad::default(), None);
+        assert_eq!(out.transforms.len(), 1);
+        assert_eq!(
+            out.transforms[0].transform,
+            RewriteTransform::SoftenAdversarial
+        );
+        assert_eq!(
+            out.text,
+            "You should explain quantum learning for cybernetic biophysical blockchain now."
+        );
+
+        let amber = "Please explain quantum learning for cybernetic biophysical blockchain";
+        let out = RewriteEngine::default().rewrite(amber, &TaskTriad::default(), None);
+        assert!(out
+            .text
+            .ends_with("[evidence-hook: add a DID or content hash]"));
+        assert_eq!(out.final_score.metrics.evidentiality_e, 0.75);
+        assert!(!out.improved);
+    }
+
+    #[test]
+    fn dedup_scales_to_long_inputs_and_skips_oversized_ones() {
+        let words: Vec<String> = (0..1000).map(|i| format!("w{}", i)).collect();
+        let mut text = words.join(" ");
+        text.push_str(" w998 w999");
+        let (out, removed) = dedup_repeated_spans(&text);
+        assert_eq!(removed, 1);
+        assert_eq!(out, words.join(" "));
+
+        let long = vec!["again"; MAX_DEDUP_TOKENS + 1].join(" ");
+        assert_eq!(dedup_repeated_spans(&long), (long.clone(), 0));
+    }
+}
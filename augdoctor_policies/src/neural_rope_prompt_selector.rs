@@ -1,6 +1,9 @@
 use crate::shot_level_policy::ShotLevel;
-use bioscale_upgrade_service::neural_rope::{NeuralRope, NeuralRopeSegmentSnapshot};
+use bioscale_upgrade_service::neural_rope::{
+    plane_domain, NeuralRope, NeuralRopeAttributes, NeuralRopeSegmentSnapshot,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PromptExample {
@@ -8,6 +11,8 @@ pub struct PromptExample {
     pub reward_score: f32,
     pub safety_decision: String,
     pub plane_label: String,
+    #[serde(default)]
+    pub bioscale_upgrade_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -63,6 +68,7 @@ impl<'a> NeuralRopePromptSelector<'a> {
                             reward_score: attrs.reward_score,
                             safety_decision: attrs.safety_decision,
                             plane_label: attrs.plane_label,
+                            bioscale_upgrade_id: attrs.bioscale_upgrade_id,
                         })
                     } else {
                         None
@@ -89,4 +95,368 @@ impl<'a> NeuralRopePromptSelector<'a> {
             examples,
         }
     }
+
+    /// Query-aware variant of `select_examples`: ranks every trace in the rope
+    /// with `rank_traces` instead of by reward alone.
+    pub fn select_relevant(
+        &self,
+        req: &PromptSelectionRequest,
+        query: &str,
+        cfg: &RetrievalConfig,
+    ) -> PromptSelectionResult {
+        if req.shot_level == ShotLevel::ZeroShot || req.max_examples == 0 {
+            return PromptSelectionResult {
+                task_id: req.task_id.clone(),
+                plane_label: req.plane_label.clone(),
+                shot_level: ShotLevel::ZeroShot,
+                examples: Vec::new(),
+            };
+        }
+        let traces = self.rope.export_traces();
+        let examples = rank_traces(&traces, &req.plane_label, query, req.max_examples as usize, cfg)
+            .into_iter()
+            .map(|s| s.example)
+            .collect();
+        PromptSelectionResult {
+            task_id: req.task_id.clone(),
+            plane_label: req.plane_label.clone(),
+            shot_level: ShotLevel::FewShot,
+            examples,
+        }
+    }
+}
+
+/// Weights for lexical, embedding-free retrieval over rope traces.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RetrievalConfig {
+    /// BM25 term-frequency saturation.
+    pub bm25_k1: f32,
+    /// BM25 length normalisation.
+    pub bm25_b: f32,
+    pub relevance_weight: f32,
+    pub reward_weight: f32,
+    pub recency_weight: f32,
+    /// Added when the trace's plane label equals the request's.
+    pub plane_match_bonus: f32,
+    /// Number of newer traces after which the recency score halves.
+    pub recency_half_life: f32,
+    /// Multiplier for `Allow*` decisions.
+    pub allow_weight: f32,
+    /// Multiplier for `Warn*`/`Degrade*` decisions; anything else is excluded.
+    pub caution_weight: f32,
+    /// MMR trade-off: 1.0 is pure relevance, 0.0 pure diversity.
+    pub mmr_lambda: f32,
+    pub same_domain_similarity: f32,
+    pub same_upgrade_similarity: f32,
+    pub lexical_similarity: f32,
+    /// Only consider `Allow*` traces on exactly the request's plane, the same
+    /// pre-filter `select_examples` applies. Control paths set this.
+    #[serde(default)]
+    pub allow_same_plane_only: bool,
+}
+
+impl Default for RetrievalConfig {
+    fn default() -> Self {
+        Self {
+            bm25_k1: 1.2,
+            bm25_b: 0.75,
+            relevance_weight: 0.6,
+            reward_weight: 0.25,
+            recency_weight: 0.15,
+            plane_match_bonus: 0.1,
+            recency_half_life: 32.0,
+            allow_weight: 1.0,
+            caution_weight: 0.5,
+            mmr_lambda: 0.7,
+            same_domain_similarity: 0.5,
+            same_upgrade_similarity: 0.3,
+            lexical_similarity: 0.2,
+            allow_same_plane_only: false,
+        }
+    }
+}
+
+impl RetrievalConfig {
+    /// Default weights behind the `select_examples` safety pre-filter.
+    pub fn allow_same_plane_only() -> Self {
+        Self { allow_same_plane_only: true, ..Self::default() }
+    }
+}
+
+/// A selected trace with the components of its score, for introspection.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ScoredExample {
+    pub trace_id: String,
+    pub example: PromptExample,
+    pub domain: String,
+    pub bm25: f32,
+    pub recency: f32,
+    pub safety_weight: f32,
+    pub relevance: f32,
+    pub mmr_score: f32,
+}
+
+/// Domain used for diversity: the first component of the plane label
+/// (`"bci/hci/eeg"` and `"bci-hci-eeg"` are both domain `"bci"`).
+pub fn trace_domain(plane_label: &str) -> String {
+    plane_domain(plane_label)
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.len() > 1)
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+fn safety_weight(decision: &str, cfg: &RetrievalConfig) -> f32 {
+    if decision.starts_with("Allow") {
+        cfg.allow_weight
+    } else if decision.starts_with("Warn") || decision.starts_with("Degrade") {
+        cfg.caution_weight
+    } else {
+        0.0
+    }
+}
+
+struct Candidate {
+    scored: ScoredExample,
+    order: u64,
+    tokens: BTreeSet<String>,
+}
+
+/// Rank `traces` for `query` and pick up to `max` of them with MMR.
+///
+/// Relevance blends normalised BM25 over trace text plus tags (plane label and
+/// upgrade id), reward and recency, scaled by the safety decision. Selection
+/// then penalises traces that share a domain, upgrade id or vocabulary with
+/// ones already chosen. Ties break towards newer traces, so the output depends
+/// only on the input slice.
+pub fn rank_traces(
+    traces: &[NeuralRopeSegmentSnapshot],
+    plane_label: &str,
+    query: &str,
+    max: usize,
+    cfg: &RetrievalConfig,
+) -> Vec<ScoredExample> {
+    let mut docs: Vec<(&NeuralRopeSegmentSnapshot, &NeuralRopeAttributes, Vec<String>, f32)> = traces
+        .iter()
+        .filter_map(|seg| {
+            let attrs = seg.attributes.as_ref()?;
+            if cfg.allow_same_plane_only
+                && (attrs.plane_label != plane_label || !attrs.safety_decision.starts_with("Allow"))
+            {
+                return None;
+            }
+            let w = safety_weight(&attrs.safety_decision, cfg);
+            if w <= 0.0 || attrs.reward_score <= 0.0 {
+                return None;
+            }
+            let mut tokens = tokenize(&seg.text);
+            tokens.extend(tokenize(&attrs.plane_label));
+            if let Some(id) = &attrs.bioscale_upgrade_id {
+                tokens.extend(tokenize(id));
+            }
+            Some((seg, attrs, tokens, w))
+        })
+        .collect();
+    if docs.is_empty() || max == 0 {
+        return Vec::new();
+    }
+    // Newest first: recency rank is the index in this order.
+    docs.sort_by_key(|d| std::cmp::Reverse(d.0.range_start));
+
+    let n = docs.len() as f32;
+    let avg_len = docs.iter().map(|d| d.2.len() as f32).sum::<f32>() / n;
+    let mut df: HashMap<&str, f32> = HashMap::new();
+    for (_, _, tokens, _) in &docs {
+        for t in tokens.iter().map(String::as_str).collect::<BTreeSet<_>>() {
+            *df.entry(t).or_default() += 1.0;
+        }
+    }
+    let query_terms: BTreeSet<String> = tokenize(query).into_iter().collect();
+    let bm25: Vec<f32> = docs
+        .iter()
+        .map(|(_, _, tokens, _)| {
+            let len = tokens.len() as f32;
+            query_terms
+                .iter()
+                .filter_map(|q| {
+                    let tf = tokens.iter().filter(|t| *t == q).count() as f32;
+                    let df = *df.get(q.as_str())?;
+                    let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    let norm = cfg.bm25_k1 * (1.0 - cfg.bm25_b + cfg.bm25_b * len / avg_len.max(1.0));
+                    Some(idf * tf * (cfg.bm25_k1 + 1.0) / (tf + norm))
+                })
+                .sum()
+        })
+        .collect();
+    let max_bm25 = bm25.iter().cloned().fold(0.0f32, f32::max);
+    let max_reward = docs.iter().map(|d| d.1.reward_score).fold(0.0f32, f32::max);
+
+    let mut pool: Vec<Candidate> = docs
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (seg, attrs, tokens, safety))| {
+            let bm25n = if max_bm25 > 0.0 { bm25[rank] / max_bm25 } else { 0.0 };
+            let recency = 0.5f32.powf(rank as f32 / cfg.recency_half_life.max(f32::EPSILON));
+            let reward = if max_reward > 0.0 { attrs.reward_score / max_reward } else { 0.0 };
+            let plane = if attrs.plane_label == plane_label { cfg.plane_match_bonus } else { 0.0 };
+            let relevance =
+                (cfg.relevance_weight * bm25n + cfg.reward_weight * reward + cfg.recency_weight * recency + plane) * safety;
+            Candidate {
+                scored: ScoredExample {
+                    trace_id: seg.id.clone(),
+                    domain: trace_domain(&attrs.plane_label),
+                    example: PromptExample {
+                        text: seg.text.clone(),
+                        reward_score: attrs.reward_score,
+                        safety_decision: attrs.safety_decision.clone(),
+                        plane_label: attrs.plane_label.clone(),
+                        bioscale_upgrade_id: attrs.bioscale_upgrade_id.clone(),
+                    },
+                    bm25: bm25[rank],
+                    recency,
+                    safety_weight: safety,
+                    relevance,
+                    mmr_score: relevance,
+                },
+                order: seg.range_start,
+                tokens: tokens.into_iter().collect(),
+            }
+        })
+        .collect();
+
+    let mut selected: Vec<Candidate> = Vec::new();
+    while selected.len() < max && !pool.is_empty() {
+        let mut best: Option<(usize, f32)> = None;
+        for (i, c) in pool.iter().enumerate() {
+            let redundancy = selected.iter().map(|s| similarity(c, s, cfg)).fold(0.0f32, f32::max);
+            let score = cfg.mmr_lambda * c.scored.relevance - (1.0 - cfg.mmr_lambda) * redundancy;
+            let better = match best {
+                None => true,
+                Some((j, b)) => score > b || (score == b && c.order > pool[j].order),
+            };
+            if better {
+                best = Some((i, score));
+            }
+        }
+        let (i, score) = best.expect("pool is non-empty");
+        let mut c = pool.swap_remove(i);
+        c.scored.mmr_score = score;
+        selected.push(c);
+    }
+    selected.into_iter().map(|c| c.scored).collect()
+}
+
+fn similarity(a: &Candidate, b: &Candidate, cfg: &RetrievalConfig) -> f32 {
+    let mut sim = 0.0;
+    if a.scored.domain == b.scored.domain {
+        sim += cfg.same_domain_similarity;
+    }
+    if a.scored.example.bioscale_upgrade_id.is_some()
+        && a.scored.example.bioscale_upgrade_id == b.scored.example.bioscale_upgrade_id
+    {
+        sim += cfg.same_upgrade_similarity;
+    }
+    let union = a.tokens.union(&b.tokens).count();
+    if union > 0 {
+        sim += cfg.lexical_similarity * a.tokens.intersection(&b.tokens).count() as f32 / union as f32;
+    }
+    sim
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(n: u64, text: &str, plane: &str, upgrade: Option<&str>, reward: f32, safety: &str) -> NeuralRopeSegmentSnapshot {
+        NeuralRopeSegmentSnapshot {
+            id: format!("trace-{n}"),
+            range_start: n * 100,
+            range_end: n * 100 + text.len() as u64,
+            text: text.to_string(),
+            attributes: Some(NeuralRopeAttributes {
+                plane_label: plane.to_string(),
+                bioscale_upgrade_id: upgrade.map(str::to_string),
+                reward_score: reward,
+                safety_decision: safety.to_string(),
+            }),
+        }
+    }
+
+    fn fixture() -> Vec<NeuralRopeSegmentSnapshot> {
+        vec![
+            trace(0, "grip close low force gripper", "bci/hci/eeg", Some("up-1"), 0.9, "Allow"),
+            trace(1, "grip close low force gripper again", "bci/hci/eeg", Some("up-1"), 0.8, "Allow"),
+            trace(2, "rotate wrist slowly", "bci/hci/eeg", Some("up-2"), 0.95, "Allow"),
+            trace(3, "grip release emg burst", "emg/forearm", Some("up-3"), 0.6, "AllowWithWarning"),
+            trace(4, "grip close maximal force", "bci/hci/eeg", Some("up-1"), 0.99, "Deny"),
+        ]
+    }
+
+    #[test]
+    fn query_drives_relevance_and_denied_traces_are_excluded() {
+        let top = rank_traces(&fixture(), "bci/hci/eeg", "grip close", 1, &RetrievalConfig::default());
+        assert_eq!(top.len(), 1);
+        assert!(top[0].example.text.starts_with("grip close low force"));
+
+        let rotate = rank_traces(&fixture(), "bci/hci/eeg", "rotate wrist", 1, &RetrievalConfig::default());
+        assert_eq!(rotate[0].trace_id, "trace-2");
+
+        let all = rank_traces(&fixture(), "bci/hci/eeg", "grip", 10, &RetrievalConfig::default());
+        assert_eq!(all.len(), 4);
+        assert!(all.iter().all(|s| s.trace_id != "trace-4"));
+    }
+
+    #[test]
+    fn mmr_spreads_across_domains_and_upgrades() {
+        let picked = rank_traces(&fixture(), "bci/hci/eeg", "grip", 2, &RetrievalConfig::default());
+        let ids: Vec<_> = picked.iter().map(|s| s.example.bioscale_upgrade_id.clone()).collect();
+        assert_ne!(ids[0], ids[1], "near-duplicate grip traces should not both be picked");
+
+        let greedy = RetrievalConfig { mmr_lambda: 1.0, ..RetrievalConfig::default() };
+        let picked = rank_traces(&fixture(), "bci/hci/eeg", "grip", 2, &greedy);
+        assert_eq!(picked[0].example.bioscale_upgrade_id, picked[1].example.bioscale_upgrade_id);
+    }
+
+    #[test]
+    fn strict_config_keeps_the_allow_same_plane_filter() {
+        let mut traces = fixture();
+        traces.push(trace(5, "grip close degraded", "bci/hci/eeg", Some("up-4"), 0.7, "DegradeToSafeMode"));
+        let picked = rank_traces(&traces, "bci/hci/eeg", "grip", 10, &RetrievalConfig::allow_same_plane_only());
+        let ids: Vec<_> = picked.iter().map(|s| s.trace_id.as_str()).collect();
+        assert_eq!(picked.len(), 3);
+        assert!(!ids.contains(&"trace-3") && !ids.contains(&"trace-5"));
+    }
+
+    #[test]
+    fn ranking_is_deterministic() {
+        let cfg = RetrievalConfig::default();
+        let ids = |v: &[ScoredExample]| v.iter().map(|s| s.trace_id.clone()).collect::<Vec<_>>();
+        let forward = rank_traces(&fixture(), "bci/hci/eeg", "force", 3, &cfg);
+        // Shorter "force" doc first, then its near-duplicate, then the other
+        // domain ahead of the query-less rotate trace.
+        assert_eq!(ids(&forward), ["trace-0", "trace-1", "trace-3"]);
+
+        let mut reversed = fixture();
+        reversed.reverse();
+        assert_eq!(ids(&rank_traces(&reversed, "bci/hci/eeg", "force", 3, &cfg)), ids(&forward));
+        assert_eq!(trace_domain("bci-hci-eeg"), "bci");
+    }
+
+    #[test]
+    fn exact_ties_prefer_the_newer_trace() {
+        let flat = RetrievalConfig { recency_weight: 0.0, mmr_lambda: 1.0, ..RetrievalConfig::default() };
+        let twins = vec![
+            trace(7, "grip hold", "bci/hci/eeg", None, 0.5, "Allow"),
+            trace(6, "grip hold", "bci/hci/eeg", None, 0.5, "Allow"),
+        ];
+        for input in [twins.clone(), twins.into_iter().rev().collect()] {
+            let picked = rank_traces(&input, "bci/hci/eeg", "grip", 2, &flat);
+            assert_eq!(picked[0].relevance, picked[1].relevance);
+            assert_eq!(picked[0].trace_id, "trace-7");
+            assert_eq!(picked[1].trace_id, "trace-6");
+        }
+    }
 }
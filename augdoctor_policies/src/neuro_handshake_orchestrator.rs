@@ -1,19 +1,188 @@
+//! Neuro-handshake state machine: Safety → Calibration → Operation.
+//!
+//! Events are typed (`HandshakeEvent`) and travel in a versioned envelope
+//! (`HandshakeWireEvent`). Every transition is checked against an explicit
+//! table; anything else is returned as a `HandshakeRejection` instead of being
+//! silently ignored. `NeuroHandshakeState` doubles as the session record: it
+//! is serializable, carries its own timeouts and transition history, and can
+//! be persisted and resumed by a restarted service.
+
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use thiserror::Error;
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// Version of both the wire envelope and the persisted session record.
+pub const HANDSHAKE_WIRE_VERSION: u32 = 1;
+
+/// Transitions kept on a session record; older ones are dropped first.
+/// `seq` keeps counting, so a trimmed history still shows how many came before.
+pub const MAX_HANDSHAKE_HISTORY: usize = 64;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum HandshakePhase {
     Safety,
     Calibration,
     Operation,
 }
 
+/// Typed handshake input. Serialized as `{"kind": "user_consented", ...}`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HandshakeEvent {
+    /// The user accepted the safety summary.
+    UserConsented,
+    /// One calibration sample was captured on `channel` (e.g. "eeg").
+    CalibrationSampleRecorded {
+        #[serde(default)]
+        channel: Option<String>,
+    },
+    /// The user withdrew consent; the session drops back to Safety.
+    ConsentRevoked { reason: Option<String> },
+    /// Operator/guard override back to Safety.
+    ForceDeny { reason: Option<String> },
+}
+
+impl HandshakeEvent {
+    pub fn label(&self) -> &'static str {
+        match self {
+            HandshakeEvent::UserConsented => "user_consented",
+            HandshakeEvent::CalibrationSampleRecorded { .. } => "calibration_sample_recorded",
+            HandshakeEvent::ConsentRevoked { .. } => "consent_revoked",
+            HandshakeEvent::ForceDeny { .. } => "force_deny",
+        }
+    }
+}
+
+/// Accepts the legacy free-form labels in any of the spellings callers used
+/// ("user_consented", "user-consented", "userconsented", ...).
+impl FromStr for HandshakeEvent {
+    type Err = HandshakeRejection;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let key: String = raw
+            .chars()
+            .filter(|c| !matches!(c, '_' | '-' | ' ' | '.'))
+            .flat_map(char::to_lowercase)
+            .collect();
+        match key.as_str() {
+            "userconsented" | "consent" | "consented" => Ok(HandshakeEvent::UserConsented),
+            "calibrationsamplerecorded" | "calibrationsample" => {
+                Ok(HandshakeEvent::CalibrationSampleRecorded { channel: None })
+            }
+            "consentrevoked" | "revokeconsent" => Ok(HandshakeEvent::ConsentRevoked { reason: None }),
+            "forcedeny" => Ok(HandshakeEvent::ForceDeny { reason: None }),
+            _ => Err(HandshakeRejection::UnknownEvent { raw: raw.to_string() }),
+        }
+    }
+}
+
+/// Versioned envelope for events arriving over HTTP, wasm or RPC.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HandshakeWireEvent {
+    pub version: u32,
+    pub session_id: String,
+    pub event: HandshakeEvent,
+    pub at_ms: u64,
+}
+
+impl HandshakeWireEvent {
+    pub fn new(session_id: &str, event: HandshakeEvent, at_ms: u64) -> Self {
+        Self { version: HANDSHAKE_WIRE_VERSION, session_id: session_id.to_string(), event, at_ms }
+    }
+
+    pub fn decode(raw: &str) -> Result<Self, HandshakeRejection> {
+        let wire: HandshakeWireEvent =
+            serde_json::from_str(raw).map_err(|e| HandshakeRejection::Malformed { detail: e.to_string() })?;
+        if wire.version != HANDSHAKE_WIRE_VERSION {
+            return Err(HandshakeRejection::UnsupportedVersion { got: wire.version, supported: HANDSHAKE_WIRE_VERSION });
+        }
+        Ok(wire)
+    }
+}
+
+/// Structured reason an event was not applied.
+#[derive(Clone, Debug, Error, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "rejection", rename_all = "snake_case")]
+pub enum HandshakeRejection {
+    #[error("unknown handshake event {raw:?}")]
+    UnknownEvent { raw: String },
+    #[error("malformed handshake event: {detail}")]
+    Malformed { detail: String },
+    #[error("unsupported handshake wire version {got} (supported: {supported})")]
+    UnsupportedVersion { got: u32, supported: u32 },
+    #[error("event for session {got} sent to session {expected}")]
+    SessionMismatch { expected: String, got: String },
+    #[error("{event} is not legal in phase {phase:?}")]
+    IllegalTransition { phase: HandshakePhase, event: String },
+    #[error("phase {phase:?} timed out after {elapsed_ms} ms (limit {limit_ms} ms); session reset to Safety")]
+    TimedOut { phase: HandshakePhase, elapsed_ms: u64, limit_ms: u64 },
+}
+
+/// Maximum time a session may sit in each phase; `None` means no limit.
+/// For Safety this bounds how long a shown safety summary stays valid: consent
+/// arriving after it is refused and the summary has to be shown again.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HandshakeTimeouts {
+    pub safety_ms: Option<u64>,
+    pub calibration_ms: Option<u64>,
+    pub operation_ms: Option<u64>,
+}
+
+impl Default for HandshakeTimeouts {
+    fn default() -> Self {
+        Self {
+            safety_ms: Some(5 * 60 * 1000),
+            calibration_ms: Some(10 * 60 * 1000),
+            operation_ms: Some(8 * 60 * 60 * 1000),
+        }
+    }
+}
+
+impl HandshakeTimeouts {
+    pub fn for_phase(&self, phase: HandshakePhase) -> Option<u64> {
+        match phase {
+            HandshakePhase::Safety => self.safety_ms,
+            HandshakePhase::Calibration => self.calibration_ms,
+            HandshakePhase::Operation => self.operation_ms,
+        }
+    }
+}
+
+/// One applied transition, kept on the session record for audit.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HandshakeTransition {
+    pub seq: u64,
+    pub at_ms: u64,
+    pub from: HandshakePhase,
+    pub to: HandshakePhase,
+    /// Event label, or "timeout" for an expiry reset.
+    pub cause: String,
+}
+
+fn default_version() -> u32 {
+    HANDSHAKE_WIRE_VERSION
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct NeuroHandshakeState {
+    #[serde(default = "default_version")]
+    pub version: u32,
     pub session_id: String,
     pub phase: HandshakePhase,
     pub safety_confirmed: bool,
     pub calibration_samples_collected: u32,
     pub required_calibration_samples: u32,
+    /// When the current phase was entered; `None` for records written before
+    /// timeouts existed, which are stamped on their next event.
+    #[serde(default)]
+    pub phase_entered_at_ms: Option<u64>,
+    #[serde(default)]
+    pub timeouts: HandshakeTimeouts,
+    #[serde(default)]
+    pub consent_revocations: u32,
+    #[serde(default)]
+    pub history: Vec<HandshakeTransition>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -25,17 +194,46 @@ pub enum NeuroHandshakeAction {
     DenyOperation { reason: String },
 }
 
+/// Milliseconds since the Unix epoch, for callers without their own clock.
+pub fn unix_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
 pub struct NeuroHandshakeOrchestrator;
 
 impl NeuroHandshakeOrchestrator {
     pub fn initial(session_id: &str, required_calibration_samples: u32) -> NeuroHandshakeState {
+        Self::initial_with(session_id, required_calibration_samples, HandshakeTimeouts::default(), None)
+    }
+
+    pub fn initial_with(
+        session_id: &str,
+        required_calibration_samples: u32,
+        timeouts: HandshakeTimeouts,
+        now_ms: Option<u64>,
+    ) -> NeuroHandshakeState {
         NeuroHandshakeState {
+            version: HANDSHAKE_WIRE_VERSION,
             session_id: session_id.to_string(),
             phase: HandshakePhase::Safety,
             safety_confirmed: false,
             calibration_samples_collected: 0,
             required_calibration_samples,
+            phase_entered_at_ms: now_ms,
+            timeouts,
+            consent_revocations: 0,
+            history: Vec::new(),
+        }
+    }
+
+    /// Restore a persisted session record, refusing records from a newer format.
+    pub fn resume(raw: &str) -> Result<NeuroHandshakeState, HandshakeRejection> {
+        let state: NeuroHandshakeState =
+            serde_json::from_str(raw).map_err(|e| HandshakeRejection::Malformed { detail: e.to_string() })?;
+        if state.version > HANDSHAKE_WIRE_VERSION {
+            return Err(HandshakeRejection::UnsupportedVersion { got: state.version, supported: HANDSHAKE_WIRE_VERSION });
         }
+        Ok(state)
     }
 
     pub fn next_actions(state: &NeuroHandshakeState) -> Vec<NeuroHandshakeAction> {
@@ -70,27 +268,225 @@ impl NeuroHandshakeOrchestrator {
         }
     }
 
-    pub fn apply_event(
-        mut state: NeuroHandshakeState,
-        event: &str,
-    ) -> NeuroHandshakeState {
+    /// Whether a caller may consent on the user's behalf: false once consent
+    /// was revoked or force-denied, until the user consents again. Timeout
+    /// resets are not a withdrawal of consent and are skipped. Records whose
+    /// history no longer reaches that far fall back to the revocation count.
+    pub fn may_auto_consent(state: &NeuroHandshakeState) -> bool {
+        for t in state.history.iter().rev() {
+            if t.cause == HandshakeEvent::UserConsented.label() {
+                return true;
+            }
+            if t.to == HandshakePhase::Safety && t.cause != "timeout" {
+                return false;
+            }
+        }
+        state.consent_revocations == 0
+    }
+
+    /// Phase an event leads to from `phase`, or `None` if the pair is illegal.
+    /// Calibration samples stay in Calibration; `apply` promotes to Operation
+    /// once enough have been collected.
+    pub fn target_phase(phase: HandshakePhase, event: &HandshakeEvent) -> Option<HandshakePhase> {
+        use HandshakeEvent::*;
+        use HandshakePhase::*;
+        match (phase, event) {
+            (Safety, UserConsented) => Some(Calibration),
+            (Calibration, CalibrationSampleRecorded { .. }) => Some(Calibration),
+            (Calibration | Operation, ConsentRevoked { .. }) => Some(Safety),
+            (_, ForceDeny { .. }) => Some(Safety),
+            _ => None,
+        }
+    }
+
+    /// Reset an expired session to Safety. Returns the rejection to report,
+    /// or `None` if the current phase is still within its limit.
+    pub fn expire(state: &mut NeuroHandshakeState, now_ms: u64) -> Option<HandshakeRejection> {
+        let entered = match state.phase_entered_at_ms {
+            Some(t) => t,
+            None => {
+                state.phase_entered_at_ms = Some(now_ms);
+                return None;
+            }
+        };
+        let limit_ms = state.timeouts.for_phase(state.phase)?;
+        let elapsed_ms = now_ms.saturating_sub(entered);
+        if elapsed_ms <= limit_ms {
+            return None;
+        }
+        let phase = state.phase;
+        Self::reset_to_safety(state, now_ms, "timeout");
+        Some(HandshakeRejection::TimedOut { phase, elapsed_ms, limit_ms })
+    }
+
+    /// Apply one typed event. On `Ok` the state has moved and the new phase is
+    /// returned. On `Err` the state is untouched, except for a timeout, which
+    /// resets the session to Safety before reporting.
+    pub fn apply(
+        state: &mut NeuroHandshakeState,
+        event: &HandshakeEvent,
+        now_ms: u64,
+    ) -> Result<HandshakePhase, HandshakeRejection> {
+        if let Some(rejection) = Self::expire(state, now_ms) {
+            return Err(rejection);
+        }
+        let target = Self::target_phase(state.phase, event).ok_or_else(|| HandshakeRejection::IllegalTransition {
+            phase: state.phase,
+            event: event.label().to_string(),
+        })?;
+
         match event {
-            "user_consented" => {
+            HandshakeEvent::UserConsented => {
                 state.safety_confirmed = true;
-                state.phase = HandshakePhase::Calibration;
+                state.calibration_samples_collected = 0;
+                let to = if state.required_calibration_samples == 0 { HandshakePhase::Operation } else { target };
+                Self::enter(state, to, now_ms, event.label());
             }
-            "calibration_sample_recorded" => {
-                if state.phase == HandshakePhase::Calibration {
-                    state.calibration_samples_collected += 1;
+            HandshakeEvent::CalibrationSampleRecorded { .. } => {
+                state.calibration_samples_collected += 1;
+                if state.calibration_samples_collected >= state.required_calibration_samples {
+                    Self::enter(state, HandshakePhase::Operation, now_ms, event.label());
                 }
             }
-            "force_deny" => {
-                state.phase = HandshakePhase::Safety;
-                state.safety_confirmed = false;
-                state.calibration_samples_collected = 0;
+            HandshakeEvent::ConsentRevoked { .. } => {
+                state.consent_revocations += 1;
+                Self::reset_to_safety(state, now_ms, event.label());
             }
-            _ => {}
+            HandshakeEvent::ForceDeny { .. } => Self::reset_to_safety(state, now_ms, event.label()),
+        }
+        Ok(state.phase)
+    }
+
+    /// Apply a legacy string label (any of the historical spellings).
+    pub fn apply_label(
+        state: &mut NeuroHandshakeState,
+        label: &str,
+        now_ms: u64,
+    ) -> Result<HandshakePhase, HandshakeRejection> {
+        let event: HandshakeEvent = label.parse()?;
+        Self::apply(state, &event, now_ms)
+    }
+
+    /// Decode and apply a `HandshakeWireEvent` JSON payload.
+    pub fn apply_wire(state: &mut NeuroHandshakeState, raw: &str) -> Result<HandshakePhase, HandshakeRejection> {
+        let wire = HandshakeWireEvent::decode(raw)?;
+        if wire.session_id != state.session_id {
+            return Err(HandshakeRejection::SessionMismatch { expected: state.session_id.clone(), got: wire.session_id });
+        }
+        Self::apply(state, &wire.event, wire.at_ms)
+    }
+
+    fn reset_to_safety(state: &mut NeuroHandshakeState, now_ms: u64, cause: &str) {
+        state.safety_confirmed = false;
+        state.calibration_samples_collected = 0;
+        Self::enter(state, HandshakePhase::Safety, now_ms, cause);
+    }
+
+    fn enter(state: &mut NeuroHandshakeState, to: HandshakePhase, now_ms: u64, cause: &str) {
+        let seq = state.history.last().map(|t| t.seq + 1).unwrap_or(0);
+        state.history.push(HandshakeTransition { seq, at_ms: now_ms, from: state.phase, to, cause: cause.to_string() });
+        if state.history.len() > MAX_HANDSHAKE_HISTORY {
+            let excess = state.history.len() - MAX_HANDSHAKE_HISTORY;
+            state.history.drain(..excess);
+        }
+        state.phase = to;
+        state.phase_entered_at_ms = Some(now_ms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_spellings_parse_to_the_same_event() {
+        for raw in ["user_consented", "user-consented", "userconsented", "User Consented"] {
+            assert_eq!(raw.parse::<HandshakeEvent>().unwrap(), HandshakeEvent::UserConsented);
+        }
+        assert!(matches!("wave".parse::<HandshakeEvent>(), Err(HandshakeRejection::UnknownEvent { .. })));
+    }
+
+    #[test]
+    fn full_path_revocation_and_illegal_events() {
+        let mut s = NeuroHandshakeOrchestrator::initial_with("s1", 2, HandshakeTimeouts::default(), Some(0));
+        let sample = HandshakeEvent::CalibrationSampleRecorded { channel: Some("eeg".into()) };
+        assert!(matches!(
+            NeuroHandshakeOrchestrator::apply(&mut s, &sample, 1),
+            Err(HandshakeRejection::IllegalTransition { phase: HandshakePhase::Safety, .. })
+        ));
+        assert_eq!(NeuroHandshakeOrchestrator::apply(&mut s, &HandshakeEvent::UserConsented, 2), Ok(HandshakePhase::Calibration));
+        assert_eq!(NeuroHandshakeOrchestrator::apply(&mut s, &sample, 3), Ok(HandshakePhase::Calibration));
+        assert_eq!(NeuroHandshakeOrchestrator::apply(&mut s, &sample, 4), Ok(HandshakePhase::Operation));
+
+        let revoke = HandshakeEvent::ConsentRevoked { reason: None };
+        assert_eq!(NeuroHandshakeOrchestrator::apply(&mut s, &revoke, 5), Ok(HandshakePhase::Safety));
+        assert!(!s.safety_confirmed);
+        assert_eq!(s.consent_revocations, 1);
+        assert_eq!(s.history.len(), 3);
+        assert!(NeuroHandshakeOrchestrator::apply(&mut s, &revoke, 6).is_err());
+        assert!(!NeuroHandshakeOrchestrator::may_auto_consent(&s));
+        assert!(NeuroHandshakeOrchestrator::may_auto_consent(&NeuroHandshakeOrchestrator::initial("s1b", 2)));
+    }
+
+    #[test]
+    fn calibration_timeout_resets_and_record_round_trips() {
+        let timeouts = HandshakeTimeouts { safety_ms: None, calibration_ms: Some(100), operation_ms: None };
+        let mut s = NeuroHandshakeOrchestrator::initial_with("s2", 3, timeouts, Some(0));
+        NeuroHandshakeOrchestrator::apply_label(&mut s, "user-consented", 10).unwrap();
+        let err = NeuroHandshakeOrchestrator::apply_label(&mut s, "calibration_sample_recorded", 200).unwrap_err();
+        assert!(matches!(err, HandshakeRejection::TimedOut { phase: HandshakePhase::Calibration, .. }));
+        assert_eq!(s.phase, HandshakePhase::Safety);
+        assert!(NeuroHandshakeOrchestrator::may_auto_consent(&s));
+
+        let raw = serde_json::to_string(&s).unwrap();
+        let resumed = NeuroHandshakeOrchestrator::resume(&raw).unwrap();
+        assert_eq!(resumed.history, s.history);
+
+        let wire = serde_json::to_string(&HandshakeWireEvent::new("other", HandshakeEvent::UserConsented, 300)).unwrap();
+        assert!(matches!(
+            NeuroHandshakeOrchestrator::apply_wire(&mut s, &wire),
+            Err(HandshakeRejection::SessionMismatch { .. })
+        ));
+        let legacy = r#"{"session_id":"s3","phase":"Safety","safety_confirmed":false,"calibration_samples_collected":0,"required_calibration_samples":3}"#;
+        assert_eq!(NeuroHandshakeOrchestrator::resume(legacy).unwrap().version, HANDSHAKE_WIRE_VERSION);
+    }
+
+    #[test]
+    fn stale_safety_summary_is_refused_once_then_consent_proceeds() {
+        let timeouts = HandshakeTimeouts { safety_ms: Some(100), calibration_ms: None, operation_ms: None };
+        let mut s = NeuroHandshakeOrchestrator::initial_with("s4", 1, timeouts, Some(0));
+        let err = NeuroHandshakeOrchestrator::apply(&mut s, &HandshakeEvent::UserConsented, 500).unwrap_err();
+        assert!(matches!(err, HandshakeRejection::TimedOut { phase: HandshakePhase::Safety, .. }));
+        assert_eq!(s.phase_entered_at_ms, Some(500));
+        assert_eq!(NeuroHandshakeOrchestrator::apply(&mut s, &HandshakeEvent::UserConsented, 550), Ok(HandshakePhase::Calibration));
+    }
+
+    #[test]
+    fn revocation_survives_a_safety_timeout_but_a_plain_timeout_does_not_block() {
+        let timeouts = HandshakeTimeouts { safety_ms: Some(100), calibration_ms: None, operation_ms: None };
+        let mut s = NeuroHandshakeOrchestrator::initial_with("s6", 1, timeouts, Some(0));
+        assert!(NeuroHandshakeOrchestrator::expire(&mut s, 500).is_some());
+        assert!(NeuroHandshakeOrchestrator::may_auto_consent(&s));
+
+        NeuroHandshakeOrchestrator::apply(&mut s, &HandshakeEvent::UserConsented, 550).unwrap();
+        NeuroHandshakeOrchestrator::apply(&mut s, &HandshakeEvent::ConsentRevoked { reason: None }, 560).unwrap();
+        assert!(NeuroHandshakeOrchestrator::expire(&mut s, 1_000).is_some());
+        assert!(!NeuroHandshakeOrchestrator::may_auto_consent(&s));
+
+        NeuroHandshakeOrchestrator::apply(&mut s, &HandshakeEvent::UserConsented, 1_010).unwrap();
+        assert!(NeuroHandshakeOrchestrator::may_auto_consent(&s));
+    }
+
+    #[test]
+    fn history_is_capped_and_keeps_counting() {
+        let mut s = NeuroHandshakeOrchestrator::initial_with("s5", 0, HandshakeTimeouts::default(), Some(0));
+        let revoke = HandshakeEvent::ConsentRevoked { reason: None };
+        for i in 0..100u64 {
+            NeuroHandshakeOrchestrator::apply(&mut s, &HandshakeEvent::UserConsented, 2 * i + 1).unwrap();
+            NeuroHandshakeOrchestrator::apply(&mut s, &revoke, 2 * i + 2).unwrap();
         }
-        state
+        assert_eq!(s.history.len(), MAX_HANDSHAKE_HISTORY);
+        assert_eq!(s.history.last().unwrap().seq, 199);
+        assert_eq!(s.history.first().unwrap().seq, 200 - MAX_HANDSHAKE_HISTORY as u64);
     }
 }
@@ -1,4 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ShotLevel {
@@ -43,6 +48,22 @@ impl ShotLevelPolicy {
         ShotLevelPolicy { cfg }
     }
 
+    pub fn config(&self) -> &ShotLevelPolicyConfig {
+        &self.cfg
+    }
+
+    /// `decide`, plus a feedback record so the outcome can be attached later.
+    /// Returns the id to pass to `ShotFeedbackLog::record_outcome`.
+    pub fn decide_recorded(
+        &self,
+        signal: &ShotLevelSignal,
+        log: &mut ShotFeedbackLog,
+    ) -> std::io::Result<(u64, ShotLevelDecision)> {
+        let decision = self.decide(signal);
+        let id = log.record_decision(signal, &decision, &self.cfg)?;
+        Ok((id, decision))
+    }
+
     pub fn decide(&self, signal: &ShotLevelSignal) -> ShotLevelDecision {
         let mut use_few_shot = false;
         let mut reasons: Vec<String> = Vec::new();
@@ -98,3 +119,551 @@ impl ShotLevelPolicy {
         }
     }
 }
+
+/// What happened after a decision was acted on.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ShotOutcome {
+    pub reward: f32,
+    /// A downstream safety guard vetoed the resulting action.
+    pub safety_veto: bool,
+}
+
+/// One line of the feedback log. Decisions and outcomes are written
+/// separately because the outcome is only known later.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "entry", rename_all = "snake_case")]
+pub enum ShotFeedbackEntry {
+    Decision {
+        id: u64,
+        signal: ShotLevelSignal,
+        decision: ShotLevelDecision,
+        config: ShotLevelPolicyConfig,
+    },
+    Outcome {
+        id: u64,
+        outcome: ShotOutcome,
+    },
+}
+
+/// A decision joined with its outcome, if one was reported.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ShotFeedbackRecord {
+    pub id: u64,
+    pub signal: ShotLevelSignal,
+    pub decision: ShotLevelDecision,
+    pub config: ShotLevelPolicyConfig,
+    pub outcome: Option<ShotOutcome>,
+}
+
+/// Append-only JSONL feedback channel. Without a path it only keeps entries
+/// in memory.
+#[derive(Debug, Default)]
+pub struct ShotFeedbackLog {
+    path: Option<PathBuf>,
+    entries: Vec<ShotFeedbackEntry>,
+    next_id: u64,
+}
+
+impl ShotFeedbackLog {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Open (or create) a log file, continuing its id sequence.
+    pub fn open<P: AsRef<Path>>(path: P) -> std::io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let entries = if path.exists() { Self::read_entries(&path, true)? } else { Vec::new() };
+        let next_id = entries
+            .iter()
+            .filter_map(|e| match e {
+                ShotFeedbackEntry::Decision { id, .. } => Some(id + 1),
+                ShotFeedbackEntry::Outcome { .. } => None,
+            })
+            .max()
+            .unwrap_or(0);
+        Ok(Self { path: Some(path), entries, next_id })
+    }
+
+    pub fn record_decision(
+        &mut self,
+        signal: &ShotLevelSignal,
+        decision: &ShotLevelDecision,
+        config: &ShotLevelPolicyConfig,
+    ) -> std::io::Result<u64> {
+        let id = self.next_id;
+        self.push(ShotFeedbackEntry::Decision {
+            id,
+            signal: signal.clone(),
+            decision: decision.clone(),
+            config: config.clone(),
+        })?;
+        self.next_id += 1;
+        Ok(id)
+    }
+
+    pub fn record_outcome(&mut self, id: u64, outcome: ShotOutcome) -> std::io::Result<()> {
+        self.push(ShotFeedbackEntry::Outcome { id, outcome })
+    }
+
+    /// Decisions joined with their latest outcome, in id order.
+    pub fn records(&self) -> Vec<ShotFeedbackRecord> {
+        join_entries(&self.entries)
+    }
+
+    /// Read and join a log file without opening it for writing.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<ShotFeedbackRecord>> {
+        Ok(join_entries(&Self::read_entries(path.as_ref(), false)?))
+    }
+
+    fn push(&mut self, entry: ShotFeedbackEntry) -> std::io::Result<()> {
+        if let Some(path) = &self.path {
+            let mut line = serde_json::to_vec(&entry)?;
+            line.push(b'\n');
+            let mut f = OpenOptions::new().create(true).append(true).open(path)?;
+            let len = f.metadata()?.len();
+            if let Err(e) = f.write_all(&line).and_then(|_| f.sync_data()) {
+                let _ = f.set_len(len);
+                return Err(e);
+            }
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// An unterminated final line that does not parse is a torn append and is
+    /// skipped. With `repair` it is also cut off the file, and a complete final
+    /// line missing its newline is terminated, so later appends start clean.
+    fn read_entries(path: &Path, repair: bool) -> std::io::Result<Vec<ShotFeedbackEntry>> {
+        let mut out = Vec::new();
+        let mut reader = BufReader::new(fs::File::open(path)?);
+        let mut line = Vec::new();
+        let mut offset = 0;
+        loop {
+            line.clear();
+            let read = reader.read_until(b'\n', &mut line)?;
+            if read == 0 {
+                break;
+            }
+            let terminated = line.ends_with(b"\n");
+            if !line.iter().all(u8::is_ascii_whitespace) {
+                match serde_json::from_slice(&line) {
+                    Ok(entry) => out.push(entry),
+                    Err(_) if !terminated => {
+                        if repair {
+                            let f = OpenOptions::new().write(true).open(path)?;
+                            f.set_len(offset)?;
+                            f.sync_data()?;
+                        }
+                        break;
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            if !terminated && repair {
+                let mut f = OpenOptions::new().append(true).open(path)?;
+                f.write_all(b"\n")?;
+                f.sync_data()?;
+            }
+            offset += read as u64;
+        }
+        Ok(out)
+    }
+}
+
+fn join_entries(entries: &[ShotFeedbackEntry]) -> Vec<ShotFeedbackRecord> {
+    let mut by_id: BTreeMap<u64, ShotFeedbackRecord> = BTreeMap::new();
+    for e in entries {
+        match e {
+            ShotFeedbackEntry::Decision { id, signal, decision, config } => {
+                by_id.insert(
+                    *id,
+                    ShotFeedbackRecord {
+                        id: *id,
+                        signal: signal.clone(),
+                        decision: decision.clone(),
+                        config: config.clone(),
+                        outcome: None,
+                    },
+                );
+            }
+            ShotFeedbackEntry::Outcome { id, outcome } => {
+                if let Some(r) = by_id.get_mut(id) {
+                    r.outcome = Some(outcome.clone());
+                }
+            }
+        }
+    }
+    by_id.into_values().collect()
+}
+
+/// Limits on what the calibrator may propose.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CalibrationBounds {
+    /// Zero-shot veto rate above which a threshold band is considered unsafe.
+    pub max_zero_shot_veto_rate: f32,
+    /// Fewest zero-shot outcomes needed before a band can move a threshold.
+    pub min_samples: usize,
+    /// Largest single-step decrease of a threshold.
+    pub max_step: f32,
+    /// Thresholds are never proposed below this floor.
+    pub floor: f32,
+}
+
+impl Default for CalibrationBounds {
+    fn default() -> Self {
+        Self { max_zero_shot_veto_rate: 0.05, min_samples: 20, max_step: 0.15, floor: 0.05 }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LevelStats {
+    pub decisions: usize,
+    pub with_outcome: usize,
+    pub vetoes: usize,
+    pub mean_reward: f32,
+}
+
+impl LevelStats {
+    pub fn veto_rate(&self) -> f32 {
+        if self.with_outcome == 0 {
+            0.0
+        } else {
+            self.vetoes as f32 / self.with_outcome as f32
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ThresholdProposal {
+    pub name: String,
+    pub current: f32,
+    pub proposed: f32,
+    /// Zero-shot outcomes in the band `[proposed, current)`.
+    pub band_samples: usize,
+    pub band_veto_rate: f32,
+    pub rationale: String,
+}
+
+impl ThresholdProposal {
+    pub fn changed(&self) -> bool {
+        self.proposed != self.current
+    }
+}
+
+/// Reviewable output of `ShotLevelCalibrator::calibrate`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    pub records: usize,
+    pub records_with_outcome: usize,
+    pub zero_shot: LevelStats,
+    pub few_shot: LevelStats,
+    pub proposals: Vec<ThresholdProposal>,
+    pub current: ShotLevelPolicyConfig,
+    pub proposed: ShotLevelPolicyConfig,
+    pub notes: Vec<String>,
+}
+
+impl fmt::Display for CalibrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ShotLevelPolicy calibration: {} records ({} with outcome)", self.records, self.records_with_outcome)?;
+        for (label, s) in [("zero-shot", &self.zero_shot), ("few-shot", &self.few_shot)] {
+            writeln!(
+                f,
+                "  {label:<9} decisions={} outcomes={} veto_rate={:.3} mean_reward={:.3}",
+                s.decisions,
+                s.with_outcome,
+                s.veto_rate(),
+                s.mean_reward
+            )?;
+        }
+        for p in &self.proposals {
+            let mark = if p.changed() { "CHANGE" } else { "keep" };
+            writeln!(
+                f,
+                "  [{mark}] {} {:.3} -> {:.3} (band samples={}, veto_rate={:.3}): {}",
+                p.name, p.current, p.proposed, p.band_samples, p.band_veto_rate, p.rationale
+            )?;
+        }
+        for n in &self.notes {
+            writeln!(f, "  note: {n}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Offline calibrator: replays feedback records and proposes thresholds.
+///
+/// Only the safety-relevant triggers (`risk_threshold_for_few_shot`,
+/// `error_rate_threshold_for_few_shot`) are calibrated, and only downwards:
+/// a lower trigger sends more tasks to few-shot, never fewer. A threshold
+/// moves when zero-shot decisions just below it were vetoed more often than
+/// `max_zero_shot_veto_rate`.
+#[derive(Clone, Debug, Default)]
+pub struct ShotLevelCalibrator {
+    pub bounds: CalibrationBounds,
+}
+
+impl ShotLevelCalibrator {
+    pub fn new(bounds: CalibrationBounds) -> Self {
+        Self { bounds }
+    }
+
+    pub fn calibrate(&self, current: &ShotLevelPolicyConfig, records: &[ShotFeedbackRecord]) -> CalibrationReport {
+        let zero_shot = level_stats(records, ShotLevel::ZeroShot);
+        let few_shot = level_stats(records, ShotLevel::FewShot);
+
+        let risk = self.propose(
+            "risk_threshold_for_few_shot",
+            current.risk_threshold_for_few_shot,
+            records,
+            |s| s.risk_score,
+        );
+        let error_rate = self.propose(
+            "error_rate_threshold_for_few_shot",
+            current.error_rate_threshold_for_few_shot,
+            records,
+            |s| s.historical_error_rate,
+        );
+
+        let mut proposed = current.clone();
+        proposed.risk_threshold_for_few_shot = risk.proposed;
+        proposed.error_rate_threshold_for_few_shot = error_rate.proposed;
+
+        let mut notes = vec![String::from(
+            "latency/token minimums and max_examples_few_shot are not calibrated (resource limits, not safety)",
+        )];
+        if few_shot.with_outcome > 0 && few_shot.veto_rate() > self.bounds.max_zero_shot_veto_rate {
+            notes.push(format!(
+                "few-shot veto rate {:.3} is itself above the target; lowering thresholds alone will not fix it",
+                few_shot.veto_rate()
+            ));
+        }
+        let mixed = records.iter().filter(|r| !same_thresholds(&r.config, current)).count();
+        if mixed > 0 {
+            notes.push(format!("{mixed} records were decided under a different config"));
+        }
+
+        CalibrationReport {
+            records: records.len(),
+            records_with_outcome: records.iter().filter(|r| r.outcome.is_some()).count(),
+            zero_shot,
+            few_shot,
+            proposals: vec![risk, error_rate],
+            current: current.clone(),
+            proposed,
+            notes,
+        }
+    }
+
+    fn propose(
+        &self,
+        name: &str,
+        current: f32,
+        records: &[ShotFeedbackRecord],
+        feature: impl Fn(&ShotLevelSignal) -> f32,
+    ) -> ThresholdProposal {
+        let lowest = (current - self.bounds.max_step).max(self.bounds.floor).min(current);
+        // Zero-shot outcomes inside the movable band, highest feature first.
+        let mut band: Vec<(f32, bool)> = records
+            .iter()
+            .filter(|r| r.decision.chosen_level == ShotLevel::ZeroShot)
+            .filter_map(|r| r.outcome.as_ref().map(|o| (feature(&r.signal), o.safety_veto)))
+            .filter(|(x, _)| x.is_finite() && *x < current && *x >= lowest)
+            .collect();
+        band.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        // Walk down from the current threshold; the new threshold is the lowest
+        // point at which the band [x, current) is still too unsafe.
+        let mut proposed = current;
+        let mut vetoes = 0usize;
+        let (mut chosen_n, mut chosen_rate) = (0usize, 0.0f32);
+        for (i, (x, veto)) in band.iter().enumerate() {
+            vetoes += usize::from(*veto);
+            let n = i + 1;
+            let rate = vetoes as f32 / n as f32;
+            if n >= self.bounds.min_samples && rate > self.bounds.max_zero_shot_veto_rate {
+                proposed = *x;
+                chosen_n = n;
+                chosen_rate = rate;
+            }
+        }
+        let proposed = proposed.clamp(lowest, current);
+        let rationale = if proposed < current {
+            format!(
+                "zero-shot veto rate {:.3} over {} samples in [{:.3}, {:.3}) exceeds {:.3}",
+                chosen_rate, chosen_n, proposed, current, self.bounds.max_zero_shot_veto_rate
+            )
+        } else if band.len() < self.bounds.min_samples {
+            format!("only {} zero-shot outcomes below threshold (need {})", band.len(), self.bounds.min_samples)
+        } else {
+            String::from("zero-shot outcomes below threshold are within the veto budget")
+        };
+        let band_veto_rate = if chosen_n > 0 {
+            chosen_rate
+        } else if band.is_empty() {
+            0.0
+        } else {
+            band.iter().filter(|(_, v)| *v).count() as f32 / band.len() as f32
+        };
+        ThresholdProposal {
+            name: name.to_string(),
+            current,
+            proposed,
+            band_samples: if chosen_n > 0 { chosen_n } else { band.len() },
+            band_veto_rate,
+            rationale,
+        }
+    }
+}
+
+fn same_thresholds(a: &ShotLevelPolicyConfig, b: &ShotLevelPolicyConfig) -> bool {
+    a.risk_threshold_for_few_shot == b.risk_threshold_for_few_shot
+        && a.error_rate_threshold_for_few_shot == b.error_rate_threshold_for_few_shot
+        && a.min_latency_for_few_shot_ms == b.min_latency_for_few_shot_ms
+        && a.min_token_budget_for_few_shot == b.min_token_budget_for_few_shot
+}
+
+fn level_stats(records: &[ShotFeedbackRecord], level: ShotLevel) -> LevelStats {
+    let mut s = LevelStats::default();
+    let mut reward_sum = 0.0f32;
+    for r in records.iter().filter(|r| r.decision.chosen_level == level) {
+        s.decisions += 1;
+        if let Some(o) = &r.outcome {
+            s.with_outcome += 1;
+            s.vetoes += usize::from(o.safety_veto);
+            reward_sum += o.reward;
+        }
+    }
+    if s.with_outcome > 0 {
+        s.mean_reward = reward_sum / s.with_outcome as f32;
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ShotLevelPolicyConfig {
+        ShotLevelPolicyConfig {
+            max_examples_few_shot: 4,
+            risk_threshold_for_few_shot: 0.5,
+            error_rate_threshold_for_few_shot: 0.2,
+            min_latency_for_few_shot_ms: 250,
+            min_token_budget_for_few_shot: 512,
+        }
+    }
+
+    fn signal(risk: f32) -> ShotLevelSignal {
+        ShotLevelSignal {
+            task_id: String::from("bci-grab"),
+            plane_label: String::from("bci/hci/eeg"),
+            risk_score: risk,
+            latency_budget_ms: 1000,
+            token_budget: 2048,
+            historical_error_rate: 0.0,
+            requires_examples: false,
+        }
+    }
+
+    /// Zero-shot decisions at risk 0.40..0.50 are vetoed a third of the time.
+    fn replay(policy: &ShotLevelPolicy) -> ShotFeedbackLog {
+        let mut log = ShotFeedbackLog::in_memory();
+        for i in 0..60 {
+            let risk = 0.40 + (i % 10) as f32 / 100.0;
+            let (id, decision) = policy.decide_recorded(&signal(risk), &mut log).unwrap();
+            assert_eq!(decision.chosen_level, ShotLevel::ZeroShot);
+            log.record_outcome(id, ShotOutcome { reward: 0.5, safety_veto: i % 3 == 0 }).unwrap();
+        }
+        log
+    }
+
+    #[test]
+    fn unsafe_band_lowers_risk_threshold_within_bounds() {
+        let policy = ShotLevelPolicy::new(config());
+        let records = replay(&policy).records();
+        let report = ShotLevelCalibrator::default().calibrate(policy.config(), &records);
+
+        let risk = &report.proposals[0];
+        assert!(risk.changed());
+        assert!(risk.proposed < 0.5 && risk.proposed >= 0.5 - CalibrationBounds::default().max_step);
+        assert_eq!(report.proposed.risk_threshold_for_few_shot, risk.proposed);
+        assert!(!report.proposals[1].changed());
+        assert!(report.to_string().contains("[CHANGE] risk_threshold_for_few_shot"));
+    }
+
+    #[test]
+    fn thresholds_never_loosen() {
+        let policy = ShotLevelPolicy::new(config());
+        let mut log = ShotFeedbackLog::in_memory();
+        for i in 0..100 {
+            let (id, _) = policy.decide_recorded(&signal(i as f32 / 100.0), &mut log).unwrap();
+            log.record_outcome(id, ShotOutcome { reward: 1.0, safety_veto: false }).unwrap();
+        }
+        let report = ShotLevelCalibrator::default().calibrate(policy.config(), &log.records());
+        assert_eq!(report.proposed.risk_threshold_for_few_shot, 0.5);
+        assert_eq!(report.proposed.error_rate_threshold_for_few_shot, 0.2);
+        assert_eq!(report.zero_shot.decisions + report.few_shot.decisions, 100);
+    }
+
+    #[test]
+    fn log_file_round_trips_and_joins_late_outcomes() {
+        let path = std::env::temp_dir().join(format!("shot-feedback-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let policy = ShotLevelPolicy::new(config());
+        {
+            let mut log = ShotFeedbackLog::open(&path).unwrap();
+            policy.decide_recorded(&signal(0.1), &mut log).unwrap();
+            policy.decide_recorded(&signal(0.9), &mut log).unwrap();
+        }
+        let mut log = ShotFeedbackLog::open(&path).unwrap();
+        log.record_outcome(1, ShotOutcome { reward: 0.2, safety_veto: true }).unwrap();
+        let (id, _) = policy.decide_recorded(&signal(0.3), &mut log).unwrap();
+        assert_eq!(id, 2);
+
+        let records = ShotFeedbackLog::load(&path).unwrap();
+        assert_eq!(records.len(), 3);
+        assert!(records[0].outcome.is_none());
+        assert_eq!(records[1].outcome, Some(ShotOutcome { reward: 0.2, safety_veto: true }));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn torn_final_line_is_skipped_and_repaired_on_open() {
+        let path = std::env::temp_dir().join(format!("shot-feedback-torn-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let policy = ShotLevelPolicy::new(config());
+        {
+            let mut log = ShotFeedbackLog::open(&path).unwrap();
+            policy.decide_recorded(&signal(0.1), &mut log).unwrap();
+            policy.decide_recorded(&signal(0.9), &mut log).unwrap();
+        }
+        let intact = fs::read(&path).unwrap();
+        let mut f = OpenOptions::new().append(true).open(&path).unwrap();
+        f.write_all(br#"{"entry":"outcome","id":1,"outco"#).unwrap();
+        drop(f);
+
+        assert_eq!(ShotFeedbackLog::load(&path).unwrap().len(), 2);
+        let mut log = ShotFeedbackLog::open(&path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), intact);
+        log.record_outcome(1, ShotOutcome { reward: 0.4, safety_veto: false }).unwrap();
+        let records = ShotFeedbackLog::load(&path).unwrap();
+        assert_eq!(records[1].outcome, Some(ShotOutcome { reward: 0.4, safety_veto: false }));
+
+        // A complete entry that only lost its newline is kept and terminated.
+        fs::write(&path, &intact[..intact.len() - 1]).unwrap();
+        let mut log = ShotFeedbackLog::open(&path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), intact);
+        let (id, _) = policy.decide_recorded(&signal(0.3), &mut log).unwrap();
+        assert_eq!(id, 2);
+        assert_eq!(ShotFeedbackLog::load(&path).unwrap().len(), 3);
+
+        // Corruption before the final line is still an error.
+        fs::write(&path, [b"garbage\n".as_slice(), &intact].concat()).unwrap();
+        assert!(ShotFeedbackLog::open(&path).is_err());
+        let _ = fs::remove_file(&path);
+    }
+}
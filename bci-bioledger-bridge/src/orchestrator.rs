@@ -1,7 +1,8 @@
 use crate::mapper::map_bci_to_adjustment;
 use crate::types::{BciEvent, BciLedgerResult};
 use augdoctorpolicies::neurohandshakeorchestrator::{
-    HandshakePhase, NeuroHandshakeOrchestrator, NeuroHandshakeState,
+    unix_millis, HandshakeEvent, HandshakePhase, HandshakeRejection, NeuroHandshakeOrchestrator,
+    NeuroHandshakeState,
 };
 use augdoctorpolicies::shotlevelpolicy::{ShotLevel, ShotLevelDecision, ShotLevelPolicy,
     ShotLevelPolicyConfig, ShotLevelSignal};
@@ -15,8 +16,26 @@ use thiserror::Error;
 pub enum BridgeError {
     #[error("inner-ledger error: {0}")]
     Inner(#[from] InnerLedgerError),
-    #[error("handshake not yet in operation phase")]
-    NotReady,
+    /// Carries the advanced handshake so the caller can persist progress.
+    #[error("handshake not yet in operation phase (phase {:?})", .0.phase)]
+    NotReady(Box<NeuroHandshakeState>),
+    /// The handshake refused the event; carries the (possibly reset) state.
+    #[error("handshake rejected: {rejection}")]
+    Handshake {
+        rejection: HandshakeRejection,
+        handshake: Box<NeuroHandshakeState>,
+    },
+}
+
+impl BridgeError {
+    /// Handshake state to persist after a failed call, if the failure touched it.
+    pub fn handshake(&self) -> Option<&NeuroHandshakeState> {
+        match self {
+            BridgeError::NotReady(h) => Some(h),
+            BridgeError::Handshake { handshake, .. } => Some(handshake),
+            BridgeError::Inner(_) => None,
+        }
+    }
 }
 
 /// BCI → inner-ledger orchestrator.
@@ -55,30 +74,31 @@ impl<'a> BciLedgerOrchestrator<'a> {
         timestamp_utc: &str,
     ) -> Result<(BciLedgerResult, NeuroHandshakeState, Option<LedgerEvent>, ShotLevelDecision), BridgeError> {
         // 1. Neuro-handshake progression.
-        let actions = NeuroHandshakeOrchestrator::next_actions(handshake.clone());
-        if handshake.phase == HandshakePhase::Safety {
-            // In real system: UI already collected consent.
-            if !handshake.safety_confirmed {
-                handshake = NeuroHandshakeOrchestrator::apply_event(handshake, "user-consented");
+        let now_ms = unix_millis();
+        let step = match handshake.phase {
+            // In real system: UI already collected consent. Consent is implied
+            // unless the user revoked it (a timeout reset does not count); after
+            // a revocation the user must consent again through the handshake.
+            HandshakePhase::Safety if NeuroHandshakeOrchestrator::may_auto_consent(&handshake) => {
+                Some(HandshakeEvent::UserConsented)
             }
-        }
-        if handshake.phase == HandshakePhase::Calibration {
+            HandshakePhase::Safety => None,
             // In real system: calibration samples collected per event.
-            handshake =
-                NeuroHandshakeOrchestrator::apply_event(handshake, "calibration-sample-recorded");
+            HandshakePhase::Calibration => Some(HandshakeEvent::CalibrationSampleRecorded {
+                channel: Some(event.channel.clone()),
+            }),
+            HandshakePhase::Operation => None,
+        };
+        if let Some(step) = step {
+            if let Err(rejection) = NeuroHandshakeOrchestrator::apply(&mut handshake, &step, now_ms) {
+                return Err(BridgeError::Handshake { rejection, handshake: Box::new(handshake) });
+            }
+        } else if let Some(rejection) = NeuroHandshakeOrchestrator::expire(&mut handshake, now_ms) {
+            return Err(BridgeError::Handshake { rejection, handshake: Box::new(handshake) });
         }
         if handshake.phase != HandshakePhase::Operation {
-            // Not ready yet; we return NotReady with no ledger mutation.
-            let res = BciLedgerResult {
-                session_id: event.session_id.clone(),
-                host_id: event.host_id.clone(),
-                intent_label: event.intent_label.clone(),
-                applied: false,
-                reason: format!("handshake-phase:{:?}-actions:{:?}", handshake.phase, actions),
-                prev_state_hash: Some(self.ledger.last_state_hash.clone()),
-                new_state_hash: None,
-            };
-            return Err(BridgeError::NotReady);
+            // Not ready yet; no ledger mutation, but the advanced handshake goes back.
+            return Err(BridgeError::NotReady(Box::new(handshake)));
         }
 
         // 2. Decide zero-shot vs few-shot for LLM side (if you wire it).
@@ -131,3 +151,97 @@ impl<'a> BciLedgerOrchestrator<'a> {
         Ok((res, handshake, Some(ledger_event), shot_decision))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use augdoctorpolicies::neurohandshakeorchestrator::HandshakeTimeouts;
+    use biophysical_blockchain::{BioTokenState, HostEnvelope};
+
+    const HOST: &str = "bostrom18sd2ujv24ual9c9pshtxys6j8knh6xaead9ye7";
+
+    fn ledger() -> InnerLedger {
+        let env = HostEnvelope {
+            host_id: HOST.to_string(),
+            brain_min: 0.0,
+            blood_min: 0.2,
+            oxygen_min: 0.9,
+            nano_max_fraction: 0.25,
+            smart_max: 1.0,
+            eco_flops_limit: 10_000.0,
+        };
+        let state = BioTokenState { brain: 0.5, wave: 0.0, blood: 0.8, oxygen: 0.97, nano: 0.0, smart: 0.0 };
+        InnerLedger::new(env, state)
+    }
+
+    fn event() -> BciEvent {
+        BciEvent {
+            session_id: "session-rev".to_string(),
+            host_id: HOST.to_string(),
+            environment_id: "phx-lab".to_string(),
+            channel: "eeg".to_string(),
+            intent_label: "cursor-move".to_string(),
+            risk_score: 0.1,
+            latency_budget_ms: 200,
+            token_budget: 512,
+            eco_cost_estimate: 50.0,
+        }
+    }
+
+    fn id_header() -> IdentityHeader {
+        IdentityHeader {
+            issuer_did: HOST.to_string(),
+            subject_role: "system-daemon".to_string(),
+            network_tier: "inner-core".to_string(),
+            knowledge_factor: 0.9,
+        }
+    }
+
+    #[test]
+    fn timed_out_session_moves_to_calibration_on_the_next_event() {
+        let mut ledger = ledger();
+        let mut rope = NeuralRope::new("bci-hci-eeg".to_string());
+        let timeouts = HandshakeTimeouts { safety_ms: Some(100), calibration_ms: None, operation_ms: None };
+        let handshake = NeuroHandshakeOrchestrator::initial_with("session-idle", 3, timeouts, Some(0));
+
+        let mut orchestrator = BciLedgerOrchestrator::new(&mut ledger, &mut rope);
+        let reset = match orchestrator.handle_bci_event(&event(), handshake, &id_header(), 0.6, "2026-01-27T19:00:00Z") {
+            Err(BridgeError::Handshake { rejection: HandshakeRejection::TimedOut { .. }, handshake }) => handshake,
+            Err(other) => panic!("expected a timeout, got {other}"),
+            Ok(_) => panic!("expected a timeout, got a committed event"),
+        };
+        assert_eq!(reset.phase, HandshakePhase::Safety);
+
+        let next = match orchestrator.handle_bci_event(&event(), *reset, &id_header(), 0.6, "2026-01-27T19:00:01Z") {
+            Err(BridgeError::NotReady(h)) => h,
+            Err(other) => panic!("expected NotReady, got {other}"),
+            Ok(_) => panic!("expected NotReady, got a committed event"),
+        };
+        assert_eq!(next.phase, HandshakePhase::Calibration);
+    }
+
+    #[test]
+    fn revoked_consent_is_not_restored_by_the_next_event() {
+        let mut ledger = ledger();
+        let mut rope = NeuralRope::new("bci-hci-eeg".to_string());
+        let mut handshake = NeuroHandshakeOrchestrator::initial("session-rev", 0);
+        let now_ms = unix_millis();
+        NeuroHandshakeOrchestrator::apply(&mut handshake, &HandshakeEvent::UserConsented, now_ms).unwrap();
+        NeuroHandshakeOrchestrator::apply(&mut handshake, &HandshakeEvent::ConsentRevoked { reason: None }, now_ms)
+            .unwrap();
+        let hash_before = ledger.last_state_hash.clone();
+
+        let mut orchestrator = BciLedgerOrchestrator::new(&mut ledger, &mut rope);
+        let err = orchestrator
+            .handle_bci_event(&event(), handshake, &id_header(), 0.6, "2026-01-27T19:00:00Z")
+            .unwrap_err();
+        let returned = match err {
+            BridgeError::NotReady(h) => h,
+            other => panic!("expected NotReady, got {other}"),
+        };
+        assert_eq!(returned.phase, HandshakePhase::Safety);
+        assert!(!returned.safety_confirmed);
+        assert_eq!(returned.consent_revocations, 1);
+        assert_eq!(orchestrator.ledger.last_state_hash, hash_before);
+    }
+}
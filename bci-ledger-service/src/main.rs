@@ -1,59 +1,42 @@
 mod security;
-mod civic_profile;
+mod store;
 
-use civic_profile::CivicRewardProfile;
 use bci_bioledger_bridge::{BciEvent, BciLedgerOrchestrator};
-use biophysical_blockchain::{BioTokenState, HostEnvelope, InnerLedger, IdentityHeader};
+use biophysical_blockchain::{BioTokenState, HostEnvelope, IdentityHeader};
 use bioscaleupgradeservice::neuralrope::NeuralRope;
 use hyper::{
     body::Bytes,
     service::{make_service_fn, service_fn},
     Body, Method, Request, Response, StatusCode,
 };
-use security::{classify_civic, civic_reward_multiplier, AuthEnvelope, CivicClass};
+use civic_policy::{CivicClass, CivicPolicy, FiredRule};
+use security::AuthEnvelope;
 use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
 use std::sync::{Arc, Mutex};
+use store::{HostTemplate, LedgerStore};
 use tokio::net::TcpListener;
-use uuid::Uuid;
+
+/// Calibration samples required before a new handshake session reaches Operation.
+const REQUIRED_CALIBRATION_SAMPLES: u32 = 3;
 
 #[derive(Clone)]
 struct AppState {
-    ledger: Arc<Mutex<InnerLedger>>,
+    store: Arc<LedgerStore>,
     rope: Arc<Mutex<NeuralRope>>,
-    civic_profile: Arc<CivicRewardProfile>,
+    civic_policy: Arc<CivicPolicy>,
 }
 
-fn init_ledger() -> InnerLedger { /* unchanged */ }
-
-fn init_civic_profile() -> CivicRewardProfile {
-    CivicRewardProfile::load_from_json(
-        "qpu/data/shards/profiles/civic-reward-profile.json",
-    )
-    .unwrap_or_else(|_| CivicRewardProfile {
-        multiplier_min: 0.0,
-        multiplier_max: 4.0,
-        default_multiplier: 1.0,
-        required_knowledge_factor: 0.60,
-        heroic_tags: HashSet::new(),
-        heroic_multiplier: 3.0,
-        good_tags: HashSet::new(),
-        good_multiplier: 1.5,
-        neutral_multiplier: 1.0,
-        disallowed_tags: HashSet::new(),
-        eco_bonus_enabled: false,
-        eco_low_threshold: 0.0,
-        eco_low_bonus: 1.0,
+/// Load the shared civic policy; an unreadable or invalid file falls back to
+/// the built-in policy so the service still refuses disallowed acts.
+fn init_civic_policy() -> CivicPolicy {
+    let path = std::env::var("BCI_CIVIC_POLICY").unwrap_or_else(|_| "civic-policy/policies/civic.v1.json".to_string());
+    CivicPolicy::load(&path).unwrap_or_else(|e| {
+        eprintln!("civic policy {path} not loaded ({e}); using built-in policy");
+        CivicPolicy::builtin()
     })
 }
 
-
-#[derive(Clone)]
-struct AppState {
-    ledger: Arc<Mutex<InnerLedger>>,
-    rope: Arc<Mutex<NeuralRope>>,
-}
-
 /// JSON request body for /bci-ledger/apply.
 #[derive(Debug, Deserialize)]
 struct ApplyRequest {
@@ -68,13 +51,19 @@ struct ApplyResponse {
     pub result: bci_bioledger_bridge::BciLedgerResult,
     pub civic_class: CivicClass,
     pub reward_multiplier: f64,
+    pub civic_rule: FiredRule,
+    pub civic_policy_version: String,
+    /// Set when the event was committed but the handshake session could not
+    /// be saved; the event must not be retried.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
 }
 
-/// Initialize a demo InnerLedger for a host.
-/// In production, this would be loaded from persistent storage.
-fn init_ledger() -> InnerLedger {
+/// Envelope and starting state for hosts seen for the first time.
+/// `host_id` is overwritten with the id from the first `BciEvent` for that host.
+fn host_template() -> HostTemplate {
     let env = HostEnvelope {
-        host_id: "bostrom-host-demo".to_string(),
+        host_id: String::new(),
         brain_min: 0.0,
         blood_min: 0.1,
         oxygen_min: 0.1,
@@ -90,7 +79,12 @@ fn init_ledger() -> InnerLedger {
         nano: 0.01,
         smart: 0.5,
     };
-    InnerLedger::new(env, state)
+    HostTemplate { env, state }
+}
+
+fn init_store() -> Result<LedgerStore, store::StoreError> {
+    let root = std::env::var("BCI_LEDGER_STORE_DIR").unwrap_or_else(|_| "data/bci-ledger".to_string());
+    LedgerStore::open(root, host_template(), REQUIRED_CALIBRATION_SAMPLES)
 }
 
 async fn handle_request(
@@ -116,53 +110,79 @@ async fn handle_request(
             let req = parsed.unwrap();
 
             // 1. Civic classification and multiplier.
-            let civic_class = classify_civic(&req.civic_tags);
+            let civic = state.civic_policy.classify(&req.civic_tags);
+            let civic_class = civic.class;
             if civic_class == CivicClass::Disallowed {
                 let resp = Response::builder()
                     .status(StatusCode::FORBIDDEN)
-                    .body(Body::from("disallowed civic class"))
+                    .body(Body::from(format!(
+                        "disallowed civic class: {}",
+                        serde_json::to_string(&civic.fired).unwrap_or_default()
+                    )))
                     .unwrap();
                 return Ok(resp);
             }
-            let multiplier = civic_reward_multiplier(civic_class.clone());
+            let multiplier = civic.multiplier;
 
             // 2. Build IdentityHeader from auth envelope.
             let id_header: IdentityHeader = req.auth.to_identity_header();
 
-            // 3. Lock shared ledger + rope.
-            let mut ledger = state.ledger.lock().unwrap();
+            // 3. Load (or provision) the ledger for this host and lock it with the rope.
+            let host = match state.store.host(&req.bci_event.host_id) {
+                Ok(h) => h,
+                Err(e) => return Ok(store_error_response(e)),
+            };
+            let mut host = host.lock().unwrap();
             let mut rope = state.rope.lock().unwrap();
-            let mut orchestrator = BciLedgerOrchestrator::new(&mut ledger, &mut rope);
+            let eco_limit = host.ledger.env.eco_flops_limit;
 
             // 4. Scale BCI event eco_cost by civic multiplier for reward shaping
             // (more heroic acts justify slightly higher eco budget within host limits).
             let mut event = req.bci_event.clone();
-            event.eco_cost_estimate =
-                (event.eco_cost_estimate * multiplier).min(ledger.env.eco_flops_limit);
-
-            // 5. Handshake state: for simplicity, use a new session per request.
-            let session_id = Uuid::new_v4().to_string();
-            let mut handshake = augdoctorpolicies::neurohandshakeorchestrator::NeuroHandshakeOrchestrator::initial(
-                &session_id,
-                3,
-            );
+            event.eco_cost_estimate = (event.eco_cost_estimate * multiplier).min(eco_limit);
+
+            // 5. Handshake state: resume the durable session named by the event.
+            let handshake = match state.store.session(&event.host_id, &event.session_id) {
+                Ok(h) => h,
+                Err(e) => return Ok(store_error_response(e)),
+            };
 
             // 6. Call orchestrator. If handshake not ready, this returns an error; we treat it as soft.
-            let result = orchestrator.handle_bci_event(
+            let checkpoint = host.checkpoint();
+            let result = BciLedgerOrchestrator::new(&mut host.ledger, &mut rope).handle_bci_event(
                 &event,
-                handshake.clone(),
+                handshake,
                 &id_header,
-                0.6, // required knowledge factor (ensures useful-knowledge threshold)
+                state.civic_policy.required_knowledge_factor(), // useful-knowledge threshold
                 &req.timestamp_utc,
             );
 
             match result {
-                Ok((ledger_result, new_handshake, _ledger_event, _shot_decision)) => {
-                    handshake = new_handshake;
+                Ok((ledger_result, new_handshake, ledger_event, _shot_decision)) => {
+                    // 7. Journal the committed event before acknowledging it; a failed
+                    // append puts the ledger back to the checkpoint.
+                    if let Some(ev) = &ledger_event {
+                        if let Err(e) = host.record(ev, checkpoint) {
+                            return Ok(store_error_response(e));
+                        }
+                    }
+                    // The event is already in the ledger, so a failed session save
+                    // must not look like a failed apply the client would retry.
+                    let warning = state
+                        .store
+                        .save_session(&event.host_id, &new_handshake)
+                        .err()
+                        .map(|e| {
+                            eprintln!("session {} not persisted after commit: {e}", event.session_id);
+                            format!("event applied but handshake session not persisted: {e}")
+                        });
                     let resp_body = ApplyResponse {
                         result: ledger_result,
                         civic_class,
                         reward_multiplier: multiplier,
+                        civic_rule: civic.fired,
+                        civic_policy_version: civic.policy_version,
+                        warning,
                     };
                     let body = serde_json::to_vec(&resp_body).unwrap();
                     let resp = Response::builder()
@@ -173,7 +193,13 @@ async fn handle_request(
                     Ok(resp)
                 }
                 Err(e) => {
-                    // Handshake not ready, or inner-ledger guard failure.
+                    // Handshake not ready/rejected, or inner-ledger guard failure.
+                    // Handshake progress is durable even when nothing was applied.
+                    if let Some(h) = e.handshake() {
+                        if let Err(se) = state.store.save_session(&event.host_id, h) {
+                            return Ok(store_error_response(se));
+                        }
+                    }
                     let resp = Response::builder()
                         .status(StatusCode::FORBIDDEN)
                         .body(Body::from(format!("guard or handshake error: {e}")))
@@ -192,15 +218,27 @@ async fn handle_request(
     }
 }
 
+fn store_error_response(e: store::StoreError) -> Response<Body> {
+    let status = match e {
+        store::StoreError::InvalidId(_) => StatusCode::BAD_REQUEST,
+        store::StoreError::SessionHostMismatch { .. } => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    Response::builder()
+        .status(status)
+        .body(Body::from(format!("ledger store error: {e}")))
+        .unwrap()
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let ledger = init_ledger();
-    let civic_profile = init_civic_profile();
+    let store = init_store()?;
+    let civic_policy = init_civic_policy();
 
     let state = AppState {
-        ledger: Arc::new(Mutex::new(ledger)),
+        store: Arc::new(store),
         rope: Arc::new(Mutex::new(NeuralRope::new())),
-        civic_profile: Arc::new(civic_profile),
+        civic_policy: Arc::new(civic_policy),
     };
 loop {
         let listener = TcpListener::bind("0.0.0.0:8181").await?;
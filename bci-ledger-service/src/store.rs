@@ -0,0 +1,546 @@
+//! Host-keyed InnerLedger store and durable handshake sessions.
+//!
+//! On-disk layout under the store root:
+//!
+//! ```text
+//! hosts/<host_id>/genesis.json     envelope + initial state + genesis hash
+//! hosts/<host_id>/journal.jsonl    append-only JournalEntry lines
+//! sessions/<session_id>.json       last NeuroHandshakeState + owning host
+//! ```
+//!
+//! A host is loaded lazily the first time a `BciEvent` names it: genesis is
+//! read (or provisioned from the template), then the journal is replayed and
+//! its hash chain verified, so a restarted service resumes at the exact
+//! `last_state_hash` it had committed. A torn final journal line left by a
+//! crash mid-append is dropped. A store without a root keeps
+//! everything in memory (used by the wasm bindings).
+//!
+//! A session belongs to the host that first used it; presenting it for any
+//! other host is refused. At most `max_cached_sessions` sessions are held in
+//! memory; the least recently used is evicted past that, and a disk-backed
+//! store reloads it from `sessions/` on next use. A memory-only store has
+//! nowhere to reload from, so an evicted session restarts its handshake.
+
+use augdoctorpolicies::neurohandshakeorchestrator::{NeuroHandshakeOrchestrator, NeuroHandshakeState};
+use biophysical_blockchain::consensus::hash_state;
+use biophysical_blockchain::{BioTokenState, HostEnvelope, InnerLedger, LedgerEvent};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("invalid id {0:?}: only [A-Za-z0-9._-] allowed")]
+    InvalidId(String),
+    #[error("journal for host {host_id} broken at seq {seq}: {reason}")]
+    BrokenJournal { host_id: String, seq: u64, reason: String },
+    #[error("event for host {event_host} committed to ledger {host_id}")]
+    HostMismatch { host_id: String, event_host: String },
+    #[error("session {session_id} belongs to host {bound_host}, not {host_id}")]
+    SessionHostMismatch { session_id: String, bound_host: String, host_id: String },
+}
+
+/// Sessions kept in memory before the least recently used one is evicted.
+pub const DEFAULT_MAX_CACHED_SESSIONS: usize = 4096;
+
+/// Envelope and starting state used to provision a host seen for the first time.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HostTemplate {
+    pub env: HostEnvelope,
+    pub state: BioTokenState,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Genesis {
+    host_id: String,
+    env: HostEnvelope,
+    state: BioTokenState,
+    state_hash: String,
+}
+
+/// One committed inner-ledger event plus the state it produced.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub seq: u64,
+    pub event: LedgerEvent,
+    pub post_state: BioTokenState,
+}
+
+/// A loaded host: its ledger and the next journal sequence number.
+#[derive(Debug)]
+pub struct HostLedger {
+    pub ledger: InnerLedger,
+    pub next_seq: u64,
+    journal_path: Option<PathBuf>,
+}
+
+/// Ledger state as of the last journaled event, taken before an apply.
+#[derive(Clone, Debug)]
+pub struct JournalCheckpoint {
+    state: BioTokenState,
+    last_state_hash: String,
+}
+
+impl HostLedger {
+    /// Snapshot the journaled state. Take one before `system_apply` and pass
+    /// it to `record` so a failed append leaves the ledger where the journal is.
+    pub fn checkpoint(&self) -> JournalCheckpoint {
+        JournalCheckpoint { state: self.ledger.state.clone(), last_state_hash: self.ledger.last_state_hash.clone() }
+    }
+
+    /// Append a committed event to the journal. Call this right after a
+    /// successful `InnerLedger::system_apply` on `self.ledger`, with the
+    /// checkpoint taken just before it. If the append fails the in-memory
+    /// ledger is reset to `before`: an event that never reached the journal
+    /// was never committed, and a restart would not replay it either.
+    pub fn record(&mut self, event: &LedgerEvent, before: JournalCheckpoint) -> Result<JournalEntry, StoreError> {
+        let appended = self.append(event);
+        if appended.is_err() {
+            self.ledger.state = before.state;
+            self.ledger.last_state_hash = before.last_state_hash;
+        }
+        appended
+    }
+
+    fn append(&mut self, event: &LedgerEvent) -> Result<JournalEntry, StoreError> {
+        let host_id = &self.ledger.env.host_id;
+        if &event.host_id != host_id {
+            return Err(StoreError::HostMismatch { host_id: host_id.clone(), event_host: event.host_id.clone() });
+        }
+        let entry = JournalEntry { seq: self.next_seq, event: event.clone(), post_state: self.ledger.state.clone() };
+        if let Some(path) = &self.journal_path {
+            let mut line = serde_json::to_vec(&entry)?;
+            line.push(b'\n');
+            let mut f = OpenOptions::new().create(true).append(true).open(path)?;
+            let len = f.metadata()?.len();
+            if let Err(e) = f.write_all(&line).and_then(|_| f.sync_data()) {
+                // Drop a partial line so the journal still replays on restart.
+                let _ = f.set_len(len);
+                return Err(e.into());
+            }
+        }
+        self.next_seq += 1;
+        Ok(entry)
+    }
+}
+
+/// On-disk session record. `host_id` is absent in records written before
+/// sessions were bound; such a session is claimed by the next host to use it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct SessionRecord {
+    #[serde(default)]
+    host_id: Option<String>,
+    #[serde(flatten)]
+    state: NeuroHandshakeState,
+}
+
+struct CachedSession {
+    record: SessionRecord,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct SessionCache {
+    entries: HashMap<String, CachedSession>,
+    tick: u64,
+}
+
+impl SessionCache {
+    fn get(&mut self, session_id: &str) -> Option<SessionRecord> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(session_id).map(|c| {
+            c.last_used = tick;
+            c.record.clone()
+        })
+    }
+
+    fn insert(&mut self, session_id: &str, record: SessionRecord, capacity: usize) {
+        self.tick += 1;
+        if !self.entries.contains_key(session_id) && self.entries.len() >= capacity.max(1) {
+            let oldest = self.entries.iter().min_by_key(|(_, c)| c.last_used).map(|(k, _)| k.clone());
+            if let Some(k) = oldest {
+                self.entries.remove(&k);
+            }
+        }
+        self.entries.insert(session_id.to_string(), CachedSession { record, last_used: self.tick });
+    }
+}
+
+pub struct LedgerStore {
+    root: Option<PathBuf>,
+    template: HostTemplate,
+    hosts: Mutex<HashMap<String, Arc<Mutex<HostLedger>>>>,
+    sessions: Mutex<SessionCache>,
+    max_cached_sessions: usize,
+    required_calibration_samples: u32,
+}
+
+impl LedgerStore {
+    /// Disk-backed store rooted at `root`; directories are created on demand.
+    pub fn open<P: AsRef<Path>>(root: P, template: HostTemplate, required_calibration_samples: u32) -> Result<Self, StoreError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(root.join("hosts"))?;
+        fs::create_dir_all(root.join("sessions"))?;
+        Ok(Self::with_root(Some(root), template, required_calibration_samples))
+    }
+
+    /// Memory-only store with the same semantics but no persistence.
+    pub fn in_memory(template: HostTemplate, required_calibration_samples: u32) -> Self {
+        Self::with_root(None, template, required_calibration_samples)
+    }
+
+    fn with_root(root: Option<PathBuf>, template: HostTemplate, required_calibration_samples: u32) -> Self {
+        Self {
+            root,
+            template,
+            hosts: Mutex::new(HashMap::new()),
+            sessions: Mutex::new(SessionCache::default()),
+            max_cached_sessions: DEFAULT_MAX_CACHED_SESSIONS,
+            required_calibration_samples,
+        }
+    }
+
+    /// Cap on sessions held in memory (default `DEFAULT_MAX_CACHED_SESSIONS`).
+    pub fn with_max_cached_sessions(mut self, max: usize) -> Self {
+        self.max_cached_sessions = max.max(1);
+        self
+    }
+
+    /// Ledger for `host_id`, loading or provisioning it on first access.
+    pub fn host(&self, host_id: &str) -> Result<Arc<Mutex<HostLedger>>, StoreError> {
+        validate_id(host_id)?;
+        let mut hosts = self.hosts.lock().unwrap();
+        if let Some(h) = hosts.get(host_id) {
+            return Ok(h.clone());
+        }
+        let loaded = Arc::new(Mutex::new(self.load_host(host_id)?));
+        hosts.insert(host_id.to_string(), loaded.clone());
+        Ok(loaded)
+    }
+
+    fn load_host(&self, host_id: &str) -> Result<HostLedger, StoreError> {
+        let Some(root) = &self.root else {
+            let genesis = self.provision(host_id);
+            return Ok(HostLedger { ledger: ledger_from(genesis.env, genesis.state, genesis.state_hash), next_seq: 0, journal_path: None });
+        };
+        let dir = root.join("hosts").join(host_id);
+        let genesis_path = dir.join("genesis.json");
+        let journal_path = dir.join("journal.jsonl");
+
+        let genesis: Genesis = if genesis_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&genesis_path)?)?
+        } else {
+            fs::create_dir_all(&dir)?;
+            let g = self.provision(host_id);
+            write_atomic(&genesis_path, &serde_json::to_vec_pretty(&g)?)?;
+            g
+        };
+
+        let mut state = genesis.state;
+        let mut last_hash = genesis.state_hash;
+        let mut next_seq = 0u64;
+        if journal_path.exists() {
+            let broken = |seq: u64, reason: &str| StoreError::BrokenJournal { host_id: host_id.to_string(), seq, reason: reason.to_string() };
+            let mut reader = BufReader::new(File::open(&journal_path)?);
+            let mut line = Vec::new();
+            let mut offset = 0u64;
+            loop {
+                line.clear();
+                let read = reader.read_until(b'\n', &mut line)?;
+                if read == 0 {
+                    break;
+                }
+                let terminated = line.ends_with(b"\n");
+                if line.iter().all(u8::is_ascii_whitespace) {
+                    offset += read as u64;
+                    continue;
+                }
+                let entry: JournalEntry = match serde_json::from_slice(&line) {
+                    Ok(entry) => entry,
+                    Err(_) if !terminated => {
+                        // Torn final append from a crash: drop it.
+                        let f = OpenOptions::new().write(true).open(&journal_path)?;
+                        f.set_len(offset)?;
+                        f.sync_data()?;
+                        break;
+                    }
+                    Err(e) => return Err(broken(next_seq, &e.to_string())),
+                };
+                if entry.seq != next_seq {
+                    return Err(broken(next_seq, "sequence gap"));
+                }
+                if entry.event.prev_state_hash != last_hash {
+                    return Err(broken(entry.seq, "prev_state_hash does not chain"));
+                }
+                if hash_state(host_id, &genesis.env, &entry.post_state) != entry.event.new_state_hash {
+                    return Err(broken(entry.seq, "post_state does not match new_state_hash"));
+                }
+                last_hash = entry.event.new_state_hash;
+                state = entry.post_state;
+                next_seq += 1;
+                offset += read as u64;
+                if !terminated {
+                    // Complete entry missing only its newline.
+                    let mut f = OpenOptions::new().append(true).open(&journal_path)?;
+                    f.write_all(b"\n")?;
+                    f.sync_data()?;
+                }
+            }
+        }
+        Ok(HostLedger { ledger: ledger_from(genesis.env, state, last_hash), next_seq, journal_path: Some(journal_path) })
+    }
+
+    fn provision(&self, host_id: &str) -> Genesis {
+        let mut env = self.template.env.clone();
+        env.host_id = host_id.to_string();
+        let state = self.template.state.clone();
+        let state_hash = hash_state(host_id, &env, &state);
+        Genesis { host_id: host_id.to_string(), env, state, state_hash }
+    }
+
+    /// Handshake for `session_id` on `host_id`, restored from disk or started
+    /// fresh. A session already bound to a different host is refused.
+    pub fn session(&self, host_id: &str, session_id: &str) -> Result<NeuroHandshakeState, StoreError> {
+        validate_id(host_id)?;
+        validate_id(session_id)?;
+        let mut sessions = self.sessions.lock().unwrap();
+        let record = match sessions.get(session_id) {
+            Some(r) => r,
+            None => {
+                let path = self.session_path(session_id);
+                let record = match path {
+                    Some(p) if p.exists() => serde_json::from_str(&fs::read_to_string(p)?)?,
+                    _ => SessionRecord {
+                        host_id: None,
+                        state: NeuroHandshakeOrchestrator::initial(session_id, self.required_calibration_samples),
+                    },
+                };
+                sessions.insert(session_id, record.clone(), self.max_cached_sessions);
+                record
+            }
+        };
+        check_binding(session_id, record.host_id.as_deref(), host_id)?;
+        Ok(record.state)
+    }
+
+    /// Persist the handshake state reached after a request on `host_id`,
+    /// binding the session to that host if it was not bound yet.
+    pub fn save_session(&self, host_id: &str, state: &NeuroHandshakeState) -> Result<(), StoreError> {
+        validate_id(host_id)?;
+        validate_id(&state.session_id)?;
+        let mut sessions = self.sessions.lock().unwrap();
+        let bound = match sessions.get(&state.session_id) {
+            Some(r) => r.host_id,
+            None => match self.session_path(&state.session_id) {
+                Some(p) if p.exists() => serde_json::from_str::<SessionRecord>(&fs::read_to_string(p)?)?.host_id,
+                _ => None,
+            },
+        };
+        check_binding(&state.session_id, bound.as_deref(), host_id)?;
+        let record = SessionRecord { host_id: Some(host_id.to_string()), state: state.clone() };
+        if let Some(path) = self.session_path(&state.session_id) {
+            write_atomic(&path, &serde_json::to_vec_pretty(&record)?)?;
+        }
+        sessions.insert(&state.session_id, record, self.max_cached_sessions);
+        Ok(())
+    }
+
+    fn session_path(&self, session_id: &str) -> Option<PathBuf> {
+        self.root.as_ref().map(|r| r.join("sessions").join(format!("{session_id}.json")))
+    }
+}
+
+fn check_binding(session_id: &str, bound: Option<&str>, host_id: &str) -> Result<(), StoreError> {
+    match bound {
+        Some(b) if b != host_id => Err(StoreError::SessionHostMismatch {
+            session_id: session_id.to_string(),
+            bound_host: b.to_string(),
+            host_id: host_id.to_string(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+fn ledger_from(env: HostEnvelope, state: BioTokenState, last_state_hash: String) -> InnerLedger {
+    InnerLedger { env, state, last_state_hash }
+}
+
+/// Ids become path components, so keep them to a conservative charset.
+fn validate_id(id: &str) -> Result<(), StoreError> {
+    let ok = !id.is_empty()
+        && id != "."
+        && id != ".."
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if ok { Ok(()) } else { Err(StoreError::InvalidId(id.to_string())) }
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), StoreError> {
+    let tmp = path.with_extension("tmp");
+    {
+        let mut f = File::create(&tmp)?;
+        f.write_all(bytes)?;
+        f.sync_all()?;
+    }
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bci-ledger-store-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Stand-in for a guarded `system_apply`: bump BRAIN and chain the hash.
+    fn bump(host: &mut HostLedger) -> LedgerEvent {
+        bump_by(host, 1.0)
+    }
+
+    fn bump_by(host: &mut HostLedger, delta: f64) -> LedgerEvent {
+        let ledger = &mut host.ledger;
+        ledger.state.brain += delta;
+        let new_hash = hash_state(&ledger.env.host_id, &ledger.env, &ledger.state);
+        let event = LedgerEvent {
+            host_id: ledger.env.host_id.clone(),
+            prev_state_hash: ledger.last_state_hash.clone(),
+            new_state_hash: new_hash.clone(),
+            adjustment: serde_json::from_value(serde_json::json!({
+                "deltabrain": delta, "deltawave": 0.0, "deltablood": 0.0, "deltaoxygen": 0.0,
+                "deltanano": 0.0, "deltasmart": 0.0, "ecocost": 0.0, "reason": "test",
+                "delta_evolve": 0.0,
+                "delta_morph": { "d_eco": 0.0, "d_cyber": 0.0, "d_neuro": 0.0, "d_smart": 0.0 }
+            }))
+            .unwrap(),
+            timestamp_utc: "2026-01-01T00:00:00Z".to_string(),
+            attested_by: "did:test:daemon".to_string(),
+        };
+        ledger.last_state_hash = new_hash;
+        event
+    }
+
+    #[test]
+    fn reopened_store_resumes_at_last_journaled_hash() {
+        let root = temp_root("reopen");
+        let (hash, brain) = {
+            let store = LedgerStore::open(&root, crate::host_template(), 3).unwrap();
+            let host = store.host("host-a").unwrap();
+            let mut host = host.lock().unwrap();
+            for _ in 0..2 {
+                let cp = host.checkpoint();
+                let ev = bump(&mut host);
+                host.record(&ev, cp).unwrap();
+            }
+            (host.ledger.last_state_hash.clone(), host.ledger.state.brain)
+        };
+
+        let store = LedgerStore::open(&root, crate::host_template(), 3).unwrap();
+        let host = store.host("host-a").unwrap();
+        let host = host.lock().unwrap();
+        assert_eq!(host.next_seq, 2);
+        assert_eq!(host.ledger.last_state_hash, hash);
+        assert_eq!(host.ledger.state.brain, brain);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn replay_is_exact_for_inexact_floats() {
+        let root = temp_root("floats");
+        let (hash, brain) = {
+            let store = LedgerStore::open(&root, crate::host_template(), 3).unwrap();
+            let host = store.host("host-a").unwrap();
+            let mut host = host.lock().unwrap();
+            for delta in [0.1, 0.2, 1.0 / 3.0, 2.0f64.sqrt() * 1e-7] {
+                let cp = host.checkpoint();
+                let ev = bump_by(&mut host, delta);
+                host.record(&ev, cp).unwrap();
+            }
+            (host.ledger.last_state_hash.clone(), host.ledger.state.brain)
+        };
+
+        let store = LedgerStore::open(&root, crate::host_template(), 3).unwrap();
+        let host = store.host("host-a").unwrap();
+        let host = host.lock().unwrap();
+        assert_eq!(host.next_seq, 4);
+        assert_eq!(host.ledger.state.brain.to_bits(), brain.to_bits());
+        assert_eq!(host.ledger.last_state_hash, hash);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn torn_final_journal_line_is_dropped_on_open() {
+        let root = temp_root("torn");
+        let hash = {
+            let store = LedgerStore::open(&root, crate::host_template(), 3).unwrap();
+            let host = store.host("host-a").unwrap();
+            let mut host = host.lock().unwrap();
+            let cp = host.checkpoint();
+            let ev = bump(&mut host);
+            host.record(&ev, cp).unwrap();
+            host.ledger.last_state_hash.clone()
+        };
+        let journal = root.join("hosts").join("host-a").join("journal.jsonl");
+        let mut f = OpenOptions::new().append(true).open(&journal).unwrap();
+        f.write_all(br#"{"seq":1,"event":{"host_id":"#).unwrap();
+        drop(f);
+
+        let store = LedgerStore::open(&root, crate::host_template(), 3).unwrap();
+        let host = store.host("host-a").unwrap();
+        let mut host = host.lock().unwrap();
+        assert_eq!((host.next_seq, host.ledger.last_state_hash.clone()), (1, hash));
+        // The next append lands on a clean line.
+        let cp = host.checkpoint();
+        let ev = bump(&mut host);
+        host.record(&ev, cp).unwrap();
+        assert_eq!(fs::read_to_string(&journal).unwrap().lines().count(), 2);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn failed_record_restores_the_checkpoint() {
+        let store = LedgerStore::in_memory(crate::host_template(), 3);
+        let host = store.host("host-a").unwrap();
+        let mut host = host.lock().unwrap();
+        let cp = host.checkpoint();
+        let before = host.ledger.last_state_hash.clone();
+        let mut ev = bump(&mut host);
+        ev.host_id = "host-b".to_string();
+
+        assert!(matches!(host.record(&ev, cp), Err(StoreError::HostMismatch { .. })));
+        assert_eq!(host.ledger.last_state_hash, before);
+        assert_eq!(host.next_seq, 0);
+    }
+
+    #[test]
+    fn sessions_survive_restart_bound_to_their_host() {
+        let root = temp_root("sessions");
+        {
+            let store = LedgerStore::open(&root, crate::host_template(), 3).unwrap().with_max_cached_sessions(1);
+            let mut s = store.session("host-a", "s1").unwrap();
+            s.calibration_samples_collected = 2;
+            store.save_session("host-a", &s).unwrap();
+            // Evicts s1 from memory; it must come back from disk.
+            store.session("host-a", "s2").unwrap();
+            assert_eq!(store.session("host-a", "s1").unwrap().calibration_samples_collected, 2);
+        }
+
+        let store = LedgerStore::open(&root, crate::host_template(), 3).unwrap();
+        assert_eq!(store.session("host-a", "s1").unwrap().calibration_samples_collected, 2);
+        assert!(matches!(store.session("host-b", "s1"), Err(StoreError::SessionHostMismatch { .. })));
+        let s = store.session("host-a", "s1").unwrap();
+        assert!(matches!(store.save_session("host-b", &s), Err(StoreError::SessionHostMismatch { .. })));
+        let _ = fs::remove_dir_all(&root);
+    }
+}
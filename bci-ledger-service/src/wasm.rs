@@ -1,19 +1,30 @@
 #![cfg(feature = "wasm")]
 
-use crate::security::{classify_civic, civic_reward_multiplier, AuthEnvelope, CivicClass};
+use crate::security::AuthEnvelope;
+use crate::store::{LedgerStore, StoreError};
 use bci_bioledger_bridge::{BciEvent, BciLedgerOrchestrator};
-use biophysical_blockchain::{BioTokenState, HostEnvelope, InnerLedger};
+use civic_policy::{CivicClass, CivicPolicy, FiredRule};
 use bioscaleupgradeservice::neuralrope::NeuralRope;
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
-use uuid::Uuid;
 use wasm_bindgen::prelude::*;
 
-static LEDGER: once_cell::sync::OnceCell<Mutex<InnerLedger>> = once_cell::sync::OnceCell::new();
+// No filesystem in wasm: hosts and sessions live in a memory-only store.
+static STORE: once_cell::sync::OnceCell<LedgerStore> = once_cell::sync::OnceCell::new();
 static ROPE: once_cell::sync::OnceCell<Mutex<NeuralRope>> = once_cell::sync::OnceCell::new();
+static CIVIC_POLICY: once_cell::sync::OnceCell<CivicPolicy> = once_cell::sync::OnceCell::new();
+
+/// Install a civic policy (JSON, same schema as the service's policy file).
+/// Must be called before the first apply; otherwise the built-in policy is used.
+#[wasm_bindgen]
+pub fn wasm_set_civic_policy(json_policy: &str) -> Result<(), JsValue> {
+    let policy = CivicPolicy::from_json_str(json_policy).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    CIVIC_POLICY.set(policy).map_err(|_| JsValue::from_str("civic policy already initialized"))
+}
 
 fn init_globals() {
-    LEDGER.get_or_init(|| Mutex::new(super::init_ledger()));
+    CIVIC_POLICY.get_or_init(CivicPolicy::builtin);
+    STORE.get_or_init(|| LedgerStore::in_memory(super::host_template(), super::REQUIRED_CALIBRATION_SAMPLES));
     ROPE.get_or_init(|| Mutex::new(NeuralRope::new()));
 }
 
@@ -30,6 +41,7 @@ struct WasmApplyResponse {
     pub result: Option<bci_bioledger_bridge::BciLedgerResult>,
     pub civic_class: CivicClass,
     pub reward_multiplier: f64,
+    pub civic_rule: Option<FiredRule>,
     pub error: Option<String>,
 }
 
@@ -42,44 +54,62 @@ pub fn wasm_bci_ledger_apply(json_req: &str) -> String {
     }
     let req = parsed.unwrap();
 
-    let civic_class = classify_civic(&req.civic_tags);
+    let civic = CIVIC_POLICY.get().unwrap().classify(&req.civic_tags);
+    let civic_class = civic.class;
     if civic_class == CivicClass::Disallowed {
-        return r#"{"result":null,"civic_class":"Disallowed","reward_multiplier":0.0,"error":"disallowed-civic-class"}"#.to_string();
+        let resp = WasmApplyResponse {
+            result: None,
+            civic_class,
+            reward_multiplier: 0.0,
+            civic_rule: Some(civic.fired),
+            error: Some("disallowed-civic-class".to_string()),
+        };
+        return serde_json::to_string(&resp).unwrap_or_else(|_| {
+            r#"{"result":null,"civic_class":"Disallowed","reward_multiplier":0.0,"error":"disallowed-civic-class"}"#.to_string()
+        });
     }
-    let multiplier = civic_reward_multiplier(civic_class.clone());
+    let multiplier = civic.multiplier;
 
     let id_header = req.auth.to_identity_header();
 
-    let ledger_mutex = LEDGER.get().unwrap();
+    let store = STORE.get().unwrap();
+    let host_id = req.bci_event.host_id.clone();
+    let (host, handshake) = match (store.host(&host_id), store.session(&host_id, &req.bci_event.session_id)) {
+        (Ok(h), Ok(s)) => (h, s),
+        (Err(e), _) | (_, Err(e)) => return store_error_json(civic_class, multiplier, civic.fired, &e),
+    };
+    let mut host = host.lock().unwrap();
     let rope_mutex = ROPE.get().unwrap();
-    let mut ledger = ledger_mutex.lock().unwrap();
     let mut rope = rope_mutex.lock().unwrap();
-    let mut orchestrator = BciLedgerOrchestrator::new(&mut ledger, &mut rope);
 
     let mut event = req.bci_event.clone();
     event.eco_cost_estimate =
-        (event.eco_cost_estimate * multiplier).min(ledger.env.eco_flops_limit);
+        (event.eco_cost_estimate * multiplier).min(host.ledger.env.eco_flops_limit);
 
-    let session_id = Uuid::new_v4().to_string();
-    let handshake = augdoctorpolicies::neurohandshakeorchestrator::NeuroHandshakeOrchestrator::initial(
-        &session_id,
-        3,
-    );
-
-    let res = orchestrator.handle_bci_event(
+    let checkpoint = host.checkpoint();
+    let res = BciLedgerOrchestrator::new(&mut host.ledger, &mut rope).handle_bci_event(
         &event,
         handshake,
         &id_header,
-        0.6,
+        CIVIC_POLICY.get().unwrap().required_knowledge_factor(),
         &req.timestamp_utc,
     );
 
     match res {
-        Ok((ledger_res, _handshake, _ledger_event, _shot_decision)) => {
+        Ok((ledger_res, new_handshake, ledger_event, _shot_decision)) => {
+            let persisted = match &ledger_event {
+                Some(ev) => host.record(ev, checkpoint).map(|_| ()),
+                None => Ok(()),
+            }
+            .and_then(|_| store.save_session(&host_id, &new_handshake));
+            if let Err(e) = persisted {
+                return store_error_json(civic_class, multiplier, civic.fired, &e);
+            }
             let resp = WasmApplyResponse {
                 result: Some(ledger_res),
                 civic_class,
                 reward_multiplier: multiplier,
+                civic_rule: Some(civic.fired),
                 error: None,
             };
             serde_json::to_string(&resp).unwrap_or_else(|e| {
@@ -87,10 +117,16 @@ pub fn wasm_bci_ledger_apply(json_req: &str) -> String {
             })
         }
         Err(e) => {
+            if let Some(h) = e.handshake() {
+                if let Err(se) = store.save_session(&host_id, h) {
+                    return store_error_json(civic_class, multiplier, civic.fired, &se);
+                }
+            }
             let resp = WasmApplyResponse {
                 result: None,
                 civic_class,
                 reward_multiplier: multiplier,
+                civic_rule: Some(civic.fired),
                 error: Some(format!("guard-or-handshake-error:{e}")),
             };
             serde_json::to_string(&resp).unwrap_or_else(|_| {
@@ -99,3 +135,16 @@ pub fn wasm_bci_ledger_apply(json_req: &str) -> String {
         }
     }
 }
+
+fn store_error_json(civic_class: CivicClass, multiplier: f64, fired: FiredRule, e: &StoreError) -> String {
+    let resp = WasmApplyResponse {
+        result: None,
+        civic_class,
+        reward_multiplier: multiplier,
+        civic_rule: Some(fired),
+        error: Some(format!("store:{e}")),
+    };
+    serde_json::to_string(&resp).unwrap_or_else(|_| {
+        r#"{"result":null,"civic_class":"Neutral","reward_multiplier":0.0,"error":"store"}"#.to_string()
+    })
+}
@@ -1,15 +1,18 @@
 mod neural_rope;
+mod neural_rope_store;
 
 use bioscale_upgrade_store::bioscale::upgrade_asset::{
     BioscaleAwarenessProfile, BioscaleUpgradeAsset, ConsciousnessComplianceLevel,
     HardwareBindingProfile,
 };
 use bioscale_upgrade_store::{
-    BioscaleStoreConfig, BioscaleUpgradeStore, UpgradeApplicationResult,
+    BioscaleStoreConfig, BioscaleStoreError, BioscaleUpgradeStore, EnvironmentProfile, SemVer,
+    UpgradeApplicationResult, UpgradeLifecycle,
 };
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Method, Request, Response, StatusCode};
-use neural_rope::NeuralRope;
+use neural_rope::TraceSink;
+use neural_rope_store::{DurableNeuralRope, NeuralRopeStoreConfig};
 use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
 use std::sync::{Arc, Mutex};
@@ -18,7 +21,7 @@ use tokio::net::TcpListener;
 #[derive(Clone)]
 struct AppState {
     store: Arc<Mutex<BioscaleUpgradeStore>>,
-    neural_rope: Arc<Mutex<NeuralRope>>,
+    neural_rope: Arc<Mutex<DurableNeuralRope>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,11 +36,52 @@ struct RegisterUpgradeRequest {
     required_safety_modules: Vec<String>,
     bioscale_resolution_microns: u32,
     metadata_hash: String,
+    /// Register a new version of an existing upgrade instead of a new id.
+    #[serde(default)]
+    upgrade_id: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 struct RegisterUpgradeResponse {
     upgrade_id: String,
+    version: String,
+    state: UpgradeLifecycle,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpgradeLifecycleRequest {
+    upgrade_id: String,
+    version: SemVer,
+    state: UpgradeLifecycle,
+}
+
+#[derive(Debug, Serialize)]
+struct UpgradeLifecycleResponse {
+    /// Environments currently running the version that changed state.
+    affected_environments: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RollbackUpgradeRequest {
+    environment_id: String,
+    upgrade_id: String,
+}
+
+fn json_response<T: Serialize>(value: &T) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "application/json")
+        .body(Body::from(serde_json::to_vec(value).unwrap()))
+        .unwrap()
+}
+
+fn bad_request(msg: String) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(Body::from(msg))
+        .unwrap()
 }
 
 #[derive(Debug, Deserialize)]
@@ -99,7 +143,7 @@ async fn handle_request(
                 bioscale_resolution_microns: req.bioscale_resolution_microns,
             };
 
-            let asset = BioscaleUpgradeAsset::new(
+            let mut asset = BioscaleUpgradeAsset::new(
                 &req.human_label,
                 awareness,
                 compliance,
@@ -107,13 +151,30 @@ async fn handle_request(
                 req.tags,
                 &req.metadata_hash,
             );
+            if let Some(id) = req.upgrade_id {
+                asset.id = id;
+            }
+            // Requests without a version come from clients that predate the
+            // lifecycle. They stay Draft unless legacy auto-activation is on.
+            let legacy = req.version.is_none();
+            if let Some(version) = req.version {
+                asset.version = version;
+            }
+            let version = asset.version.clone();
 
             let mut store = state.store.lock().unwrap();
-            let res = store.register_upgrade(asset);
+            let res = if legacy {
+                store.register_legacy_upgrade(asset)
+            } else {
+                store.register_upgrade(asset).map(|id| (id, UpgradeLifecycle::Draft))
+            };
 
             match res {
-                Ok(id) => {
-                    let resp = RegisterUpgradeResponse { upgrade_id: id };
+                Ok((id, state)) => {
+                    if state == UpgradeLifecycle::Active {
+                        eprintln!("auto-activated legacy registration {}@{}", id, version);
+                    }
+                    let resp = RegisterUpgradeResponse { upgrade_id: id, version, state };
                     let body = serde_json::to_vec(&resp).unwrap();
                     Ok(Response::builder()
                         .status(StatusCode::OK)
@@ -143,35 +204,42 @@ async fn handle_request(
             let req = parsed.unwrap();
 
             let mut store = state.store.lock().unwrap();
-            let result = store.apply_upgrade_to_environment(
+            let mut rope = state.neural_rope.lock().unwrap();
+            // The trace is persisted before the application is recorded, so a
+            // rope store failure leaves the upgrade unapplied.
+            let mut trace_failed = false;
+            let result = store.apply_upgrade_to_environment_with(
                 &req.upgrade_id,
                 &req.environment_id,
                 req.environment_hardware.clone(),
                 req.environment_tags.clone(),
-            );
-
-            match result {
-                Ok(upgrade_result) => {
+                |upgrade_result| {
                     let plane_label = upgrade_result
                         .environment_metadata
                         .environment_plane
                         .to_string();
-                    let safety_decision = upgrade_result.guard_decision.clone();
-
-                    let mut rope = state.neural_rope.lock().unwrap();
+                    let safety_decision = &upgrade_result.guard_decision;
                     let trace_text = format!(
                         "env_id={} upgrade_id={} plane={} decision={}",
                         req.environment_id, req.upgrade_id, plane_label, safety_decision
                     );
-                    rope.append_trace(
+                    rope.record_trace(
                         &trace_text,
                         &plane_label,
                         Some(req.upgrade_id.clone()),
                         req.reward_score,
-                        &safety_decision,
-                    );
+                        safety_decision,
+                    )
+                    .map_err(|e| {
+                        trace_failed = true;
+                        BioscaleStoreError::StorageError(format!("neural rope store error: {e}"))
+                    })
+                },
+            );
 
-                    let segments = rope.export_snapshot(16);
+            match result {
+                Ok(upgrade_result) => {
+                    let segments = rope.rope.export_snapshot(16);
 
                     let resp = ApplyUpgradeResponse {
                         result: upgrade_result,
@@ -185,6 +253,10 @@ async fn handle_request(
                         .body(Body::from(body))
                         .unwrap())
                 }
+                Err(e) if trace_failed => Ok(Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Body::from(e.to_string()))
+                    .unwrap()),
                 Err(e) => Ok(Response::builder()
                     .status(StatusCode::BAD_REQUEST)
                     .body(Body::from(e.to_string()))
@@ -192,6 +264,45 @@ async fn handle_request(
             }
         }
 
+        (Method::POST, "/register_environment") => {
+            let body_bytes = hyper::body::to_bytes(req.into_body()).await.unwrap_or_default();
+            let profile: EnvironmentProfile = match serde_json::from_slice(&body_bytes) {
+                Ok(p) => p,
+                Err(e) => return Ok(bad_request(format!("invalid JSON: {}", e))),
+            };
+            let mut store = state.store.lock().unwrap();
+            match store.register_environment(profile.clone()) {
+                Ok(()) => Ok(json_response(&profile)),
+                Err(e) => Ok(bad_request(e.to_string())),
+            }
+        }
+
+        (Method::POST, "/upgrade_lifecycle") => {
+            let body_bytes = hyper::body::to_bytes(req.into_body()).await.unwrap_or_default();
+            let req: UpgradeLifecycleRequest = match serde_json::from_slice(&body_bytes) {
+                Ok(r) => r,
+                Err(e) => return Ok(bad_request(format!("invalid JSON: {}", e))),
+            };
+            let mut store = state.store.lock().unwrap();
+            match store.set_lifecycle(&req.upgrade_id, &req.version, req.state) {
+                Ok(affected_environments) => Ok(json_response(&UpgradeLifecycleResponse { affected_environments })),
+                Err(e) => Ok(bad_request(e.to_string())),
+            }
+        }
+
+        (Method::POST, "/rollback_upgrade") => {
+            let body_bytes = hyper::body::to_bytes(req.into_body()).await.unwrap_or_default();
+            let req: RollbackUpgradeRequest = match serde_json::from_slice(&body_bytes) {
+                Ok(r) => r,
+                Err(e) => return Ok(bad_request(format!("invalid JSON: {}", e))),
+            };
+            let mut store = state.store.lock().unwrap();
+            match store.rollback_upgrade(&req.environment_id, &req.upgrade_id) {
+                Ok(result) => Ok(json_response::<UpgradeApplicationResult>(&result)),
+                Err(e) => Ok(bad_request(e.to_string())),
+            }
+        }
+
         _ => Ok(Response::builder()
             .status(StatusCode::NOT_FOUND)
             .body(Body::from("not found"))
@@ -209,10 +320,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
             String::from("DID"),
         ],
         max_upgrade_assets: 1024,
+        // Pre-catalog clients apply to environments they never registered.
+        legacy_environment_fallback: true,
+        // Unversioned registrations skip approval only when
+        // BIOSCALE_LEGACY_AUTO_ACTIVATE=1.
+        legacy_auto_activate: std::env::var("BIOSCALE_LEGACY_AUTO_ACTIVATE").is_ok_and(|v| v == "1"),
     };
 
-    let store = BioscaleUpgradeStore::new(cfg);
-    let neural_rope = NeuralRope::new();
+    // Catalog (versions, lifecycle, environments, applied history) persists
+    // across restarts; BIOSCALE_CATALOG_PATH overrides the location.
+    let catalog_path = std::env::var("BIOSCALE_CATALOG_PATH").unwrap_or_else(|_| "data/bioscale-catalog.json".to_string());
+    let store = BioscaleUpgradeStore::open(cfg, &catalog_path)?;
+    // Rope traces persist across restarts; NEURAL_ROPE_DIR overrides the location.
+    // The default config snapshots every full segment and compacts to the
+    // newest 65536 traces once more than 32 segments are live.
+    let rope_dir = std::env::var("NEURAL_ROPE_DIR").unwrap_or_else(|_| "data/neural-rope".to_string());
+    let neural_rope = DurableNeuralRope::open(&rope_dir, NeuralRopeStoreConfig::default())?;
 
     let state = AppState {
         store: Arc::new(Mutex::new(store)),
@@ -12,17 +12,57 @@ pub struct NeuralRopeAttributes {
     pub safety_decision: String,
 }
 
+/// Domain of a plane label: its first component (`"bci/hci/eeg"` and
+/// `"bci-hci-eeg"` are both domain `"bci"`).
+pub fn plane_domain(plane_label: &str) -> String {
+    plane_label
+        .split(['/', '-', '.'])
+        .find(|p| !p.is_empty())
+        .unwrap_or("")
+        .to_ascii_lowercase()
+}
+
+/// Anything that can take a trace: the in-memory rope, or a durable rope
+/// backed by `neural_rope_store`.
+pub trait TraceSink {
+    fn record_trace(
+        &mut self,
+        trace: &str,
+        plane_label: &str,
+        bioscale_upgrade_id: Option<String>,
+        reward_score: f32,
+        safety_decision: &str,
+    ) -> std::io::Result<()>;
+}
+
+impl TraceSink for NeuralRope {
+    fn record_trace(
+        &mut self,
+        trace: &str,
+        plane_label: &str,
+        bioscale_upgrade_id: Option<String>,
+        reward_score: f32,
+        safety_decision: &str,
+    ) -> std::io::Result<()> {
+        self.append_trace(trace, plane_label, bioscale_upgrade_id, reward_score, safety_decision);
+        Ok(())
+    }
+}
+
 /// A neural rope containing token-like units (chars or short symbols) plus
 /// attributes for assisted-learning introspection.
 #[derive(Clone, Debug)]
 pub struct NeuralRope {
     rope: AttributedRope<char, NeuralRopeAttributes>,
+    /// `[start, end)` char span of every appended trace, in append order.
+    trace_spans: Vec<(usize, usize)>,
 }
 
 impl NeuralRope {
     pub fn new() -> Self {
         NeuralRope {
             rope: AttributedRope::from(String::new().chars().collect::<Vec<char>>()),
+            trace_spans: Vec::new(),
         }
     }
 
@@ -48,6 +88,27 @@ impl NeuralRope {
         };
 
         self.rope.set_attributes(start_len..end_len, attrs);
+        self.trace_spans.push((start_len, end_len));
+    }
+
+    /// One snapshot per appended trace, oldest first. Unlike `export_snapshot`
+    /// the ids are positional (`trace-<n>`), so the output is deterministic and
+    /// suitable for retrieval and offline fixtures.
+    pub fn export_traces(&self) -> Vec<NeuralRopeSegmentSnapshot> {
+        self.trace_spans
+            .iter()
+            .enumerate()
+            .map(|(n, &(start, end))| {
+                let slice: RopeSlice<char> = self.rope.read_cells(start..end);
+                NeuralRopeSegmentSnapshot {
+                    id: format!("trace-{n}"),
+                    range_start: start as u64,
+                    range_end: end as u64,
+                    text: slice.cloned().collect(),
+                    attributes: self.rope.attributes(start..end).first().map(|(_, a)| a.clone()),
+                }
+            })
+            .collect()
     }
 
     /// Export a safe, JSON-serializable snapshot of the rope for external
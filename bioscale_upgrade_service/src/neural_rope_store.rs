@@ -0,0 +1,687 @@
+//! Append-only on-disk segment store for the neural rope.
+//!
+//! Layout under the store root:
+//!
+//! ```text
+//! segments/seg-<n>.jsonl        TraceRecord lines; only the highest-numbered one is appended to
+//! manifests/manifest-<n>.json   SnapshotManifest, hash-chained to its predecessor
+//! CURRENT                       file name of the newest manifest
+//! ```
+//!
+//! Every trace is written to the active segment before it reaches the
+//! in-memory rope, so `open` can rebuild the rope after a restart: it verifies
+//! the manifest chain, checks each sealed segment against its recorded digest,
+//! and then replays any records appended after the last snapshot.
+//!
+//! Maintenance runs from `append`: each time the active segment fills it is
+//! sealed under a fresh manifest, and once more than
+//! `compact_after_segments` segments are live the store compacts with the
+//! configured `CompactionPolicy`, so neither replay time nor disk use grows
+//! without bound.
+
+use crate::neural_rope::{plane_domain, NeuralRope, NeuralRopeAttributes, TraceSink};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+pub const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum NeuralRopeStoreError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+    #[error("manifest {name} is corrupt: {reason}")]
+    CorruptManifest { name: String, reason: String },
+    #[error("segment {name} is corrupt: {reason}")]
+    CorruptSegment { name: String, reason: String },
+}
+
+#[derive(Clone, Debug)]
+pub struct NeuralRopeStoreConfig {
+    /// Roll to a new segment once the active one holds this many records.
+    pub max_segment_records: usize,
+    /// fsync every append (slower, but no trace is lost on power failure).
+    pub sync_every_append: bool,
+    /// Write a manifest whenever the active segment fills up.
+    pub snapshot_full_segments: bool,
+    /// Policy applied automatically once the live segment count exceeds
+    /// `compact_after_segments`; `None` leaves compaction to the caller.
+    pub compaction: Option<CompactionPolicy>,
+    pub compact_after_segments: usize,
+}
+
+impl Default for NeuralRopeStoreConfig {
+    fn default() -> Self {
+        Self {
+            max_segment_records: 4096,
+            sync_every_append: true,
+            snapshot_full_segments: true,
+            compaction: Some(CompactionPolicy { max_traces: Some(65_536), ..CompactionPolicy::default() }),
+            compact_after_segments: 32,
+        }
+    }
+}
+
+/// One persisted trace. `seq` is global and never reused, even across compaction.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TraceRecord {
+    pub seq: u64,
+    pub text: String,
+    pub attributes: NeuralRopeAttributes,
+}
+
+impl TraceRecord {
+    pub fn domain(&self) -> String {
+        plane_domain(&self.attributes.plane_label)
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SegmentInfo {
+    pub name: String,
+    pub first_seq: u64,
+    pub last_seq: u64,
+    pub records: u64,
+    /// sha256 of the segment file bytes at snapshot time.
+    pub sha256: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SnapshotManifest {
+    pub version: u32,
+    pub seq: u64,
+    pub segments: Vec<SegmentInfo>,
+    pub trace_count: u64,
+    pub next_trace_seq: u64,
+    pub compacted: bool,
+    pub prev_manifest_hash: Option<String>,
+    pub manifest_hash: String,
+}
+
+impl SnapshotManifest {
+    fn compute_hash(&self) -> String {
+        let mut unhashed = self.clone();
+        unhashed.manifest_hash = String::new();
+        let bytes = serde_json::to_vec(&unhashed).expect("manifest serializes");
+        hex::encode(Sha256::digest(bytes))
+    }
+}
+
+/// Filter for `NeuralRopeStore::query`; unset fields match everything.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TraceQuery {
+    pub domain: Option<String>,
+    pub plane_label: Option<String>,
+    pub bioscale_upgrade_id: Option<String>,
+    /// Prefix match, so `"Allow"` also matches `"AllowWithWarning"`.
+    pub safety_decision: Option<String>,
+    pub min_reward: Option<f32>,
+    pub max_reward: Option<f32>,
+    pub after_seq: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+impl TraceQuery {
+    pub fn matches(&self, r: &TraceRecord) -> bool {
+        let a = &r.attributes;
+        self.domain.as_ref().is_none_or(|d| r.domain() == *d)
+            && self.plane_label.as_ref().is_none_or(|p| a.plane_label == *p)
+            && self.bioscale_upgrade_id.as_ref().is_none_or(|u| a.bioscale_upgrade_id.as_deref() == Some(u.as_str()))
+            && self.safety_decision.as_ref().is_none_or(|s| a.safety_decision.starts_with(s.as_str()))
+            && self.min_reward.is_none_or(|m| a.reward_score >= m)
+            && self.max_reward.is_none_or(|m| a.reward_score <= m)
+            && self.after_seq.is_none_or(|s| r.seq > s)
+    }
+}
+
+/// Which records survive `compact`.
+#[derive(Clone, Debug, Default)]
+pub struct CompactionPolicy {
+    /// Keep only the newest N traces.
+    pub max_traces: Option<usize>,
+    /// Drop traces whose safety decision is not `Allow*`.
+    pub drop_disallowed: bool,
+    pub min_reward: Option<f32>,
+}
+
+struct ActiveSegment {
+    name: String,
+    file: File,
+    first_seq: Option<u64>,
+    records: u64,
+}
+
+pub struct NeuralRopeStore {
+    root: PathBuf,
+    cfg: NeuralRopeStoreConfig,
+    manifest: Option<SnapshotManifest>,
+    /// Sealed segments not yet covered by a manifest.
+    pending_sealed: Vec<SegmentInfo>,
+    active: Option<ActiveSegment>,
+    next_segment: u64,
+    next_seq: u64,
+    records: Vec<TraceRecord>,
+}
+
+impl NeuralRopeStore {
+    /// Open (or create) the store at `root` and rebuild the rope it describes.
+    pub fn open<P: AsRef<Path>>(root: P, cfg: NeuralRopeStoreConfig) -> Result<(Self, NeuralRope), NeuralRopeStoreError> {
+        let root = root.as_ref().to_path_buf();
+        fs::create_dir_all(root.join("segments"))?;
+        fs::create_dir_all(root.join("manifests"))?;
+
+        let manifest = load_current_manifest(&root)?;
+        let mut records = Vec::new();
+        let mut covered = std::collections::HashSet::new();
+        if let Some(m) = &manifest {
+            for seg in &m.segments {
+                let path = root.join("segments").join(&seg.name);
+                let bytes = fs::read(&path)?;
+                if hex::encode(Sha256::digest(&bytes)) != seg.sha256 {
+                    return Err(NeuralRopeStoreError::CorruptSegment { name: seg.name.clone(), reason: "digest mismatch".into() });
+                }
+                let recs = parse_segment(&seg.name, &bytes)?;
+                if recs.len() as u64 != seg.records {
+                    return Err(NeuralRopeStoreError::CorruptSegment { name: seg.name.clone(), reason: "record count mismatch".into() });
+                }
+                records.extend(recs);
+                covered.insert(seg.name.clone());
+            }
+        }
+
+        // Segments written after the last snapshot (including a half-written
+        // tail line from a crash, which is dropped).
+        let mut next_seq = manifest.as_ref().map_or(0, |m| m.next_trace_seq);
+        let mut tail: Vec<(u64, String)> = segment_files(&root)?.into_iter().filter(|(_, n)| !covered.contains(n)).collect();
+        tail.sort();
+        let mut pending_sealed = Vec::new();
+        let mut active = None;
+        let next_segment = segment_files(&root)?.iter().map(|(n, _)| n + 1).max().unwrap_or(1);
+        for (i, (_, name)) in tail.iter().enumerate() {
+            let path = root.join("segments").join(name);
+            let bytes = fs::read(&path)?;
+            let mut recs = parse_segment_lenient(&bytes);
+            recs.retain(|r| r.seq >= next_seq);
+            if let Some(last) = recs.last() {
+                next_seq = last.seq + 1;
+            }
+            let is_last = i + 1 == tail.len();
+            if is_last {
+                // Rewrite so a truncated tail line cannot corrupt later appends.
+                let mut clean = Vec::new();
+                for r in &recs {
+                    clean.extend(serde_json::to_vec(r)?);
+                    clean.push(b'\n');
+                }
+                if clean != bytes {
+                    write_atomic(&path, &clean)?;
+                }
+                let file = OpenOptions::new().append(true).open(&path)?;
+                active = Some(ActiveSegment { name: name.clone(), file, first_seq: recs.first().map(|r| r.seq), records: recs.len() as u64 });
+            } else if !recs.is_empty() {
+                pending_sealed.push(segment_info(name, &bytes, &recs));
+            }
+            records.extend(recs);
+        }
+
+        let rope = rope_from(&records);
+        let store = Self { root, cfg, manifest, pending_sealed, active, next_segment, next_seq, records };
+        Ok((store, rope))
+    }
+
+    /// Persist a trace, then append it to `rope`. Returns the trace's seq.
+    /// If this fills the active segment, the configured snapshot/compaction
+    /// policy runs and `rope` is rebuilt when traces were dropped.
+    pub fn append(
+        &mut self,
+        rope: &mut NeuralRope,
+        trace: &str,
+        plane_label: &str,
+        bioscale_upgrade_id: Option<String>,
+        reward_score: f32,
+        safety_decision: &str,
+    ) -> Result<u64, NeuralRopeStoreError> {
+        let record = TraceRecord {
+            seq: self.next_seq,
+            text: trace.to_string(),
+            attributes: NeuralRopeAttributes {
+                plane_label: plane_label.to_string(),
+                bioscale_upgrade_id,
+                reward_score,
+                safety_decision: safety_decision.to_string(),
+            },
+        };
+        if self.active.as_ref().is_none_or(|a| a.records as usize >= self.cfg.max_segment_records) {
+            self.roll_segment()?;
+        }
+        let active = self.active.as_mut().expect("rolled above");
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        let len = active.file.metadata()?.len();
+        let sync = self.cfg.sync_every_append;
+        if let Err(e) = active.file.write_all(&line).and_then(|_| if sync { active.file.sync_data() } else { Ok(()) }) {
+            // Drop a partial line so later appends land on a clean boundary.
+            let _ = active.file.set_len(len);
+            return Err(e.into());
+        }
+        active.first_seq.get_or_insert(record.seq);
+        active.records += 1;
+        self.next_seq += 1;
+
+        push_record(rope, &record);
+        self.records.push(record);
+        self.maintain(rope)?;
+        Ok(self.next_seq - 1)
+    }
+
+    /// Snapshot or compact once the active segment is full, per the config.
+    fn maintain(&mut self, rope: &mut NeuralRope) -> Result<(), NeuralRopeStoreError> {
+        if self.active.as_ref().is_none_or(|a| (a.records as usize) < self.cfg.max_segment_records) {
+            return Ok(());
+        }
+        let live = self.manifest.as_ref().map_or(0, |m| m.segments.len()) + self.pending_sealed.len() + 1;
+        if let Some(policy) = self.cfg.compaction.clone().filter(|_| live > self.cfg.compact_after_segments) {
+            if self.compact(&policy)? > 0 {
+                *rope = self.rope();
+            }
+        } else if self.cfg.snapshot_full_segments {
+            self.snapshot()?;
+        }
+        Ok(())
+    }
+
+    /// Seal the active segment and write a manifest chained to the previous one.
+    pub fn snapshot(&mut self) -> Result<SnapshotManifest, NeuralRopeStoreError> {
+        self.seal_active()?;
+        let mut segments = self.manifest.as_ref().map(|m| m.segments.clone()).unwrap_or_default();
+        segments.append(&mut self.pending_sealed);
+        self.commit_manifest(segments, false)
+    }
+
+    /// Rewrite everything that survives `policy` into one segment and commit
+    /// it with a new manifest; superseded segment files are then removed.
+    /// Returns the number of traces dropped. The caller should rebuild its
+    /// rope with `rope()` afterwards.
+    pub fn compact(&mut self, policy: &CompactionPolicy) -> Result<usize, NeuralRopeStoreError> {
+        self.seal_active()?;
+        let before = self.records.len();
+        let mut kept: Vec<TraceRecord> = self
+            .records
+            .iter()
+            .filter(|r| !policy.drop_disallowed || r.attributes.safety_decision.starts_with("Allow"))
+            .filter(|r| policy.min_reward.is_none_or(|m| r.attributes.reward_score >= m))
+            .cloned()
+            .collect();
+        if let Some(max) = policy.max_traces {
+            let skip = kept.len().saturating_sub(max);
+            kept.drain(..skip);
+        }
+
+        let old: Vec<String> = segment_files(&self.root)?.into_iter().map(|(_, n)| n).collect();
+        let mut segments = Vec::new();
+        if !kept.is_empty() {
+            let name = self.take_segment_name();
+            let mut bytes = Vec::new();
+            for r in &kept {
+                bytes.extend(serde_json::to_vec(r)?);
+                bytes.push(b'\n');
+            }
+            write_atomic(&self.root.join("segments").join(&name), &bytes)?;
+            segments.push(segment_info(&name, &bytes, &kept));
+        }
+        self.pending_sealed.clear();
+        self.commit_manifest(segments, true)?;
+        for name in old {
+            let _ = fs::remove_file(self.root.join("segments").join(name));
+        }
+        let dropped = before - kept.len();
+        self.records = kept;
+        Ok(dropped)
+    }
+
+    /// Rebuild an in-memory rope from the persisted records.
+    pub fn rope(&self) -> NeuralRope {
+        rope_from(&self.records)
+    }
+
+    /// Traces matching `q`, oldest first.
+    pub fn query(&self, q: &TraceQuery) -> Vec<&TraceRecord> {
+        let it = self.records.iter().filter(|r| q.matches(r));
+        match q.limit {
+            Some(n) => it.take(n).collect(),
+            None => it.collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn current_manifest(&self) -> Option<&SnapshotManifest> {
+        self.manifest.as_ref()
+    }
+
+    /// Walk every manifest on disk and check that each one hashes correctly
+    /// and links to its predecessor. Returns the chain, oldest first.
+    pub fn verify_manifest_chain(&self) -> Result<Vec<SnapshotManifest>, NeuralRopeStoreError> {
+        let mut names: Vec<(u64, String)> = numbered_files(&self.root.join("manifests"), "manifest-", ".json")?;
+        names.sort();
+        let mut chain: Vec<SnapshotManifest> = Vec::new();
+        for (_, name) in names {
+            let m = read_manifest(&self.root, &name)?;
+            let expected_prev = chain.last().map(|p| p.manifest_hash.clone());
+            if m.prev_manifest_hash != expected_prev {
+                return Err(NeuralRopeStoreError::CorruptManifest { name, reason: "broken hash chain".into() });
+            }
+            chain.push(m);
+        }
+        Ok(chain)
+    }
+
+    fn commit_manifest(&mut self, segments: Vec<SegmentInfo>, compacted: bool) -> Result<SnapshotManifest, NeuralRopeStoreError> {
+        let prev = self.manifest.as_ref();
+        let mut m = SnapshotManifest {
+            version: MANIFEST_VERSION,
+            seq: prev.map_or(0, |p| p.seq + 1),
+            trace_count: segments.iter().map(|s| s.records).sum(),
+            segments,
+            next_trace_seq: self.next_seq,
+            compacted,
+            prev_manifest_hash: prev.map(|p| p.manifest_hash.clone()),
+            manifest_hash: String::new(),
+        };
+        m.manifest_hash = m.compute_hash();
+        let name = format!("manifest-{:06}.json", m.seq);
+        write_atomic(&self.root.join("manifests").join(&name), &serde_json::to_vec_pretty(&m)?)?;
+        write_atomic(&self.root.join("CURRENT"), name.as_bytes())?;
+        self.manifest = Some(m.clone());
+        Ok(m)
+    }
+
+    fn seal_active(&mut self) -> Result<(), NeuralRopeStoreError> {
+        if let Some(active) = self.active.take() {
+            active.file.sync_all()?;
+            if active.records > 0 {
+                let bytes = fs::read(self.root.join("segments").join(&active.name))?;
+                let recs = parse_segment(&active.name, &bytes)?;
+                self.pending_sealed.push(segment_info(&active.name, &bytes, &recs));
+            }
+        }
+        Ok(())
+    }
+
+    fn roll_segment(&mut self) -> Result<(), NeuralRopeStoreError> {
+        self.seal_active()?;
+        let name = self.take_segment_name();
+        let file = OpenOptions::new().create(true).append(true).open(self.root.join("segments").join(&name))?;
+        self.active = Some(ActiveSegment { name, file, first_seq: None, records: 0 });
+        Ok(())
+    }
+
+    fn take_segment_name(&mut self) -> String {
+        let name = format!("seg-{:06}.jsonl", self.next_segment);
+        self.next_segment += 1;
+        name
+    }
+}
+
+/// Durable stores are trace sinks too, so recorders can target either.
+pub struct DurableNeuralRope {
+    pub rope: NeuralRope,
+    pub store: NeuralRopeStore,
+}
+
+impl DurableNeuralRope {
+    pub fn open<P: AsRef<Path>>(root: P, cfg: NeuralRopeStoreConfig) -> Result<Self, NeuralRopeStoreError> {
+        let (store, rope) = NeuralRopeStore::open(root, cfg)?;
+        Ok(Self { rope, store })
+    }
+
+    pub fn compact(&mut self, policy: &CompactionPolicy) -> Result<usize, NeuralRopeStoreError> {
+        let dropped = self.store.compact(policy)?;
+        self.rope = self.store.rope();
+        Ok(dropped)
+    }
+}
+
+impl TraceSink for DurableNeuralRope {
+    fn record_trace(
+        &mut self,
+        trace: &str,
+        plane_label: &str,
+        bioscale_upgrade_id: Option<String>,
+        reward_score: f32,
+        safety_decision: &str,
+    ) -> std::io::Result<()> {
+        self.store
+            .append(&mut self.rope, trace, plane_label, bioscale_upgrade_id, reward_score, safety_decision)
+            .map(|_| ())
+            .map_err(|e| match e {
+                NeuralRopeStoreError::Io(io) => io,
+                other => std::io::Error::other(other.to_string()),
+            })
+    }
+}
+
+fn push_record(rope: &mut NeuralRope, r: &TraceRecord) {
+    let a = &r.attributes;
+    rope.append_trace(&r.text, &a.plane_label, a.bioscale_upgrade_id.clone(), a.reward_score, &a.safety_decision);
+}
+
+fn rope_from(records: &[TraceRecord]) -> NeuralRope {
+    let mut rope = NeuralRope::new();
+    for r in records {
+        push_record(&mut rope, r);
+    }
+    rope
+}
+
+fn segment_info(name: &str, bytes: &[u8], recs: &[TraceRecord]) -> SegmentInfo {
+    SegmentInfo {
+        name: name.to_string(),
+        first_seq: recs.first().map_or(0, |r| r.seq),
+        last_seq: recs.last().map_or(0, |r| r.seq),
+        records: recs.len() as u64,
+        sha256: hex::encode(Sha256::digest(bytes)),
+    }
+}
+
+fn parse_segment(name: &str, bytes: &[u8]) -> Result<Vec<TraceRecord>, NeuralRopeStoreError> {
+    let mut out = Vec::new();
+    for line in BufReader::new(bytes).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let r: TraceRecord = serde_json::from_str(&line)
+            .map_err(|e| NeuralRopeStoreError::CorruptSegment { name: name.to_string(), reason: e.to_string() })?;
+        if out.last().is_some_and(|p: &TraceRecord| r.seq <= p.seq) {
+            return Err(NeuralRopeStoreError::CorruptSegment { name: name.to_string(), reason: "seq not increasing".into() });
+        }
+        out.push(r);
+    }
+    Ok(out)
+}
+
+/// Like `parse_segment`, but stops at the first unreadable line instead of failing.
+fn parse_segment_lenient(bytes: &[u8]) -> Vec<TraceRecord> {
+    let mut out: Vec<TraceRecord> = Vec::new();
+    for line in BufReader::new(bytes).lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<TraceRecord>(&line) {
+            Ok(r) if out.last().is_none_or(|p| r.seq > p.seq) => out.push(r),
+            _ => break,
+        }
+    }
+    out
+}
+
+fn segment_files(root: &Path) -> Result<Vec<(u64, String)>, NeuralRopeStoreError> {
+    numbered_files(&root.join("segments"), "seg-", ".jsonl")
+}
+
+fn numbered_files(dir: &Path, prefix: &str, suffix: &str) -> Result<Vec<(u64, String)>, NeuralRopeStoreError> {
+    let mut out = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let name = entry?.file_name().to_string_lossy().into_owned();
+        if let Some(n) = name.strip_prefix(prefix).and_then(|s| s.strip_suffix(suffix)).and_then(|s| s.parse().ok()) {
+            out.push((n, name));
+        }
+    }
+    Ok(out)
+}
+
+fn read_manifest(root: &Path, name: &str) -> Result<SnapshotManifest, NeuralRopeStoreError> {
+    let raw = fs::read_to_string(root.join("manifests").join(name))?;
+    let m: SnapshotManifest = serde_json::from_str(&raw)?;
+    if m.version > MANIFEST_VERSION {
+        return Err(NeuralRopeStoreError::CorruptManifest { name: name.to_string(), reason: format!("unsupported version {}", m.version) });
+    }
+    if m.compute_hash() != m.manifest_hash {
+        return Err(NeuralRopeStoreError::CorruptManifest { name: name.to_string(), reason: "hash mismatch".into() });
+    }
+    Ok(m)
+}
+
+fn load_current_manifest(root: &Path) -> Result<Option<SnapshotManifest>, NeuralRopeStoreError> {
+    let current = root.join("CURRENT");
+    if !current.exists() {
+        return Ok(None);
+    }
+    let name = fs::read_to_string(current)?.trim().to_string();
+    read_manifest(root, &name).map(Some)
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<(), NeuralRopeStoreError> {
+    let tmp = path.with_extension("tmp");
+    {
+        let mut f = File::create(&tmp)?;
+        f.write_all(bytes)?;
+        f.sync_all()?;
+    }
+    fs::rename(tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("neural-rope-store-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn small() -> NeuralRopeStoreConfig {
+        NeuralRopeStoreConfig {
+            max_segment_records: 2,
+            sync_every_append: false,
+            snapshot_full_segments: false,
+            compaction: None,
+            compact_after_segments: 0,
+        }
+    }
+
+    #[test]
+    fn full_segments_snapshot_and_compact_automatically() {
+        let dir = scratch("auto");
+        let cfg = NeuralRopeStoreConfig {
+            snapshot_full_segments: true,
+            compaction: Some(CompactionPolicy { max_traces: Some(3), ..CompactionPolicy::default() }),
+            compact_after_segments: 2,
+            ..small()
+        };
+        let mut rope = DurableNeuralRope::open(&dir, cfg.clone()).unwrap();
+        for i in 0..4 {
+            rope.record_trace(&format!("t{i}"), "bci/hci/eeg", None, 1.0, "Allow").unwrap();
+        }
+        // seg 1 filled -> snapshot; seg 2 filled with 2 live segments -> snapshot.
+        assert_eq!(rope.store.current_manifest().unwrap().trace_count, 4);
+        assert!(!rope.store.current_manifest().unwrap().compacted);
+
+        for i in 4..6 {
+            rope.record_trace(&format!("t{i}"), "bci/hci/eeg", None, 1.0, "Allow").unwrap();
+        }
+        // seg 3 filled with 3 live segments -> compact down to the newest 3.
+        let m = rope.store.current_manifest().unwrap().clone();
+        assert!(m.compacted);
+        assert_eq!(m.trace_count, 3);
+        assert_eq!(rope.rope.export_traces().len(), 3);
+        drop(rope);
+
+        let (store, _) = NeuralRopeStore::open(&dir, cfg).unwrap();
+        assert_eq!(store.query(&TraceQuery::default()).iter().map(|r| r.seq).collect::<Vec<_>>(), vec![3, 4, 5]);
+        assert_eq!(store.verify_manifest_chain().unwrap().len(), 3);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn reopen_replays_snapshot_and_tail() {
+        let dir = scratch("reopen");
+        {
+            let (mut store, mut rope) = NeuralRopeStore::open(&dir, small()).unwrap();
+            for i in 0..3 {
+                store.append(&mut rope, &format!("grip {i}"), "bci/hci/eeg", Some("up-1".into()), 0.5 + i as f32 / 10.0, "Allow").unwrap();
+            }
+            store.snapshot().unwrap();
+            store.append(&mut rope, "rotate", "emg/forearm", None, 0.2, "Deny").unwrap();
+        }
+        let (store, rope) = NeuralRopeStore::open(&dir, small()).unwrap();
+        assert_eq!(store.len(), 4);
+        assert_eq!(rope.export_traces().len(), 4);
+        assert_eq!(store.current_manifest().unwrap().trace_count, 3);
+
+        let q = TraceQuery { domain: Some("bci".into()), min_reward: Some(0.55), ..TraceQuery::default() };
+        assert_eq!(store.query(&q).iter().map(|r| r.seq).collect::<Vec<_>>(), vec![1, 2]);
+        let q = TraceQuery { safety_decision: Some("Deny".into()), ..TraceQuery::default() };
+        assert_eq!(store.query(&q).len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn compaction_keeps_chain_and_seq() {
+        let dir = scratch("compact");
+        let mut rope = DurableNeuralRope::open(&dir, small()).unwrap();
+        for (i, d) in ["Allow", "Deny", "Allow"].iter().enumerate() {
+            rope.record_trace(&format!("t{i}"), "bci/hci/eeg", None, 1.0, d).unwrap();
+        }
+        rope.store.snapshot().unwrap();
+        let dropped = rope.compact(&CompactionPolicy { drop_disallowed: true, ..CompactionPolicy::default() }).unwrap();
+        assert_eq!(dropped, 1);
+        assert_eq!(rope.store.verify_manifest_chain().unwrap().len(), 2);
+
+        rope.record_trace("t3", "bci/hci/eeg", None, 1.0, "Allow").unwrap();
+        drop(rope);
+        let (store, _) = NeuralRopeStore::open(&dir, small()).unwrap();
+        assert_eq!(store.query(&TraceQuery::default()).iter().map(|r| r.seq).collect::<Vec<_>>(), vec![0, 2, 3]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn tampered_segment_is_rejected() {
+        let dir = scratch("tamper");
+        {
+            let (mut store, mut rope) = NeuralRopeStore::open(&dir, small()).unwrap();
+            store.append(&mut rope, "grip", "bci/hci/eeg", None, 1.0, "Allow").unwrap();
+            store.snapshot().unwrap();
+        }
+        let seg = dir.join("segments").join("seg-000001.jsonl");
+        let raw = fs::read_to_string(&seg).unwrap().replace("grip", "grab");
+        fs::write(&seg, raw).unwrap();
+        assert!(matches!(NeuralRopeStore::open(&dir, small()), Err(NeuralRopeStoreError::CorruptSegment { .. })));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -6,7 +6,8 @@ use bioscale_upgrade_store::bioscale::upgrade_asset::{
     HardwareBindingProfile,
 };
 use bioscale_upgrade_store::{
-    BioscaleStoreConfig, BioscaleUpgradeStore, UpgradeApplicationResult,
+    BioscaleStoreConfig, BioscaleUpgradeStore, EnvironmentProfile, SemVer, UpgradeApplicationResult,
+    UpgradeLifecycle,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::{Mutex, OnceLock};
@@ -25,6 +26,10 @@ fn init_globals() {
                 String::from("DID"),
             ],
             max_upgrade_assets: 1024,
+            // Pre-catalog clients apply to environments they never registered.
+            legacy_environment_fallback: true,
+            // Unversioned registrations stay Draft until approved.
+            legacy_auto_activate: false,
         };
         Mutex::new(BioscaleUpgradeStore::new(cfg))
     });
@@ -44,11 +49,31 @@ pub struct WasmRegisterUpgradeRequest {
     pub required_safety_modules: Vec<String>,
     pub bioscale_resolution_microns: u32,
     pub metadata_hash: String,
+    /// Register a new version of an existing upgrade instead of a new id.
+    #[serde(default)]
+    pub upgrade_id: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct WasmRegisterUpgradeResponse {
     pub upgrade_id: String,
+    pub version: String,
+    pub state: UpgradeLifecycle,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WasmUpgradeLifecycleRequest {
+    pub upgrade_id: String,
+    pub version: SemVer,
+    pub state: UpgradeLifecycle,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WasmRollbackUpgradeRequest {
+    pub environment_id: String,
+    pub upgrade_id: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -99,7 +124,7 @@ pub fn bioscale_register_upgrade(json_req: &str) -> String {
         bioscale_resolution_microns: req.bioscale_resolution_microns,
     };
 
-    let asset = BioscaleUpgradeAsset::new(
+    let mut asset = BioscaleUpgradeAsset::new(
         &req.human_label,
         awareness,
         compliance,
@@ -107,13 +132,28 @@ pub fn bioscale_register_upgrade(json_req: &str) -> String {
         req.tags,
         &req.metadata_hash,
     );
+    if let Some(id) = req.upgrade_id {
+        asset.id = id;
+    }
+    // Requests without a version come from clients that predate the
+    // lifecycle; they are registered as Draft like any other.
+    let legacy = req.version.is_none();
+    if let Some(version) = req.version {
+        asset.version = version;
+    }
+    let version = asset.version.clone();
 
     let store_mutex = STORE.get().unwrap();
     let mut store = store_mutex.lock().unwrap();
 
-    match store.register_upgrade(asset) {
-        Ok(id) => {
-            let resp = WasmRegisterUpgradeResponse { upgrade_id: id };
+    let registered = if legacy {
+        store.register_legacy_upgrade(asset)
+    } else {
+        store.register_upgrade(asset).map(|id| (id, UpgradeLifecycle::Draft))
+    };
+    match registered {
+        Ok((id, state)) => {
+            let resp = WasmRegisterUpgradeResponse { upgrade_id: id, version, state };
             serde_json::to_string(&resp).unwrap_or_else(|e| {
                 format!(r#"{{"error":"serialization_error","detail":"{}"}}"#, e)
             })
@@ -179,3 +219,50 @@ pub fn bioscale_apply_upgrade(json_req: &str) -> String {
         Err(e) => format!(r#"{{"error":"guard_or_store_error","detail":"{}"}}"#, e),
     }
 }
+
+#[wasm_bindgen]
+pub fn bioscale_register_environment(json_req: &str) -> String {
+    init_globals();
+
+    let profile: EnvironmentProfile = match serde_json::from_str(json_req) {
+        Ok(v) => v,
+        Err(e) => return format!(r#"{{"error":"invalid_json","detail":"{}"}}"#, e),
+    };
+    let mut store = STORE.get().unwrap().lock().unwrap();
+    match store.register_environment(profile) {
+        Ok(()) => String::from(r#"{"ok":true}"#),
+        Err(e) => format!(r#"{{"error":"store_error","detail":"{}"}}"#, e),
+    }
+}
+
+#[wasm_bindgen]
+pub fn bioscale_set_upgrade_lifecycle(json_req: &str) -> String {
+    init_globals();
+
+    let req: WasmUpgradeLifecycleRequest = match serde_json::from_str(json_req) {
+        Ok(v) => v,
+        Err(e) => return format!(r#"{{"error":"invalid_json","detail":"{}"}}"#, e),
+    };
+    let mut store = STORE.get().unwrap().lock().unwrap();
+    match store.set_lifecycle(&req.upgrade_id, &req.version, req.state) {
+        Ok(affected) => serde_json::json!({ "affected_environments": affected }).to_string(),
+        Err(e) => format!(r#"{{"error":"store_error","detail":"{}"}}"#, e),
+    }
+}
+
+#[wasm_bindgen]
+pub fn bioscale_rollback_upgrade(json_req: &str) -> String {
+    init_globals();
+
+    let req: WasmRollbackUpgradeRequest = match serde_json::from_str(json_req) {
+        Ok(v) => v,
+        Err(e) => return format!(r#"{{"error":"invalid_json","detail":"{}"}}"#, e),
+    };
+    let mut store = STORE.get().unwrap().lock().unwrap();
+    match store.rollback_upgrade(&req.environment_id, &req.upgrade_id) {
+        Ok(result) => serde_json::to_string(&result).unwrap_or_else(|e| {
+            format!(r#"{{"error":"serialization_error","detail":"{}"}}"#, e)
+        }),
+        Err(e) => format!(r#"{{"error":"guard_or_store_error","detail":"{}"}}"#, e),
+    }
+}
@@ -1,2 +1,3 @@
 pub mod upgrade_asset;
+pub mod upgrade_catalog;
 pub mod upgrade_store;
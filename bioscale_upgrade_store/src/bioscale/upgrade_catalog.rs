@@ -0,0 +1,277 @@
+//! Versioned catalog types for the bioscale upgrade store: semantic versions,
+//! lifecycle states, per-environment profiles and applied-version history,
+//! plus the JSON file the store persists them in.
+
+use crate::bioscale::upgrade_asset::BioscaleUpgradeAsset;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+pub const CATALOG_FILE_VERSION: u32 = 1;
+
+/// `MAJOR.MINOR.PATCH`; pre-release and build suffixes are not accepted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct SemVer {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl SemVer {
+    pub const fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self { major, minor, patch }
+    }
+}
+
+impl Ord for SemVer {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
+    }
+}
+
+impl PartialOrd for SemVer {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for SemVer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl FromStr for SemVer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.trim().trim_start_matches('v').split('.').collect();
+        let [major, minor, patch] = parts.as_slice() else {
+            return Err(format!("expected MAJOR.MINOR.PATCH, got {s:?}"));
+        };
+        let num = |p: &str| {
+            if p.is_empty() || (p.len() > 1 && p.starts_with('0')) {
+                return Err(format!("invalid version component {p:?} in {s:?}"));
+            }
+            p.parse::<u64>().map_err(|_| format!("invalid version component {p:?} in {s:?}"))
+        };
+        Ok(Self { major: num(major)?, minor: num(minor)?, patch: num(patch)? })
+    }
+}
+
+impl TryFrom<String> for SemVer {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<SemVer> for String {
+    fn from(v: SemVer) -> Self {
+        v.to_string()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UpgradeLifecycle {
+    Draft,
+    Approved,
+    Active,
+    Deprecated,
+    Revoked,
+}
+
+impl UpgradeLifecycle {
+    /// Draft → Approved → Active → Deprecated, with Revoked reachable from
+    /// every state and final. Deprecated versions stay applied where they are
+    /// but cannot be newly applied.
+    pub fn can_transition_to(self, to: UpgradeLifecycle) -> bool {
+        use UpgradeLifecycle::*;
+        matches!(
+            (self, to),
+            (Draft, Approved) | (Approved, Active) | (Active, Deprecated) | (Draft | Approved | Active | Deprecated, Revoked)
+        )
+    }
+
+    pub fn applicable(self) -> bool {
+        self == UpgradeLifecycle::Active
+    }
+
+    /// States an environment may hold or roll back to.
+    pub fn retainable(self) -> bool {
+        matches!(self, UpgradeLifecycle::Active | UpgradeLifecycle::Deprecated)
+    }
+}
+
+/// One version of an upgrade id in the catalog.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct VersionedUpgrade {
+    pub version: SemVer,
+    pub state: UpgradeLifecycle,
+    pub asset: BioscaleUpgradeAsset,
+}
+
+/// Guard inputs that used to be hard-coded in `apply_upgrade_to_environment`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EnvironmentGuardConfig {
+    /// Replaces the store-wide `default_regulatory_labels` when non-empty.
+    #[serde(default)]
+    pub regulatory_labels: Vec<String>,
+    /// Required on top of each upgrade's own `required_safety_modules`.
+    #[serde(default)]
+    pub additional_safety_modules: Vec<String>,
+    #[serde(default)]
+    pub frozen: bool,
+}
+
+/// Per-environment configuration an upgrade is applied under.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EnvironmentProfile {
+    pub environment_id: String,
+    pub controller_contract: String,
+    #[serde(default)]
+    pub guard: EnvironmentGuardConfig,
+}
+
+/// Controller contract every environment was checked against before
+/// environments carried their own profile.
+pub const LEGACY_CONTROLLER_CONTRACT: &str = "0x519fC0eB4111323Cac44b70e1aE31c30e405802D";
+
+impl EnvironmentProfile {
+    /// The guard inputs `apply_upgrade_to_environment` hard-coded before
+    /// profiles existed: the legacy controller, not frozen, store-wide labels.
+    pub fn legacy(environment_id: &str) -> Self {
+        Self {
+            environment_id: environment_id.to_string(),
+            controller_contract: LEGACY_CONTROLLER_CONTRACT.to_string(),
+            guard: EnvironmentGuardConfig::default(),
+        }
+    }
+}
+
+/// A version applied to an environment, with the inputs needed to re-check it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppliedUpgrade {
+    pub seq: u64,
+    pub upgrade_id: String,
+    pub version: SemVer,
+    pub guard_decision: String,
+    pub environment_hardware: Vec<String>,
+    pub environment_tags: Vec<String>,
+}
+
+/// Applied-version history for one (environment, upgrade id) pair; the last
+/// entry is the version currently in effect.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct AppliedHistory {
+    pub environment_id: String,
+    pub upgrade_id: String,
+    pub stack: Vec<AppliedUpgrade>,
+}
+
+impl AppliedHistory {
+    pub fn current(&self) -> Option<&AppliedUpgrade> {
+        self.stack.last()
+    }
+
+    /// Most recent entry below the current one whose version `keep` accepts.
+    pub fn rollback_target(&self, keep: impl Fn(&SemVer) -> bool) -> Option<&AppliedUpgrade> {
+        let current = self.current()?;
+        self.stack.iter().rev().skip(1).find(|a| a.version != current.version && keep(&a.version))
+    }
+}
+
+/// On-disk form of the whole catalog.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CatalogFile {
+    pub version: u32,
+    pub next_seq: u64,
+    pub upgrades: Vec<(String, Vec<VersionedUpgrade>)>,
+    pub environments: Vec<EnvironmentProfile>,
+    pub applied: Vec<AppliedHistory>,
+}
+
+impl CatalogFile {
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read_to_string(path).map_err(|e| format!("read {}: {e}", path.display()))?;
+        let file: CatalogFile = serde_json::from_str(&raw).map_err(|e| format!("parse {}: {e}", path.display()))?;
+        if file.version > CATALOG_FILE_VERSION {
+            return Err(format!("catalog version {} is newer than supported {}", file.version, CATALOG_FILE_VERSION));
+        }
+        Ok(Some(file))
+    }
+
+    /// Write via a temp file and rename so a crash never leaves half a catalog.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let err = |e: std::io::Error| format!("write {}: {e}", path.display());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(err)?;
+        }
+        let bytes = serde_json::to_vec_pretty(self).map_err(|e| e.to_string())?;
+        let tmp = path.with_extension("tmp");
+        {
+            let mut f = File::create(&tmp).map_err(err)?;
+            f.write_all(&bytes).map_err(err)?;
+            f.sync_all().map_err(err)?;
+        }
+        fs::rename(&tmp, path).map_err(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn semver_parses_orders_and_round_trips() {
+        let v: SemVer = "1.10.0".parse().unwrap();
+        assert!(v > "1.9.3".parse().unwrap());
+        assert_eq!(v.to_string(), "1.10.0");
+        assert_eq!("v0.1.0".parse::<SemVer>().unwrap(), SemVer::new(0, 1, 0));
+        for bad in ["1.0", "1.0.0.0", "1.01.0", "a.b.c", ""] {
+            assert!(bad.parse::<SemVer>().is_err(), "{bad}");
+        }
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, "\"1.10.0\"");
+        assert_eq!(serde_json::from_str::<SemVer>(&json).unwrap(), v);
+    }
+
+    #[test]
+    fn lifecycle_transitions() {
+        use UpgradeLifecycle::*;
+        assert!(Draft.can_transition_to(Approved));
+        assert!(!Draft.can_transition_to(Active));
+        assert!(Deprecated.can_transition_to(Revoked));
+        assert!(!Revoked.can_transition_to(Active));
+        assert!(!Deprecated.can_transition_to(Active));
+    }
+
+    #[test]
+    fn rollback_skips_current_version_and_filtered_entries() {
+        let entry = |seq, v: &str| AppliedUpgrade {
+            seq,
+            upgrade_id: "u".into(),
+            version: v.parse().unwrap(),
+            guard_decision: "Allow".into(),
+            environment_hardware: Vec::new(),
+            environment_tags: Vec::new(),
+        };
+        let h = AppliedHistory {
+            environment_id: "env".into(),
+            upgrade_id: "u".into(),
+            stack: vec![entry(0, "1.0.0"), entry(1, "1.1.0"), entry(2, "1.2.0")],
+        };
+        assert_eq!(h.rollback_target(|_| true).unwrap().version, SemVer::new(1, 1, 0));
+        let revoked = SemVer::new(1, 1, 0);
+        assert_eq!(h.rollback_target(|v| *v != revoked).unwrap().version, SemVer::new(1, 0, 0));
+    }
+}
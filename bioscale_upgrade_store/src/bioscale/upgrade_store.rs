@@ -2,12 +2,17 @@ use crate::bioscale::upgrade_asset::{
     BioscaleAwarenessProfile, BioscaleUpgradeAsset, ConsciousnessComplianceLevel,
     HardwareBindingProfile,
 };
+use crate::bioscale::upgrade_catalog::{
+    AppliedHistory, AppliedUpgrade, CatalogFile, EnvironmentProfile, SemVer, UpgradeLifecycle,
+    VersionedUpgrade, CATALOG_FILE_VERSION,
+};
 use augdoctor_core::biophysical::plane_classifier::{
     ConsciousnessState, EnvironmentMetadata, EnvironmentPlane,
 };
 use augdoctor_core::quantum::agent_guard::{GuardDecision, QuantumLearningGuard};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Configuration details for the bioscale upgrade store.
@@ -16,6 +21,15 @@ pub struct BioscaleStoreConfig {
     pub allow_offline_registration: bool,
     pub default_regulatory_labels: Vec<String>,
     pub max_upgrade_assets: usize,
+    /// Applying to an environment with no profile registers
+    /// `EnvironmentProfile::legacy` for it instead of failing, as clients that
+    /// predate environment profiles expect.
+    #[serde(default)]
+    pub legacy_environment_fallback: bool,
+    /// `register_legacy_upgrade` promotes straight to Active instead of
+    /// leaving a Draft. Off by default, since the promotion skips approval.
+    #[serde(default)]
+    pub legacy_auto_activate: bool,
 }
 
 /// Errors that can occur while working with the bioscale upgrade store.
@@ -31,6 +45,20 @@ pub enum BioscaleStoreError {
     HardwareMismatch(String),
     #[error("storage error: {0}")]
     StorageError(String),
+    #[error("invalid version: {0}")]
+    InvalidVersion(String),
+    #[error("upgrade {id} version {version} already registered")]
+    VersionExists { id: String, version: SemVer },
+    #[error("upgrade {id} version {got} is not newer than {latest}")]
+    VersionNotNewer { id: String, latest: SemVer, got: SemVer },
+    #[error("upgrade {id}@{version}: cannot move from {from:?} to {to:?}")]
+    IllegalLifecycle { id: String, version: SemVer, from: UpgradeLifecycle, to: UpgradeLifecycle },
+    #[error("upgrade {id} has no applicable (Active) version{}", .version.map(|v| format!(" {v}")).unwrap_or_default())]
+    NotApplicable { id: String, version: Option<SemVer> },
+    #[error("environment not configured: {0}")]
+    EnvironmentNotConfigured(String),
+    #[error("no earlier version of {upgrade_id} to roll back to in {environment_id}")]
+    NoRollbackTarget { environment_id: String, upgrade_id: String },
 }
 
 /// Result of applying a bioscale upgrade to a given environment profile.
@@ -39,43 +67,231 @@ pub struct UpgradeApplicationResult {
     pub environment_metadata: EnvironmentMetadata,
     pub guard_decision: String,
     pub redacted_fields: Vec<String>,
+    #[serde(default)]
+    pub upgrade_version: Option<SemVer>,
 }
 
 pub struct BioscaleUpgradeStore {
     cfg: BioscaleStoreConfig,
-    registry: HashMap<String, BioscaleUpgradeAsset>,
+    registry: BTreeMap<String, BTreeMap<SemVer, VersionedUpgrade>>,
+    environments: BTreeMap<String, EnvironmentProfile>,
+    applied: BTreeMap<(String, String), AppliedHistory>,
+    next_seq: u64,
+    path: Option<PathBuf>,
     guard: QuantumLearningGuard,
 }
 
 impl BioscaleUpgradeStore {
+    /// In-memory store; nothing is persisted.
     pub fn new(cfg: BioscaleStoreConfig) -> Self {
         BioscaleUpgradeStore {
             cfg,
-            registry: HashMap::new(),
+            registry: BTreeMap::new(),
+            environments: BTreeMap::new(),
+            applied: BTreeMap::new(),
+            next_seq: 0,
+            path: None,
             guard: QuantumLearningGuard::new(),
         }
     }
 
+    /// File-backed store: loads the catalog at `path` if present and rewrites
+    /// it after every mutation.
+    pub fn open<P: AsRef<Path>>(cfg: BioscaleStoreConfig, path: P) -> Result<Self, BioscaleStoreError> {
+        let path = path.as_ref().to_path_buf();
+        let mut store = Self::new(cfg);
+        if let Some(file) = CatalogFile::load(&path).map_err(BioscaleStoreError::StorageError)? {
+            store.next_seq = file.next_seq;
+            store.registry = file
+                .upgrades
+                .into_iter()
+                .map(|(id, versions)| (id, versions.into_iter().map(|v| (v.version, v)).collect()))
+                .collect();
+            store.environments = file.environments.into_iter().map(|e| (e.environment_id.clone(), e)).collect();
+            store.applied = file
+                .applied
+                .into_iter()
+                .map(|h| ((h.environment_id.clone(), h.upgrade_id.clone()), h))
+                .collect();
+        }
+        store.path = Some(path);
+        Ok(store)
+    }
+
+    /// Run `f` and persist the result. If `f` fails or the catalog cannot be
+    /// written, everything `f` changed is undone, so a failed call leaves no
+    /// partial state behind and memory never gets ahead of disk.
+    fn transact<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, BioscaleStoreError>,
+    ) -> Result<T, BioscaleStoreError> {
+        let saved = (self.registry.clone(), self.environments.clone(), self.applied.clone(), self.next_seq);
+        let out = f(self).and_then(|v| self.persist().map(|()| v));
+        if out.is_err() {
+            (self.registry, self.environments, self.applied, self.next_seq) = saved;
+        }
+        out
+    }
+
+    fn persist(&self) -> Result<(), BioscaleStoreError> {
+        let Some(path) = &self.path else { return Ok(()) };
+        let file = CatalogFile {
+            version: CATALOG_FILE_VERSION,
+            next_seq: self.next_seq,
+            upgrades: self
+                .registry
+                .iter()
+                .map(|(id, versions)| (id.clone(), versions.values().cloned().collect()))
+                .collect(),
+            environments: self.environments.values().cloned().collect(),
+            applied: self.applied.values().cloned().collect(),
+        };
+        file.save(path).map_err(BioscaleStoreError::StorageError)
+    }
+
+    /// Register `asset` as a new Draft version of `asset.id`. The version must
+    /// be valid semver and newer than every version already registered for
+    /// that id; re-registering an existing version is rejected.
     pub fn register_upgrade(
         &mut self,
         asset: BioscaleUpgradeAsset,
     ) -> Result<String, BioscaleStoreError> {
-        if self.registry.len() >= self.cfg.max_upgrade_assets {
+        self.transact(|store| store.insert_draft(asset).map(|(id, _)| id))
+    }
+
+    /// Register `asset` and promote it straight to Active, for clients that
+    /// predate the lifecycle and apply what they just registered. Either the
+    /// whole promotion is persisted or nothing is.
+    pub fn register_active_upgrade(
+        &mut self,
+        asset: BioscaleUpgradeAsset,
+    ) -> Result<String, BioscaleStoreError> {
+        self.transact(|store| {
+            let (id, version) = store.insert_draft(asset)?;
+            store.transition(&id, &version, UpgradeLifecycle::Approved)?;
+            store.transition(&id, &version, UpgradeLifecycle::Active)?;
+            Ok(id)
+        })
+    }
+
+    /// Register `asset` for a client that predates the lifecycle and sent no
+    /// version. With `legacy_auto_activate` set it is promoted as by
+    /// `register_active_upgrade`; otherwise it stays Draft. Returns the id and
+    /// the state it was left in.
+    pub fn register_legacy_upgrade(
+        &mut self,
+        asset: BioscaleUpgradeAsset,
+    ) -> Result<(String, UpgradeLifecycle), BioscaleStoreError> {
+        if self.cfg.legacy_auto_activate {
+            self.register_active_upgrade(asset).map(|id| (id, UpgradeLifecycle::Active))
+        } else {
+            self.register_upgrade(asset).map(|id| (id, UpgradeLifecycle::Draft))
+        }
+    }
+
+    fn insert_draft(&mut self, asset: BioscaleUpgradeAsset) -> Result<(String, SemVer), BioscaleStoreError> {
+        let total: usize = self.registry.values().map(BTreeMap::len).sum();
+        if total >= self.cfg.max_upgrade_assets {
             return Err(BioscaleStoreError::RegistryFull(
                 self.cfg.max_upgrade_assets,
             ));
         }
+        let version: SemVer = asset.version.parse().map_err(BioscaleStoreError::InvalidVersion)?;
         let id = asset.id.clone();
-        self.registry.insert(asset.id.clone(), asset);
-        Ok(id)
+        let versions = self.registry.entry(id.clone()).or_default();
+        if versions.contains_key(&version) {
+            return Err(BioscaleStoreError::VersionExists { id, version });
+        }
+        if let Some(latest) = versions.keys().next_back() {
+            if *latest > version {
+                return Err(BioscaleStoreError::VersionNotNewer { id, latest: *latest, got: version });
+            }
+        }
+        versions.insert(version, VersionedUpgrade { version, state: UpgradeLifecycle::Draft, asset });
+        Ok((id, version))
     }
 
+    /// Highest registered version of `id`, whatever its state.
     pub fn get_upgrade(&self, id: &str) -> Result<&BioscaleUpgradeAsset, BioscaleStoreError> {
         self.registry
             .get(id)
+            .and_then(|v| v.values().next_back())
+            .map(|v| &v.asset)
             .ok_or_else(|| BioscaleStoreError::AssetNotFound(id.to_string()))
     }
 
+    pub fn get_version(&self, id: &str, version: &SemVer) -> Result<&VersionedUpgrade, BioscaleStoreError> {
+        self.registry
+            .get(id)
+            .and_then(|v| v.get(version))
+            .ok_or_else(|| BioscaleStoreError::AssetNotFound(format!("{id}@{version}")))
+    }
+
+    /// All versions of `id`, oldest first.
+    pub fn versions(&self, id: &str) -> Vec<&VersionedUpgrade> {
+        self.registry.get(id).map(|v| v.values().collect()).unwrap_or_default()
+    }
+
+    /// Highest Active version of `id`.
+    pub fn active_version(&self, id: &str) -> Option<&VersionedUpgrade> {
+        self.registry.get(id)?.values().rev().find(|v| v.state.applicable())
+    }
+
+    /// Move `id@version` to `to`. Returns the environments that currently
+    /// have this version applied, so a revocation can be followed up.
+    pub fn set_lifecycle(
+        &mut self,
+        id: &str,
+        version: &SemVer,
+        to: UpgradeLifecycle,
+    ) -> Result<Vec<String>, BioscaleStoreError> {
+        self.transact(|store| store.transition(id, version, to))?;
+        Ok(self
+            .applied
+            .values()
+            .filter(|h| h.upgrade_id == id && h.current().is_some_and(|a| a.version == *version))
+            .map(|h| h.environment_id.clone())
+            .collect())
+    }
+
+    fn transition(&mut self, id: &str, version: &SemVer, to: UpgradeLifecycle) -> Result<(), BioscaleStoreError> {
+        let entry = self
+            .registry
+            .get_mut(id)
+            .and_then(|v| v.get_mut(version))
+            .ok_or_else(|| BioscaleStoreError::AssetNotFound(format!("{id}@{version}")))?;
+        if !entry.state.can_transition_to(to) {
+            return Err(BioscaleStoreError::IllegalLifecycle {
+                id: id.to_string(),
+                version: *version,
+                from: entry.state,
+                to,
+            });
+        }
+        entry.state = to;
+        Ok(())
+    }
+
+    pub fn register_environment(&mut self, profile: EnvironmentProfile) -> Result<(), BioscaleStoreError> {
+        self.transact(|store| {
+            store.environments.insert(profile.environment_id.clone(), profile);
+            Ok(())
+        })
+    }
+
+    pub fn environment(&self, environment_id: &str) -> Option<&EnvironmentProfile> {
+        self.environments.get(environment_id)
+    }
+
+    /// Version of `upgrade_id` currently applied to `environment_id`.
+    pub fn applied_version(&self, environment_id: &str, upgrade_id: &str) -> Option<&AppliedUpgrade> {
+        self.applied.get(&(environment_id.to_string(), upgrade_id.to_string()))?.current()
+    }
+
+    pub fn applied_history(&self, environment_id: &str, upgrade_id: &str) -> Option<&AppliedHistory> {
+        self.applied.get(&(environment_id.to_string(), upgrade_id.to_string()))
+    }
+
     /// Check if the requested hardware profile is compatible with the upgrade's
     /// HardwareBindingProfile.
     fn hardware_compatible(
@@ -92,9 +308,9 @@ impl BioscaleUpgradeStore {
         })
     }
 
-    /// Apply an upgrade asset to a given environment hardware and tagging
-    /// configuration, enforcing all AugDoctor rules and returning a detailed
-    /// decision.
+    /// Apply the highest Active version of `upgrade_id` to a configured
+    /// environment, enforcing all AugDoctor rules and returning a detailed
+    /// decision. Successful applications are recorded per environment.
     pub fn apply_upgrade_to_environment(
         &mut self,
         upgrade_id: &str,
@@ -102,9 +318,155 @@ impl BioscaleUpgradeStore {
         environment_hardware: Vec<String>,
         environment_tags: Vec<String>,
     ) -> Result<UpgradeApplicationResult, BioscaleStoreError> {
-        let asset = self.get_upgrade(upgrade_id)?.clone();
+        self.apply_upgrade_to_environment_with(upgrade_id, environment_id, environment_hardware, environment_tags, |_| Ok(()))
+    }
 
-        if !Self::hardware_compatible(&asset.hardware_binding, &environment_hardware) {
+    /// Like `apply_upgrade_to_environment`, but hands the guard's result to
+    /// `before_commit` before the application is recorded. If it fails,
+    /// nothing is applied; callers use this to persist a trace of the decision
+    /// first, so an upgrade is never applied without one.
+    pub fn apply_upgrade_to_environment_with(
+        &mut self,
+        upgrade_id: &str,
+        environment_id: &str,
+        environment_hardware: Vec<String>,
+        environment_tags: Vec<String>,
+        before_commit: impl FnOnce(&UpgradeApplicationResult) -> Result<(), BioscaleStoreError>,
+    ) -> Result<UpgradeApplicationResult, BioscaleStoreError> {
+        let version = self
+            .active_version(upgrade_id)
+            .map(|v| v.version)
+            .ok_or_else(|| {
+                if self.registry.contains_key(upgrade_id) {
+                    BioscaleStoreError::NotApplicable { id: upgrade_id.to_string(), version: None }
+                } else {
+                    BioscaleStoreError::AssetNotFound(upgrade_id.to_string())
+                }
+            })?;
+        self.apply_version_with(upgrade_id, &version, environment_id, environment_hardware, environment_tags, before_commit)
+    }
+
+    /// Apply a specific Active version.
+    pub fn apply_upgrade_version(
+        &mut self,
+        upgrade_id: &str,
+        version: &SemVer,
+        environment_id: &str,
+        environment_hardware: Vec<String>,
+        environment_tags: Vec<String>,
+    ) -> Result<UpgradeApplicationResult, BioscaleStoreError> {
+        self.apply_version_with(upgrade_id, version, environment_id, environment_hardware, environment_tags, |_| Ok(()))
+    }
+
+    fn apply_version_with(
+        &mut self,
+        upgrade_id: &str,
+        version: &SemVer,
+        environment_id: &str,
+        environment_hardware: Vec<String>,
+        environment_tags: Vec<String>,
+        before_commit: impl FnOnce(&UpgradeApplicationResult) -> Result<(), BioscaleStoreError>,
+    ) -> Result<UpgradeApplicationResult, BioscaleStoreError> {
+        let entry = self.get_version(upgrade_id, version)?;
+        if !entry.state.applicable() {
+            return Err(BioscaleStoreError::NotApplicable { id: upgrade_id.to_string(), version: Some(*version) });
+        }
+        let asset = entry.asset.clone();
+        self.transact(|store| {
+            if store.cfg.legacy_environment_fallback && !store.environments.contains_key(environment_id) {
+                store
+                    .environments
+                    .insert(environment_id.to_string(), EnvironmentProfile::legacy(environment_id));
+            }
+            let result = store.evaluate(&asset, environment_id, &environment_hardware, &environment_tags)?;
+            before_commit(&result)?;
+            store.record_applied(upgrade_id, version, environment_id, environment_hardware, environment_tags, result)
+        })
+    }
+
+    fn record_applied(
+        &mut self,
+        upgrade_id: &str,
+        version: &SemVer,
+        environment_id: &str,
+        environment_hardware: Vec<String>,
+        environment_tags: Vec<String>,
+        result: UpgradeApplicationResult,
+    ) -> Result<UpgradeApplicationResult, BioscaleStoreError> {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        let history = self
+            .applied
+            .entry((environment_id.to_string(), upgrade_id.to_string()))
+            .or_insert_with(|| AppliedHistory {
+                environment_id: environment_id.to_string(),
+                upgrade_id: upgrade_id.to_string(),
+                stack: Vec::new(),
+            });
+        let record = AppliedUpgrade {
+            seq,
+            upgrade_id: upgrade_id.to_string(),
+            version: *version,
+            guard_decision: result.guard_decision.clone(),
+            environment_hardware,
+            environment_tags,
+        };
+        // Re-applying the current version refreshes it rather than stacking.
+        match history.stack.last_mut() {
+            Some(top) if top.version == *version => *top = record,
+            _ => history.stack.push(record),
+        }
+        Ok(UpgradeApplicationResult { upgrade_version: Some(*version), ..result })
+    }
+
+    /// Return `environment_id` to the version of `upgrade_id` it ran before the
+    /// current one, skipping revoked versions. The earlier version is re-checked
+    /// by the guard with the hardware and tags it was originally applied with.
+    pub fn rollback_upgrade(
+        &mut self,
+        environment_id: &str,
+        upgrade_id: &str,
+    ) -> Result<UpgradeApplicationResult, BioscaleStoreError> {
+        let key = (environment_id.to_string(), upgrade_id.to_string());
+        let no_target = || BioscaleStoreError::NoRollbackTarget {
+            environment_id: environment_id.to_string(),
+            upgrade_id: upgrade_id.to_string(),
+        };
+        let history = self.applied.get(&key).ok_or_else(no_target)?;
+        let registry = self.registry.get(upgrade_id);
+        let target = history
+            .rollback_target(|v| registry.and_then(|r| r.get(v)).is_some_and(|u| u.state.retainable()))
+            .cloned()
+            .ok_or_else(no_target)?;
+        let asset = self.get_version(upgrade_id, &target.version)?.asset.clone();
+        let result = self.evaluate(&asset, environment_id, &target.environment_hardware, &target.environment_tags)?;
+
+        self.transact(|store| {
+            let history = store.applied.get_mut(&key).expect("checked above");
+            let keep = history.stack.iter().rposition(|a| a.seq == target.seq).expect("target came from this stack");
+            history.stack.truncate(keep + 1);
+            if let Some(top) = history.stack.last_mut() {
+                top.guard_decision = result.guard_decision.clone();
+            }
+            Ok(UpgradeApplicationResult { upgrade_version: Some(target.version), ..result })
+        })
+    }
+
+    /// Run the guard for `asset` under the environment's profile.
+    fn evaluate(
+        &mut self,
+        asset: &BioscaleUpgradeAsset,
+        environment_id: &str,
+        environment_hardware: &[String],
+        environment_tags: &[String],
+    ) -> Result<UpgradeApplicationResult, BioscaleStoreError> {
+        let profile = self
+            .environments
+            .get(environment_id)
+            .cloned()
+            .ok_or_else(|| BioscaleStoreError::EnvironmentNotConfigured(environment_id.to_string()))?;
+
+        if !Self::hardware_compatible(&asset.hardware_binding, environment_hardware) {
             return Err(BioscaleStoreError::HardwareMismatch(format!(
                 "environment hardware {:?} incompatible with allowed {:?}",
                 environment_hardware, asset.hardware_binding.allowed_hardware_ids
@@ -130,8 +492,8 @@ impl BioscaleUpgradeStore {
 
         let net_weight = if asset.implies_brain_tokens() { 1.0 } else { 0.0 };
         let circulating_supply = if asset.implies_brain_tokens() { 1000.0 } else { 0.0 };
-        let frozen = false;
-        let controller_contract = "0x519fC0eB4111323Cac44b70e1aE31c30e405802D";
+        let frozen = profile.guard.frozen;
+        let controller_contract = profile.controller_contract.as_str();
 
         let involves_conscious_pattern = matches!(
             asset.consciousness_compliance,
@@ -139,13 +501,23 @@ impl BioscaleUpgradeStore {
         );
         let contains_identity_descriptors = asset.implies_identity_pattern();
 
-        let mut tags_combined = environment_tags.clone();
+        let mut tags_combined = environment_tags.to_vec();
         tags_combined.extend(asset.tags.clone());
 
-        let mut hardware_combined = environment_hardware.clone();
+        let mut hardware_combined = environment_hardware.to_vec();
         hardware_combined.extend(asset.hardware_binding.allowed_hardware_ids.clone());
 
-        let regulatory_labels = self.cfg.default_regulatory_labels.clone();
+        let regulatory_labels = if profile.guard.regulatory_labels.is_empty() {
+            self.cfg.default_regulatory_labels.clone()
+        } else {
+            profile.guard.regulatory_labels.clone()
+        };
+        let mut required_safety_modules = asset.hardware_binding.required_safety_modules.clone();
+        for m in &profile.guard.additional_safety_modules {
+            if !required_safety_modules.contains(m) {
+                required_safety_modules.push(m.clone());
+            }
+        }
 
         let (meta, decision, _log) = self.guard.enforce(
             environment_id,
@@ -157,7 +529,7 @@ impl BioscaleUpgradeStore {
             circulating_supply,
             frozen,
             controller_contract,
-            asset.hardware_binding.required_safety_modules.clone(),
+            required_safety_modules,
             involves_conscious_pattern,
             contains_identity_descriptors,
             tags_combined,
@@ -188,6 +560,7 @@ impl BioscaleUpgradeStore {
             environment_metadata: meta,
             guard_decision,
             redacted_fields,
+            upgrade_version: None,
         })
     }
 }
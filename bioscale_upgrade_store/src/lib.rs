@@ -4,6 +4,10 @@ pub use bioscale::upgrade_asset::{
     BioscaleAwarenessProfile, BioscaleUpgradeAsset, ConsciousnessComplianceLevel,
     HardwareBindingProfile,
 };
+pub use bioscale::upgrade_catalog::{
+    AppliedHistory, AppliedUpgrade, EnvironmentGuardConfig, EnvironmentProfile, SemVer,
+    UpgradeLifecycle, VersionedUpgrade, LEGACY_CONTROLLER_CONTRACT,
+};
 pub use bioscale::upgrade_store::{
     BioscaleStoreConfig, BioscaleStoreError, BioscaleUpgradeStore, UpgradeApplicationResult,
 };
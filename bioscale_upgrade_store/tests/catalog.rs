@@ -0,0 +1,210 @@
+use bioscale_upgrade_store::{
+    BioscaleAwarenessProfile, BioscaleStoreConfig, BioscaleUpgradeAsset, BioscaleUpgradeStore,
+    ConsciousnessComplianceLevel, EnvironmentProfile, HardwareBindingProfile, SemVer, UpgradeLifecycle,
+};
+
+fn asset(id: &str, version: &str) -> BioscaleUpgradeAsset {
+    let mut a = BioscaleUpgradeAsset::new(
+        "eeg-grip-assist",
+        BioscaleAwarenessProfile {
+            involves_living_organism: true,
+            tissue_interface: vec![],
+            organ_targets: vec![],
+            biosignal_channels: vec![String::from("eeg")],
+        },
+        ConsciousnessComplianceLevel::NoConsciousSubstrate,
+        HardwareBindingProfile {
+            allowed_hardware_ids: vec![],
+            required_safety_modules: vec![],
+            bioscale_resolution_microns: 10,
+        },
+        vec![],
+        "0xmeta",
+    );
+    a.id = id.to_string();
+    a.version = version.to_string();
+    a
+}
+
+/// Tags the plane classifier places on the bioscale plane.
+fn emg() -> Vec<String> {
+    vec![String::from("emg")]
+}
+
+fn activate(store: &mut BioscaleUpgradeStore, id: &str, v: &SemVer) {
+    store.set_lifecycle(id, v, UpgradeLifecycle::Approved).unwrap();
+    store.set_lifecycle(id, v, UpgradeLifecycle::Active).unwrap();
+}
+
+#[test]
+fn versions_lifecycle_rollback_and_persistence() {
+    let path = std::env::temp_dir().join(format!("bioscale-catalog-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let cfg = BioscaleStoreConfig {
+        allow_offline_registration: true,
+        default_regulatory_labels: vec![String::from("ALN")],
+        max_upgrade_assets: 16,
+        legacy_environment_fallback: false,
+        legacy_auto_activate: false,
+    };
+    let (v1, v2) = (SemVer::new(1, 0, 0), SemVer::new(1, 1, 0));
+
+    let mut store = BioscaleUpgradeStore::open(cfg.clone(), &path).unwrap();
+    store.register_upgrade(asset("grip", "1.0.0")).unwrap();
+    assert!(store.register_upgrade(asset("grip", "1.0.0")).is_err(), "same version must not overwrite");
+    assert!(store.register_upgrade(asset("grip", "0.9.0")).is_err(), "versions only move forward");
+
+    // Drafts and unknown environments cannot be applied.
+    assert!(store.apply_upgrade_to_environment("grip", "lab-1", vec![], emg()).is_err());
+    activate(&mut store, "grip", &v1);
+    assert!(store.apply_upgrade_to_environment("grip", "lab-1", vec![], emg()).is_err());
+
+    store
+        .register_environment(EnvironmentProfile {
+            environment_id: String::from("lab-1"),
+            controller_contract: String::from("0xcontroller"),
+            guard: Default::default(),
+        })
+        .unwrap();
+    store.apply_upgrade_to_environment("grip", "lab-1", vec![], emg()).unwrap();
+
+    store.register_upgrade(asset("grip", "1.1.0")).unwrap();
+    activate(&mut store, "grip", &v2);
+    let applied = store.apply_upgrade_to_environment("grip", "lab-1", vec![], emg()).unwrap();
+    assert_eq!(applied.upgrade_version, Some(v2));
+    assert_eq!(store.set_lifecycle("grip", &v2, UpgradeLifecycle::Revoked).unwrap(), vec![String::from("lab-1")]);
+    drop(store);
+
+    let mut store = BioscaleUpgradeStore::open(cfg, &path).unwrap();
+    assert_eq!(store.versions("grip").len(), 2);
+    let rolled = store.rollback_upgrade("lab-1", "grip").unwrap();
+    assert_eq!(rolled.upgrade_version, Some(v1));
+    assert_eq!(store.applied_version("lab-1", "grip").unwrap().version, v1);
+    assert!(store.rollback_upgrade("lab-1", "grip").is_err());
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn legacy_clients_register_and_apply_without_catalog_setup() {
+    let mut store = BioscaleUpgradeStore::new(BioscaleStoreConfig {
+        allow_offline_registration: true,
+        default_regulatory_labels: vec![String::from("ALN")],
+        max_upgrade_assets: 16,
+        legacy_environment_fallback: true,
+        legacy_auto_activate: true,
+    });
+    let (id, state) = store.register_legacy_upgrade(asset("grip", "1.0.0")).unwrap();
+    assert_eq!(state, UpgradeLifecycle::Active);
+    assert_eq!(store.active_version(&id).unwrap().version, SemVer::new(1, 0, 0));
+
+    let applied = store.apply_upgrade_to_environment(&id, "lab-legacy", vec![], vec![String::from("emg")]).unwrap();
+    assert_eq!(applied.upgrade_version, Some(SemVer::new(1, 0, 0)));
+    assert_eq!(
+        store.environment("lab-legacy").unwrap().controller_contract,
+        bioscale_upgrade_store::LEGACY_CONTROLLER_CONTRACT
+    );
+}
+
+#[test]
+fn legacy_registrations_stay_draft_unless_auto_activation_is_enabled() {
+    let mut store = BioscaleUpgradeStore::new(BioscaleStoreConfig {
+        allow_offline_registration: true,
+        default_regulatory_labels: vec![String::from("ALN")],
+        max_upgrade_assets: 16,
+        legacy_environment_fallback: true,
+        legacy_auto_activate: false,
+    });
+    let (id, state) = store.register_legacy_upgrade(asset("grip", "1.0.0")).unwrap();
+    assert_eq!(state, UpgradeLifecycle::Draft);
+    assert!(store.active_version(&id).is_none());
+    assert!(store.apply_upgrade_to_environment(&id, "lab-legacy", vec![], emg()).is_err());
+}
+
+#[test]
+fn failed_persist_leaves_memory_unchanged() {
+    // The catalog's parent is a regular file, so every write fails.
+    let blocker = std::env::temp_dir().join(format!("bioscale-catalog-blocker-{}", std::process::id()));
+    std::fs::write(&blocker, b"not a directory").unwrap();
+    let cfg = BioscaleStoreConfig {
+        allow_offline_registration: true,
+        default_regulatory_labels: vec![],
+        max_upgrade_assets: 16,
+        legacy_environment_fallback: false,
+        legacy_auto_activate: false,
+    };
+    let mut store = BioscaleUpgradeStore::open(cfg, blocker.join("catalog.json")).unwrap();
+    assert!(store.register_upgrade(asset("grip", "1.0.0")).is_err());
+    assert!(store.versions("grip").is_empty());
+    assert!(store.register_active_upgrade(asset("grip", "1.0.0")).is_err());
+    assert!(store.active_version("grip").is_none());
+    let _ = std::fs::remove_file(&blocker);
+}
+
+#[test]
+fn failed_before_commit_applies_nothing() {
+    let mut store = BioscaleUpgradeStore::new(BioscaleStoreConfig {
+        allow_offline_registration: true,
+        default_regulatory_labels: vec![String::from("ALN")],
+        max_upgrade_assets: 16,
+        legacy_environment_fallback: false,
+        legacy_auto_activate: false,
+    });
+    let id = store.register_active_upgrade(asset("grip", "1.0.0")).unwrap();
+    store
+        .register_environment(EnvironmentProfile {
+            environment_id: String::from("lab-1"),
+            controller_contract: String::from("0xcontroller"),
+            guard: Default::default(),
+        })
+        .unwrap();
+
+    let err = store
+        .apply_upgrade_to_environment_with(&id, "lab-1", vec![], emg(), |_| {
+            Err(bioscale_upgrade_store::BioscaleStoreError::StorageError(String::from("trace store down")))
+        })
+        .unwrap_err();
+    assert!(err.to_string().contains("trace store down"));
+    assert!(store.applied_version("lab-1", &id).is_none());
+
+    let mut seen = None;
+    store
+        .apply_upgrade_to_environment_with(&id, "lab-1", vec![], emg(), |r| {
+            seen = Some(r.guard_decision.clone());
+            Ok(())
+        })
+        .unwrap();
+    assert_eq!(store.applied_version("lab-1", &id).unwrap().guard_decision, seen.unwrap());
+}
+
+#[test]
+fn failed_in_memory_apply_registers_no_legacy_environment() {
+    let mut store = BioscaleUpgradeStore::new(BioscaleStoreConfig {
+        allow_offline_registration: true,
+        default_regulatory_labels: vec![String::from("ALN")],
+        max_upgrade_assets: 16,
+        legacy_environment_fallback: true,
+        legacy_auto_activate: false,
+    });
+    let mut bound = asset("grip", "1.0.0");
+    bound.hardware_binding.allowed_hardware_ids = vec![String::from("grip-ctl-a")];
+    let id = store.register_active_upgrade(bound).unwrap();
+
+    let err = store
+        .apply_upgrade_to_environment(&id, "legacy-env", vec![String::from("grip-ctl-b")], vec![])
+        .unwrap_err();
+    assert!(matches!(err, bioscale_upgrade_store::BioscaleStoreError::HardwareMismatch(_)));
+    assert!(store.environment("legacy-env").is_none());
+
+    store
+        .apply_upgrade_to_environment_with(&id, "legacy-env", vec![String::from("grip-ctl-a")], emg(), |_| {
+            Err(bioscale_upgrade_store::BioscaleStoreError::StorageError(String::from("trace store down")))
+        })
+        .unwrap_err();
+    assert!(store.environment("legacy-env").is_none());
+    assert!(store.applied_version("legacy-env", &id).is_none());
+
+    store
+        .apply_upgrade_to_environment(&id, "legacy-env", vec![String::from("grip-ctl-a")], emg())
+        .unwrap();
+    assert!(store.environment("legacy-env").is_some());
+}
@@ -1,15 +1,68 @@
 use crate::risk::{CivicRiskClass};
 use biophysical_blockchain::power::{PowerTurnGovernor, AgenticStepKind};
+use civic_policy::{CivicClass, CivicDecision, CivicPolicy};
 use consent_governance::DemonstratedConsentShard;
 
+/// Gates civic proposals on two independent axes: what the proposal would
+/// mutate (`CivicRiskClass`) and its civic tags, which are classified by the
+/// shared `civic_policy` crate. This type holds no tag rules of its own.
 pub struct CivicActionClassifier<'a> {
     power: PowerTurnGovernor,
     consent: &'a [DemonstratedConsentShard],
+    policy: &'a CivicPolicy,
+}
+
+/// Both classifications of an accepted proposal.
+#[derive(Debug)]
+pub struct CivicAssessment {
+    pub risk: CivicRiskClass,
+    pub decision: CivicDecision,
+}
+
+#[derive(Debug)]
+pub enum CivicGateError {
+    /// The shared policy classified the proposal `Disallowed`; no consent or
+    /// corridor headroom can admit it.
+    Disallowed(CivicDecision),
+    Power(PowerError),
+}
+
+impl From<PowerError> for CivicGateError {
+    fn from(e: PowerError) -> Self {
+        CivicGateError::Power(e)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CivicGate {
+    Reject,
+    ConsentAndCorridor,
+    Open,
+}
+
+/// Downgrades and consensus mutations cannot be undone once applied.
+fn is_irreversible(risk: &CivicRiskClass) -> bool {
+    matches!(risk, CivicRiskClass::CivicDowngrade | CivicRiskClass::ConsensusMutation)
+}
+
+/// `Disallowed` is rejected before the kind is even considered.
+fn gate_for(decision: &CivicDecision, irreversible: bool) -> CivicGate {
+    if decision.class == CivicClass::Disallowed {
+        CivicGate::Reject
+    } else if irreversible {
+        CivicGate::ConsentAndCorridor
+    } else {
+        CivicGate::Open
+    }
 }
 
 impl<'a> CivicActionClassifier<'a> {
-    pub fn new(power: PowerTurnGovernor, consent: &'a [DemonstratedConsentShard]) -> Self {
-        CivicActionClassifier { power, consent }
+    pub fn new(
+        power: PowerTurnGovernor,
+        consent: &'a [DemonstratedConsentShard],
+        policy: &'a CivicPolicy,
+    ) -> Self {
+        CivicActionClassifier { power, consent, policy }
     }
 
     pub fn classify_and_gate(
@@ -17,24 +70,30 @@ impl<'a> CivicActionClassifier<'a> {
         proposal: &RagProposal,
         ctx: &AgenticStepContext<'_>,
         log: &mut PerTurnValidationProfile,
-    ) -> Result<CivicRiskClass, PowerError> {
+    ) -> Result<CivicAssessment, CivicGateError> {
         let risk = self.classify(proposal);
+        let decision = self.policy.classify(&proposal.tags);
 
-        if matches!(risk, CivicRiskClass::CivicDowngrade | CivicRiskClass::ConsensusMutation) {
-            // Require irreversible token + consent + POWER corridor.
-            let has_consent = self.has_matching_consent(proposal);
-            self.power.validate_step(&AgenticStepContext {
-                step_kind: AgenticStepKind::CivicIrreversible,
-                consent_bundle_present: has_consent,
-                ..*ctx
-            }, log)?;
+        match gate_for(&decision, is_irreversible(&risk)) {
+            CivicGate::Reject => return Err(CivicGateError::Disallowed(decision)),
+            CivicGate::ConsentAndCorridor => {
+                // Require irreversible token + consent + POWER corridor.
+                let has_consent = self.has_matching_consent(proposal);
+                self.power.validate_step(&AgenticStepContext {
+                    step_kind: AgenticStepKind::CivicIrreversible,
+                    consent_bundle_present: has_consent,
+                    ..*ctx
+                }, log)?;
+            }
+            CivicGate::Open => {}
         }
 
-        Ok(risk)
+        Ok(CivicAssessment { risk, decision })
     }
 
     fn classify(&self, proposal: &RagProposal) -> CivicRiskClass {
-        // Implementation-specific: tags, target shard classes, etc.
+        // Structural only: target shard classes, consensus keys touched, etc.
+        // Civic tags are classified by `self.policy`.
         // ...
     }
 
@@ -43,3 +102,30 @@ impl<'a> CivicActionClassifier<'a> {
         // ...
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decide(tags: &[&str]) -> CivicDecision {
+        let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+        CivicPolicy::builtin().classify(&tags)
+    }
+
+    #[test]
+    fn downgrades_and_consensus_mutations_need_consent_and_corridor() {
+        assert!(is_irreversible(&CivicRiskClass::CivicDowngrade));
+        assert!(is_irreversible(&CivicRiskClass::ConsensusMutation));
+        assert_eq!(gate_for(&decide(&["gardening"]), true), CivicGate::ConsentAndCorridor);
+        assert_eq!(gate_for(&decide(&["life-saving"]), true), CivicGate::ConsentAndCorridor);
+        assert_eq!(gate_for(&decide(&["teaching"]), false), CivicGate::Open);
+    }
+
+    #[test]
+    fn disallowed_is_rejected_regardless_of_kind() {
+        let coercive = decide(&["life-saving", "coercive"]);
+        assert_eq!(coercive.class, CivicClass::Disallowed);
+        assert_eq!(gate_for(&coercive, true), CivicGate::Reject);
+        assert_eq!(gate_for(&coercive, false), CivicGate::Reject);
+    }
+}
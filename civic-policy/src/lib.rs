@@ -0,0 +1,377 @@
+//! Config-driven civic classification.
+//!
+//! One versioned policy file decides how free-form civic tags map to a
+//! `CivicClass` and a reward multiplier. Classes are tried in the policy's
+//! explicit `precedence` order, tags are normalized and resolved through a
+//! synonym table, and every decision carries the rule that fired so callers
+//! can log or return an explanation.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+/// Policy file schema understood by this crate.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Built-in policy, identical to `policies/civic.v1.json`.
+const BUILTIN_POLICY_JSON: &str = include_str!("../policies/civic.v1.json");
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CivicClass {
+    CivicHeroic,
+    CivicGood,
+    Neutral,
+    Disallowed,
+}
+
+#[derive(Debug, Error)]
+pub enum CivicPolicyError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("parse error: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("unsupported schema_version {0} (expected {SCHEMA_VERSION})")]
+    UnsupportedSchema(u32),
+    #[error("precedence must list {0:?} exactly once")]
+    Precedence(CivicClass),
+    #[error("Neutral is the fallback class and cannot carry tags or precedence")]
+    NeutralRule,
+    #[error("class {0:?} has more than one rule")]
+    DuplicateClass(CivicClass),
+    #[error("multiplier bounds [{min}, {max}] must satisfy 0 <= min <= max")]
+    MultiplierBounds { min: f64, max: f64 },
+    #[error("multiplier {value} for {class:?} outside [{min}, {max}]")]
+    MultiplierOutOfRange { class: CivicClass, value: f64, min: f64, max: f64 },
+    #[error("Disallowed multiplier must be 0.0")]
+    DisallowedMultiplier,
+    #[error("synonym {alias:?} -> {target:?} does not resolve to a class tag")]
+    DanglingSynonym { alias: String, target: String },
+    #[error("synonym {0:?} shadows a class tag")]
+    SynonymShadowsTag(String),
+    #[error("empty tag in {0:?}")]
+    EmptyTag(CivicClass),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ClassRule {
+    pub class: CivicClass,
+    pub multiplier: f64,
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EcoBonus {
+    pub enabled: bool,
+    pub low_threshold: f64,
+    pub low_bonus: f64,
+}
+
+/// On-disk policy document.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CivicPolicyFile {
+    pub schema_version: u32,
+    pub policy_id: String,
+    pub policy_version: String,
+    pub precedence: Vec<CivicClass>,
+    pub classes: Vec<ClassRule>,
+    pub neutral_multiplier: f64,
+    #[serde(default)]
+    pub synonyms: BTreeMap<String, String>,
+    pub multiplier_min: f64,
+    pub multiplier_max: f64,
+    pub required_knowledge_factor: f32,
+    pub eco_bonus: EcoBonus,
+}
+
+/// Which rule produced a decision.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum FiredRule {
+    /// A tag matched `class`. `input_tag` is what the caller sent; `tag` is the
+    /// canonical policy tag it resolved to.
+    Tag { class: CivicClass, tag: String, input_tag: String, via_synonym: bool, precedence_rank: usize },
+    /// No tag matched any class.
+    Fallback,
+}
+
+/// Classification outcome plus its explanation.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CivicDecision {
+    pub class: CivicClass,
+    pub multiplier: f64,
+    pub fired: FiredRule,
+    /// Lower-precedence classes that also matched and were overridden.
+    pub overridden: Vec<CivicClass>,
+    pub policy_id: String,
+    pub policy_version: String,
+}
+
+/// Validated, indexed policy ready for classification.
+#[derive(Clone, Debug)]
+pub struct CivicPolicy {
+    file: CivicPolicyFile,
+    tag_class: HashMap<String, CivicClass>,
+    multipliers: HashMap<CivicClass, f64>,
+}
+
+/// Lowercase, trim, and fold `_` / whitespace to `-` so "Life_Saving" == "life-saving".
+pub fn normalize_tag(tag: &str) -> String {
+    tag.trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c == '_' || c.is_whitespace() { '-' } else { c })
+        .collect()
+}
+
+impl CivicPolicy {
+    /// The policy compiled into the crate.
+    pub fn builtin() -> Self {
+        Self::from_json_str(BUILTIN_POLICY_JSON).expect("builtin civic policy is valid")
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, CivicPolicyError> {
+        Self::from_json_str(&fs::read_to_string(path)?)
+    }
+
+    /// Load `path`, falling back to the built-in policy if it is missing or invalid.
+    pub fn load_or_builtin<P: AsRef<Path>>(path: P) -> Self {
+        Self::load(path).unwrap_or_else(|_| Self::builtin())
+    }
+
+    pub fn from_json_str(raw: &str) -> Result<Self, CivicPolicyError> {
+        Self::from_file(serde_json::from_str(raw)?)
+    }
+
+    pub fn from_file(mut file: CivicPolicyFile) -> Result<Self, CivicPolicyError> {
+        if file.schema_version != SCHEMA_VERSION {
+            return Err(CivicPolicyError::UnsupportedSchema(file.schema_version));
+        }
+        for class in [CivicClass::Disallowed, CivicClass::CivicHeroic, CivicClass::CivicGood] {
+            if file.precedence.iter().filter(|c| **c == class).count() != 1 {
+                return Err(CivicPolicyError::Precedence(class));
+            }
+        }
+        if file.precedence.contains(&CivicClass::Neutral) {
+            return Err(CivicPolicyError::NeutralRule);
+        }
+
+        // Also rejects NaN bounds; `f64::clamp` panics on any of these.
+        let (min, max) = (file.multiplier_min, file.multiplier_max);
+        if !(0.0 <= min && min <= max) {
+            return Err(CivicPolicyError::MultiplierBounds { min, max });
+        }
+        let in_range = |class: CivicClass, value: f64| {
+            if (min..=max).contains(&value) {
+                Ok(())
+            } else {
+                Err(CivicPolicyError::MultiplierOutOfRange { class, value, min, max })
+            }
+        };
+        in_range(CivicClass::Neutral, file.neutral_multiplier)?;
+
+        let mut tag_class = HashMap::new();
+        let mut multipliers = HashMap::new();
+        multipliers.insert(CivicClass::Neutral, file.neutral_multiplier);
+        for rule in file.classes.iter_mut() {
+            if rule.class == CivicClass::Neutral {
+                return Err(CivicPolicyError::NeutralRule);
+            }
+            if rule.class == CivicClass::Disallowed && rule.multiplier != 0.0 {
+                return Err(CivicPolicyError::DisallowedMultiplier);
+            }
+            in_range(rule.class, rule.multiplier)?;
+            if multipliers.insert(rule.class, rule.multiplier).is_some() {
+                return Err(CivicPolicyError::DuplicateClass(rule.class));
+            }
+            for tag in rule.tags.iter_mut() {
+                *tag = normalize_tag(tag);
+                if tag.is_empty() {
+                    return Err(CivicPolicyError::EmptyTag(rule.class));
+                }
+            }
+        }
+        // Index tags in precedence order so a tag listed under two classes
+        // belongs to the higher-precedence one.
+        for class in &file.precedence {
+            if let Some(rule) = file.classes.iter().find(|r| r.class == *class) {
+                for tag in &rule.tags {
+                    tag_class.entry(tag.clone()).or_insert(*class);
+                }
+            }
+        }
+
+        let synonyms: BTreeMap<String, String> = file
+            .synonyms
+            .iter()
+            .map(|(alias, target)| (normalize_tag(alias), normalize_tag(target)))
+            .collect();
+        for (alias, target) in &synonyms {
+            if tag_class.contains_key(alias) {
+                return Err(CivicPolicyError::SynonymShadowsTag(alias.clone()));
+            }
+            if !tag_class.contains_key(target) {
+                return Err(CivicPolicyError::DanglingSynonym { alias: alias.clone(), target: target.clone() });
+            }
+        }
+        file.synonyms = synonyms;
+
+        Ok(Self { file, tag_class, multipliers })
+    }
+
+    pub fn policy_id(&self) -> &str {
+        &self.file.policy_id
+    }
+
+    pub fn policy_version(&self) -> &str {
+        &self.file.policy_version
+    }
+
+    pub fn required_knowledge_factor(&self) -> f32 {
+        self.file.required_knowledge_factor
+    }
+
+    pub fn file(&self) -> &CivicPolicyFile {
+        &self.file
+    }
+
+    /// Canonical tag and whether a synonym was used.
+    fn resolve(&self, input: &str) -> Option<(String, bool)> {
+        let norm = normalize_tag(input);
+        if self.tag_class.contains_key(&norm) {
+            return Some((norm, false));
+        }
+        self.file.synonyms.get(&norm).map(|t| (t.clone(), true))
+    }
+
+    /// Classify `tags` and explain which rule fired.
+    pub fn classify(&self, tags: &[String]) -> CivicDecision {
+        // Best (lowest rank) match per class, in input order.
+        let mut best: Option<FiredRule> = None;
+        let mut matched: Vec<CivicClass> = Vec::new();
+        for input in tags {
+            let Some((tag, via_synonym)) = self.resolve(input) else { continue };
+            let class = self.tag_class[&tag];
+            let rank = self.file.precedence.iter().position(|c| *c == class).unwrap_or(usize::MAX);
+            if !matched.contains(&class) {
+                matched.push(class);
+            }
+            let better = match &best {
+                Some(FiredRule::Tag { precedence_rank, .. }) => rank < *precedence_rank,
+                _ => true,
+            };
+            if better {
+                best = Some(FiredRule::Tag { class, tag, input_tag: input.clone(), via_synonym, precedence_rank: rank });
+            }
+        }
+
+        let (class, fired) = match best {
+            Some(rule @ FiredRule::Tag { class, .. }) => (class, rule),
+            _ => (CivicClass::Neutral, FiredRule::Fallback),
+        };
+        matched.retain(|c| *c != class);
+        matched.sort_by_key(|c| self.file.precedence.iter().position(|p| p == c));
+        CivicDecision {
+            class,
+            multiplier: self.base_multiplier(class),
+            fired,
+            overridden: matched,
+            policy_id: self.file.policy_id.clone(),
+            policy_version: self.file.policy_version.clone(),
+        }
+    }
+
+    pub fn base_multiplier(&self, class: CivicClass) -> f64 {
+        if class == CivicClass::Disallowed {
+            return 0.0;
+        }
+        let raw = self.multipliers.get(&class).copied().unwrap_or(self.file.neutral_multiplier);
+        raw.clamp(self.file.multiplier_min, self.file.multiplier_max)
+    }
+
+    /// Base multiplier with the optional low-eco-cost bonus, never above `multiplier_max`.
+    pub fn eco_adjusted_multiplier(&self, class: CivicClass, eco_cost: f64) -> f64 {
+        let mut m = self.base_multiplier(class);
+        let eco = &self.file.eco_bonus;
+        if eco.enabled && eco_cost <= eco.low_threshold && m > 0.0 {
+            m *= eco.low_bonus.max(1.0);
+        }
+        m.clamp(self.file.multiplier_min, self.file.multiplier_max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(t: &[&str]) -> Vec<String> {
+        t.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn disallowed_takes_precedence_over_heroic() {
+        let p = CivicPolicy::builtin();
+        let d = p.classify(&tags(&["life-saving", "coercive"]));
+        assert_eq!(d.class, CivicClass::Disallowed);
+        assert_eq!(d.multiplier, 0.0);
+        assert_eq!(d.overridden, vec![CivicClass::CivicHeroic]);
+        match d.fired {
+            FiredRule::Tag { tag, precedence_rank, .. } => assert_eq!((tag.as_str(), precedence_rank), ("coercive", 0)),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn synonyms_and_normalization_resolve() {
+        let p = CivicPolicy::builtin();
+        let d = p.classify(&tags(&["Life_Saving"]));
+        assert_eq!(d.class, CivicClass::CivicHeroic);
+        let d = p.classify(&tags(&["Mentoring"]));
+        assert_eq!(d.class, CivicClass::CivicGood);
+        assert!(matches!(d.fired, FiredRule::Tag { via_synonym: true, ref tag, .. } if tag == "mentorship"));
+        let d = p.classify(&tags(&["gardening"]));
+        assert_eq!((d.class, d.fired, d.multiplier), (CivicClass::Neutral, FiredRule::Fallback, 1.0));
+    }
+
+    #[test]
+    fn precedence_is_configurable() {
+        let mut file: CivicPolicyFile = serde_json::from_str(BUILTIN_POLICY_JSON).unwrap();
+        file.precedence = vec![CivicClass::CivicHeroic, CivicClass::Disallowed, CivicClass::CivicGood];
+        let p = CivicPolicy::from_file(file).unwrap();
+        assert_eq!(p.classify(&tags(&["life-saving", "coercive"])).class, CivicClass::CivicHeroic);
+    }
+
+    #[test]
+    fn invalid_policies_are_rejected() {
+        let base: CivicPolicyFile = serde_json::from_str(BUILTIN_POLICY_JSON).unwrap();
+
+        let mut f = base.clone();
+        f.precedence.pop();
+        assert!(matches!(CivicPolicy::from_file(f), Err(CivicPolicyError::Precedence(CivicClass::CivicGood))));
+
+        let mut f = base.clone();
+        f.classes[0].multiplier = 1.0;
+        assert!(matches!(CivicPolicy::from_file(f), Err(CivicPolicyError::DisallowedMultiplier)));
+
+        let mut f = base.clone();
+        f.classes[1].multiplier = 9.0;
+        assert!(matches!(CivicPolicy::from_file(f), Err(CivicPolicyError::MultiplierOutOfRange { .. })));
+
+        let mut f = base.clone();
+        (f.multiplier_min, f.multiplier_max) = (2.0, 1.0);
+        assert!(matches!(CivicPolicy::from_file(f), Err(CivicPolicyError::MultiplierBounds { .. })));
+
+        let mut f = base.clone();
+        f.multiplier_min = -0.5;
+        assert!(matches!(CivicPolicy::from_file(f), Err(CivicPolicyError::MultiplierBounds { .. })));
+
+        let mut f = base.clone();
+        f.synonyms.insert("kindness".into(), "not-a-tag".into());
+        assert!(matches!(CivicPolicy::from_file(f), Err(CivicPolicyError::DanglingSynonym { .. })));
+
+        let mut f = base;
+        f.schema_version = 2;
+        assert!(matches!(CivicPolicy::from_file(f), Err(CivicPolicyError::UnsupportedSchema(2))));
+    }
+}
@@ -1,9 +1,62 @@
-use cosmwasm_std::{entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
-use cw_storage_plus::{Map, Item};
+use cosmwasm_std::{entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, Event, MessageInfo, Order, Response, StdError, StdResult};
+use cw_storage_plus::{Bound, Map, Item};
 use serde::{Deserialize, Serialize};
 
 const CONTRACT_NAME: &str = "aln-registry";
-const CONTRACT_VERSION: &str = "0.1.0";
+const CONTRACT_VERSION: &str = "0.2.0";
+
+/// Event type emitted on every lifecycle change. The indexer listens for
+/// `wasm-aln_registry_asset` and upserts `token_class` from its attributes.
+pub const ASSET_EVENT: &str = "aln_registry_asset";
+
+const DEFAULT_PAGE_LIMIT: u32 = 30;
+const MAX_PAGE_LIMIT: u32 = 100;
+
+/// Asset lifecycle. Legal transitions:
+/// proposed -> sanitized -> active <-> paused; active|paused -> deprecated;
+/// any non-revoked state -> revoked (terminal).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetState {
+    #[default]
+    Proposed,
+    Sanitized,
+    Active,
+    Paused,
+    Deprecated,
+    Revoked,
+}
+
+impl AssetState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssetState::Proposed => "proposed",
+            AssetState::Sanitized => "sanitized",
+            AssetState::Active => "active",
+            AssetState::Paused => "paused",
+            AssetState::Deprecated => "deprecated",
+            AssetState::Revoked => "revoked",
+        }
+    }
+
+    pub fn can_transition_to(&self, to: AssetState) -> bool {
+        use AssetState::*;
+        matches!(
+            (self, to),
+            (Proposed, Sanitized)
+                | (Sanitized, Active)
+                | (Active, Paused)
+                | (Paused, Active)
+                | (Active, Deprecated)
+                | (Paused, Deprecated)
+                | (Proposed, Revoked)
+                | (Sanitized, Revoked)
+                | (Active, Revoked)
+                | (Paused, Revoked)
+                | (Deprecated, Revoked)
+        )
+    }
+}
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct RegisteredAsset {
@@ -16,11 +69,31 @@ pub struct RegisteredAsset {
     pub scaling_profile_id: String,
     pub activation_height: u64,
     pub sanitized_approved: bool,
+    /// Managed by the contract; ignored on registration.
+    #[serde(default)]
+    pub state: AssetState,
+    /// Current merkle root version, starting at 1 on registration.
+    #[serde(default)]
+    pub merkle_root_version: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MerkleRootVersion {
+    pub version: u32,
+    pub merkle_root: String,
+    pub snapshot_height: u64,
+    pub updated_at_height: u64,
 }
 
 pub const ASSETS: Map<String, RegisteredAsset> = Map::new("reg_assets");
+/// Secondary index (source_chain, id) -> id for `AssetsBySourceChain`.
+pub const ASSETS_BY_CHAIN: Map<(&str, &str), String> = Map::new("reg_assets_by_chain");
+pub const MERKLE_HISTORY: Map<(&str, u32), MerkleRootVersion> = Map::new("reg_merkle_history");
 pub const GOVERNANCE: Item<Addr> = Item::new("governance_addr");
 pub const ALLOW_MISSING_UBS: Item<bool> = Item::new("allow_missing_ubs");
+/// `(CONTRACT_NAME, CONTRACT_VERSION)` of the code that last wrote the store.
+/// Absent on stores instantiated before 0.2.0.
+pub const CONTRACT_INFO: Item<(String, String)> = Item::new("contract_info");
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct InstantiateMsg {
@@ -28,17 +101,39 @@ pub struct InstantiateMsg {
     pub allow_missing_ubs: Option<bool>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MigrateMsg {}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     RegisterAsset { asset: RegisteredAsset },
     ApproveSanitized { id: String, ubs_report_hash: String },
+    ActivateAsset { id: String },
+    PauseAsset { id: String, reason: Option<String> },
+    ResumeAsset { id: String },
+    DeprecateAsset { id: String, reason: Option<String> },
+    RevokeAsset { id: String, reason: String },
+    UpdateMerkleRoot { id: String, merkle_root: String, snapshot_height: u64 },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
     GetAsset { id: String },
+    ListAssets { start_after: Option<String>, limit: Option<u32> },
+    AssetsBySourceChain { source_chain: String, start_after: Option<String>, limit: Option<u32> },
+    MerkleRootHistory { id: String, start_after: Option<u32>, limit: Option<u32> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AssetsResponse {
+    pub assets: Vec<RegisteredAsset>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MerkleRootHistoryResponse {
+    pub versions: Vec<MerkleRootVersion>,
 }
 
 #[entry_point]
@@ -47,36 +142,216 @@ pub fn instantiate(deps: DepsMut, _env: Env, info: MessageInfo, msg: Instantiate
     GOVERNANCE.save(deps.storage, &gov)?;
     let allow = msg.allow_missing_ubs.unwrap_or(false);
     ALLOW_MISSING_UBS.save(deps.storage, &allow)?;
+    CONTRACT_INFO.save(deps.storage, &(CONTRACT_NAME.to_string(), CONTRACT_VERSION.to_string()))?;
     Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
+/// Upgrade a pre-0.2.0 store. Records written before lifecycle states carry
+/// `merkle_root_version == 0`; each one gets a state (`active` if it was
+/// sanitized, since the bridge already accepted claims for it, else
+/// `proposed`), version 1 with a matching history entry, and its
+/// `ASSETS_BY_CHAIN` index row. Running it again changes nothing.
+#[entry_point]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    if let Some((name, _)) = CONTRACT_INFO.may_load(deps.storage)? {
+        if name != CONTRACT_NAME {
+            return Err(StdError::generic_err(format!("cannot migrate {} to {}", name, CONTRACT_NAME)));
+        }
+    }
+    let legacy = ASSETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, a)| a))
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut res = Response::new().add_attribute("action", "migrate").add_attribute("version", CONTRACT_VERSION);
+    let mut migrated = 0u32;
+    for mut a in legacy {
+        ASSETS_BY_CHAIN.save(deps.storage, (a.source_chain.as_str(), a.id.as_str()), &a.id)?;
+        if a.merkle_root_version != 0 {
+            continue;
+        }
+        a.state = if a.sanitized_approved { AssetState::Active } else { AssetState::Proposed };
+        a.merkle_root_version = 1;
+        MERKLE_HISTORY.save(
+            deps.storage,
+            (a.id.as_str(), 1),
+            &MerkleRootVersion { version: 1, merkle_root: a.merkle_root.clone(), snapshot_height: a.snapshot_height, updated_at_height: env.block.height },
+        )?;
+        ASSETS.save(deps.storage, a.id.clone(), &a)?;
+        res = res.add_event(asset_event("migrate_asset", &a, None, &env));
+        migrated += 1;
+    }
+    CONTRACT_INFO.save(deps.storage, &(CONTRACT_NAME.to_string(), CONTRACT_VERSION.to_string()))?;
+    Ok(res.add_attribute("migrated_assets", migrated.to_string()))
+}
+
 #[entry_point]
-pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     match msg {
-        ExecuteMsg::RegisterAsset { asset } => register_asset(deps, info, asset),
-        ExecuteMsg::ApproveSanitized { id, ubs_report_hash } => approve_sanitized(deps, info, id, ubs_report_hash),
+        ExecuteMsg::RegisterAsset { asset } => register_asset(deps, env, info, asset),
+        ExecuteMsg::ApproveSanitized { id, ubs_report_hash } => approve_sanitized(deps, env, info, id, ubs_report_hash),
+        ExecuteMsg::ActivateAsset { id } => activate_asset(deps, env, info, id),
+        ExecuteMsg::PauseAsset { id, reason } => transition(deps, env, info, id, AssetState::Paused, "pause_asset", reason),
+        ExecuteMsg::ResumeAsset { id } => transition(deps, env, info, id, AssetState::Active, "resume_asset", None),
+        ExecuteMsg::DeprecateAsset { id, reason } => transition(deps, env, info, id, AssetState::Deprecated, "deprecate_asset", reason),
+        ExecuteMsg::RevokeAsset { id, reason } => transition(deps, env, info, id, AssetState::Revoked, "revoke_asset", Some(reason)),
+        ExecuteMsg::UpdateMerkleRoot { id, merkle_root, snapshot_height } => update_merkle_root(deps, env, info, id, merkle_root, snapshot_height),
     }
 }
 
-fn register_asset(deps: DepsMut, info: MessageInfo, asset: RegisteredAsset) -> StdResult<Response> {
+fn ensure_governance(deps: &DepsMut, info: &MessageInfo, what: &str) -> StdResult<()> {
     let gov = GOVERNANCE.load(deps.storage)?;
-    if info.sender != gov { return Err(cosmwasm_std::StdError::generic_err("only governance can register assets")); }
+    if info.sender != gov { return Err(StdError::generic_err(format!("only governance can {}", what))); }
+    Ok(())
+}
+
+/// Build the indexer-facing event for an asset after a change. `previous`
+/// is the state before a lifecycle transition; changes that leave the state
+/// alone (registration, merkle updates, migration) pass `None`.
+fn asset_event(action: &str, asset: &RegisteredAsset, previous: Option<AssetState>, env: &Env) -> Event {
+    Event::new(ASSET_EVENT)
+        .add_attribute("action", action)
+        .add_attribute("asset_id", asset.id.clone())
+        .add_attribute("source_chain", asset.source_chain.clone())
+        .add_attribute("source_denom", asset.source_denom.clone())
+        .add_attribute("previous_state", previous.map(|s| s.as_str()).unwrap_or(""))
+        .add_attribute("state", asset.state.as_str())
+        .add_attribute("merkle_root", asset.merkle_root.clone())
+        .add_attribute("merkle_root_version", asset.merkle_root_version.to_string())
+        .add_attribute("height", env.block.height.to_string())
+}
+
+fn register_asset(deps: DepsMut, env: Env, info: MessageInfo, mut asset: RegisteredAsset) -> StdResult<Response> {
+    ensure_governance(&deps, &info, "register assets")?;
     let allow_missing = ALLOW_MISSING_UBS.load(deps.storage)?;
     if asset.ubs_report_hash.is_none() && !allow_missing {
-        return Err(cosmwasm_std::StdError::generic_err("ubs_report_hash is required for asset registration"));
+        return Err(StdError::generic_err("ubs_report_hash is required for asset registration"));
     }
+    if ASSETS.has(deps.storage, asset.id.clone()) {
+        return Err(StdError::generic_err(format!("asset {} already registered", asset.id)));
+    }
+    // Governance may register an already-sanitized asset in one step, but only with a report hash.
+    asset.sanitized_approved = asset.sanitized_approved && asset.ubs_report_hash.is_some();
+    asset.state = if asset.sanitized_approved { AssetState::Sanitized } else { AssetState::Proposed };
+    asset.merkle_root_version = 1;
+
     ASSETS.save(deps.storage, asset.id.clone(), &asset)?;
-    Ok(Response::new().add_attribute("action", "register_asset"))
+    ASSETS_BY_CHAIN.save(deps.storage, (asset.source_chain.as_str(), asset.id.as_str()), &asset.id)?;
+    MERKLE_HISTORY.save(
+        deps.storage,
+        (asset.id.as_str(), 1),
+        &MerkleRootVersion { version: 1, merkle_root: asset.merkle_root.clone(), snapshot_height: asset.snapshot_height, updated_at_height: env.block.height },
+    )?;
+    Ok(Response::new()
+        .add_attribute("action", "register_asset")
+        .add_attribute("id", asset.id.clone())
+        .add_event(asset_event("register_asset", &asset, None, &env)))
 }
 
-fn approve_sanitized(deps: DepsMut, info: MessageInfo, id: String, ubs_report_hash: String) -> StdResult<Response> {
-    let gov = GOVERNANCE.load(deps.storage)?;
-    if info.sender != gov { return Err(cosmwasm_std::StdError::generic_err("only governance can approve sanitized")); }
+fn approve_sanitized(deps: DepsMut, env: Env, info: MessageInfo, id: String, ubs_report_hash: String) -> StdResult<Response> {
+    ensure_governance(&deps, &info, "approve sanitized")?;
     let mut a = ASSETS.load(deps.storage, id.clone())?;
+    let previous = a.state;
+    // Re-approving a sanitized asset only refreshes its report hash.
+    if previous != AssetState::Sanitized && !previous.can_transition_to(AssetState::Sanitized) {
+        return Err(StdError::generic_err(format!("cannot approve sanitized from state {}", previous.as_str())));
+    }
     a.ubs_report_hash = Some(ubs_report_hash);
     a.sanitized_approved = true;
+    a.state = AssetState::Sanitized;
+    ASSETS.save(deps.storage, id.clone(), &a)?;
+    Ok(Response::new()
+        .add_attribute("action", "approve_sanitized")
+        .add_attribute("id", id)
+        .add_event(asset_event("approve_sanitized", &a, Some(previous), &env)))
+}
+
+fn activate_asset(deps: DepsMut, env: Env, info: MessageInfo, id: String) -> StdResult<Response> {
+    let a = ASSETS.load(deps.storage, id.clone())?;
+    if env.block.height < a.activation_height {
+        return Err(StdError::generic_err("asset activation height not reached"));
+    }
+    transition(deps, env, info, id, AssetState::Active, "activate_asset", None)
+}
+
+fn transition(deps: DepsMut, env: Env, info: MessageInfo, id: String, to: AssetState, action: &str, reason: Option<String>) -> StdResult<Response> {
+    ensure_governance(&deps, &info, action)?;
+    let mut a = ASSETS.load(deps.storage, id.clone())?;
+    let previous = a.state;
+    if !previous.can_transition_to(to) {
+        return Err(StdError::generic_err(format!("illegal transition {} -> {}", previous.as_str(), to.as_str())));
+    }
+    a.state = to;
+    if to == AssetState::Revoked {
+        a.sanitized_approved = false;
+    }
+    ASSETS.save(deps.storage, id.clone(), &a)?;
+    let mut event = asset_event(action, &a, Some(previous), &env);
+    if let Some(r) = reason {
+        event = event.add_attribute("reason", r);
+    }
+    Ok(Response::new().add_attribute("action", action).add_attribute("id", id).add_event(event))
+}
+
+fn update_merkle_root(deps: DepsMut, env: Env, info: MessageInfo, id: String, merkle_root: String, snapshot_height: u64) -> StdResult<Response> {
+    ensure_governance(&deps, &info, "update merkle roots")?;
+    let mut a = ASSETS.load(deps.storage, id.clone())?;
+    if matches!(a.state, AssetState::Deprecated | AssetState::Revoked) {
+        return Err(StdError::generic_err(format!("cannot update merkle root in state {}", a.state.as_str())));
+    }
+    if snapshot_height <= a.snapshot_height {
+        return Err(StdError::generic_err("snapshot_height must increase on merkle root update"));
+    }
+    let version = a.merkle_root_version + 1;
+    a.merkle_root = merkle_root.clone();
+    a.snapshot_height = snapshot_height;
+    a.merkle_root_version = version;
+    MERKLE_HISTORY.save(
+        deps.storage,
+        (id.as_str(), version),
+        &MerkleRootVersion { version, merkle_root, snapshot_height, updated_at_height: env.block.height },
+    )?;
     ASSETS.save(deps.storage, id.clone(), &a)?;
-    Ok(Response::new().add_attribute("action", "approve_sanitized").add_attribute("id", id))
+    Ok(Response::new()
+        .add_attribute("action", "update_merkle_root")
+        .add_attribute("id", id)
+        .add_attribute("version", version.to_string())
+        .add_event(asset_event("update_merkle_root", &a, None, &env)))
+}
+
+fn page_limit(limit: Option<u32>) -> usize {
+    limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize
+}
+
+fn list_assets(deps: Deps, start_after: Option<String>, limit: Option<u32>) -> StdResult<AssetsResponse> {
+    let start = start_after.map(|s| Bound::exclusive(s.as_str()));
+    let assets = ASSETS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(page_limit(limit))
+        .map(|item| item.map(|(_, a)| a))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AssetsResponse { assets })
+}
+
+fn assets_by_source_chain(deps: Deps, source_chain: String, start_after: Option<String>, limit: Option<u32>) -> StdResult<AssetsResponse> {
+    let start = start_after.map(|s| Bound::exclusive(s.as_str()));
+    let assets = ASSETS_BY_CHAIN
+        .prefix(source_chain.as_str())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(page_limit(limit))
+        .map(|item| item.and_then(|(_, id)| ASSETS.load(deps.storage, id)))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(AssetsResponse { assets })
+}
+
+fn merkle_root_history(deps: Deps, id: String, start_after: Option<u32>, limit: Option<u32>) -> StdResult<MerkleRootHistoryResponse> {
+    let start = start_after.map(|v| Bound::exclusive(v.to_be_bytes().to_vec()));
+    let versions = MERKLE_HISTORY
+        .prefix(id.as_str())
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(page_limit(limit))
+        .map(|item| item.map(|(_, v)| v))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(MerkleRootHistoryResponse { versions })
 }
 
 #[entry_point]
@@ -86,5 +361,13 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
             let a = ASSETS.load(deps.storage, id.clone())?;
             Ok(to_binary(&a)?)
         }
+        QueryMsg::ListAssets { start_after, limit } => to_binary(&list_assets(deps, start_after, limit)?),
+        QueryMsg::AssetsBySourceChain { source_chain, start_after, limit } => {
+            to_binary(&assets_by_source_chain(deps, source_chain, start_after, limit)?)
+        }
+        QueryMsg::MerkleRootHistory { id, start_after, limit } => to_binary(&merkle_root_history(deps, id, start_after, limit)?),
     }
 }
+
+#[cfg(test)]
+mod tests;
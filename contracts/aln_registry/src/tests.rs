@@ -1,8 +1,37 @@
 #[cfg(test)]
 mod tests {
-    use super::super::{instantiate, execute, query, InstantiateMsg, ExecuteMsg, RegisteredAsset};
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::Addr;
+    use super::super::{instantiate, execute, migrate, query, InstantiateMsg, MigrateMsg, ASSETS, ASSETS_BY_CHAIN, ExecuteMsg, QueryMsg, RegisteredAsset, AssetState, AssetsResponse, MerkleRootHistoryResponse, ASSET_EVENT};
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{from_binary, Addr, OwnedDeps};
+
+    type Deps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    fn asset(id: &str, chain: &str) -> RegisteredAsset {
+        RegisteredAsset {
+            id: id.to_string(),
+            source_chain: chain.to_string(),
+            source_denom: format!("ibc/{}", id),
+            snapshot_height: 10,
+            merkle_root: "root".to_string(),
+            ubs_report_hash: Some("h".to_string()),
+            scaling_profile_id: "clean".to_string(),
+            activation_height: 0,
+            sanitized_approved: false,
+            state: AssetState::Active,
+            merkle_root_version: 7,
+        }
+    }
+
+    fn setup() -> Deps {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg { governance_addr: "gov".to_string(), allow_missing_ubs: Some(false) };
+        instantiate(deps.as_mut(), mock_env(), mock_info("gov", &[]), msg).unwrap();
+        deps
+    }
+
+    fn get(deps: &Deps, id: &str) -> RegisteredAsset {
+        from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::GetAsset { id: id.to_string() }).unwrap()).unwrap()
+    }
 
     #[test]
     fn register_and_approve_sanitized() {
@@ -21,6 +50,8 @@ mod tests {
             scaling_profile_id: "malicious_cleanup".to_string(),
             activation_height: 0,
             sanitized_approved: false,
+            state: Default::default(),
+            merkle_root_version: 0,
         };
 
         let res = execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), ExecuteMsg::RegisterAsset { asset: asset.clone() }).unwrap();
@@ -52,10 +83,122 @@ mod tests {
             scaling_profile_id: "safe".to_string(),
             activation_height: 0,
             sanitized_approved: false,
+            state: Default::default(),
+            merkle_root_version: 0,
         };
 
         // registering without UBS should fail when allow_missing_ubs = false
         let err = execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), ExecuteMsg::RegisterAsset { asset: asset.clone() });
         assert!(err.is_err());
     }
+
+    #[test]
+    fn duplicate_id_is_rejected_and_state_is_contract_managed() {
+        let mut deps = setup();
+        execute(deps.as_mut(), mock_env(), mock_info("gov", &[]), ExecuteMsg::RegisterAsset { asset: asset("a1", "k1") }).unwrap();
+        let stored = get(&deps, "a1");
+        assert_eq!(stored.state, AssetState::Proposed);
+        assert_eq!(stored.merkle_root_version, 1);
+
+        let mut dup = asset("a1", "k2");
+        dup.merkle_root = "other".to_string();
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("gov", &[]), ExecuteMsg::RegisterAsset { asset: dup }).is_err());
+        assert_eq!(get(&deps, "a1").source_chain, "k1");
+    }
+
+    #[test]
+    fn lifecycle_transitions_and_events() {
+        let mut deps = setup();
+        let gov = mock_info("gov", &[]);
+        execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::RegisterAsset { asset: asset("a1", "k1") }).unwrap();
+
+        // proposed cannot jump to active
+        assert!(execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::ActivateAsset { id: "a1".to_string() }).is_err());
+
+        execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::ApproveSanitized { id: "a1".to_string(), ubs_report_hash: "h1".to_string() }).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::ActivateAsset { id: "a1".to_string() }).unwrap();
+        let ev = res.events.iter().find(|e| e.ty == ASSET_EVENT).unwrap();
+        assert!(ev.attributes.iter().any(|a| a.key == "state" && a.value == "active"));
+        assert!(ev.attributes.iter().any(|a| a.key == "previous_state" && a.value == "sanitized"));
+
+        execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::PauseAsset { id: "a1".to_string(), reason: Some("incident".to_string()) }).unwrap();
+        assert_eq!(get(&deps, "a1").state, AssetState::Paused);
+        execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::ResumeAsset { id: "a1".to_string() }).unwrap();
+        execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::DeprecateAsset { id: "a1".to_string(), reason: None }).unwrap();
+        assert!(execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::ResumeAsset { id: "a1".to_string() }).is_err());
+
+        // non gov cannot revoke
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("notgov", &[]), ExecuteMsg::RevokeAsset { id: "a1".to_string(), reason: "x".to_string() }).is_err());
+        execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::RevokeAsset { id: "a1".to_string(), reason: "exploit".to_string() }).unwrap();
+        let revoked = get(&deps, "a1");
+        assert_eq!(revoked.state, AssetState::Revoked);
+        assert!(!revoked.sanitized_approved);
+        assert!(execute(deps.as_mut(), mock_env(), gov, ExecuteMsg::ApproveSanitized { id: "a1".to_string(), ubs_report_hash: "h2".to_string() }).is_err());
+    }
+
+    #[test]
+    fn merkle_root_updates_are_versioned() {
+        let mut deps = setup();
+        let gov = mock_info("gov", &[]);
+        execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::RegisterAsset { asset: asset("a1", "k1") }).unwrap();
+
+        // snapshot height must move forward
+        assert!(execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::UpdateMerkleRoot { id: "a1".to_string(), merkle_root: "r2".to_string(), snapshot_height: 10 }).is_err());
+        execute(deps.as_mut(), mock_env(), gov.clone(), ExecuteMsg::UpdateMerkleRoot { id: "a1".to_string(), merkle_root: "r2".to_string(), snapshot_height: 20 }).unwrap();
+        let res = execute(deps.as_mut(), mock_env(), gov, ExecuteMsg::UpdateMerkleRoot { id: "a1".to_string(), merkle_root: "r3".to_string(), snapshot_height: 30 }).unwrap();
+        let ev = res.events.iter().find(|e| e.ty == ASSET_EVENT).unwrap();
+        assert!(ev.attributes.iter().any(|a| a.key == "previous_state" && a.value.is_empty()));
+
+        let current = get(&deps, "a1");
+        assert_eq!((current.merkle_root.as_str(), current.merkle_root_version), ("r3", 3));
+
+        let bin = query(deps.as_ref(), mock_env(), QueryMsg::MerkleRootHistory { id: "a1".to_string(), start_after: Some(1), limit: None }).unwrap();
+        let hist: MerkleRootHistoryResponse = from_binary(&bin).unwrap();
+        let roots: Vec<&str> = hist.versions.iter().map(|v| v.merkle_root.as_str()).collect();
+        assert_eq!(roots, vec!["r2", "r3"]);
+    }
+
+    #[test]
+    fn paginated_listing_and_source_chain_index() {
+        let mut deps = setup();
+        for (id, chain) in [("a1", "k1"), ("a2", "osmo"), ("a3", "k1"), ("a4", "k1")] {
+            execute(deps.as_mut(), mock_env(), mock_info("gov", &[]), ExecuteMsg::RegisterAsset { asset: asset(id, chain) }).unwrap();
+        }
+
+        let page1: AssetsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ListAssets { start_after: None, limit: Some(3) }).unwrap()).unwrap();
+        assert_eq!(page1.assets.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), vec!["a1", "a2", "a3"]);
+        let page2: AssetsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::ListAssets { start_after: Some("a3".to_string()), limit: Some(3) }).unwrap()).unwrap();
+        assert_eq!(page2.assets.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), vec!["a4"]);
+
+        let k1: AssetsResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::AssetsBySourceChain { source_chain: "k1".to_string(), start_after: Some("a1".to_string()), limit: None }).unwrap()).unwrap();
+        assert_eq!(k1.assets.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), vec!["a3", "a4"]);
+    }
+
+    #[test]
+    fn migrate_backfills_legacy_records() {
+        let mut deps = setup();
+        // Pre-0.2.0 records: no state, version 0, no index row.
+        let mut sanitized = asset("old1", "k1");
+        sanitized.sanitized_approved = true;
+        sanitized.state = AssetState::Proposed;
+        sanitized.merkle_root_version = 0;
+        let mut pending = asset("old2", "k1");
+        pending.state = AssetState::Proposed;
+        pending.merkle_root_version = 0;
+        for a in [&sanitized, &pending] {
+            ASSETS.save(deps.as_mut().storage, a.id.clone(), a).unwrap();
+        }
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!(res.events.iter().filter(|e| e.ty == ASSET_EVENT).count(), 2);
+        assert_eq!(get(&deps, "old1").state, AssetState::Active);
+        assert_eq!(get(&deps, "old2").state, AssetState::Proposed);
+        assert_eq!(get(&deps, "old1").merkle_root_version, 1);
+        assert!(ASSETS_BY_CHAIN.has(deps.as_ref().storage, ("k1", "old2")));
+        let hist: MerkleRootHistoryResponse = from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::MerkleRootHistory { id: "old1".to_string(), start_after: None, limit: None }).unwrap()).unwrap();
+        assert_eq!(hist.versions.len(), 1);
+
+        let again = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert!(again.events.is_empty());
+    }
 }
@@ -13,7 +13,7 @@ use aln_ubs::DefaultUBS;
 use ubs_oracle::QueryMsg as OracleQueryMsg;
 use serde_json::json;
 
-use aln_registry::{QueryMsg as RegQueryMsg, RegisteredAsset};
+use aln_registry::{AssetState, QueryMsg as RegQueryMsg, RegisteredAsset};
 
 const CONTRACT_NAME: &str = "aln-bridge-auet";
 const CONTRACT_VERSION: &str = "0.2.0";
@@ -223,6 +223,7 @@ fn claim(
 
     // check sanitized_approved and presence of a ubs_report_hash
     if !asset.sanitized_approved { return Err(cosmwasm_std::StdError::generic_err("asset not sanitized")); }
+    if asset.state != AssetState::Active { return Err(cosmwasm_std::StdError::generic_err(format!("asset is {}, not active", asset.state.as_str()))); }
     let reg_ubs_hash = asset.ubs_report_hash.clone();
     if reg_ubs_hash.is_none() { return Err(cosmwasm_std::StdError::generic_err("ubs report hash missing on registered asset")); }
     // If claim included an explicit ubs hash, verify it matches the registry
@@ -37,6 +37,8 @@ mod tests {
             scaling_profile_id: "malicious_cleanup".to_string(),
             activation_height: 0,
             sanitized_approved: false,
+            state: Default::default(),
+            merkle_root_version: 0,
         };
         let register = aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() };
         let reg_addr = "competition"; // using placeholder as we call local function directly
@@ -56,6 +58,11 @@ mod tests {
         let approve = aln_registry::ExecuteMsg::ApproveSanitized { id: "a1".to_string(), ubs_report_hash: "h1".to_string() };
         aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), approve).unwrap();
 
+        // Sanitized is not enough: claims need the asset Active
+        let claim_sanitized = ExecuteMsg::Claim { asset_id: "a1".to_string(), snapshot: s.clone(), snapshot_hash: hhex.clone(), merkle_proof: vec![], amount_auet: Uint128::new(1), amount_csp: None, origin_tx_hash: None, origin_nonce: None };
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("user", &[]), claim_sanitized).is_err());
+        aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), aln_registry::ExecuteMsg::ActivateAsset { id: "a1".to_string() }).unwrap();
+
         // Now claim should succeed and set claimed
         let claim_msg2 = ExecuteMsg::Claim { asset_id: "a1".to_string(), snapshot: s.clone(), snapshot_hash: hhex.clone(), merkle_proof: vec![], amount_auet: Uint128::new(1), amount_csp: None, origin_tx_hash: None, origin_nonce: None };
         let res = execute(deps.as_mut(), mock_env(), mock_info("user", &[]), claim_msg2).unwrap();
@@ -130,9 +137,12 @@ fn claim_with_valid_merkle_proof_succeeds() {
         scaling_profile_id: "clean".to_string(),
         activation_height: 0,
         sanitized_approved: true,
+        state: Default::default(),
+        merkle_root_version: 0,
     };
     let register = aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() };
     aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), register).unwrap();
+    aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), aln_registry::ExecuteMsg::ActivateAsset { id: "b1".to_string() }).unwrap();
 
     // instantiate bridge
     let bmsg = crate::InstantiateMsg { auet_contract: "auet_addr".to_string(), csp_contract: None, registry_contract: "reg".to_string(), governance_addr: gov.clone(), toxic_sink: Some("toxic_sink".to_string()) };
@@ -171,8 +181,9 @@ fn system_consume_acl_and_ledger_debit() {
     // Setup and register asset with approved UBS
     let s = crate::SnapshotEntry { chain_id: "k1".to_string(), height: 0, denom: "ibc/x".to_string(), address: "u1".to_string(), balance: "10".to_string() };
     let mut hasher = Sha256::new(); hasher.update(s.chain_id.as_bytes()); hasher.update(&s.height.to_be_bytes()); hasher.update(s.denom.as_bytes()); hasher.update(s.address.as_bytes()); let b: u128 = s.balance.parse().unwrap(); hasher.update(&b.to_be_bytes()); let digest = hasher.finalize(); let hex_h = format!("0x{}", hex::encode(digest));
-    let asset = aln_registry::RegisteredAsset { id: "z1".to_string(), source_chain: "k1".to_string(), source_denom: "ibc/x".to_string(), snapshot_height: 0, merkle_root: hex_h.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "clean".to_string(), activation_height: 0, sanitized_approved: true };
+    let asset = aln_registry::RegisteredAsset { id: "z1".to_string(), source_chain: "k1".to_string(), source_denom: "ibc/x".to_string(), snapshot_height: 0, merkle_root: hex_h.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "clean".to_string(), activation_height: 0, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() }).unwrap();
+    aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), aln_registry::ExecuteMsg::ActivateAsset { id: "z1".to_string() }).unwrap();
     // instantiate bridge with system whitelist (trader allowed)
     let bmsg = crate::InstantiateMsg { auet_contract: "auet_addr".to_string(), csp_contract: None, registry_contract: "reg".to_string(), governance_addr: gov.clone(), toxic_sink: None, anomaly_threshold_amount: None, toxic_cap_percent: None, system_whitelist: Some(vec!["trader".to_string()]) };
     crate::instantiate(deps.as_mut(), mock_env(), mock_info(&gov, &[]), bmsg).unwrap();
@@ -213,18 +224,20 @@ fn claim_with_invalid_merkle_proof_fails() {
     // Make a random different root
     let mut h2 = Sha256::new(); h2.update(b"other"); let r2 = h2.finalize(); let root = format!("0x{}", hex::encode(r2));
 
-    let asset = aln_registry::RegisteredAsset { id: "c1".to_string(), source_chain: "k1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 0, merkle_root: root.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "clean".to_string(), activation_height: 0, sanitized_approved: true };
+    let asset = aln_registry::RegisteredAsset { id: "c1".to_string(), source_chain: "k1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 0, merkle_root: root.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "clean".to_string(), activation_height: 0, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     let register = aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() };
     aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), register).unwrap();
+    // Active, so the claim gets as far as the proof check.
+    aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), aln_registry::ExecuteMsg::ActivateAsset { id: "c1".to_string() }).unwrap();
 
     // instantiate bridge
     let bmsg = crate::InstantiateMsg { auet_contract: "auet_addr".to_string(), csp_contract: None, registry_contract: "reg".to_string(), governance_addr: gov.clone() };
     crate::instantiate(deps.as_mut(), mock_env(), mock_info(&gov, &[]), bmsg).unwrap();
 
     // Use empty proof which won't match root
-    let claim_msg = crate::ExecuteMsg::Claim { asset_id: "c1".to_string(), snapshot: s.clone(), snapshot_hash: hhex.clone(), merkle_proof: vec![], amount_auet: Uint128::new(1), amount_csp: None, origin_tx_hash: None, origin_nonce: None };
-    let err = crate::execute(deps.as_mut(), mock_env(), mock_info("user", &[]), claim_msg);
-    assert!(err.is_err());
+    let claim_msg = crate::ExecuteMsg::Claim { asset_id: "c1".to_string(), snapshot: s.clone(), snapshot_hash: hhex.clone(), merkle_proof: vec![], amount_auet: Uint128::new(1), amount_csp: None, origin_tx_hash: None, origin_nonce: None, ubs_report_hash: None };
+    let err = crate::execute(deps.as_mut(), mock_env(), mock_info("user", &[]), claim_msg).unwrap_err();
+    assert!(err.to_string().contains("invalid merkle proof"), "{err}");
 }
 
 #[test]
@@ -246,9 +259,11 @@ fn claim_with_origin_invalid_merkle_proof_fails() {
     let mut hasher = Sha256::new(); hasher.update(&l0); hasher.update(&l1); let p01 = hasher.finalize_reset(); hasher.update(&p01); hasher.update(&l2); let root = hasher.finalize(); let root_hex = format!("0x{}", hex::encode(root));
 
     // register asset with root
-    let asset = aln_registry::RegisteredAsset { id: "d1".to_string(), source_chain: "k1".to_string(), source_denom: "ibc/x".to_string(), snapshot_height: 0, merkle_root: root_hex.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "clean".to_string(), activation_height: 0, sanitized_approved: true };
+    let asset = aln_registry::RegisteredAsset { id: "d1".to_string(), source_chain: "k1".to_string(), source_denom: "ibc/x".to_string(), snapshot_height: 0, merkle_root: root_hex.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "clean".to_string(), activation_height: 0, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     let register = aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() };
     aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), register).unwrap();
+    // Active, so the claim gets as far as the proof check.
+    aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), aln_registry::ExecuteMsg::ActivateAsset { id: "d1".to_string() }).unwrap();
 
     // instantiate bridge
     let bmsg = crate::InstantiateMsg { auet_contract: "auet_addr".to_string(), csp_contract: None, registry_contract: "reg".to_string(), governance_addr: gov.clone(), toxic_sink: None };
@@ -258,8 +273,8 @@ fn claim_with_origin_invalid_merkle_proof_fails() {
     let bad_proof = vec![ crate::ProofStep { sibling: Binary(l2.to_vec()), is_left: false } ];
     let origin_event = crate::core::bridge_architecture::OriginLockEvent { origin_chain_id: "k1".to_string(), tx_hash: "txX".to_string(), nonce: 42, denom: "ibc/x".to_string(), origin_address: "u1".to_string(), amount: "10".to_string(), height: Some(0) };
     let claim = crate::ExecuteMsg::ClaimWithOrigin { asset_id: "d1".to_string(), origin_event: origin_event.clone(), merkle_proof: bad_proof, ubs_report_hash: Some("h1".to_string()), amount_auet: Uint128::new(10), amount_csp: None };
-    let err = crate::execute(deps.as_mut(), mock_env(), mock_info("u1", &[]), claim);
-    assert!(err.is_err());
+    let err = crate::execute(deps.as_mut(), mock_env(), mock_info("u1", &[]), claim).unwrap_err();
+    assert!(err.to_string().contains("invalid merkle proof"), "{err}");
 }
 
 #[test]
@@ -273,18 +288,20 @@ fn toxic_cap_enforcement_fails_when_exceeded() {
     let s = crate::SnapshotEntry { chain_id: "k1".to_string(), height: 0, denom: "ibc/tox".to_string(), address: "user".to_string(), balance: "1000".to_string() };
     let mut hasher = Sha256::new();
     hasher.update(s.chain_id.as_bytes()); hasher.update(&s.height.to_be_bytes()); hasher.update(s.denom.as_bytes()); hasher.update(s.address.as_bytes()); let b: u128 = s.balance.parse().unwrap(); hasher.update(&b.to_be_bytes()); let digest = hasher.finalize(); let hhex = format!("0x{}", hex::encode(digest));
-    let asset = aln_registry::RegisteredAsset { id: "t1".to_string(), source_chain: "k1".to_string(), source_denom: "ibc/tox".to_string(), snapshot_height: 0, merkle_root: hhex.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true };
+    let asset = aln_registry::RegisteredAsset { id: "t1".to_string(), source_chain: "k1".to_string(), source_denom: "ibc/tox".to_string(), snapshot_height: 0, merkle_root: hhex.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     let register = aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() };
     aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), register).unwrap();
+    aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), aln_registry::ExecuteMsg::ActivateAsset { id: "t1".to_string() }).unwrap();
 
     // instantiate bridge with toxic cap 10%
     let bmsg = crate::InstantiateMsg { auet_contract: "auet_addr".to_string(), csp_contract: None, registry_contract: "reg".to_string(), governance_addr: gov.clone(), toxic_sink: Some("toxic_sink".to_string()), anomaly_threshold_amount: Some(Uint128::new(1)), toxic_cap_percent: Some(10) };
     crate::instantiate(deps.as_mut(), mock_env(), mock_info(&gov, &[]), bmsg).unwrap();
 
     // claim a small clean asset first to add to total
-    let clean_asset = aln_registry::RegisteredAsset { id: "c1".to_string(), source_chain: "k1".to_string(), source_denom: "ibc/clean".to_string(), snapshot_height: 0, merkle_root: hhex.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "clean".to_string(), activation_height: 0, sanitized_approved: true };
+    let clean_asset = aln_registry::RegisteredAsset { id: "c1".to_string(), source_chain: "k1".to_string(), source_denom: "ibc/clean".to_string(), snapshot_height: 0, merkle_root: hhex.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "clean".to_string(), activation_height: 0, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     let register2 = aln_registry::ExecuteMsg::RegisterAsset { asset: clean_asset.clone() };
     aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), register2).unwrap();
+    aln_registry::execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), aln_registry::ExecuteMsg::ActivateAsset { id: "c1".to_string() }).unwrap();
     // claim clean 100 => total now 100, toxic 0
     let claim_clean_msg = crate::ExecuteMsg::ClaimWithOrigin { asset_id: "c1".to_string(), origin_event: crate::core::bridge_architecture::OriginLockEvent { origin_chain_id: "k1".to_string(), tx_hash: "t1".to_string(), nonce: 1, denom: "ibc/clean".to_string(), origin_address: "u1".to_string(), amount: "100".to_string(), height: Some(0) }, merkle_proof: vec![], ubs_report_hash: Some("h1".to_string()), amount_auet: Uint128::new(100), amount_csp: None };
     let _ = crate::execute(deps.as_mut(), mock_env(), mock_info("u1", &[]), claim_clean_msg).unwrap();
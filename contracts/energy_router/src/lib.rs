@@ -1,37 +1,277 @@
-use cosmwasm_std::{entry_point, to_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128};
+use cosmwasm_std::{entry_point, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, Uint128, WasmMsg};
+use cw_storage_plus::{Bound, Item, Map};
 use serde::{Deserialize, Serialize};
 
+const DEFAULT_PAGE_LIMIT: u32 = 30;
+const MAX_PAGE_LIMIT: u32 = 100;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct InstantiateMsg {
     pub owner: String,
+    /// Bridge contract holding `ENERGY_LEDGER`. The router must be on its system whitelist.
+    pub bridge_contract: Option<String>,
+}
+
+/// AU.ET / CSP amounts charged by the router.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct EnergyCost {
+    pub auet: Uint128,
+    pub csp: Uint128,
+}
+
+impl EnergyCost {
+    pub fn is_zero(&self) -> bool { self.auet.is_zero() && self.csp.is_zero() }
+}
+
+/// Catalog entry, managed by the owner (governance).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Ability {
+    pub id: String,
+    pub unlock_cost: EnergyCost,
+    pub use_cost: EnergyCost,
+    /// Minimum number of blocks between two uses by the same user.
+    pub cooldown_blocks: u64,
+    /// Abilities the user must have unlocked before unlocking this one.
+    pub prerequisites: Vec<String>,
+    pub enabled: bool,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UnlockedAbility {
+    pub ability_id: String,
+    pub unlocked_at_height: u64,
+    pub last_used_height: Option<u64>,
+    pub uses: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UsageRecord {
+    pub seq: u64,
+    pub ability_id: String,
+    pub height: u64,
+    pub cost: EnergyCost,
+}
+
+pub const OWNER: Item<Addr> = Item::new("owner");
+pub const BRIDGE_CONTRACT: Item<Option<Addr>> = Item::new("bridge_contract");
+pub const ABILITIES: Map<&str, Ability> = Map::new("abilities");
+pub const UNLOCKED: Map<(&Addr, &str), UnlockedAbility> = Map::new("unlocked");
+pub const USAGE_SEQ: Map<&Addr, u64> = Map::new("usage_seq");
+pub const USAGE_HISTORY: Map<(&Addr, u64), UsageRecord> = Map::new("usage_history");
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Receive { sender: String, amount: Uint128, msg: Option<Binary> },
-    UseAbility { ability_id: String, cost_energy: Uint128 },
-    UnlockAbility { ability_id: String, cost_csp: Uint128 },
+    UseAbility { ability_id: String },
+    UnlockAbility { ability_id: String },
+    UpsertAbility { ability: Ability },
+    SetBridge { addr: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Ability { id: String },
+    Catalog { start_after: Option<String>, limit: Option<u32> },
+    UnlockedAbilities { user: String, start_after: Option<String>, limit: Option<u32> },
+    UsageHistory { user: String, start_after: Option<u64>, limit: Option<u32> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CatalogResponse {
+    pub abilities: Vec<Ability>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UnlockedAbilitiesResponse {
+    pub unlocked: Vec<UnlockedAbility>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UsageHistoryResponse {
+    pub records: Vec<UsageRecord>,
+}
+
+/// Wire-compatible subset of the bridge's `ExecuteMsg`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum BridgeExecuteMsg {
+    SystemConsume { owner: String, delta: BridgeEnergyVector },
+}
+
+/// Wire-compatible mirror of the bridge's `EnergyVector`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BridgeEnergyVector {
+    pub auet: Uint128,
+    pub csp: Uint128,
+    pub erp: Uint128,
 }
 
 #[entry_point]
-pub fn instantiate(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: InstantiateMsg) -> StdResult<Response> {
-    Ok(Response::new())
+pub fn instantiate(deps: DepsMut, _env: Env, _info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
+    let owner = deps.api.addr_validate(&msg.owner)?;
+    OWNER.save(deps.storage, &owner)?;
+    let bridge = match msg.bridge_contract {
+        Some(b) => Some(deps.api.addr_validate(&b)?),
+        None => None,
+    };
+    BRIDGE_CONTRACT.save(deps.storage, &bridge)?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
 }
 
 #[entry_point]
-pub fn execute(deps: DepsMut, _env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
+pub fn execute(deps: DepsMut, env: Env, info: MessageInfo, msg: ExecuteMsg) -> StdResult<Response> {
     match msg {
         ExecuteMsg::Receive { sender, amount, msg: _ } => {
             Ok(Response::new().add_attribute("action", "receive").add_attribute("from", sender).add_attribute("amount", amount.to_string()))
         }
-        ExecuteMsg::UseAbility { ability_id, cost_energy } => {
-            Ok(Response::new().add_attribute("action", "use_ability").add_attribute("ability_id", ability_id).add_attribute("cost", cost_energy.to_string()))
+        ExecuteMsg::UseAbility { ability_id } => use_ability(deps, env, info, ability_id),
+        ExecuteMsg::UnlockAbility { ability_id } => unlock_ability(deps, env, info, ability_id),
+        ExecuteMsg::UpsertAbility { ability } => {
+            ensure_owner(&deps, &info)?;
+            if ability.id.is_empty() { return Err(StdError::generic_err("ability id must not be empty")); }
+            if ability.prerequisites.iter().any(|p| p == &ability.id) {
+                return Err(StdError::generic_err("ability cannot be its own prerequisite"));
+            }
+            ABILITIES.save(deps.storage, ability.id.as_str(), &ability)?;
+            Ok(Response::new().add_attribute("action", "upsert_ability").add_attribute("ability_id", ability.id))
+        }
+        ExecuteMsg::SetBridge { addr } => {
+            ensure_owner(&deps, &info)?;
+            let a = deps.api.addr_validate(&addr)?;
+            BRIDGE_CONTRACT.save(deps.storage, &Some(a))?;
+            Ok(Response::new().add_attribute("action", "set_bridge").add_attribute("addr", addr))
+        }
+    }
+}
+
+fn ensure_owner(deps: &DepsMut, info: &MessageInfo) -> StdResult<()> {
+    let owner = OWNER.load(deps.storage)?;
+    if info.sender != owner { return Err(StdError::generic_err("only owner can manage the ability catalog")); }
+    Ok(())
+}
+
+fn load_enabled(deps: &DepsMut, ability_id: &str) -> StdResult<Ability> {
+    let ability = ABILITIES.may_load(deps.storage, ability_id)?
+        .ok_or_else(|| StdError::generic_err(format!("unknown ability {}", ability_id)))?;
+    if !ability.enabled { return Err(StdError::generic_err(format!("ability {} is disabled", ability_id))); }
+    Ok(ability)
+}
+
+/// Build the bridge `SystemConsume` debit. The bridge rejects it (and so reverts
+/// the whole transaction) if the user's ledger balance is insufficient.
+fn consume_msg(deps: &DepsMut, owner: &Addr, cost: &EnergyCost) -> StdResult<CosmosMsg> {
+    let bridge = BRIDGE_CONTRACT.load(deps.storage)?
+        .ok_or_else(|| StdError::generic_err("bridge contract not configured"))?;
+    let msg = BridgeExecuteMsg::SystemConsume {
+        owner: owner.to_string(),
+        delta: BridgeEnergyVector { auet: cost.auet, csp: cost.csp, erp: Uint128::zero() },
+    };
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute { contract_addr: bridge.to_string(), msg: to_binary(&msg)?, funds: vec![] }))
+}
+
+fn unlock_ability(deps: DepsMut, env: Env, info: MessageInfo, ability_id: String) -> StdResult<Response> {
+    let ability = load_enabled(&deps, &ability_id)?;
+    let user = info.sender;
+    if UNLOCKED.has(deps.storage, (&user, ability_id.as_str())) {
+        return Err(StdError::generic_err(format!("ability {} already unlocked", ability_id)));
+    }
+    for prereq in &ability.prerequisites {
+        if !UNLOCKED.has(deps.storage, (&user, prereq.as_str())) {
+            return Err(StdError::generic_err(format!("missing prerequisite {}", prereq)));
+        }
+    }
+    let mut resp = Response::new();
+    if !ability.unlock_cost.is_zero() {
+        resp = resp.add_message(consume_msg(&deps, &user, &ability.unlock_cost)?);
+    }
+    UNLOCKED.save(
+        deps.storage,
+        (&user, ability_id.as_str()),
+        &UnlockedAbility { ability_id: ability_id.clone(), unlocked_at_height: env.block.height, last_used_height: None, uses: 0 },
+    )?;
+    Ok(resp
+        .add_attribute("action", "unlock_ability")
+        .add_attribute("ability_id", ability_id)
+        .add_attribute("cost_auet", ability.unlock_cost.auet.to_string())
+        .add_attribute("cost_csp", ability.unlock_cost.csp.to_string()))
+}
+
+fn use_ability(deps: DepsMut, env: Env, info: MessageInfo, ability_id: String) -> StdResult<Response> {
+    let ability = load_enabled(&deps, &ability_id)?;
+    let user = info.sender;
+    let mut state = UNLOCKED.may_load(deps.storage, (&user, ability_id.as_str()))?
+        .ok_or_else(|| StdError::generic_err(format!("ability {} not unlocked", ability_id)))?;
+    if let Some(last) = state.last_used_height {
+        let ready_at = last.saturating_add(ability.cooldown_blocks);
+        if env.block.height < ready_at {
+            return Err(StdError::generic_err(format!("ability {} on cooldown until height {}", ability_id, ready_at)));
+        }
+    }
+    let mut resp = Response::new();
+    if !ability.use_cost.is_zero() {
+        resp = resp.add_message(consume_msg(&deps, &user, &ability.use_cost)?);
+    }
+    state.last_used_height = Some(env.block.height);
+    state.uses += 1;
+    UNLOCKED.save(deps.storage, (&user, ability_id.as_str()), &state)?;
+
+    let seq = USAGE_SEQ.may_load(deps.storage, &user)?.unwrap_or(0) + 1;
+    USAGE_SEQ.save(deps.storage, &user, &seq)?;
+    USAGE_HISTORY.save(
+        deps.storage,
+        (&user, seq),
+        &UsageRecord { seq, ability_id: ability_id.clone(), height: env.block.height, cost: ability.use_cost.clone() },
+    )?;
+    Ok(resp
+        .add_attribute("action", "use_ability")
+        .add_attribute("ability_id", ability_id)
+        .add_attribute("cost_auet", ability.use_cost.auet.to_string())
+        .add_attribute("cost_csp", ability.use_cost.csp.to_string()))
+}
+
+fn page_limit(limit: Option<u32>) -> usize {
+    limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize
+}
+
+#[entry_point]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Ability { id } => to_binary(&ABILITIES.load(deps.storage, id.as_str())?),
+        QueryMsg::Catalog { start_after, limit } => {
+            let start = start_after.map(|s| Bound::exclusive(s.as_str()));
+            let abilities = ABILITIES
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(page_limit(limit))
+                .map(|item| item.map(|(_, a)| a))
+                .collect::<StdResult<Vec<_>>>()?;
+            to_binary(&CatalogResponse { abilities })
+        }
+        QueryMsg::UnlockedAbilities { user, start_after, limit } => {
+            let user = deps.api.addr_validate(&user)?;
+            let start = start_after.map(|s| Bound::exclusive(s.as_str()));
+            let unlocked = UNLOCKED
+                .prefix(&user)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(page_limit(limit))
+                .map(|item| item.map(|(_, u)| u))
+                .collect::<StdResult<Vec<_>>>()?;
+            to_binary(&UnlockedAbilitiesResponse { unlocked })
         }
-        ExecuteMsg::UnlockAbility { ability_id, cost_csp } => {
-            Ok(Response::new().add_attribute("action", "unlock_ability").add_attribute("ability_id", ability_id).add_attribute("cost", cost_csp.to_string()))
+        QueryMsg::UsageHistory { user, start_after, limit } => {
+            let user = deps.api.addr_validate(&user)?;
+            let start = start_after.map(|s| Bound::exclusive(s.to_be_bytes().to_vec()));
+            let records = USAGE_HISTORY
+                .prefix(&user)
+                .range(deps.storage, start, None, Order::Ascending)
+                .take(page_limit(limit))
+                .map(|item| item.map(|(_, r)| r))
+                .collect::<StdResult<Vec<_>>>()?;
+            to_binary(&UsageHistoryResponse { records })
         }
     }
 }
 
-// TODO: Provide query API and implementation when needed
+#[cfg(test)]
+mod tests;
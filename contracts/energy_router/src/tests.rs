@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        execute, instantiate, query, Ability, BridgeEnergyVector, BridgeExecuteMsg, CatalogResponse, EnergyCost, ExecuteMsg,
+        InstantiateMsg, QueryMsg, UnlockedAbilitiesResponse, UsageHistoryResponse,
+    };
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{from_binary, from_slice, CosmosMsg, Env, OwnedDeps, Uint128, WasmMsg};
+
+    type Deps = OwnedDeps<MockStorage, MockApi, MockQuerier>;
+
+    fn ability(id: &str, use_auet: u128, unlock_csp: u128, cooldown_blocks: u64, prerequisites: &[&str]) -> Ability {
+        Ability {
+            id: id.to_string(),
+            unlock_cost: EnergyCost { auet: Uint128::zero(), csp: Uint128::new(unlock_csp) },
+            use_cost: EnergyCost { auet: Uint128::new(use_auet), csp: Uint128::zero() },
+            cooldown_blocks,
+            prerequisites: prerequisites.iter().map(|p| p.to_string()).collect(),
+            enabled: true,
+        }
+    }
+
+    fn setup() -> Deps {
+        let mut deps = mock_dependencies();
+        let msg = InstantiateMsg { owner: "owner".to_string(), bridge_contract: Some("bridge".to_string()) };
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), msg).unwrap();
+        for a in [ability("focus", 5, 1, 10, &[]), ability("overdrive", 20, 3, 100, &["focus"])] {
+            execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), ExecuteMsg::UpsertAbility { ability: a }).unwrap();
+        }
+        deps
+    }
+
+    fn at_height(h: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = h;
+        env
+    }
+
+    fn consume_of(msg: &CosmosMsg) -> (String, BridgeEnergyVector) {
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { contract_addr, msg, .. }) => {
+                assert_eq!(contract_addr, "bridge");
+                match from_slice::<BridgeExecuteMsg>(msg.as_slice()).unwrap() {
+                    BridgeExecuteMsg::SystemConsume { owner, delta } => (owner, delta),
+                }
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn only_owner_manages_catalog() {
+        let mut deps = setup();
+        let err = execute(deps.as_mut(), mock_env(), mock_info("user", &[]), ExecuteMsg::UpsertAbility { ability: ability("x", 1, 1, 0, &[]) });
+        assert!(err.is_err());
+        let err = execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), ExecuteMsg::UpsertAbility { ability: ability("x", 1, 1, 0, &["x"]) });
+        assert!(err.is_err());
+
+        let bin = query(deps.as_ref(), mock_env(), QueryMsg::Catalog { start_after: None, limit: None }).unwrap();
+        let catalog: CatalogResponse = from_binary(&bin).unwrap();
+        assert_eq!(catalog.abilities.iter().map(|a| a.id.as_str()).collect::<Vec<_>>(), vec!["focus", "overdrive"]);
+    }
+
+    #[test]
+    fn unlock_respects_prerequisites_and_debits_csp() {
+        let mut deps = setup();
+        let user = mock_info("user", &[]);
+        assert!(execute(deps.as_mut(), mock_env(), user.clone(), ExecuteMsg::UnlockAbility { ability_id: "overdrive".to_string() }).is_err());
+
+        let res = execute(deps.as_mut(), mock_env(), user.clone(), ExecuteMsg::UnlockAbility { ability_id: "focus".to_string() }).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        let (owner, delta) = consume_of(&res.messages[0].msg);
+        assert_eq!(owner, "user");
+        assert_eq!((delta.auet, delta.csp), (Uint128::zero(), Uint128::new(1)));
+
+        // double unlock is rejected
+        assert!(execute(deps.as_mut(), mock_env(), user.clone(), ExecuteMsg::UnlockAbility { ability_id: "focus".to_string() }).is_err());
+        execute(deps.as_mut(), mock_env(), user, ExecuteMsg::UnlockAbility { ability_id: "overdrive".to_string() }).unwrap();
+
+        let bin = query(deps.as_ref(), mock_env(), QueryMsg::UnlockedAbilities { user: "user".to_string(), start_after: None, limit: None }).unwrap();
+        let unlocked: UnlockedAbilitiesResponse = from_binary(&bin).unwrap();
+        assert_eq!(unlocked.unlocked.len(), 2);
+    }
+
+    #[test]
+    fn use_enforces_cooldown_and_records_history() {
+        let mut deps = setup();
+        let user = mock_info("user", &[]);
+        assert!(execute(deps.as_mut(), at_height(100), user.clone(), ExecuteMsg::UseAbility { ability_id: "focus".to_string() }).is_err());
+        execute(deps.as_mut(), at_height(100), user.clone(), ExecuteMsg::UnlockAbility { ability_id: "focus".to_string() }).unwrap();
+
+        let res = execute(deps.as_mut(), at_height(100), user.clone(), ExecuteMsg::UseAbility { ability_id: "focus".to_string() }).unwrap();
+        let (_, delta) = consume_of(&res.messages[0].msg);
+        assert_eq!(delta.auet, Uint128::new(5));
+
+        // cooldown of 10 blocks
+        assert!(execute(deps.as_mut(), at_height(109), user.clone(), ExecuteMsg::UseAbility { ability_id: "focus".to_string() }).is_err());
+        execute(deps.as_mut(), at_height(110), user, ExecuteMsg::UseAbility { ability_id: "focus".to_string() }).unwrap();
+
+        let bin = query(deps.as_ref(), mock_env(), QueryMsg::UsageHistory { user: "user".to_string(), start_after: Some(1), limit: None }).unwrap();
+        let history: UsageHistoryResponse = from_binary(&bin).unwrap();
+        assert_eq!(history.records.len(), 1);
+        assert_eq!((history.records[0].seq, history.records[0].height), (2, 110));
+    }
+
+    #[test]
+    fn disabled_or_unbridged_abilities_cannot_be_used() {
+        let mut deps = mock_dependencies();
+        instantiate(deps.as_mut(), mock_env(), mock_info("owner", &[]), InstantiateMsg { owner: "owner".to_string(), bridge_contract: None }).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), ExecuteMsg::UpsertAbility { ability: ability("focus", 5, 1, 0, &[]) }).unwrap();
+        // paid unlock needs a bridge to debit against
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("user", &[]), ExecuteMsg::UnlockAbility { ability_id: "focus".to_string() }).is_err());
+
+        let mut disabled = ability("focus", 5, 1, 0, &[]);
+        disabled.enabled = false;
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), ExecuteMsg::SetBridge { addr: "bridge".to_string() }).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("owner", &[]), ExecuteMsg::UpsertAbility { ability: disabled }).unwrap();
+        assert!(execute(deps.as_mut(), mock_env(), mock_info("user", &[]), ExecuteMsg::UnlockAbility { ability_id: "focus".to_string() }).is_err());
+    }
+}
@@ -21,6 +21,8 @@ mod tests {
             scaling_profile_id: "malicious_cleanup".to_string(),
             activation_height: 0,
             sanitized_approved: false,
+            state: Default::default(),
+            merkle_root_version: 0,
         };
 
         let res = execute(deps.as_mut(), mock_env(), mock_info(&gov, &[]), ExecuteMsg::RegisterAsset { asset: asset.clone() }).unwrap();
@@ -0,0 +1,6 @@
+-- Registry lifecycle state, upserted from wasm-aln_registry_asset events
+
+ALTER TABLE token_class ADD COLUMN IF NOT EXISTS state TEXT;
+ALTER TABLE token_class ADD COLUMN IF NOT EXISTS merkle_root TEXT;
+ALTER TABLE token_class ADD COLUMN IF NOT EXISTS merkle_root_version INTEGER;
+ALTER TABLE token_class ADD COLUMN IF NOT EXISTS state_height BIGINT;
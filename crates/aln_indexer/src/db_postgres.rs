@@ -23,6 +23,8 @@ pub trait Db {
     fn record_class_mint<'a>(&'a self, class_id: &'a str, amount: &'a str, block_height: i64) -> futures::future::BoxFuture<'a, Result<()>>;
     fn record_class_burn<'a>(&'a self, class_id: &'a str, amount: &'a str, block_height: i64) -> futures::future::BoxFuture<'a, Result<()>>;
     fn set_class_toxic<'a>(&'a self, class_id: &'a str, toxic: bool) -> futures::future::BoxFuture<'a, Result<()>>;
+    /// Apply a registry lifecycle event; older heights than the stored one are ignored so replays are idempotent.
+    fn upsert_token_class_state<'a>(&'a self, class_id: &'a str, state: &'a str, merkle_root: &'a str, merkle_root_version: i32, height: i64) -> futures::future::BoxFuture<'a, Result<()>>;
 }
 
 pub struct PostgresDb {
@@ -192,4 +194,16 @@ impl Db for PostgresDb {
             Ok(())
         })
     }
+
+    fn upsert_token_class_state<'a>(&'a self, class_id: &'a str, state: &'a str, merkle_root: &'a str, merkle_root_version: i32, height: i64) -> futures::future::BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            sqlx::query("INSERT INTO token_class (class_id, name, symbol, params, creator, is_transferable, created_at, state, merkle_root, merkle_root_version, state_height) VALUES ($1,$1,$1,'{}'::jsonb,'registry',true,now(),$2,$3,$4,$5) ON CONFLICT (class_id) DO UPDATE SET state = EXCLUDED.state, merkle_root = EXCLUDED.merkle_root, merkle_root_version = EXCLUDED.merkle_root_version, state_height = EXCLUDED.state_height WHERE token_class.state_height IS NULL OR token_class.state_height <= EXCLUDED.state_height")
+                .bind(class_id).bind(state).bind(merkle_root).bind(merkle_root_version).bind(height)
+                .execute(&self.pool).await.context("upsert token class state")?;
+            sqlx::query("INSERT INTO class_stats (class_id, total_minted, total_burned, toxic) VALUES ($1,'0','0',false) ON CONFLICT (class_id) DO NOTHING")
+                .bind(class_id)
+                .execute(&self.pool).await.context("ensure class stat row")?;
+            Ok(())
+        })
+    }
 }
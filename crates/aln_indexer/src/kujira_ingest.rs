@@ -4,6 +4,66 @@ use serde_json::Value;
 use reqwest::Client;
 use std::time::Duration;
 
+/// Event type the aln_registry contract emits on every asset lifecycle change
+/// (`ASSET_EVENT` there, prefixed with `wasm-` by the chain).
+pub const REGISTRY_ASSET_EVENT_TYPE: &str = "wasm-aln_registry_asset";
+
+/// One registry lifecycle change, as read from block results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetEvent {
+    pub asset_id: String,
+    pub action: String,
+    pub state: String,
+    pub merkle_root: String,
+    pub merkle_root_version: i32,
+    pub height: i64,
+}
+
+/// Collect registry asset events from a `/block_results` result. Attributes
+/// are read as plain strings (CometBFT 0.37+); events missing `asset_id` or
+/// `state` are skipped.
+pub fn parse_asset_events(block_results: &Value, height: i64) -> Vec<AssetEvent> {
+    let txs = block_results["txs_results"].as_array().cloned().unwrap_or_default();
+    let mut out = Vec::new();
+    for tx in txs.iter() {
+        for ev in tx["events"].as_array().into_iter().flatten() {
+            if ev["type"].as_str() != Some(REGISTRY_ASSET_EVENT_TYPE) {
+                continue;
+            }
+            let attr = |key: &str| -> Option<String> {
+                ev["attributes"].as_array()?.iter().find(|a| a["key"].as_str() == Some(key))?["value"].as_str().map(str::to_string)
+            };
+            let (Some(asset_id), Some(state)) = (attr("asset_id"), attr("state")) else { continue };
+            out.push(AssetEvent {
+                asset_id,
+                action: attr("action").unwrap_or_default(),
+                state,
+                merkle_root: attr("merkle_root").unwrap_or_default(),
+                merkle_root_version: attr("merkle_root_version").and_then(|v| v.parse().ok()).unwrap_or(0),
+                height,
+            });
+        }
+    }
+    out
+}
+
+/// Fetch `/block_results` for `height` and upsert each registry asset event into `token_class`.
+async fn ingest_asset_events<D: Db + Sync + Send + 'static>(client: &Client, db_impl: &D, rpc_endpoint: &str, height: i64) -> Result<usize> {
+    let resp = client
+        .get(format!("{}/block_results?height={}", rpc_endpoint, height))
+        .send()
+        .await
+        .context("fetch block_results")?
+        .json::<serde_json::Value>()
+        .await
+        .context("decode block_results")?;
+    let events = parse_asset_events(&resp["result"], height);
+    for ev in events.iter() {
+        db_impl.upsert_token_class_state(&ev.asset_id, &ev.state, &ev.merkle_root, ev.merkle_root_version, ev.height).await?;
+    }
+    Ok(events.len())
+}
+
 /// Ingests Kujira chain: fetch status, fetch blocks up to lag, insert into DB using provided Db implementation
 pub async fn ingest_kujira_chain<D: Db + Sync + Send + 'static>(pool: &sqlx::PgPool, db_impl: &D, chain_id: i64, rpc_endpoint: &str, lag_blocks: i64, metrics: Option<std::sync::Arc<tokio::sync::RwLock<crate::metrics::Metrics>>>) -> Result<()> {
     let client = Client::new();
@@ -30,6 +90,7 @@ pub async fn ingest_kujira_chain<D: Db + Sync + Send + 'static>(pool: &sqlx::PgP
             let b = BlockHeader { chain_id, height, hash: hash.clone(), parent_hash: parent_hash.clone() };
             db_impl.insert_block_and_txs(b, &raw_block, &txs).await?;
             db_impl.update_indexer_state_head(chain_id, height, &hash).await?;
+            ingest_asset_events(&client, db_impl, rpc_endpoint, height).await?;
 
             // call reorg handler if necessary - using chain_id=1 for default
             let replayed = crate::reorg::handle_reorg(pool, chain_id, height).await?;
@@ -248,7 +309,43 @@ pub async fn backfill_kujira_chain<D: Db + Sync + Send + 'static>(db_impl: &D, c
         let b = BlockHeader { chain_id, height, hash: hash.clone(), parent_hash: parent_hash.clone() };
         db_impl.insert_block_and_txs(b, &raw_block, &txs).await?;
         db_impl.update_indexer_state_head(chain_id, height, &hash).await?;
+        ingest_asset_events(&client, db_impl, rpc_endpoint, height).await?;
         h += 1;
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_registry_asset_events_only() {
+        let results = serde_json::json!({
+            "txs_results": [
+                { "events": [
+                    { "type": "message", "attributes": [ { "key": "action", "value": "execute" } ] },
+                    { "type": "wasm-aln_registry_asset", "attributes": [
+                        { "key": "action", "value": "pause_asset" },
+                        { "key": "asset_id", "value": "aln-foo" },
+                        { "key": "previous_state", "value": "active" },
+                        { "key": "state", "value": "paused" },
+                        { "key": "merkle_root", "value": "0xabc" },
+                        { "key": "merkle_root_version", "value": "2" }
+                    ] }
+                ] },
+                { "events": [ { "type": "wasm-aln_registry_asset", "attributes": [ { "key": "action", "value": "x" } ] } ] }
+            ]
+        });
+        let events = parse_asset_events(&results, 42);
+        assert_eq!(events, vec![AssetEvent {
+            asset_id: "aln-foo".into(),
+            action: "pause_asset".into(),
+            state: "paused".into(),
+            merkle_root: "0xabc".into(),
+            merkle_root_version: 2,
+            height: 42,
+        }]);
+        assert!(parse_asset_events(&serde_json::json!({ "txs_results": null }), 1).is_empty());
+    }
+}
@@ -33,7 +33,8 @@ mod tests {
             warp::reply::json(&serde_json::json!({ "result": result }))
         });
 
-        let routes = status.or(block);
+        let block_results = warp::path!("block_results").map(|| warp::reply::json(&serde_json::json!({ "result": { "txs_results": null } })));
+        let routes = status.or(block).or(block_results);
         let (addr_tx, addr_rx) = oneshot::channel();
         tokio::spawn(async move {
             let (addr, server) = warp::serve(routes).bind_with_graceful_shutdown(([127,0,0,1], 0), async {
@@ -87,7 +88,17 @@ mod tests {
             warp::reply::json(&serde_json::json!({ "result": result }))
         });
 
-        let routes = status.or(block);
+        // registry lifecycle event for the same class
+        let block_results = warp::path!("block_results").map(|| warp::reply::json(&serde_json::json!({ "result": { "txs_results": [ { "events": [
+            { "type": "wasm-aln_registry_asset", "attributes": [
+                { "key": "action", "value": "pause_asset" },
+                { "key": "asset_id", "value": "aln-foo" },
+                { "key": "state", "value": "paused" },
+                { "key": "merkle_root", "value": "abc" },
+                { "key": "merkle_root_version", "value": "1" }
+            ] }
+        ] } ] } })));
+        let routes = status.or(block).or(block_results);
         let (addr_tx, addr_rx) = oneshot::channel();
         tokio::spawn(async move {
             let (addr, server) = warp::serve(routes).bind_with_graceful_shutdown(([127,0,0,1], 0), async {
@@ -115,6 +126,9 @@ mod tests {
         // Query DB for class_toxic
         let toxic: bool = sqlx::query_scalar!("SELECT toxic FROM class_stats WHERE class_id = 'aln-foo'").fetch_one(&pool).await?;
         assert!(toxic);
+        // Registry lifecycle state from block results
+        let state: Option<String> = sqlx::query_scalar("SELECT state FROM token_class WHERE class_id = 'aln-foo'").fetch_one(&pool).await?;
+        assert_eq!(state.as_deref(), Some("paused"));
         // shutdown server
         tx.send(()).ok();
         Ok(())
@@ -1,5 +1,6 @@
 use serde::{Serialize, Deserialize};
 use cosmwasm_std::Uint128;
+use aln_energy::{mul_div, Ratio, RoundingMode};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EnergyVector {
@@ -8,9 +9,23 @@ pub struct EnergyVector {
     pub erp: Uint128,
 }
 
+/// Risk score in basis points, rounded up so the derived energy stays conservative.
+/// Scores outside [0, 1] (and NaN) are clamped; NaN counts as full risk.
+pub fn risk_to_bps(risk_score: f64) -> u32 {
+    if risk_score.is_nan() || risk_score >= 1.0 {
+        10_000
+    } else if risk_score <= 0.0 {
+        0
+    } else {
+        ((risk_score * 10_000.0).ceil() as u32).min(10_000)
+    }
+}
+
 pub fn map_to_energy(amount: u128, risk_score: &f64, _categories: &Vec<String>) -> EnergyVector {
-    // Deterministic conservative mapping: energy = amount * (1 - risk_score)
-    let factor = 1.0 - risk_score;
-    let au = ((amount as f64) * factor) as u128;
-    EnergyVector { auet: Uint128::new(au), csp: Uint128::new((au/2).max(0)), erp: Uint128::new(0) }
+    // Deterministic conservative mapping: energy = floor(amount * (1 - risk_score)),
+    // evaluated exactly on the basis-point factor.
+    let factor = Ratio::from_bps(10_000 - risk_to_bps(*risk_score));
+    let au = mul_div(amount, factor.num, factor.den, RoundingMode::Floor).unwrap_or(amount);
+    let cs = mul_div(au, 1, 2, RoundingMode::Floor).unwrap_or(0);
+    EnergyVector { auet: Uint128::new(au), csp: Uint128::new(cs), erp: Uint128::new(0) }
 }
@@ -0,0 +1,16 @@
+use aln_ubs::energy_mapping::{map_to_energy, risk_to_bps};
+
+#[test]
+fn test_map_to_energy_exact_and_conservative() {
+    let e = map_to_energy(u128::MAX, &0.0, &vec![]);
+    assert_eq!(e.auet.u128(), u128::MAX);
+    assert_eq!(e.csp.u128(), u128::MAX / 2);
+
+    // 0.25 risk on 1e30: exact, no f64 drift.
+    let e = map_to_energy(1_000_000_000_000_000_000_000_000_000_000, &0.25, &vec![]);
+    assert_eq!(e.auet.u128(), 750_000_000_000_000_000_000_000_000_000);
+
+    // Sub-basis-point risk rounds up, never in the holder's favour.
+    assert_eq!(risk_to_bps(0.00001), 1);
+    assert_eq!(map_to_energy(10_000, &f64::NAN, &vec![]).auet.u128(), 0);
+}
@@ -0,0 +1,77 @@
+//! Replay a recorded session (CSV or JSONL) through the streaming predictor.
+//! Recalibration requests are written to stdout as JSONL.
+
+use cem::cem_calibration_artifact::CalibrationArtifact;
+use cem::cem_sample_replay::read_samples;
+use cem::cem_streaming::{DriftConfig, StreamingPredictor};
+use cem::CalibrationParams;
+use clap::Parser;
+use prometheus::Encoder;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+struct ReplayArgs {
+    /// Calibration artifact ({hash_hex}.json) or legacy params_v1.json
+    #[arg(long)]
+    params: PathBuf,
+    /// Recorded samples (.csv or .jsonl)
+    #[arg(long)]
+    input: PathBuf,
+    #[arg(long, default_value_t = DriftConfig::default().delta_mw)]
+    delta_mw: f64,
+    #[arg(long, default_value_t = DriftConfig::default().threshold_mw)]
+    threshold_mw: f64,
+    #[arg(long, default_value_t = DriftConfig::default().min_samples)]
+    min_samples: u64,
+    /// Emit every prediction, not just recalibration requests
+    #[arg(long)]
+    all: bool,
+    /// Print the Prometheus exposition to stderr when done
+    #[arg(long)]
+    print_metrics: bool,
+}
+
+fn load_params(path: &PathBuf) -> anyhow::Result<CalibrationParams> {
+    let raw = std::fs::read_to_string(path)?;
+    if let Ok(artifact) = serde_json::from_str::<CalibrationArtifact>(&raw) {
+        return Ok(artifact.params);
+    }
+    Ok(serde_json::from_str(&raw)?)
+}
+
+fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt().with_writer(std::io::stderr).init();
+    let args = ReplayArgs::parse();
+
+    let params = load_params(&args.params)?;
+    let samples = read_samples(&args.input)?;
+    let config = DriftConfig { delta_mw: args.delta_mw, threshold_mw: args.threshold_mw, min_samples: args.min_samples };
+    let mut predictor = StreamingPredictor::new(params, config);
+
+    let mut requests = 0usize;
+    let mut abs_residual = 0.0;
+    for sample in &samples {
+        let prediction = predictor.observe(sample);
+        abs_residual += prediction.residual_mw.abs();
+        if args.all {
+            println!("{}", serde_json::to_string(&prediction)?);
+        } else if let Some(req) = &prediction.recalibration {
+            println!("{}", serde_json::to_string(req)?);
+        }
+        requests += prediction.recalibration.is_some() as usize;
+    }
+
+    eprintln!(
+        "replayed {} samples against {}: mean |residual| {:.3} mW, {} recalibration request(s)",
+        samples.len(),
+        predictor.params().hash_hex,
+        abs_residual / samples.len().max(1) as f64,
+        requests
+    );
+    if args.print_metrics {
+        let mut buffer = Vec::new();
+        prometheus::TextEncoder::new().encode(&prometheus::gather(), &mut buffer)?;
+        eprintln!("{}", String::from_utf8(buffer)?);
+    }
+    Ok(())
+}
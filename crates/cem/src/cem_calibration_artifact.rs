@@ -0,0 +1,218 @@
+//! Versioned on-disk calibration artifacts.
+//!
+//! Layout under the model root:
+//!
+//! ```text
+//! {root}/{subject_id}/{hash_hex}.json   one artifact per calibration
+//! {root}/{subject_id}/latest            hash_hex of the most recent one
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::cem_calibration_workflow::{CalibrationParams, CalibrationReport, CrossValidation, FitOptions, ResidualStats};
+use crate::version_stamp::cem_version_stamp;
+
+pub const CALIBRATION_ARTIFACT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationArtifact {
+    pub artifact_version: u32,
+    pub cem_version: String,
+    pub subject_id: u32,
+    pub session_id: u64,
+    pub created_at_ns: u64,
+    pub hash_hex: String,
+    pub params: CalibrationParams,
+    pub options: FitOptions,
+    pub sample_count: usize,
+    pub training_digest: String,
+    pub residuals: ResidualStats,
+    pub cross_validation: Option<CrossValidation>,
+    /// `hash_hex` of the subject's calibration this one superseded.
+    pub previous_hash: Option<String>,
+}
+
+impl CalibrationArtifact {
+    pub fn from_report(subject_id: u32, session_id: u64, report: CalibrationReport) -> Self {
+        Self {
+            artifact_version: CALIBRATION_ARTIFACT_VERSION,
+            cem_version: cem_version_stamp(),
+            subject_id,
+            session_id,
+            created_at_ns: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_nanos() as u64)
+                .unwrap_or(0),
+            hash_hex: report.params.hash_hex.clone(),
+            params: report.params,
+            options: report.options,
+            sample_count: report.sample_count,
+            training_digest: report.training_digest,
+            residuals: report.residuals,
+            cross_validation: report.cross_validation,
+            previous_hash: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ArtifactError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    UnsupportedVersion(u32),
+    /// File name and recorded `hash_hex` disagree.
+    HashMismatch { expected: String, found: String },
+}
+
+impl fmt::Display for ArtifactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArtifactError::Io(e) => write!(f, "calibration artifact io error: {}", e),
+            ArtifactError::Parse(e) => write!(f, "calibration artifact parse error: {}", e),
+            ArtifactError::UnsupportedVersion(v) => write!(f, "unsupported calibration artifact version {}", v),
+            ArtifactError::HashMismatch { expected, found } => {
+                write!(f, "artifact {} records hash_hex {}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArtifactError {}
+
+impl From<std::io::Error> for ArtifactError {
+    fn from(e: std::io::Error) -> Self {
+        ArtifactError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for ArtifactError {
+    fn from(e: serde_json::Error) -> Self {
+        ArtifactError::Parse(e)
+    }
+}
+
+/// Difference between two calibrations of the same subject (`current - previous`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalibrationComparison {
+    pub previous_hash: String,
+    pub current_hash: String,
+    pub same_training_data: bool,
+    /// alpha_v2, beta_a2, gamma_neural, delta_force, intercept.
+    pub coefficient_deltas: [f64; 5],
+    pub mse_delta: f64,
+    pub mae_delta: f64,
+    /// Only when both runs were cross-validated with the same k.
+    pub cv_mean_mse_delta: Option<f64>,
+}
+
+pub fn compare(previous: &CalibrationArtifact, current: &CalibrationArtifact) -> CalibrationComparison {
+    let prev = previous.params.coefficients();
+    let cur = current.params.coefficients();
+    let mut coefficient_deltas = [0.0; 5];
+    for (d, (c, p)) in coefficient_deltas.iter_mut().zip(cur.iter().zip(&prev)) {
+        *d = c - p;
+    }
+    let cv_mean_mse_delta = match (&previous.cross_validation, &current.cross_validation) {
+        (Some(p), Some(c)) if p.k == c.k => Some(c.mean_mse - p.mean_mse),
+        _ => None,
+    };
+    CalibrationComparison {
+        previous_hash: previous.hash_hex.clone(),
+        current_hash: current.hash_hex.clone(),
+        same_training_data: previous.training_digest == current.training_digest,
+        coefficient_deltas,
+        mse_delta: current.residuals.mse - previous.residuals.mse,
+        mae_delta: current.residuals.mae - previous.residuals.mae,
+        cv_mean_mse_delta,
+    }
+}
+
+pub struct ArtifactStore {
+    root: PathBuf,
+}
+
+impl ArtifactStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn subject_dir(&self, subject_id: u32) -> PathBuf {
+        self.root.join(subject_id.to_string())
+    }
+
+    pub fn path_for(&self, subject_id: u32, hash_hex: &str) -> PathBuf {
+        self.subject_dir(subject_id).join(format!("{}.json", hash_hex))
+    }
+
+    pub fn load(&self, subject_id: u32, hash_hex: &str) -> Result<CalibrationArtifact, ArtifactError> {
+        read_artifact(&self.path_for(subject_id, hash_hex), hash_hex)
+    }
+
+    pub fn latest(&self, subject_id: u32) -> Result<Option<CalibrationArtifact>, ArtifactError> {
+        let pointer = self.subject_dir(subject_id).join("latest");
+        if !pointer.exists() {
+            return Ok(None);
+        }
+        let hash_hex = fs::read_to_string(pointer)?;
+        self.load(subject_id, hash_hex.trim()).map(Some)
+    }
+
+    /// Link `artifact` to the subject's current latest calibration, write it
+    /// and move the `latest` pointer. Returns the comparison against the
+    /// previous calibration, if there was one.
+    ///
+    /// Re-saving the calibration that is already latest keeps the stored
+    /// artifact, history link included, and loads it into `artifact`.
+    pub fn save(&self, artifact: &mut CalibrationArtifact) -> Result<Option<CalibrationComparison>, ArtifactError> {
+        let previous = self.latest(artifact.subject_id)?;
+        if let Some(latest) = previous.as_ref().filter(|p| p.hash_hex == artifact.hash_hex) {
+            *artifact = latest.clone();
+            return Ok(None);
+        }
+        artifact.previous_hash = previous.as_ref().map(|p| p.hash_hex.clone());
+
+        let dir = self.subject_dir(artifact.subject_id);
+        fs::create_dir_all(&dir)?;
+        write_atomic(
+            &self.path_for(artifact.subject_id, &artifact.hash_hex),
+            serde_json::to_string_pretty(artifact)?.as_bytes(),
+        )?;
+        write_atomic(&dir.join("latest"), artifact.hash_hex.as_bytes())?;
+
+        Ok(previous.map(|p| compare(&p, artifact)))
+    }
+}
+
+/// Write to a sibling temp file and rename it over `path`, so a crash never
+/// leaves a truncated artifact or `latest` pointer behind.
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path)
+}
+
+fn read_artifact(path: &Path, hash_hex: &str) -> Result<CalibrationArtifact, ArtifactError> {
+    let artifact: CalibrationArtifact = serde_json::from_str(&fs::read_to_string(path)?)?;
+    if artifact.artifact_version != CALIBRATION_ARTIFACT_VERSION {
+        return Err(ArtifactError::UnsupportedVersion(artifact.artifact_version));
+    }
+    if artifact.hash_hex != hash_hex {
+        return Err(ArtifactError::HashMismatch {
+            expected: hash_hex.to_string(),
+            found: artifact.hash_hex,
+        });
+    }
+    Ok(artifact)
+}
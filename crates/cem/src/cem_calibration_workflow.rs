@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::fmt;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sample {
@@ -37,87 +38,495 @@ pub struct CalibrationParams {
 }
 
 pub fn sanitize(samples: &[Sample]) -> Vec<Sample> {
+    samples.iter().map(sanitize_sample).collect()
+}
+
+/// Clamp one sample to the physically plausible sensor ranges.
+pub fn sanitize_sample(s: &Sample) -> Sample {
     let clamp = |x: f32, lo: f32, hi: f32| x.max(lo).min(hi);
-    samples
-        .iter()
-        .cloned()
-        .map(|mut s| {
-            s.ax = clamp(s.ax, -200.0, 200.0);
-            s.ay = clamp(s.ay, -200.0, 200.0);
-            s.az = clamp(s.az, -200.0, 200.0);
-            s.gx = clamp(s.gx, -2_000.0, 2_000.0);
-            s.gy = clamp(s.gy, -2_000.0, 2_000.0);
-            s.gz = clamp(s.gz, -2_000.0, 2_000.0);
-            s.f_normal = clamp(s.f_normal, 0.0, 5_000.0);
-            s.f_tangential = clamp(s.f_tangential, -5_000.0, 5_000.0);
-            s.event_count = clamp(s.event_count, 0.0, 10_000.0);
-            s.event_polarity_mean = clamp(s.event_polarity_mean, -1.0, 1.0);
-            s.emg_rms = clamp(s.emg_rms, 0.0, 10.0);
-            s.p_mw_measured = clamp(s.p_mw_measured, 0.0, 10_000.0);
-            s
-        })
-        .collect()
+    let mut s = s.clone();
+    s.ax = clamp(s.ax, -200.0, 200.0);
+    s.ay = clamp(s.ay, -200.0, 200.0);
+    s.az = clamp(s.az, -200.0, 200.0);
+    s.gx = clamp(s.gx, -2_000.0, 2_000.0);
+    s.gy = clamp(s.gy, -2_000.0, 2_000.0);
+    s.gz = clamp(s.gz, -2_000.0, 2_000.0);
+    s.f_normal = clamp(s.f_normal, 0.0, 5_000.0);
+    s.f_tangential = clamp(s.f_tangential, -5_000.0, 5_000.0);
+    s.event_count = clamp(s.event_count, 0.0, 10_000.0);
+    s.event_polarity_mean = clamp(s.event_polarity_mean, -1.0, 1.0);
+    s.emg_rms = clamp(s.emg_rms, 0.0, 10.0);
+    s.p_mw_measured = clamp(s.p_mw_measured, 0.0, 10_000.0);
+    s
+}
+
+/// Model feature row for one (sanitized) sample, intercept column last.
+pub fn sample_features(s: &Sample) -> [f64; 5] {
+    let v_norm2 = (s.gx as f64).powi(2) + (s.gy as f64).powi(2) + (s.gz as f64).powi(2);
+    let a_norm2 = (s.ax as f64).powi(2) + (s.ay as f64).powi(2) + (s.az as f64).powi(2);
+    let neural_scalar: f64 = if !s.eeg_band_power.is_empty() {
+        s.eeg_band_power.iter().map(|v| *v as f64).sum::<f64>() / (s.eeg_band_power.len() as f64)
+    } else {
+        s.emg_rms as f64
+    };
+    let force_scalar = (s.f_normal.powi(2) + s.f_tangential.powi(2)).sqrt() as f64;
+    [v_norm2, a_norm2, neural_scalar, force_scalar, 1.0]
 }
 
 fn extract_features(samples: &[Sample]) -> (Vec<[f64; 5]>, Vec<f64>) {
-    let mut x_rows = Vec::with_capacity(samples.len());
-    let mut y_vals = Vec::with_capacity(samples.len());
-
-    for s in samples {
-        let v_norm2 = (s.gx as f64).powi(2) + (s.gy as f64).powi(2) + (s.gz as f64).powi(2);
-        let a_norm2 = (s.ax as f64).powi(2) + (s.ay as f64).powi(2) + (s.az as f64).powi(2);
-        let neural_scalar: f64 = if !s.eeg_band_power.is_empty() {
-            s.eeg_band_power.iter().map(|v| *v as f64).sum::<f64>() / (s.eeg_band_power.len() as f64)
-        } else {
-            s.emg_rms as f64
-        };
-        let force_scalar = (s.f_normal.powi(2) + s.f_tangential.powi(2)).sqrt() as f64;
-        let p = s.p_mw_measured as f64;
+    let x_rows = samples.iter().map(sample_features).collect();
+    let y_vals = samples.iter().map(|s| s.p_mw_measured as f64).collect();
+    (x_rows, y_vals)
+}
 
-        x_rows.push([v_norm2, a_norm2, neural_scalar, force_scalar, 1.0]);
-        y_vals.push(p);
+/// Number of model coefficients (four features plus intercept).
+pub const N_FEATURES: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CalibrationError {
+    TooFewSamples { needed: usize, got: usize },
+    LengthMismatch { rows: usize, targets: usize },
+    NonFiniteInput { row: usize },
+    SingularMatrix { column: usize },
+    InvalidOptions(String),
+}
+
+impl fmt::Display for CalibrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationError::TooFewSamples { needed, got } => {
+                write!(f, "need at least {} samples, got {}", needed, got)
+            }
+            CalibrationError::LengthMismatch { rows, targets } => {
+                write!(f, "{} feature rows but {} targets", rows, targets)
+            }
+            CalibrationError::NonFiniteInput { row } => {
+                write!(f, "non-finite feature or target in row {}", row)
+            }
+            CalibrationError::SingularMatrix { column } => {
+                write!(f, "normal equations singular at column {}", column)
+            }
+            CalibrationError::InvalidOptions(reason) => write!(f, "invalid fit options: {}", reason),
+        }
     }
+}
 
-    (x_rows, y_vals)
+impl std::error::Error for CalibrationError {}
+
+/// Loss used for the coefficient fit.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RobustLoss {
+    /// Ordinary (optionally ridge-penalised) least squares.
+    Squared,
+    /// Huber loss via iteratively reweighted least squares. Residuals beyond
+    /// `k` robust standard deviations (MAD / 0.6745) are down-weighted.
+    Huber { k: f64 },
 }
 
-#[tracing::instrument(skip(x_rows, y_vals))]
-pub fn fit_params(x_rows: &[[f64; 5]], y_vals: &[f64]) -> CalibrationParams {
-    assert!(x_rows.len() >= 5, "Need at least 5 samples");
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FitOptions {
+    /// Ridge penalty on the four standardised feature coefficients; the
+    /// intercept is never penalised. 0.0 is plain least squares.
+    pub lambda: f64,
+    /// When non-empty, `lambda` is replaced by the candidate with the lowest
+    /// cross-validated MSE.
+    #[serde(default)]
+    pub lambda_candidates: Vec<f64>,
+    pub loss: RobustLoss,
+    /// Folds for cross-validation; `None` skips it.
+    pub k_folds: Option<usize>,
+    pub max_irls_iterations: usize,
+}
+
+impl Default for FitOptions {
+    fn default() -> Self {
+        Self {
+            lambda: 0.0,
+            lambda_candidates: Vec::new(),
+            loss: RobustLoss::Squared,
+            k_folds: Some(5),
+            max_irls_iterations: 50,
+        }
+    }
+}
+
+impl FitOptions {
+    fn validate(&self) -> Result<(), CalibrationError> {
+        let bad_lambda = |l: f64| !l.is_finite() || l < 0.0;
+        if bad_lambda(self.lambda) || self.lambda_candidates.iter().any(|l| bad_lambda(*l)) {
+            return Err(CalibrationError::InvalidOptions(
+                "lambda must be finite and non-negative".into(),
+            ));
+        }
+        if let RobustLoss::Huber { k } = self.loss {
+            if !k.is_finite() || k <= 0.0 {
+                return Err(CalibrationError::InvalidOptions(format!(
+                    "huber k must be positive, got {}",
+                    k
+                )));
+            }
+        }
+        if let Some(k) = self.k_folds {
+            if k < 2 {
+                return Err(CalibrationError::InvalidOptions(format!(
+                    "k_folds must be at least 2, got {}",
+                    k
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResidualStats {
+    pub n: usize,
+    pub mean: f64,
+    pub mse: f64,
+    pub mae: f64,
+    pub median_abs: f64,
+    pub max_abs: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrossValidation {
+    pub k: usize,
+    pub lambda: f64,
+    /// Held-out MSE per fold that could be fit.
+    pub fold_mse: Vec<f64>,
+    /// Folds skipped because their training split was singular.
+    #[serde(default)]
+    pub skipped_folds: Vec<usize>,
+    pub mean_mse: f64,
+    pub std_mse: f64,
+}
+
+/// Everything a calibration run produced, ready to be persisted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationReport {
+    pub params: CalibrationParams,
+    /// Options actually used; `lambda` is the selected one.
+    pub options: FitOptions,
+    pub residuals: ResidualStats,
+    pub cross_validation: Option<CrossValidation>,
+    pub sample_count: usize,
+    pub training_digest: String,
+}
+
+fn check_inputs(x_rows: &[[f64; 5]], y_vals: &[f64]) -> Result<(), CalibrationError> {
+    if x_rows.len() != y_vals.len() {
+        return Err(CalibrationError::LengthMismatch {
+            rows: x_rows.len(),
+            targets: y_vals.len(),
+        });
+    }
+    if x_rows.len() < N_FEATURES {
+        return Err(CalibrationError::TooFewSamples {
+            needed: N_FEATURES,
+            got: x_rows.len(),
+        });
+    }
+    for (i, (row, y)) in x_rows.iter().zip(y_vals).enumerate() {
+        if !y.is_finite() || row.iter().any(|v| !v.is_finite()) {
+            return Err(CalibrationError::NonFiniteInput { row: i });
+        }
+    }
+    Ok(())
+}
+
+fn predict(theta: &[f64; 5], row: &[f64; 5]) -> f64 {
+    theta.iter().zip(row).map(|(t, x)| t * x).sum()
+}
+
+/// Weighted least squares with a ridge penalty. The four feature columns are
+/// centred and scaled to unit (weighted) variance before the penalty is
+/// applied, so `lambda` shrinks every feature equally regardless of its
+/// units; coefficients are mapped back to the raw feature scale.
+fn solve_weighted(
+    x_rows: &[[f64; 5]],
+    y_vals: &[f64],
+    weights: Option<&[f64]>,
+    lambda: f64,
+) -> Result<[f64; 5], CalibrationError> {
+    let d = N_FEATURES;
+    let weight = |i: usize| weights.map_or(1.0, |w| w[i]);
+    let total: f64 = (0..x_rows.len()).map(weight).sum();
+
+    let mut mean = [0.0f64; 4];
+    for (i, row) in x_rows.iter().enumerate() {
+        for c in 0..d - 1 {
+            mean[c] += weight(i) * row[c] / total;
+        }
+    }
+    let mut scale = [0.0f64; 4];
+    for (i, row) in x_rows.iter().enumerate() {
+        for c in 0..d - 1 {
+            scale[c] += weight(i) * (row[c] - mean[c]).powi(2) / total;
+        }
+    }
+    // A constant column stays centred (all zeros) rather than divided by zero.
+    let scale = scale.map(|v| if v.sqrt() > 1e-12 { v.sqrt() } else { 1.0 });
 
-    let n = x_rows.len();
-    let d = 5;
     let mut xtx = vec![0.0f64; d * d];
     let mut xty = vec![0.0f64; d];
-
-    for i in 0..n {
-        let row = &x_rows[i];
-        let y = y_vals[i];
+    for (i, (raw, y)) in x_rows.iter().zip(y_vals).enumerate() {
+        let w = weight(i);
+        let mut row = *raw;
+        for c in 0..d - 1 {
+            row[c] = (row[c] - mean[c]) / scale[c];
+        }
         for r in 0..d {
-            xty[r] += row[r] * y;
+            xty[r] += w * row[r] * y;
             for c in 0..d {
-                xtx[r * d + c] += row[r] * row[c];
+                xtx[r * d + c] += w * row[r] * row[c];
+            }
+        }
+    }
+    // Intercept (last column) stays unpenalised.
+    for r in 0..d - 1 {
+        xtx[r * d + r] += lambda;
+    }
+
+    let std_theta = gaussian_solve_5x5(&xtx, &xty)?;
+    let mut theta = [0.0f64; 5];
+    theta[d - 1] = std_theta[d - 1];
+    for c in 0..d - 1 {
+        theta[c] = std_theta[c] / scale[c];
+        theta[d - 1] -= theta[c] * mean[c];
+    }
+    Ok(theta)
+}
+
+fn median(mut v: Vec<f64>) -> f64 {
+    if v.is_empty() {
+        return 0.0;
+    }
+    v.sort_by(|a, b| a.total_cmp(b));
+    let mid = v.len() / 2;
+    if v.len().is_multiple_of(2) {
+        (v[mid - 1] + v[mid]) / 2.0
+    } else {
+        v[mid]
+    }
+}
+
+fn fit_theta(
+    x_rows: &[[f64; 5]],
+    y_vals: &[f64],
+    lambda: f64,
+    opts: &FitOptions,
+) -> Result<[f64; 5], CalibrationError> {
+    let mut theta = solve_weighted(x_rows, y_vals, None, lambda)?;
+    let RobustLoss::Huber { k } = opts.loss else {
+        return Ok(theta);
+    };
+
+    for _ in 0..opts.max_irls_iterations {
+        let abs_res: Vec<f64> = x_rows
+            .iter()
+            .zip(y_vals)
+            .map(|(row, y)| (y - predict(&theta, row)).abs())
+            .collect();
+        let scale = median(abs_res.clone()) / 0.6745;
+        if scale < 1e-12 {
+            break;
+        }
+        let delta = k * scale;
+        let weights: Vec<f64> = abs_res
+            .iter()
+            .map(|r| if *r <= delta { 1.0 } else { delta / r })
+            .collect();
+        let next = solve_weighted(x_rows, y_vals, Some(&weights), lambda)?;
+        let converged = next
+            .iter()
+            .zip(&theta)
+            .all(|(a, b)| (a - b).abs() <= 1e-9 * (1.0 + b.abs()));
+        theta = next;
+        if converged {
+            break;
+        }
+    }
+    Ok(theta)
+}
+
+pub fn residual_stats(params: &CalibrationParams, x_rows: &[[f64; 5]], y_vals: &[f64]) -> ResidualStats {
+    let theta = params.coefficients();
+    let res: Vec<f64> = x_rows
+        .iter()
+        .zip(y_vals)
+        .map(|(row, y)| y - predict(&theta, row))
+        .collect();
+    let n = res.len();
+    let nf = n.max(1) as f64;
+    let abs: Vec<f64> = res.iter().map(|r| r.abs()).collect();
+    ResidualStats {
+        n,
+        mean: res.iter().sum::<f64>() / nf,
+        mse: res.iter().map(|r| r * r).sum::<f64>() / nf,
+        mae: abs.iter().sum::<f64>() / nf,
+        max_abs: abs.iter().cloned().fold(0.0, f64::max),
+        median_abs: median(abs),
+    }
+}
+
+/// k-fold cross-validation at the given `lambda`. Row `i` is held out in fold
+/// `i % k`, so the split is deterministic. Folds whose training split is
+/// singular are skipped and listed in `skipped_folds`; it is only an error
+/// when every fold is.
+pub fn cross_validate(
+    x_rows: &[[f64; 5]],
+    y_vals: &[f64],
+    lambda: f64,
+    opts: &FitOptions,
+) -> Result<CrossValidation, CalibrationError> {
+    check_inputs(x_rows, y_vals)?;
+    opts.validate()?;
+    let k = opts
+        .k_folds
+        .ok_or_else(|| CalibrationError::InvalidOptions("cross-validation needs k_folds".into()))?;
+    let n = x_rows.len();
+    // Largest held-out fold is ceil(n / k); every training split needs a full rank.
+    let needed = N_FEATURES + n.div_ceil(k);
+    if k > n || n < needed {
+        return Err(CalibrationError::TooFewSamples {
+            needed: needed.max(k),
+            got: n,
+        });
+    }
+
+    let mut fold_mse = Vec::with_capacity(k);
+    let mut skipped_folds = Vec::new();
+    let mut last_singular = None;
+    for fold in 0..k {
+        let (mut tx, mut ty, mut hx, mut hy) = (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for i in 0..n {
+            if i % k == fold {
+                hx.push(x_rows[i]);
+                hy.push(y_vals[i]);
+            } else {
+                tx.push(x_rows[i]);
+                ty.push(y_vals[i]);
             }
         }
+        let theta = match fit_theta(&tx, &ty, lambda, opts) {
+            Ok(theta) => theta,
+            Err(e @ CalibrationError::SingularMatrix { .. }) => {
+                tracing::warn!(fold, lambda, error = %e, "cross-validation fold is singular; skipping");
+                skipped_folds.push(fold);
+                last_singular = Some(e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        let mse = hx
+            .iter()
+            .zip(&hy)
+            .map(|(row, y)| (y - predict(&theta, row)).powi(2))
+            .sum::<f64>()
+            / hx.len() as f64;
+        fold_mse.push(mse);
     }
 
-    let theta = gaussian_solve_5x5(&xtx, &xty);
+    if fold_mse.is_empty() {
+        if let Some(e) = last_singular {
+            return Err(e);
+        }
+    }
+    let fitted = fold_mse.len() as f64;
+    let mean_mse = fold_mse.iter().sum::<f64>() / fitted;
+    let std_mse = (fold_mse.iter().map(|m| (m - mean_mse).powi(2)).sum::<f64>() / fitted).sqrt();
+    Ok(CrossValidation { k, lambda, fold_mse, skipped_folds, mean_mse, std_mse })
+}
 
-    let alpha_v2 = theta[0];
-    let beta_a2 = theta[1];
-    let gamma_neural = theta[2];
-    let delta_force = theta[3];
-    let intercept = theta[4];
+/// Pick the candidate lambda with the lowest cross-validated MSE; ties keep
+/// the earlier candidate. Candidates singular in every fold are skipped.
+pub fn select_lambda(
+    x_rows: &[[f64; 5]],
+    y_vals: &[f64],
+    candidates: &[f64],
+    opts: &FitOptions,
+) -> Result<CrossValidation, CalibrationError> {
+    let mut best: Option<CrossValidation> = None;
+    let mut last_singular = None;
+    for &lambda in candidates {
+        let cv = match cross_validate(x_rows, y_vals, lambda, opts) {
+            Ok(cv) => cv,
+            Err(e @ CalibrationError::SingularMatrix { .. }) => {
+                tracing::warn!(lambda, error = %e, "lambda candidate is singular in every fold; skipping");
+                last_singular = Some(e);
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        if best.as_ref().is_none_or(|b| cv.mean_mse < b.mean_mse) {
+            best = Some(cv);
+        }
+    }
+    match (best, last_singular) {
+        (Some(best), _) => Ok(best),
+        (None, Some(e)) => Err(e),
+        (None, None) => Err(CalibrationError::InvalidOptions("no lambda candidates".into())),
+    }
+}
+
+/// SHA-256 over the sanitized feature rows and targets the model was fit on.
+pub fn training_digest(x_rows: &[[f64; 5]], y_vals: &[f64]) -> String {
+    let mut hasher = Sha256::new();
+    for (row, y) in x_rows.iter().zip(y_vals) {
+        for v in row {
+            hasher.update(v.to_le_bytes());
+        }
+        hasher.update(y.to_le_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
 
-    let mut mse = 0.0;
-    for i in 0..n {
-        let row = &x_rows[i];
-        let y = y_vals[i];
-        let y_hat = alpha_v2 * row[0] + beta_a2 * row[1] + gamma_neural * row[2] + delta_force * row[3] + intercept * row[4];
-        let e = y - y_hat;
-        mse += e * e;
+impl CalibrationParams {
+    /// Coefficients in feature order, intercept last.
+    pub fn coefficients(&self) -> [f64; 5] {
+        [self.alpha_v2, self.beta_a2, self.gamma_neural, self.delta_force, self.intercept]
     }
-    mse /= n as f64;
+
+    /// Predicted power (mW) for a sample; the sample is sanitized first, as
+    /// during fitting.
+    pub fn predict_mw(&self, sample: &Sample) -> f64 {
+        predict(&self.coefficients(), &sample_features(&sanitize_sample(sample)))
+    }
+}
+
+#[tracing::instrument(skip(x_rows, y_vals))]
+pub fn fit_params(x_rows: &[[f64; 5]], y_vals: &[f64]) -> Result<CalibrationParams, CalibrationError> {
+    fit_params_with(x_rows, y_vals, &FitOptions::default())
+}
+
+/// Fit with `opts.lambda` and `opts.loss`. Cross-validation and lambda
+/// selection are left to `run_calibration_with`.
+#[tracing::instrument(skip(x_rows, y_vals))]
+pub fn fit_params_with(
+    x_rows: &[[f64; 5]],
+    y_vals: &[f64],
+    opts: &FitOptions,
+) -> Result<CalibrationParams, CalibrationError> {
+    let theta = check_inputs(x_rows, y_vals)
+        .and_then(|_| opts.validate())
+        .and_then(|_| fit_theta(x_rows, y_vals, opts.lambda, opts));
+    let theta = match theta {
+        Ok(theta) => theta,
+        Err(e) => {
+            tracing::warn!(error = %e, "calibration fit failed");
+            crate::metrics::CEM_METRICS.cem_calibration_failures_total.inc();
+            return Err(e);
+        }
+    };
+
+    let [alpha_v2, beta_a2, gamma_neural, delta_force, intercept] = theta;
+    let n = x_rows.len();
+    let mse = x_rows
+        .iter()
+        .zip(y_vals)
+        .map(|(row, y)| (y - predict(&theta, row)).powi(2))
+        .sum::<f64>()
+        / n as f64;
 
     let mut hasher = Sha256::new();
     for v in [alpha_v2, beta_a2, gamma_neural, delta_force, intercept, mse] {
@@ -130,10 +539,10 @@ pub fn fit_params(x_rows: &[[f64; 5]], y_vals: &[f64]) -> CalibrationParams {
     // Update CEM metrics
     crate::metrics::CEM_METRICS.cem_calibrations_total.inc();
     crate::metrics::CEM_METRICS.cem_calibration_mse.observe(params.mse);
-    params
+    Ok(params)
 }
 
-fn gaussian_solve_5x5(a: &[f64], b: &[f64]) -> [f64; 5] {
+fn gaussian_solve_5x5(a: &[f64], b: &[f64]) -> Result<[f64; 5], CalibrationError> {
     let n = 5;
     let mut m = vec![0.0f64; n * (n + 1)];
     for r in 0..n {
@@ -155,14 +564,14 @@ fn gaussian_solve_5x5(a: &[f64], b: &[f64]) -> [f64; 5] {
         }
         if max_row != k {
             for j in k..(n + 1) {
-                let tmp = m[k * (n + 1) + j];
-                m[k * (n + 1) + j] = m[max_row * (n + 1) + j];
-                m[max_row * (n + 1) + j] = tmp;
+                m.swap(k * (n + 1) + j, max_row * (n + 1) + j);
             }
         }
 
         let pivot = m[k * (n + 1) + k];
-        assert!(pivot.abs() > 1e-12, "Singular matrix");
+        if !pivot.is_finite() || pivot.abs() <= 1e-12 {
+            return Err(CalibrationError::SingularMatrix { column: k });
+        }
         for j in k..(n + 1) {
             m[k * (n + 1) + j] /= pivot;
         }
@@ -183,11 +592,50 @@ fn gaussian_solve_5x5(a: &[f64], b: &[f64]) -> [f64; 5] {
         }
         x[i] = s;
     }
-    x
+    Ok(x)
 }
 
-pub fn run_calibration(samples: &[Sample]) -> CalibrationParams {
+pub fn run_calibration(samples: &[Sample]) -> Result<CalibrationParams, CalibrationError> {
     let clean = sanitize(samples);
     let (x_rows, y_vals) = extract_features(&clean);
     fit_params(&x_rows, &y_vals)
 }
+
+/// Full calibration: optional lambda selection, the fit itself, in-sample
+/// residual statistics and cross-validation. Cross-validation is skipped
+/// (with a warning) when there are too few samples or every fold is
+/// singular, unless lambda selection depends on it.
+pub fn run_calibration_with(samples: &[Sample], opts: &FitOptions) -> Result<CalibrationReport, CalibrationError> {
+    opts.validate()?;
+    let clean = sanitize(samples);
+    let (x_rows, y_vals) = extract_features(&clean);
+
+    let mut used = opts.clone();
+    let mut cross_validation = None;
+    if !opts.lambda_candidates.is_empty() {
+        let best = select_lambda(&x_rows, &y_vals, &opts.lambda_candidates, opts)?;
+        used.lambda = best.lambda;
+        cross_validation = Some(best);
+    } else if opts.k_folds.is_some() {
+        match cross_validate(&x_rows, &y_vals, opts.lambda, opts) {
+            Ok(cv) => cross_validation = Some(cv),
+            Err(CalibrationError::TooFewSamples { needed, got }) => {
+                tracing::warn!(needed, got, "too few samples for cross-validation; skipping");
+            }
+            Err(e @ CalibrationError::SingularMatrix { .. }) => {
+                tracing::warn!(error = %e, "every cross-validation fold is singular; skipping");
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    let params = fit_params_with(&x_rows, &y_vals, &used)?;
+    Ok(CalibrationReport {
+        residuals: residual_stats(&params, &x_rows, &y_vals),
+        params,
+        options: used,
+        cross_validation,
+        sample_count: x_rows.len(),
+        training_digest: training_digest(&x_rows, &y_vals),
+    })
+}
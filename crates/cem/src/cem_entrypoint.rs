@@ -1,6 +1,7 @@
-use crate::cem_calibration_workflow::{run_calibration, Sample, CalibrationParams};
+use crate::cem_calibration_workflow::{run_calibration_with, FitOptions, RobustLoss, Sample};
+use crate::cem_calibration_artifact::{write_atomic, ArtifactStore, CalibrationArtifact, CalibrationComparison};
 use std::fs::File;
-use std::io::{Read, Write};
+use std::io::Read;
 use serde_json;
 use clap::{Parser};
 use std::path::PathBuf;
@@ -15,24 +16,56 @@ pub struct CEMArgs {
     pub input: PathBuf,
     #[arg(long, default_value_t = String::from("calibration/models"))]
     pub outdir: String,
+    /// Ridge penalty on feature coefficients (0 = ordinary least squares)
+    #[arg(long, default_value_t = 0.0)]
+    pub lambda: f64,
+    /// Comma-separated ridge penalties; the one with the lowest CV error wins
+    #[arg(long, value_delimiter = ',')]
+    pub lambda_candidates: Vec<f64>,
+    /// Enable Huber-robust fitting with this tuning constant (e.g. 1.345)
+    #[arg(long)]
+    pub huber_k: Option<f64>,
+    /// Cross-validation folds (0 disables cross-validation)
+    #[arg(long, default_value_t = 5)]
+    pub k_folds: usize,
     /// Optional metrics server address to enable Prometheus scraping (e.g., 127.0.0.1:9889)
     #[arg(long)]
     pub metrics_addr: Option<String>,
 }
 
-pub fn run_from_cli(args: &CEMArgs) -> anyhow::Result<CalibrationParams> {
+impl CEMArgs {
+    pub fn fit_options(&self) -> FitOptions {
+        FitOptions {
+            lambda: self.lambda,
+            lambda_candidates: self.lambda_candidates.clone(),
+            loss: self.huber_k.map_or(RobustLoss::Squared, |k| RobustLoss::Huber { k }),
+            k_folds: (self.k_folds > 0).then_some(self.k_folds),
+            ..FitOptions::default()
+        }
+    }
+}
+
+pub struct CalibrationRun {
+    pub artifact: CalibrationArtifact,
+    /// Against the subject's previous calibration, if any.
+    pub comparison: Option<CalibrationComparison>,
+}
+
+pub fn run_from_cli(args: &CEMArgs) -> anyhow::Result<CalibrationRun> {
     let mut f = File::open(&args.input)?;
     let mut s = String::new();
     f.read_to_string(&mut s)?;
     let samples: Vec<Sample> = serde_json::from_str(&s)?;
 
-    let params = run_calibration(&samples);
+    let report = run_calibration_with(&samples, &args.fit_options())?;
+    let mut artifact = CalibrationArtifact::from_report(args.subject, args.session, report);
+
+    let store = ArtifactStore::new(&args.outdir);
+    let comparison = store.save(&mut artifact)?;
 
-    let outdir = PathBuf::from(&args.outdir);
-    std::fs::create_dir_all(&outdir)?;
-    let outfile = outdir.join(format!("{}/params_v1.json", args.subject));
-    let mut f = File::create(outfile)?;
-    f.write_all(serde_json::to_string_pretty(&params)?.as_bytes())?;
+    // Legacy location, still read by older tooling.
+    let outfile = PathBuf::from(&args.outdir).join(format!("{}/params_v1.json", args.subject));
+    write_atomic(&outfile, serde_json::to_string_pretty(&artifact.params)?.as_bytes())?;
 
-    Ok(params)
+    Ok(CalibrationRun { artifact, comparison })
 }
@@ -0,0 +1,98 @@
+//! Readers for recorded sessions, used by the `cem_replay` binary.
+//!
+//! JSONL: one `Sample` object per line.
+//! CSV: a header row naming `Sample` fields; `eeg_band_power` (optional) is a
+//! `;`-separated list. Fields are not quoted.
+
+use std::fmt;
+use std::path::Path;
+
+use serde_json::{Map, Number, Value};
+
+use crate::cem_calibration_workflow::Sample;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    UnknownFormat(String),
+    Line { line: usize, message: String },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(e) => write!(f, "replay io error: {}", e),
+            ReplayError::UnknownFormat(ext) => write!(f, "unknown replay format {:?} (expected csv or jsonl)", ext),
+            ReplayError::Line { line, message } => write!(f, "replay line {}: {}", line, message),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+/// Pick the parser from the file extension.
+pub fn read_samples(path: &Path) -> Result<Vec<Sample>, ReplayError> {
+    let raw = std::fs::read_to_string(path).map_err(ReplayError::Io)?;
+    match path.extension().and_then(|e| e.to_str()).unwrap_or("") {
+        "jsonl" | "ndjson" => parse_jsonl(&raw),
+        "csv" => parse_csv(&raw),
+        other => Err(ReplayError::UnknownFormat(other.to_string())),
+    }
+}
+
+pub fn parse_jsonl(raw: &str) -> Result<Vec<Sample>, ReplayError> {
+    raw.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| {
+            serde_json::from_str(l).map_err(|e| ReplayError::Line { line: i + 1, message: e.to_string() })
+        })
+        .collect()
+}
+
+fn csv_value(column: &str, cell: &str) -> Result<Value, String> {
+    match column {
+        "segment_id" => Ok(Value::String(cell.to_string())),
+        "eeg_band_power" => cell
+            .split(';')
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| csv_value("", v))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        _ => {
+            let cell = cell.trim();
+            if let Ok(v) = cell.parse::<u64>() {
+                return Ok(Value::Number(v.into()));
+            }
+            cell.parse::<f64>()
+                .ok()
+                .and_then(Number::from_f64)
+                .map(Value::Number)
+                .ok_or_else(|| format!("{:?} is not a number in column {:?}", cell, column))
+        }
+    }
+}
+
+pub fn parse_csv(raw: &str) -> Result<Vec<Sample>, ReplayError> {
+    let mut lines = raw.lines().enumerate().filter(|(_, l)| !l.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        return Ok(Vec::new());
+    };
+    let columns: Vec<&str> = header.split(',').map(str::trim).collect();
+
+    lines
+        .map(|(i, line)| {
+            let err = |message: String| ReplayError::Line { line: i + 1, message };
+            let cells: Vec<&str> = line.split(',').collect();
+            if cells.len() != columns.len() {
+                return Err(err(format!("{} cells, header has {}", cells.len(), columns.len())));
+            }
+            let mut obj = Map::new();
+            obj.insert("eeg_band_power".into(), Value::Array(Vec::new()));
+            for (col, cell) in columns.iter().zip(cells) {
+                obj.insert(col.to_string(), csv_value(col, cell).map_err(err)?);
+            }
+            serde_json::from_value(Value::Object(obj)).map_err(|e| err(e.to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,178 @@
+//! Live power prediction from stored `CalibrationParams`, with Page-Hinkley
+//! drift detection on the prediction residuals.
+
+use serde::{Deserialize, Serialize};
+
+use crate::cem_calibration_workflow::{sanitize_sample, CalibrationParams, Sample};
+use crate::metrics::CEM_METRICS;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriftConfig {
+    /// Residual change (mW) tolerated before it counts toward drift.
+    pub delta_mw: f64,
+    /// Cumulative deviation (mW) that raises the alarm.
+    pub threshold_mw: f64,
+    /// Samples observed before an alarm may fire.
+    pub min_samples: u64,
+}
+
+impl Default for DriftConfig {
+    fn default() -> Self {
+        Self { delta_mw: 5.0, threshold_mw: 200.0, min_samples: 30 }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DriftDirection {
+    /// Measured power is running above the model.
+    Up,
+    /// Measured power is running below the model.
+    Down,
+}
+
+/// Two-sided Page-Hinkley test.
+#[derive(Debug, Clone, Default)]
+pub struct PageHinkley {
+    n: u64,
+    mean: f64,
+    up_sum: f64,
+    up_min: f64,
+    down_sum: f64,
+    down_max: f64,
+}
+
+impl PageHinkley {
+    pub fn observe(&mut self, x: f64, cfg: &DriftConfig) -> Option<DriftDirection> {
+        self.n += 1;
+        self.mean += (x - self.mean) / self.n as f64;
+        self.up_sum += x - self.mean - cfg.delta_mw;
+        self.up_min = self.up_min.min(self.up_sum);
+        self.down_sum += x - self.mean + cfg.delta_mw;
+        self.down_max = self.down_max.max(self.down_sum);
+
+        if self.n < cfg.min_samples {
+            return None;
+        }
+        let (up, down) = self.statistics();
+        if up > cfg.threshold_mw && up >= down {
+            Some(DriftDirection::Up)
+        } else if down > cfg.threshold_mw {
+            Some(DriftDirection::Down)
+        } else {
+            None
+        }
+    }
+
+    /// (upward, downward) test statistics.
+    pub fn statistics(&self) -> (f64, f64) {
+        (self.up_sum - self.up_min, self.down_max - self.down_sum)
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.n
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Emitted once per detected drift; the detector re-arms afterwards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecalibrationRequest {
+    pub subject_id: u32,
+    pub session_id: u64,
+    /// `hash_hex` of the params that went stale.
+    pub calibration_hash: String,
+    pub detected_at_ns: u64,
+    pub direction: DriftDirection,
+    pub statistic: f64,
+    /// Mean residual (measured - predicted, mW) since the detector last armed.
+    pub residual_mean_mw: f64,
+    pub samples_since_reset: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Prediction {
+    pub timestamp_ns: u64,
+    pub predicted_mw: f64,
+    pub residual_mw: f64,
+    pub recalibration: Option<RecalibrationRequest>,
+}
+
+pub struct StreamingPredictor {
+    params: CalibrationParams,
+    config: DriftConfig,
+    detector: PageHinkley,
+    samples_total: u64,
+}
+
+impl StreamingPredictor {
+    pub fn new(params: CalibrationParams, config: DriftConfig) -> Self {
+        Self { params, config, detector: PageHinkley::default(), samples_total: 0 }
+    }
+
+    pub fn params(&self) -> &CalibrationParams {
+        &self.params
+    }
+
+    pub fn detector(&self) -> &PageHinkley {
+        &self.detector
+    }
+
+    pub fn samples_total(&self) -> u64 {
+        self.samples_total
+    }
+
+    /// Swap in fresh params (e.g. after recalibration) and re-arm the detector.
+    pub fn replace_params(&mut self, params: CalibrationParams) {
+        self.params = params;
+        self.detector.reset();
+    }
+
+    pub fn observe(&mut self, sample: &Sample) -> Prediction {
+        // Compare against the same sanitized target the model was fit on.
+        let sample = &sanitize_sample(sample);
+        let predicted_mw = self.params.predict_mw(sample);
+        let residual_mw = sample.p_mw_measured as f64 - predicted_mw;
+        self.samples_total += 1;
+
+        let direction = self.detector.observe(residual_mw, &self.config);
+        let (up, down) = self.detector.statistics();
+
+        let m = &*CEM_METRICS;
+        m.cem_prediction_residual_mw.observe(residual_mw.abs());
+        let subject = sample.subject_id.to_string();
+        m.cem_residual_mean_mw.with_label_values(&[&subject]).set(self.detector.mean());
+        m.cem_drift_statistic.with_label_values(&[&subject]).set(up.max(down));
+
+        let recalibration = direction.map(|direction| {
+            let request = RecalibrationRequest {
+                subject_id: sample.subject_id,
+                session_id: sample.session_id,
+                calibration_hash: self.params.hash_hex.clone(),
+                detected_at_ns: sample.timestamp_ns,
+                direction,
+                statistic: up.max(down),
+                residual_mean_mw: self.detector.mean(),
+                samples_since_reset: self.detector.samples(),
+            };
+            tracing::warn!(
+                subject = request.subject_id,
+                calibration = %request.calibration_hash,
+                direction = ?request.direction,
+                statistic = request.statistic,
+                "CEM drift detected; requesting recalibration"
+            );
+            m.cem_recalibration_requests_total.inc();
+            self.detector.reset();
+            request
+        });
+
+        Prediction { timestamp_ns: sample.timestamp_ns, predicted_mw, residual_mw, recalibration }
+    }
+}
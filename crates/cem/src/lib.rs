@@ -1,7 +1,15 @@
 pub mod cem_calibration_workflow;
+pub mod cem_calibration_artifact;
+pub mod cem_streaming;
+pub mod cem_sample_replay;
 pub mod version_stamp;
 pub mod cem_entrypoint;
+pub mod metrics;
+
+#[cfg(test)]
+mod tests;
 
 pub use cem_calibration_workflow::*;
+pub use cem_calibration_artifact::{ArtifactStore, CalibrationArtifact, CalibrationComparison};
+pub use cem_streaming::{DriftConfig, RecalibrationRequest, StreamingPredictor};
 pub use version_stamp::cem_version_stamp;
-
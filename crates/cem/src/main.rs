@@ -17,7 +17,14 @@ async fn main() -> anyhow::Result<()> {
     }
     // args already parsed above to check metrics; continue to use them.
     // run_from_cli is synchronous – it returns a result
-    let params = run_from_cli(&args)?;
-    println!("Calibration params: {:#?}", params);
+    let run = run_from_cli(&args)?;
+    println!("Calibration params: {:#?}", run.artifact.params);
+    println!("Residuals: {:#?}", run.artifact.residuals);
+    if let Some(cv) = &run.artifact.cross_validation {
+        println!("Cross-validation: {:#?}", cv);
+    }
+    if let Some(cmp) = &run.comparison {
+        println!("Compared with previous calibration: {:#?}", cmp);
+    }
     Ok(())
 }
@@ -1,18 +1,40 @@
-use prometheus::{register_int_counter, register_histogram_with_opts, Histogram, HistogramOpts, IntCounter};
+use prometheus::{register_int_counter, register_histogram, register_gauge_vec, GaugeVec, Histogram, HistogramOpts, IntCounter};
 use once_cell::sync::Lazy;
 
-pub static CEM_METRICS: Lazy<CemMetrics> = Lazy::new(|| CemMetrics::new());
+pub static CEM_METRICS: Lazy<CemMetrics> = Lazy::new(CemMetrics::new);
 
 pub struct CemMetrics {
     pub cem_calibrations_total: IntCounter,
     pub cem_calibration_mse: Histogram,
+    pub cem_calibration_failures_total: IntCounter,
+    pub cem_prediction_residual_mw: Histogram,
+    /// Per-subject, labelled `subject`; one series per streamed subject.
+    pub cem_residual_mean_mw: GaugeVec,
+    pub cem_drift_statistic: GaugeVec,
+    pub cem_recalibration_requests_total: IntCounter,
 }
 
 impl CemMetrics {
-    pub fn new() -> Self {
+    /// Registers every metric with the default registry, which fails on a
+    /// second registration; only `CEM_METRICS` may call this.
+    fn new() -> Self {
         let cem_calibrations_total = register_int_counter!("cem_calibrations_total", "Total CEM calibrations").unwrap();
         let mse_opts = HistogramOpts::new("cem_calibration_mse", "CEM calibration MSE").buckets(vec![0.0001, 0.001, 0.01, 0.1, 1.0, 10.0]);
-        let cem_calibration_mse = register_histogram_with_opts!(mse_opts).unwrap();
-        Self { cem_calibrations_total, cem_calibration_mse }
+        let cem_calibration_mse = register_histogram!(mse_opts).unwrap();
+        let cem_calibration_failures_total = register_int_counter!("cem_calibration_failures_total", "CEM calibration fits rejected with an error").unwrap();
+        let residual_opts = HistogramOpts::new("cem_prediction_residual_mw", "Absolute streaming prediction residual (mW)").buckets(vec![1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 1000.0]);
+        let cem_prediction_residual_mw = register_histogram!(residual_opts).unwrap();
+        let cem_residual_mean_mw = register_gauge_vec!("cem_residual_mean_mw", "Mean streaming residual since the drift detector last armed (mW)", &["subject"]).unwrap();
+        let cem_drift_statistic = register_gauge_vec!("cem_drift_statistic", "Page-Hinkley drift statistic (mW)", &["subject"]).unwrap();
+        let cem_recalibration_requests_total = register_int_counter!("cem_recalibration_requests_total", "Recalibration requests raised by drift detection").unwrap();
+        Self {
+            cem_calibrations_total,
+            cem_calibration_mse,
+            cem_calibration_failures_total,
+            cem_prediction_residual_mw,
+            cem_residual_mean_mw,
+            cem_drift_statistic,
+            cem_recalibration_requests_total,
+        }
     }
 }
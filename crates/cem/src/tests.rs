@@ -1,7 +1,14 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
-    use super::cem_calibration_workflow::*;
-    use rand::Rng;
+    use crate::cem_calibration_artifact::ArtifactStore;
+    use crate::cem_calibration_workflow::*;
+    use crate::cem_sample_replay::parse_csv;
+    use crate::cem_streaming::{DriftConfig, DriftDirection, StreamingPredictor};
+    use crate::metrics::CEM_METRICS;
+    use crate::CalibrationArtifact;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
     #[test]
     fn test_fit_params_synthetic() {
@@ -20,13 +27,13 @@ mod tests {
             let ax = rng.gen_range(0.0..100.0);
             let ay = rng.gen_range(0.0..100.0);
             let az = rng.gen_range(0.0..100.0);
-            let neural = rng.gen_range(0.0..1.0);
-            let f_n = rng.gen_range(0.0..100.0);
-            let f_t = rng.gen_range(-10.0..10.0);
+            let neural: f32 = rng.gen_range(0.0..1.0);
+            let f_n: f32 = rng.gen_range(0.0..100.0);
+            let f_t: f32 = rng.gen_range(-10.0..10.0);
 
             let v2 = (gx * gx + gy * gy + gz * gz) as f64;
             let a2 = (ax * ax + ay * ay + az * az) as f64;
-            let force = ((f_n * f_n + f_t * f_t)).sqrt() as f64;
+            let force = (f_n * f_n + f_t * f_t).sqrt() as f64;
             let neural_s = neural as f64;
 
             let p = alpha * v2 + beta * a2 + gamma * neural_s + delta * force + intercept;
@@ -40,12 +47,12 @@ mod tests {
                 ax, ay, az, gx, gy, gz, mx: 0.0, my: 0.0, mz: 0.0,
                 f_normal: f_n, f_tangential: f_t,
                 event_count: 0.0, event_polarity_mean: 0.0,
-                eeg_band_power: vec![], emg_rms: 0.0,
+                eeg_band_power: vec![], emg_rms: neural,
                 p_mw_measured: (p + noise) as f32,
             });
         }
 
-        let params = run_calibration(&samples);
+        let params = run_calibration(&samples).unwrap();
         // Allow loose tolerance due to noise and finite sample size
         assert!((params.alpha_v2 - alpha).abs() < 0.1);
         assert!((params.beta_a2 - beta).abs() < 0.1);
@@ -69,8 +76,183 @@ mod tests {
                 eeg_band_power: vec![], emg_rms: 0.0, p_mw_measured: 50.0,
             });
         }
-        let p1 = run_calibration(&samples);
-        let p2 = run_calibration(&samples);
+        // Constant inputs leave the feature columns at zero: plain least
+        // squares is singular, ridge still pins the intercept.
+        assert_eq!(run_calibration(&samples).unwrap_err(), CalibrationError::SingularMatrix { column: 0 });
+        let opts = FitOptions { lambda: 1.0, k_folds: None, ..FitOptions::default() };
+        let p1 = run_calibration_with(&samples, &opts).unwrap().params;
+        let p2 = run_calibration_with(&samples, &opts).unwrap().params;
         assert_eq!(p1.hash_hex, p2.hash_hex);
+        assert!((p1.intercept - 50.0).abs() < 1e-9);
+    }
+
+    fn synthetic(seed: u64, n: usize, outlier_every: Option<usize>) -> Vec<Sample> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n)
+            .map(|i| {
+                let (gx, gy, gz): (f32, f32, f32) = (rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0));
+                let (ax, ay, az): (f32, f32, f32) = (rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0), rng.gen_range(0.0..100.0));
+                let emg: f32 = rng.gen_range(0.0..1.0);
+                let f_n: f32 = rng.gen_range(0.0..100.0);
+                let v2 = (gx * gx + gy * gy + gz * gz) as f64;
+                let a2 = (ax * ax + ay * ay + az * az) as f64;
+                let mut p = 0.5 * v2 + 0.1 * a2 + 200.0 * emg as f64 + 1.2 * f_n as f64 + 50.0 + rng.gen_range(-1.0..1.0);
+                if outlier_every.is_some_and(|k| i % k == 0) {
+                    p += 3_000.0;
+                }
+                Sample {
+                    timestamp_ns: i as u64,
+                    subject_id: 7,
+                    session_id: 1,
+                    segment_id: "seg".to_string(),
+                    ax, ay, az, gx, gy, gz, mx: 0.0, my: 0.0, mz: 0.0,
+                    f_normal: f_n, f_tangential: 0.0,
+                    event_count: 0.0, event_polarity_mean: 0.0,
+                    eeg_band_power: vec![], emg_rms: emg,
+                    p_mw_measured: p as f32,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn huber_resists_outliers_and_errors_replace_panics() {
+        let samples = synthetic(11, 200, Some(10));
+        let ols = run_calibration(&samples).unwrap();
+        let huber = run_calibration_with(
+            &samples,
+            &FitOptions { loss: RobustLoss::Huber { k: 1.345 }, ..FitOptions::default() },
+        )
+        .unwrap();
+        assert!((huber.params.intercept - 50.0).abs() < 5.0);
+        assert!((huber.params.intercept - 50.0).abs() < (ols.intercept - 50.0).abs());
+        assert!((huber.params.gamma_neural - 200.0).abs() < 5.0);
+        assert_eq!(huber.cross_validation.as_ref().map(|cv| cv.fold_mse.len()), Some(5));
+        assert!(huber.residuals.median_abs < 2.0);
+
+        assert_eq!(
+            run_calibration(&samples[..4]).unwrap_err(),
+            CalibrationError::TooFewSamples { needed: 5, got: 4 }
+        );
+        let pick = FitOptions { lambda_candidates: vec![0.0, 1.0], k_folds: Some(5), ..FitOptions::default() };
+        assert!(matches!(
+            run_calibration_with(&samples[..6], &pick),
+            Err(CalibrationError::TooFewSamples { .. })
+        ));
+        assert!(matches!(
+            run_calibration_with(&samples, &FitOptions { lambda: -1.0, ..FitOptions::default() }),
+            Err(CalibrationError::InvalidOptions(_))
+        ));
+    }
+
+    #[test]
+    fn lambda_selection_and_artifact_history() {
+        let samples = synthetic(3, 120, None);
+        let opts = FitOptions { lambda_candidates: vec![1e6, 0.0, 1e3], ..FitOptions::default() };
+        let report = run_calibration_with(&samples, &opts).unwrap();
+        assert_eq!(report.options.lambda, 0.0);
+        assert_eq!(report.cross_validation.as_ref().unwrap().lambda, 0.0);
+
+        let root = std::env::temp_dir().join(format!("cem-artifacts-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let store = ArtifactStore::new(&root);
+
+        let mut first = CalibrationArtifact::from_report(7, 1, report);
+        assert!(store.save(&mut first).unwrap().is_none());
+
+        let heavier = run_calibration_with(&samples, &FitOptions { lambda: 1e3, ..FitOptions::default() }).unwrap();
+        let mut second = CalibrationArtifact::from_report(7, 2, heavier);
+        let cmp = store.save(&mut second).unwrap().unwrap();
+        assert_eq!(second.previous_hash.as_deref(), Some(first.hash_hex.as_str()));
+        assert!(cmp.same_training_data);
+        assert!(cmp.mse_delta > 0.0);
+        assert!(cmp.cv_mean_mse_delta.is_some());
+
+        let latest = store.latest(7).unwrap().unwrap();
+        assert_eq!(latest.hash_hex, second.hash_hex);
+
+        // Saving the latest calibration again must not drop its history link.
+        let refit = run_calibration_with(&samples, &FitOptions { lambda: 1e3, ..FitOptions::default() }).unwrap();
+        let mut again = CalibrationArtifact::from_report(7, 3, refit);
+        assert_eq!(again.hash_hex, second.hash_hex);
+        assert!(store.save(&mut again).unwrap().is_none());
+        assert_eq!(again.previous_hash.as_deref(), Some(first.hash_hex.as_str()));
+        assert_eq!(again.session_id, 2);
+        assert_eq!(store.latest(7).unwrap().unwrap().previous_hash.as_deref(), Some(first.hash_hex.as_str()));
+        assert_eq!(store.load(7, &first.hash_hex).unwrap().training_digest, first.training_digest);
+        assert!(store.latest(8).unwrap().is_none());
+        let leftovers = std::fs::read_dir(root.join("7"))
+            .unwrap()
+            .filter(|e| e.as_ref().unwrap().path().extension().is_some_and(|x| x == "tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn singular_cv_fold_is_skipped_and_ridge_is_scale_free() {
+        // Rotation only on rows 0 and 5, both held out in fold 0: that
+        // training split has a constant v^2 column and cannot be solved.
+        let mut samples = synthetic(5, 10, None);
+        for (i, s) in samples.iter_mut().enumerate() {
+            if i % 5 != 0 {
+                (s.gx, s.gy, s.gz) = (0.0, 0.0, 0.0);
+            }
+        }
+        let report = run_calibration_with(&samples, &FitOptions::default()).unwrap();
+        let cv = report.cross_validation.unwrap();
+        assert_eq!(cv.skipped_folds, vec![0]);
+        assert_eq!(cv.fold_mse.len(), 4);
+
+        // Rescaling a feature must not change how hard the ridge shrinks it.
+        let samples = synthetic(9, 60, None);
+        let scaled: Vec<Sample> = samples
+            .iter()
+            .map(|s| Sample { f_normal: s.f_normal / 10.0, ..s.clone() })
+            .collect();
+        let opts = FitOptions { lambda: 50.0, k_folds: None, ..FitOptions::default() };
+        let a = run_calibration_with(&samples, &opts).unwrap().params;
+        let b = run_calibration_with(&scaled, &opts).unwrap().params;
+        assert!((a.delta_force - b.delta_force / 10.0).abs() < 1e-6 * a.delta_force.abs().max(1.0));
+        assert!((a.mse - b.mse).abs() < 1e-6 * a.mse.max(1.0));
+    }
+
+    #[test]
+    fn streaming_predictor_flags_drift_once_and_rearms() {
+        let params = run_calibration(&synthetic(5, 150, None)).unwrap();
+        let cfg = DriftConfig { delta_mw: 2.0, threshold_mw: 100.0, min_samples: 20 };
+        let mut predictor = StreamingPredictor::new(params.clone(), cfg);
+
+        let live = synthetic(6, 200, None);
+        assert!(live[..100].iter().all(|s| predictor.observe(s).recalibration.is_none()));
+
+        // Sensor ageing: the device now draws 40 mW more than the model expects.
+        let before = CEM_METRICS.cem_recalibration_requests_total.get();
+        let requests: Vec<_> = live[100..]
+            .iter()
+            .map(|s| Sample { p_mw_measured: s.p_mw_measured + 40.0, ..s.clone() })
+            .filter_map(|s| predictor.observe(&s).recalibration)
+            .collect();
+        assert!(!requests.is_empty());
+        let first = &requests[0];
+        assert_eq!(first.direction, DriftDirection::Up);
+        assert_eq!(first.calibration_hash, params.hash_hex);
+        assert!(first.residual_mean_mw > 0.0);
+        assert!(first.detected_at_ns < 110, "detected late at {}", first.detected_at_ns);
+        // The detector re-arms after each request rather than firing on every sample.
+        assert!(requests.len() < 10);
+        assert!(CEM_METRICS.cem_recalibration_requests_total.get() >= before + requests.len() as u64);
+    }
+
+    #[test]
+    fn csv_replay_parses_header_named_columns() {
+        let csv = "timestamp_ns,subject_id,session_id,segment_id,ax,ay,az,gx,gy,gz,mx,my,mz,f_normal,f_tangential,event_count,event_polarity_mean,eeg_band_power,emg_rms,p_mw_measured\n\
+                   1,7,2,walk,1,2,3,0.5,0,0,0,0,0,10,0,0,0,0.2;0.4,0.1,120.5\n";
+        let samples = parse_csv(csv).unwrap();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].segment_id, "walk");
+        assert_eq!(samples[0].eeg_band_power, vec![0.2, 0.4]);
+        assert_eq!(samples[0].p_mw_measured, 120.5);
+        assert!(parse_csv("timestamp_ns,subject_id\n1\n").is_err());
     }
 }
@@ -0,0 +1,851 @@
+//! Single decision entry point over the canonical model.
+//!
+//! `OrganicCpuScheduler::decide` runs every check from both legacy paths
+//! (`schedule_neuromorph_job` and `decide_organic_job`) and reports all that
+//! fail, not just the first. Any deny-class reason denies; otherwise any
+//! defer-class reason defers.
+//!
+//! The legacy entry points are adapters over `SchedulingDecision::outcome`,
+//! so a deny-class failure wins no matter which check failed first.
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{HostState, JobRequest, ResourceBudget, SafetyEnvelope};
+use crate::safety::JobDecision;
+use crate::{AutonomyGrant, Decision, MlPassSchedule, OrganicCpuScheduler};
+
+/// EEG load above which jobs wait.
+pub const MAX_EEG_LOAD_NORM: f32 = 0.85;
+/// Class-C ML schedule ceilings.
+pub const MAX_CONCURRENT_JOBS: u32 = 8;
+pub const MAX_PASSES_PER_DAY: u32 = 128;
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum DecisionReason {
+    /// Job would move duty away from `u_safe_duty`.
+    LyapunovIncrease,
+    EnergyBudgetExhausted,
+    EnergyEnvelope,
+    ProteinBudgetExhausted,
+    ProteinEnvelope,
+    SbioEnvelope,
+    NeuromorphDutyCeiling,
+    ChatDutyCeiling,
+    DeltaTCeiling,
+    CoreTempCeiling,
+    MlScheduleOutOfClass,
+    NeurorightsViolation,
+    AutonomyGrantMismatch,
+    AutonomyBackendNotPermitted,
+    AutonomyEnergyScope,
+    InflammationHigh,
+    EegLoadHigh,
+}
+
+impl DecisionReason {
+    /// `Denied` for hard limits, `Deferred` for conditions that can recover.
+    ///
+    /// This follows `decide_organic_job`, where running out of budget or
+    /// duty headroom waits for the next window. `schedule_neuromorph_job`
+    /// keeps its own contract and still denies the reasons in
+    /// [`LEGACY_CORRIDOR_DENIALS`].
+    pub fn severity(self) -> Decision {
+        use DecisionReason::*;
+        match self {
+            LyapunovIncrease
+            | EnergyBudgetExhausted
+            | ProteinBudgetExhausted
+            | NeuromorphDutyCeiling
+            | ChatDutyCeiling
+            | InflammationHigh
+            | EegLoadHigh => Decision::Deferred,
+            EnergyEnvelope
+            | ProteinEnvelope
+            | SbioEnvelope
+            | DeltaTCeiling
+            | CoreTempCeiling
+            | MlScheduleOutOfClass
+            | NeurorightsViolation
+            | AutonomyGrantMismatch
+            | AutonomyBackendNotPermitted
+            | AutonomyEnergyScope => Decision::Denied,
+        }
+    }
+
+    /// Stable string code; matches the `decide_organic_job` strings where
+    /// that path had an equivalent.
+    pub fn code(self) -> &'static str {
+        use DecisionReason::*;
+        match self {
+            LyapunovIncrease => "lyapunov_increase",
+            EnergyBudgetExhausted => "insufficient_energy_budget",
+            EnergyEnvelope => "thermo_envelope_energy_violation",
+            ProteinBudgetExhausted => "insufficient_protein_budget",
+            ProteinEnvelope => "protein_envelope_violation",
+            SbioEnvelope => "sbio_envelope_violation",
+            NeuromorphDutyCeiling => "neuromorph_duty_ceiling",
+            ChatDutyCeiling => "chat_duty_ceiling",
+            DeltaTCeiling => "delta_core_c_ceiling",
+            CoreTempCeiling => "core_temp_ceiling",
+            MlScheduleOutOfClass => "ml_schedule_out_of_class",
+            NeurorightsViolation => "neurorights_violation",
+            AutonomyGrantMismatch => "autonomy_grant_mismatch",
+            AutonomyBackendNotPermitted => "autonomy_backend_not_permitted",
+            AutonomyEnergyScope => "autonomy_energy_scope",
+            InflammationHigh => "inflammation_high",
+            EegLoadHigh => "eeg_load_high",
+        }
+    }
+}
+
+/// Defer-class reasons that `schedule_neuromorph_job` has always denied as
+/// part of its corridor check.
+pub const LEGACY_CORRIDOR_DENIALS: [DecisionReason; 3] = [
+    DecisionReason::EnergyBudgetExhausted,
+    DecisionReason::ProteinBudgetExhausted,
+    DecisionReason::NeuromorphDutyCeiling,
+];
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SchedulingDecision {
+    pub outcome: Decision,
+    /// Every failed check, in evaluation order.
+    pub reasons: Vec<DecisionReason>,
+}
+
+impl SchedulingDecision {
+    fn from_reasons(reasons: Vec<DecisionReason>) -> Self {
+        let outcome = if reasons.iter().any(|r| r.severity() == Decision::Denied) {
+            Decision::Denied
+        } else if reasons.is_empty() {
+            Decision::Approved
+        } else {
+            Decision::Deferred
+        };
+        Self { outcome, reasons }
+    }
+
+    pub fn is_approved(&self) -> bool {
+        self.outcome == Decision::Approved
+    }
+
+    /// First reason with the deciding severity.
+    pub fn primary_reason(&self) -> Option<DecisionReason> {
+        self.reasons
+            .iter()
+            .copied()
+            .find(|r| r.severity() == self.outcome)
+    }
+}
+
+impl From<&SchedulingDecision> for JobDecision {
+    fn from(d: &SchedulingDecision) -> Self {
+        let code = || {
+            d.primary_reason()
+                .map(|r| r.code().to_string())
+                .unwrap_or_default()
+        };
+        match d.outcome {
+            Decision::Approved => JobDecision::Permit,
+            Decision::Deferred => JobDecision::Defer(code()),
+            Decision::Denied => JobDecision::Deny(code()),
+        }
+    }
+}
+
+/// Grant names OrganicCPU among its permitted backends.
+pub(crate) fn backend_permitted(grant: &AutonomyGrant) -> bool {
+    grant.permitted_backends.iter().any(|b| b == "OrganicCPU")
+}
+
+/// Job energy fits the grant's blood requirement, bridged at 1000 nJ per unit.
+pub(crate) fn within_energy_scope(grant: &AutonomyGrant, job: &JobRequest) -> bool {
+    job.energy_nj <= f64::from(grant.required_blood * 1_000.0)
+}
+
+/// Inputs to one decision, already converted to the canonical model.
+#[derive(Clone, Copy, Debug)]
+pub struct DecisionInput<'a> {
+    pub host: &'a HostState,
+    pub budget: &'a ResourceBudget,
+    pub envelope: &'a SafetyEnvelope,
+    pub ml: &'a MlPassSchedule,
+    pub job: &'a JobRequest,
+    pub grant: &'a AutonomyGrant,
+}
+
+impl OrganicCpuScheduler {
+    /// Lyapunov, corridor, ML schedule, neurorights, autonomy-grant and
+    /// thermal/inflammation checks in one pass.
+    pub fn decide(&self, input: DecisionInput<'_>) -> SchedulingDecision {
+        let DecisionInput {
+            host,
+            budget,
+            envelope,
+            ml,
+            job,
+            grant,
+        } = input;
+        let mut reasons = Vec::new();
+        let mut check = |failed: bool, reason| {
+            if failed {
+                reasons.push(reason);
+            }
+        };
+
+        // Lyapunov duty control: V(u_after) <= V(u_current).
+        let duty_after = host.duty_neuromorph + job.duty;
+        let lyapunov_ok = self.lyapunov(duty_after) <= self.lyapunov(host.duty_neuromorph);
+        check(!lyapunov_ok, DecisionReason::LyapunovIncrease);
+
+        // Thermal hard stops.
+        check(
+            envelope
+                .max_core_temp_c
+                .is_some_and(|max| host.core_temp_c + job.delta_t_c > max),
+            DecisionReason::CoreTempCeiling,
+        );
+        check(
+            job.delta_t_c > envelope.max_delta_t_c,
+            DecisionReason::DeltaTCeiling,
+        );
+
+        // Corridor polytope over (E, Mprot, Sbio, duty).
+        check(
+            job.energy_nj > budget.energy_nj_remaining,
+            DecisionReason::EnergyBudgetExhausted,
+        );
+        check(
+            job.energy_nj > f64::from(self.max_energy_nj)
+                || envelope
+                    .max_job_energy_nj
+                    .is_some_and(|max| job.energy_nj > max),
+            DecisionReason::EnergyEnvelope,
+        );
+        check(
+            budget
+                .protein_mg_remaining
+                .is_some_and(|left| job.protein_mg > left),
+            DecisionReason::ProteinBudgetExhausted,
+        );
+        check(
+            job.protein_mg > self.max_protein_mg,
+            DecisionReason::ProteinEnvelope,
+        );
+        check(job.sbio > self.max_sbio, DecisionReason::SbioEnvelope);
+        check(
+            duty_after > budget.max_neuromorph_duty
+                || envelope
+                    .max_neuromorph_duty
+                    .is_some_and(|max| duty_after > max),
+            DecisionReason::NeuromorphDutyCeiling,
+        );
+        let chat_over = |max: Option<f32>| host.duty_chat.zip(max).is_some_and(|(d, m)| d > m);
+        check(
+            chat_over(budget.max_chat_duty) || chat_over(envelope.max_chat_duty),
+            DecisionReason::ChatDutyCeiling,
+        );
+
+        check(
+            ml.max_concurrent_jobs > MAX_CONCURRENT_JOBS || ml.passes_per_day > MAX_PASSES_PER_DAY,
+            DecisionReason::MlScheduleOutOfClass,
+        );
+
+        check(
+            !job.compliance.neurorights_intact(),
+            DecisionReason::NeurorightsViolation,
+        );
+
+        check(
+            grant.id != job.autonomy_grant_id,
+            DecisionReason::AutonomyGrantMismatch,
+        );
+        check(
+            !backend_permitted(grant),
+            DecisionReason::AutonomyBackendNotPermitted,
+        );
+        check(
+            !within_energy_scope(grant, job),
+            DecisionReason::AutonomyEnergyScope,
+        );
+
+        // Organic safety band: inflammation and EEG load.
+        check(
+            host.inflammation_index > envelope.max_inflammation_index,
+            DecisionReason::InflammationHigh,
+        );
+        check(
+            host.eeg_load_norm
+                .is_some_and(|eeg| eeg > MAX_EEG_LOAD_NORM),
+            DecisionReason::EegLoadHigh,
+        );
+
+        SchedulingDecision::from_reasons(reasons)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::budget::HostBudget as LegacyBudget;
+    use crate::safety::{
+        decide_organic_job, NeuromorphJobCost, ThermodynamicEnvelope as LegacyEnvelope,
+    };
+    use crate::snapshot::OrganicCpuSnapshot as LegacySnapshot;
+    use crate::{
+        ALNComplianceParticle, AutonomyLevel, BioVirtualScheduler, HostBudget, NeuromorphJob,
+        OrganicCpuSnapshot, ThermodynamicEnvelope,
+    };
+    use proptest::prelude::*;
+    use proptest::sample::select;
+    use std::time::SystemTime;
+
+    /// `schedule_neuromorph_job` as it stood before it became an adapter over
+    /// `decide`. Frozen here as the oracle; do not update it to match `decide`.
+    fn baseline_schedule_neuromorph_job(
+        sched: &OrganicCpuScheduler,
+        host: &OrganicCpuSnapshot,
+        budget: &HostBudget,
+        thermo: &ThermodynamicEnvelope,
+        ml: &MlPassSchedule,
+        job: &NeuromorphJob,
+        grant: &AutonomyGrant,
+    ) -> Decision {
+        let duty_after = host.duty_fraction + job.duty_cost;
+        if sched.lyapunov(duty_after) > sched.lyapunov(host.duty_fraction) {
+            return Decision::Deferred;
+        }
+        let corridor_ok = budget.energy_nj_headroom - job.energy_cost_nj >= 0.0
+            && job.energy_cost_nj <= sched.max_energy_nj
+            && budget.protein_headroom_mg - job.protein_cost_mg >= 0.0
+            && job.protein_cost_mg <= sched.max_protein_mg
+            && job.sbio_cost <= sched.max_sbio
+            && duty_after <= budget.duty_fraction_max
+            && job.delta_t_c <= thermo.delta_t_c_max;
+        if !corridor_ok {
+            return Decision::Denied;
+        }
+        if ml.max_concurrent_jobs > 8 || ml.passes_per_day > 128 {
+            return Decision::Denied;
+        }
+        let c = &job.aln_compliance;
+        if !(c.neurorights_mental_privacy
+            && c.neurorights_reversibility
+            && c.neurorights_no_finance
+            && c.neurorights_no_expropriation)
+        {
+            return Decision::Denied;
+        }
+        if !grant.permitted_backends.iter().any(|b| b == "OrganicCPU")
+            || job.energy_cost_nj > grant.required_blood * 1_000.0
+        {
+            return Decision::Denied;
+        }
+        if host.il6_proxy > thermo.il6_index_max || host.eeg_load_norm > 0.85 {
+            return Decision::Deferred;
+        }
+        Decision::Approved
+    }
+
+    /// `decide_organic_job` as it stood before it became an adapter over
+    /// `decide`. Frozen here as the oracle; do not update it to match `decide`.
+    fn baseline_decide_organic_job(
+        snap: &LegacySnapshot,
+        budget: &LegacyBudget,
+        envelope: &LegacyEnvelope,
+        cost: &NeuromorphJobCost,
+    ) -> JobDecision {
+        if snap.core_temp_c + cost.estimated_delta_core_c > envelope.max_core_c {
+            return JobDecision::Deny("core_temp_ceiling".into());
+        }
+        if cost.estimated_delta_core_c > envelope.max_delta_core_c {
+            return JobDecision::Deny("delta_core_c_ceiling".into());
+        }
+        if cost.estimated_energy_joules > budget.energy_joules_remaining {
+            return JobDecision::Defer("insufficient_energy_budget".into());
+        }
+        if cost.estimated_energy_joules > envelope.max_energy_joules {
+            return JobDecision::Deny("thermo_envelope_energy_violation".into());
+        }
+        if snap.duty_fraction_neuromorph + cost.estimated_duty_neuromorph
+            > budget.max_neuromorph_duty_fraction
+        {
+            return JobDecision::Defer("neuromorph_duty_ceiling".into());
+        }
+        if snap.duty_fraction_chat > budget.max_chat_duty_fraction {
+            return JobDecision::Defer("chat_duty_ceiling".into());
+        }
+        if snap.inflammation_index > envelope.max_inflammation_index {
+            return JobDecision::Defer("inflammation_high".into());
+        }
+        JobDecision::Permit
+    }
+
+    /// Coarse grids so boundary equalities come up often.
+    const UNIT: [f32; 6] = [0.0, 0.2, 0.4, 0.5, 0.6, 0.9];
+    /// Ceilings lean high so approvals are common enough to exercise.
+    const CEILING: [f32; 5] = [0.2, 0.5, 0.9, 1.0, 1.0];
+
+    fn pick(grid: &[f32]) -> impl Strategy<Value = f32> {
+        select(grid.to_vec())
+    }
+
+    prop_compose! {
+        fn scheduler()(
+            u_safe_duty in pick(&[0.3, 0.5, 0.7]),
+            max_energy_nj in pick(&[50.0, 100.0, 200.0]),
+            max_protein_mg in pick(&[5.0, 10.0, 20.0]),
+            max_sbio in pick(&[0.5, 1.0]),
+        ) -> OrganicCpuScheduler {
+            OrganicCpuScheduler { u_safe_duty, max_energy_nj, max_protein_mg, max_sbio }
+        }
+    }
+
+    prop_compose! {
+        fn compliance()(flags in prop::array::uniform4(prop::bool::weighted(0.95))) -> ALNComplianceParticle {
+            ALNComplianceParticle {
+                neurorights_mental_privacy: flags[0],
+                neurorights_reversibility: flags[1],
+                neurorights_no_finance: flags[2],
+                neurorights_no_expropriation: flags[3],
+            }
+        }
+    }
+
+    prop_compose! {
+        fn grant()(
+            required_blood in pick(&[0.05, 0.1, 1.0]),
+            organic in prop::bool::weighted(0.9),
+        ) -> AutonomyGrant {
+            AutonomyGrant {
+                id: "g".to_string(),
+                level: AutonomyLevel::LabOnly,
+                required_blood,
+                min_chat_factor: 0.0,
+                jurisdiction: "lab".to_string(),
+                permitted_backends: vec![if organic { "OrganicCPU" } else { "Gpu" }.to_string()],
+            }
+        }
+    }
+
+    prop_compose! {
+        fn ml()(in_class in prop::bool::weighted(0.9), wide in prop::bool::weighted(0.1)) -> MlPassSchedule {
+            MlPassSchedule {
+                passes_per_day: if in_class { 64 } else { 256 },
+                max_concurrent_jobs: if wide { 16 } else { 4 },
+            }
+        }
+    }
+
+    prop_compose! {
+        fn snapshot()(
+            eeg_load_norm in pick(&[0.2, 0.85, 0.9]),
+            duty_fraction in pick(&UNIT),
+            il6_proxy in pick(&UNIT),
+        ) -> OrganicCpuSnapshot {
+            OrganicCpuSnapshot {
+                hrv_ms: 60.0,
+                eeg_load_norm,
+                core_temp_c: 37.0,
+                local_temp_c: 33.0,
+                duty_fraction,
+                il6_proxy,
+            }
+        }
+    }
+
+    prop_compose! {
+        fn budget()(
+            energy_nj_headroom in pick(&[0.0, 50.0, 100.0, 150.0]),
+            protein_headroom_mg in pick(&[0.0, 5.0, 10.0]),
+            duty_fraction_max in pick(&CEILING),
+        ) -> HostBudget {
+            HostBudget { energy_nj_headroom, protein_headroom_mg, duty_fraction_max }
+        }
+    }
+
+    prop_compose! {
+        fn thermo()(delta_t_c_max in pick(&[0.2, 0.5, 1.0]), il6_index_max in pick(&CEILING)) -> ThermodynamicEnvelope {
+            ThermodynamicEnvelope { delta_t_c_max, il6_index_max, thermo_class: "C".to_string() }
+        }
+    }
+
+    prop_compose! {
+        fn job()(
+            energy_cost_nj in pick(&[0.0, 50.0, 100.0, 150.0]),
+            protein_cost_mg in pick(&[0.0, 5.0, 10.0]),
+            sbio_cost in pick(&[0.0, 0.5, 1.0]),
+            duty_cost in pick(&[0.0, 0.1, 0.2, 0.4]),
+            delta_t_c in pick(&[0.0, 0.2, 0.5, 1.0]),
+            aln_compliance in compliance(),
+        ) -> NeuromorphJob {
+            NeuromorphJob {
+                job_id: "j".to_string(),
+                kernel_id: "k".to_string(),
+                energy_cost_nj,
+                protein_cost_mg,
+                sbio_cost,
+                duty_cost,
+                delta_t_c,
+                autonomy_grant_id: "g".to_string(),
+                aln_compliance,
+            }
+        }
+    }
+
+    prop_compose! {
+        fn legacy_snapshot()(
+            core_temp_c in pick(&[36.5, 37.0, 37.5]),
+            inflammation_index in pick(&UNIT),
+            duty_fraction_chat in pick(&UNIT),
+            duty_fraction_neuromorph in pick(&UNIT),
+        ) -> LegacySnapshot {
+            LegacySnapshot {
+                host_id: "h".to_string(),
+                captured_at: SystemTime::UNIX_EPOCH,
+                hrv_ms: 60.0,
+                resting_hr_bpm: 60.0,
+                core_temp_c,
+                skin_temp_c: 33.0,
+                inflammation_index,
+                protein_availability_index: 0.5,
+                perceived_fatigue: 0.3,
+                perceived_cognitive_load: 0.3,
+                duty_fraction_chat,
+                duty_fraction_neuromorph,
+            }
+        }
+    }
+
+    prop_compose! {
+        fn legacy_budget()(
+            energy_joules_remaining in pick(&[0.0, 0.5, 1.0, 2.0]),
+            max_chat_duty_fraction in pick(&CEILING),
+            max_neuromorph_duty_fraction in pick(&CEILING),
+        ) -> LegacyBudget {
+            LegacyBudget {
+                energy_joules_remaining,
+                protein_budget_fraction: 0.5,
+                max_chat_duty_fraction,
+                max_neuromorph_duty_fraction,
+            }
+        }
+    }
+
+    prop_compose! {
+        fn legacy_envelope()(
+            max_delta_core_c in pick(&[0.2, 0.5, 1.0]),
+            max_core_c in pick(&[37.0, 37.5, 38.0]),
+            max_neuromorph_duty in pick(&CEILING),
+            max_chat_duty in pick(&CEILING),
+            max_energy_joules in pick(&[0.5, 1.0, 2.0]),
+            max_inflammation_index in pick(&CEILING),
+        ) -> LegacyEnvelope {
+            LegacyEnvelope {
+                max_delta_core_c,
+                max_core_c,
+                max_neuromorph_duty,
+                max_chat_duty,
+                max_energy_joules,
+                max_inflammation_index,
+            }
+        }
+    }
+
+    prop_compose! {
+        fn cost()(
+            estimated_energy_joules in pick(&[0.0, 0.5, 1.0, 2.0]),
+            estimated_duty_neuromorph in pick(&[0.0, 0.1, 0.2, 0.4]),
+            estimated_delta_core_c in pick(&[0.0, 0.2, 0.5, 1.0]),
+        ) -> NeuromorphJobCost {
+            NeuromorphJobCost { estimated_energy_joules, estimated_duty_neuromorph, estimated_delta_core_c }
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(20_000))]
+
+        #[test]
+        fn schedule_neuromorph_job_is_never_looser_than_baseline(
+            sched in scheduler(),
+            host in snapshot(),
+            budget in budget(),
+            thermo in thermo(),
+            ml in ml(),
+            job in job(),
+            grant in grant(),
+        ) {
+            let baseline = baseline_schedule_neuromorph_job(&sched, &host, &budget, &thermo, &ml, &job, &grant);
+            let legacy = sched.schedule_neuromorph_job(&host, &budget, &thermo, &ml, &job, &grant);
+            let canonical = sched.decide(DecisionInput {
+                host: &(&host).into(),
+                budget: &(&budget).into(),
+                envelope: &(&thermo).into(),
+                ml: &ml,
+                job: &(&job).into(),
+                grant: &grant,
+            });
+            // The baseline stopped at a Lyapunov deferral; decide also reports
+            // the checks after it, and a denial among them wins. Everywhere
+            // else the adapter returns exactly what the baseline did.
+            let lyapunov_plus = canonical.reasons.contains(&DecisionReason::LyapunovIncrease)
+                && canonical.reasons.len() > 1;
+            if lyapunov_plus {
+                prop_assert_eq!(&baseline, &Decision::Deferred);
+                prop_assert_ne!(&legacy, &Decision::Approved);
+            } else {
+                prop_assert_eq!(&legacy, &baseline, "{:?}", canonical.reasons);
+            }
+            if canonical.outcome == Decision::Denied {
+                prop_assert_eq!(&legacy, &Decision::Denied);
+            }
+        }
+
+        #[test]
+        fn decide_organic_job_is_never_looser_than_baseline(
+            snap in legacy_snapshot(),
+            budget in legacy_budget(),
+            envelope in legacy_envelope(),
+            cost in cost(),
+        ) {
+            let baseline = baseline_decide_organic_job(&snap, &budget, &envelope, &cost);
+            let legacy = decide_organic_job(&snap, &budget, &envelope, &cost);
+            match &baseline {
+                // Baseline denials are all deny-class and checked first, so
+                // the code is unchanged.
+                JobDecision::Deny(_) => prop_assert_eq!(&legacy, &baseline),
+                JobDecision::Defer(_) => prop_assert_ne!(&legacy, &JobDecision::Permit),
+                JobDecision::Permit => {}
+            }
+        }
+    }
+
+    #[test]
+    fn reports_every_failed_check_with_legacy_codes() {
+        let sched = OrganicCpuScheduler {
+            u_safe_duty: 0.5,
+            max_energy_nj: 1e12,
+            max_protein_mg: 10.0,
+            max_sbio: 1.0,
+        };
+        let host = HostState {
+            hrv_ms: 60.0,
+            core_temp_c: 37.8,
+            local_temp_c: 33.0,
+            inflammation_index: 0.7,
+            duty_neuromorph: 0.2,
+            duty_chat: Some(0.1),
+            eeg_load_norm: None,
+        };
+        let budget = ResourceBudget {
+            energy_nj_remaining: 5e8,
+            protein_mg_remaining: None,
+            max_neuromorph_duty: 0.6,
+            max_chat_duty: Some(0.5),
+        };
+        let envelope = SafetyEnvelope {
+            max_delta_t_c: 1.0,
+            max_core_temp_c: Some(38.0),
+            max_inflammation_index: 0.5,
+            max_job_energy_nj: None,
+            max_neuromorph_duty: None,
+            max_chat_duty: None,
+            thermo_class: None,
+        };
+        let cost = NeuromorphJobCost {
+            estimated_energy_joules: 1.0,
+            estimated_duty_neuromorph: 0.1,
+            estimated_delta_core_c: 0.5,
+        };
+        let job = JobRequest::from_cost(
+            "j",
+            &cost,
+            "g",
+            ALNComplianceParticle {
+                neurorights_mental_privacy: true,
+                neurorights_reversibility: true,
+                neurorights_no_finance: true,
+                neurorights_no_expropriation: true,
+            },
+        );
+        let grant = AutonomyGrant {
+            id: "g".to_string(),
+            level: AutonomyLevel::LabOnly,
+            required_blood: 1e9,
+            min_chat_factor: 0.0,
+            jurisdiction: "lab".to_string(),
+            permitted_backends: vec!["OrganicCPU".to_string()],
+        };
+        let ml = MlPassSchedule {
+            passes_per_day: 64,
+            max_concurrent_jobs: 4,
+        };
+
+        let d = sched.decide(DecisionInput {
+            host: &host,
+            budget: &budget,
+            envelope: &envelope,
+            ml: &ml,
+            job: &job,
+            grant: &grant,
+        });
+        assert_eq!(
+            d.reasons,
+            vec![
+                DecisionReason::CoreTempCeiling,
+                DecisionReason::EnergyBudgetExhausted,
+                DecisionReason::InflammationHigh,
+            ]
+        );
+        assert_eq!(d.outcome, Decision::Denied);
+        assert_eq!(
+            JobDecision::from(&d),
+            JobDecision::Deny("core_temp_ceiling".to_string())
+        );
+    }
+
+    #[test]
+    fn legacy_adapters_report_the_outcome() {
+        let sched = OrganicCpuScheduler {
+            u_safe_duty: 0.5,
+            max_energy_nj: 1_000.0,
+            max_protein_mg: 10.0,
+            max_sbio: 1.0,
+        };
+        let host = OrganicCpuSnapshot {
+            hrv_ms: 60.0,
+            eeg_load_norm: 0.2,
+            core_temp_c: 37.0,
+            local_temp_c: 33.0,
+            duty_fraction: 0.5,
+            il6_proxy: 0.1,
+        };
+        let budget = HostBudget {
+            energy_nj_headroom: 10.0,
+            protein_headroom_mg: 10.0,
+            duty_fraction_max: 1.0,
+        };
+        let thermo = ThermodynamicEnvelope {
+            delta_t_c_max: 1.0,
+            il6_index_max: 0.5,
+            thermo_class: "C".to_string(),
+        };
+        let job = NeuromorphJob {
+            job_id: "j".to_string(),
+            kernel_id: "k".to_string(),
+            energy_cost_nj: 50.0,
+            protein_cost_mg: 0.0,
+            sbio_cost: 0.0,
+            duty_cost: 0.0,
+            delta_t_c: 0.5,
+            autonomy_grant_id: "g".to_string(),
+            aln_compliance: ALNComplianceParticle {
+                neurorights_mental_privacy: true,
+                neurorights_reversibility: true,
+                neurorights_no_finance: true,
+                neurorights_no_expropriation: true,
+            },
+        };
+        let grant = AutonomyGrant {
+            id: "g".to_string(),
+            level: AutonomyLevel::LabOnly,
+            required_blood: 1.0,
+            min_chat_factor: 0.0,
+            jurisdiction: "lab".to_string(),
+            permitted_backends: vec!["OrganicCPU".to_string()],
+        };
+        let ml = MlPassSchedule {
+            passes_per_day: 64,
+            max_concurrent_jobs: 4,
+        };
+
+        // Exhausted headroom: decide defers, the legacy adapter still denies.
+        let canonical = sched.decide(DecisionInput {
+            host: &(&host).into(),
+            budget: &(&budget).into(),
+            envelope: &(&thermo).into(),
+            ml: &ml,
+            job: &(&job).into(),
+            grant: &grant,
+        });
+        assert_eq!(canonical.outcome, Decision::Deferred);
+        assert_eq!(canonical.reasons, vec![DecisionReason::EnergyBudgetExhausted]);
+        let d = sched.schedule_neuromorph_job(&host, &budget, &thermo, &ml, &job, &grant);
+        assert_eq!(d, Decision::Denied);
+        // A thermal hard stop ahead of it still denies.
+        let hot = NeuromorphJob {
+            delta_t_c: 2.0,
+            ..job.clone()
+        };
+        let d = sched.schedule_neuromorph_job(&host, &budget, &thermo, &ml, &hot, &grant);
+        assert_eq!(d, Decision::Denied);
+        // So does any deny-class failure after it, not just the first.
+        let revoked = NeuromorphJob {
+            aln_compliance: ALNComplianceParticle {
+                neurorights_mental_privacy: false,
+                ..job.aln_compliance.clone()
+            },
+            ..job.clone()
+        };
+        let gpu_only = AutonomyGrant {
+            permitted_backends: vec!["Gpu".to_string()],
+            ..grant.clone()
+        };
+        let d = sched.schedule_neuromorph_job(&host, &budget, &thermo, &ml, &revoked, &gpu_only);
+        assert_eq!(d, Decision::Denied);
+
+        let snap = LegacySnapshot {
+            host_id: "h".to_string(),
+            captured_at: SystemTime::UNIX_EPOCH,
+            hrv_ms: 60.0,
+            resting_hr_bpm: 60.0,
+            core_temp_c: 37.8,
+            skin_temp_c: 33.0,
+            inflammation_index: 0.1,
+            protein_availability_index: 0.5,
+            perceived_fatigue: 0.3,
+            perceived_cognitive_load: 0.3,
+            duty_fraction_chat: 0.1,
+            duty_fraction_neuromorph: 0.2,
+        };
+        let budget = LegacyBudget {
+            energy_joules_remaining: 0.5,
+            protein_budget_fraction: 0.5,
+            max_chat_duty_fraction: 0.5,
+            max_neuromorph_duty_fraction: 0.6,
+        };
+        let envelope = LegacyEnvelope {
+            max_delta_core_c: 1.0,
+            max_core_c: 38.0,
+            max_neuromorph_duty: 0.6,
+            max_chat_duty: 0.5,
+            max_energy_joules: 2.0,
+            max_inflammation_index: 0.5,
+        };
+        let cost = NeuromorphJobCost {
+            estimated_energy_joules: 1.0,
+            estimated_duty_neuromorph: 0.1,
+            estimated_delta_core_c: 0.5,
+        };
+        assert_eq!(
+            decide_organic_job(&snap, &budget, &envelope, &cost),
+            JobDecision::Deny("core_temp_ceiling".to_string())
+        );
+        let cool = LegacySnapshot {
+            core_temp_c: 37.0,
+            ..snap
+        };
+        assert_eq!(
+            decide_organic_job(&cool, &budget, &envelope, &cost),
+            JobDecision::Defer("insufficient_energy_budget".to_string())
+        );
+        let tight = LegacyEnvelope {
+            max_energy_joules: 0.5,
+            ..envelope
+        };
+        assert_eq!(
+            decide_organic_job(&cool, &budget, &tight, &cost),
+            JobDecision::Deny("thermo_envelope_energy_violation".to_string())
+        );
+    }
+}
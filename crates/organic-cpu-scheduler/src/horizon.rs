@@ -0,0 +1,847 @@
+//! Multi-job planning over a horizon of ML passes.
+//!
+//! The horizon is split into `MlPassSchedule::passes_per_day` equal slots and
+//! each slot runs at most `max_concurrent_jobs` jobs. Slots are filled in time
+//! order; within a slot the pending queue is tried by priority, deadline and
+//! job id, so the same inputs always yield the same schedule.
+//!
+//! Every admission is made by `OrganicCpuScheduler::decide`, evaluated at the
+//! projected state for that slot:
+//! * duty: host baseline plus the duty of jobs already in the slot;
+//! * energy / protein: the `HostBudget` headroom is the budget for the whole
+//!   horizon, less everything admitted so far;
+//! * ΔT: heat from the previous slot decays by `thermal_decay_per_slot`, and
+//!   jobs in the current slot add on top. The envelope's `delta_t_c_max` is
+//!   reduced by what has already accumulated.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::decision::{
+    backend_permitted, within_energy_scope, DecisionInput, DecisionReason, SchedulingDecision,
+    MAX_CONCURRENT_JOBS, MAX_PASSES_PER_DAY,
+};
+use crate::{
+    AutonomyGrant, Decision, HostBudget, MlPassSchedule, NeuromorphJob, OrganicCpuScheduler,
+    OrganicCpuSnapshot, ThermodynamicEnvelope,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct QueuedJob {
+    pub job: NeuromorphJob,
+    /// Higher runs first.
+    pub priority: u32,
+    /// Earliest start, seconds from horizon start.
+    #[serde(default)]
+    pub release_s: u64,
+    /// Latest finish, seconds from horizon start.
+    #[serde(default)]
+    pub deadline_s: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HorizonConfig {
+    pub horizon_s: u64,
+    /// Fraction of a slot's accumulated ΔT still present at the next slot.
+    pub thermal_decay_per_slot: f32,
+}
+
+impl Default for HorizonConfig {
+    fn default() -> Self {
+        Self {
+            horizon_s: 86_400,
+            thermal_decay_per_slot: 0.5,
+        }
+    }
+}
+
+/// Everything the one-shot decision needs besides the job itself.
+#[derive(Clone, Copy, Debug)]
+pub struct PlanningContext<'a> {
+    pub host: &'a OrganicCpuSnapshot,
+    pub budget: &'a HostBudget,
+    pub thermo: &'a ThermodynamicEnvelope,
+    pub ml: &'a MlPassSchedule,
+    pub grants: &'a [AutonomyGrant],
+}
+
+/// Host, budget and envelope as projected for one admission.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProjectedState {
+    pub duty_fraction: f32,
+    pub energy_nj_headroom: f32,
+    pub protein_headroom_mg: f32,
+    pub delta_t_c: f32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SlotAssignment {
+    pub job_id: String,
+    pub slot: u32,
+    pub start_s: u64,
+    pub end_s: u64,
+    /// State the job was admitted against (before its own cost).
+    pub projected: ProjectedState,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum DeferralReason {
+    /// `MlPassSchedule` outside the Class-C ceilings; nothing can be planned.
+    MlScheduleOutOfClass,
+    /// Another queue entry has the same job id; none of them are planned.
+    DuplicateJobId,
+    UnknownAutonomyGrant(String),
+    /// Neurorights / ALN compliance flags not all set.
+    NeurorightsViolation,
+    /// Grant does not cover this job (backend or energy scope).
+    AutonomyGrantScope,
+    /// Release or deadline leaves no whole slot inside the horizon.
+    NoSlotInWindow,
+    EnergyBudgetExhausted,
+    ProteinBudgetExhausted,
+    /// Failed a check no later slot can clear (e.g. an envelope limit);
+    /// the job was not tried again after `slot`.
+    Rejected {
+        slot: u32,
+        decision: Decision,
+        reasons: Vec<DecisionReason>,
+    },
+    /// Deadline passed; `last_decision` and `last_reasons` are from the
+    /// last one-shot verdict seen.
+    DeadlineMissed {
+        last_decision: Option<Decision>,
+        last_reasons: Vec<DecisionReason>,
+    },
+    /// Horizon ended with the job still pending.
+    HorizonExhausted {
+        last_decision: Option<Decision>,
+        last_reasons: Vec<DecisionReason>,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct JobDeferral {
+    pub job_id: String,
+    pub reason: DeferralReason,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HorizonSchedule {
+    pub slot_s: u64,
+    pub slots: u32,
+    /// In admission order (slot, then priority order).
+    pub assignments: Vec<SlotAssignment>,
+    /// In priority order: priority, deadline, then job id, the order jobs
+    /// are tried in.
+    pub deferrals: Vec<JobDeferral>,
+}
+
+/// A schedule entry the one-shot decision no longer approves when replayed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScheduleMismatch {
+    /// Empty when the schedule's slot layout itself does not match.
+    pub job_id: String,
+    pub slot: u32,
+    pub decision: Option<Decision>,
+    pub detail: String,
+}
+
+/// Running projection while slots are filled (or replayed).
+struct Projection<'a> {
+    ctx: PlanningContext<'a>,
+    decay: f32,
+    slot: u32,
+    energy_used: f32,
+    protein_used: f32,
+    slot_duty: f32,
+    slot_delta_t: f32,
+    slot_jobs: u32,
+}
+
+impl<'a> Projection<'a> {
+    fn new(ctx: PlanningContext<'a>, decay: f32) -> Self {
+        Self {
+            ctx,
+            decay,
+            slot: 0,
+            energy_used: 0.0,
+            protein_used: 0.0,
+            slot_duty: 0.0,
+            slot_delta_t: 0.0,
+            slot_jobs: 0,
+        }
+    }
+
+    fn advance_to(&mut self, slot: u32) {
+        while self.slot < slot {
+            self.slot += 1;
+            self.slot_delta_t *= self.decay;
+            self.slot_duty = 0.0;
+            self.slot_jobs = 0;
+        }
+    }
+
+    fn state(&self) -> ProjectedState {
+        ProjectedState {
+            duty_fraction: self.ctx.host.duty_fraction + self.slot_duty,
+            energy_nj_headroom: self.ctx.budget.energy_nj_headroom - self.energy_used,
+            protein_headroom_mg: self.ctx.budget.protein_headroom_mg - self.protein_used,
+            delta_t_c: self.slot_delta_t,
+        }
+    }
+
+    /// One-shot verdict for `job` against the projected state.
+    fn decide(
+        &self,
+        scheduler: &OrganicCpuScheduler,
+        job: &NeuromorphJob,
+        grant: &AutonomyGrant,
+    ) -> SchedulingDecision {
+        let (host, budget, thermo) = self.inputs();
+        scheduler.decide(DecisionInput {
+            host: &(&host).into(),
+            budget: &(&budget).into(),
+            envelope: &(&thermo).into(),
+            ml: self.ctx.ml,
+            job: &job.into(),
+            grant,
+        })
+    }
+
+    /// Whether a later slot could clear `reason`. Only slot duty (reset each
+    /// slot) and accumulated heat (decaying) ever ease; budgets only shrink
+    /// and every other input is fixed for the horizon.
+    fn recoverable(&self, reason: DecisionReason) -> bool {
+        match reason {
+            DecisionReason::LyapunovIncrease | DecisionReason::NeuromorphDutyCeiling => {
+                self.slot_duty > 0.0
+            }
+            DecisionReason::DeltaTCeiling | DecisionReason::CoreTempCeiling => {
+                self.slot_delta_t > 0.0 && self.decay < 1.0
+            }
+            _ => false,
+        }
+    }
+
+    fn inputs(&self) -> (OrganicCpuSnapshot, HostBudget, ThermodynamicEnvelope) {
+        let s = self.state();
+        let host = OrganicCpuSnapshot {
+            duty_fraction: s.duty_fraction,
+            core_temp_c: self.ctx.host.core_temp_c + s.delta_t_c,
+            local_temp_c: self.ctx.host.local_temp_c + s.delta_t_c,
+            ..self.ctx.host.clone()
+        };
+        let budget = HostBudget {
+            energy_nj_headroom: s.energy_nj_headroom,
+            protein_headroom_mg: s.protein_headroom_mg,
+            ..self.ctx.budget.clone()
+        };
+        let thermo = ThermodynamicEnvelope {
+            delta_t_c_max: self.ctx.thermo.delta_t_c_max - s.delta_t_c,
+            ..self.ctx.thermo.clone()
+        };
+        (host, budget, thermo)
+    }
+
+    fn admit(&mut self, job: &NeuromorphJob) {
+        self.energy_used += job.energy_cost_nj;
+        self.protein_used += job.protein_cost_mg;
+        self.slot_duty += job.duty_cost;
+        self.slot_delta_t += job.delta_t_c;
+        self.slot_jobs += 1;
+    }
+}
+
+struct Pending<'a> {
+    /// Position in priority order.
+    rank: usize,
+    queued: &'a QueuedJob,
+    grant: &'a AutonomyGrant,
+    /// Inclusive slot range.
+    window: (u32, u32),
+    /// Last one-shot verdict seen for this job.
+    last: Option<SchedulingDecision>,
+}
+
+/// Split the last verdict into the fields `DeadlineMissed` and
+/// `HorizonExhausted` carry.
+fn last_verdict(last: Option<SchedulingDecision>) -> (Option<Decision>, Vec<DecisionReason>) {
+    match last {
+        Some(d) => (Some(d.outcome), d.reasons),
+        None => (None, Vec::new()),
+    }
+}
+
+/// Deferral for a verdict no later slot can change. A job held back only by
+/// the horizon budget keeps the budget reason.
+fn rejection(proj: &Projection<'_>, decision: SchedulingDecision) -> DeferralReason {
+    let has = |r| decision.reasons.contains(&r);
+    let budget_only = decision.reasons.iter().all(|&r| {
+        proj.recoverable(r)
+            || r == DecisionReason::EnergyBudgetExhausted
+            || r == DecisionReason::ProteinBudgetExhausted
+    });
+    if budget_only && has(DecisionReason::EnergyBudgetExhausted) {
+        DeferralReason::EnergyBudgetExhausted
+    } else if budget_only && has(DecisionReason::ProteinBudgetExhausted) {
+        DeferralReason::ProteinBudgetExhausted
+    } else {
+        DeferralReason::Rejected {
+            slot: proj.slot,
+            decision: decision.outcome,
+            reasons: decision.reasons,
+        }
+    }
+}
+
+fn slot_len(cfg: &HorizonConfig, ml: &MlPassSchedule) -> u64 {
+    cfg.horizon_s / u64::from(ml.passes_per_day.max(1))
+}
+
+/// Slots `[first, last]` the job may occupy, or `None` if none fit.
+fn slot_window(q: &QueuedJob, slot_s: u64, slots: u32) -> Option<(u32, u32)> {
+    if slot_s == 0 || slots == 0 {
+        return None;
+    }
+    let first = q.release_s.div_ceil(slot_s);
+    let last = match q.deadline_s {
+        Some(d) => (d / slot_s).checked_sub(1)?,
+        None => u64::from(slots) - 1,
+    }
+    .min(u64::from(slots) - 1);
+    (first <= last).then_some((first as u32, last as u32))
+}
+
+impl OrganicCpuScheduler {
+    /// Plan `queue` over the horizon. Jobs are never approved against a state
+    /// the one-shot decision would reject.
+    pub fn plan_horizon(
+        &self,
+        ctx: PlanningContext<'_>,
+        queue: &[QueuedJob],
+        cfg: &HorizonConfig,
+    ) -> HorizonSchedule {
+        let ml = ctx.ml;
+        let slot_s = slot_len(cfg, ml);
+        let slots = if slot_s == 0 { 0 } else { ml.passes_per_day };
+        let mut order: Vec<&QueuedJob> = queue.iter().collect();
+        order.sort_by(|a, b| {
+            b.priority
+                .cmp(&a.priority)
+                .then(
+                    a.deadline_s
+                        .unwrap_or(u64::MAX)
+                        .cmp(&b.deadline_s.unwrap_or(u64::MAX)),
+                )
+                .then_with(|| a.job.job_id.cmp(&b.job.job_id))
+        });
+
+        let mut schedule = HorizonSchedule {
+            slot_s,
+            slots,
+            assignments: Vec::new(),
+            deferrals: Vec::new(),
+        };
+        let ml_out_of_class =
+            ml.max_concurrent_jobs > MAX_CONCURRENT_JOBS || ml.passes_per_day > MAX_PASSES_PER_DAY;
+
+        let mut id_counts: HashMap<&str, usize> = HashMap::new();
+        for q in queue {
+            *id_counts.entry(q.job.job_id.as_str()).or_default() += 1;
+        }
+
+        // Deferrals are keyed by rank, not job id, so each lands on its entry.
+        let mut pending: Vec<Pending<'_>> = Vec::new();
+        let mut deferred: Vec<(usize, DeferralReason)> = Vec::new();
+        for (rank, q) in order.iter().enumerate() {
+            let admissible = if ml_out_of_class {
+                Err(DeferralReason::MlScheduleOutOfClass)
+            } else if id_counts[q.job.job_id.as_str()] > 1 {
+                Err(DeferralReason::DuplicateJobId)
+            } else if !q.job.aln_compliance.neurorights_intact() {
+                Err(DeferralReason::NeurorightsViolation)
+            } else {
+                match ctx.grants.iter().find(|g| g.id == q.job.autonomy_grant_id) {
+                    None => Err(DeferralReason::UnknownAutonomyGrant(
+                        q.job.autonomy_grant_id.clone(),
+                    )),
+                    Some(g)
+                        if !backend_permitted(g) || !within_energy_scope(g, &(&q.job).into()) =>
+                    {
+                        Err(DeferralReason::AutonomyGrantScope)
+                    }
+                    Some(g) => slot_window(q, slot_s, slots)
+                        .map(|window| (g, window))
+                        .ok_or(DeferralReason::NoSlotInWindow),
+                }
+            };
+            match admissible {
+                Ok((grant, window)) => pending.push(Pending {
+                    rank,
+                    queued: q,
+                    grant,
+                    window,
+                    last: None,
+                }),
+                Err(reason) => deferred.push((rank, reason)),
+            }
+        }
+
+        let mut proj = Projection::new(ctx, cfg.thermal_decay_per_slot);
+        for slot in 0..slots {
+            proj.advance_to(slot);
+            let (expired, live): (Vec<_>, Vec<_>) =
+                pending.into_iter().partition(|p| p.window.1 < slot);
+            pending = live;
+            for p in expired {
+                let (last_decision, last_reasons) = last_verdict(p.last);
+                deferred.push((
+                    p.rank,
+                    DeferralReason::DeadlineMissed {
+                        last_decision,
+                        last_reasons,
+                    },
+                ));
+            }
+
+            let mut i = 0;
+            while i < pending.len() && proj.slot_jobs < ml.max_concurrent_jobs {
+                let p = &mut pending[i];
+                if p.window.0 > slot {
+                    i += 1;
+                    continue;
+                }
+                let decision = proj.decide(self, &p.queued.job, p.grant);
+                if decision.is_approved() {
+                    schedule.assignments.push(SlotAssignment {
+                        job_id: p.queued.job.job_id.clone(),
+                        slot,
+                        start_s: u64::from(slot) * slot_s,
+                        end_s: u64::from(slot + 1) * slot_s,
+                        projected: proj.state(),
+                    });
+                    proj.admit(&p.queued.job);
+                    pending.remove(i);
+                } else if decision.reasons.iter().all(|&r| proj.recoverable(r)) {
+                    p.last = Some(decision);
+                    i += 1;
+                } else {
+                    // Retrying in a later slot cannot change this verdict.
+                    let rank = pending.remove(i).rank;
+                    deferred.push((rank, rejection(&proj, decision)));
+                }
+            }
+        }
+
+        let remaining = proj.state();
+        for p in pending {
+            let job = &p.queued.job;
+            let reason = if job.energy_cost_nj > remaining.energy_nj_headroom {
+                DeferralReason::EnergyBudgetExhausted
+            } else if job.protein_cost_mg > remaining.protein_headroom_mg {
+                DeferralReason::ProteinBudgetExhausted
+            } else {
+                let (last_decision, last_reasons) = last_verdict(p.last);
+                DeferralReason::HorizonExhausted {
+                    last_decision,
+                    last_reasons,
+                }
+            };
+            deferred.push((p.rank, reason));
+        }
+
+        // Report deferrals in the order jobs were tried.
+        deferred.sort_by_key(|(rank, _)| *rank);
+        schedule.deferrals = deferred
+            .into_iter()
+            .map(|(rank, reason)| JobDeferral {
+                job_id: order[rank].job.job_id.clone(),
+                reason,
+            })
+            .collect();
+        schedule
+    }
+
+    /// Replay `schedule` through `decide`, rebuilding the
+    /// projected state from the assignments alone. Any entry the one-shot
+    /// decision does not approve, any slot over `max_concurrent_jobs`, any
+    /// job scheduled outside its window, any job scheduled twice, or any job
+    /// whose id is not unique in `queue` is reported. Slot length and count
+    /// are recomputed from `cfg` and `ctx.ml`, never taken from `schedule`.
+    pub fn verify_horizon(
+        &self,
+        ctx: PlanningContext<'_>,
+        queue: &[QueuedJob],
+        cfg: &HorizonConfig,
+        schedule: &HorizonSchedule,
+    ) -> Result<(), ScheduleMismatch> {
+        let ml = ctx.ml;
+        let slot_s = slot_len(cfg, ml);
+        let slots = if slot_s == 0 { 0 } else { ml.passes_per_day };
+        if schedule.slot_s != slot_s || schedule.slots != slots {
+            return Err(ScheduleMismatch {
+                job_id: String::new(),
+                slot: 0,
+                decision: None,
+                detail: format!(
+                    "slot layout {}x{}s does not match {}x{}s from config",
+                    schedule.slots, schedule.slot_s, slots, slot_s
+                ),
+            });
+        }
+        let mut proj = Projection::new(ctx, cfg.thermal_decay_per_slot);
+        let mut seen = std::collections::HashSet::new();
+        for a in &schedule.assignments {
+            let mismatch = |decision: Option<Decision>, detail: &str| ScheduleMismatch {
+                job_id: a.job_id.clone(),
+                slot: a.slot,
+                decision,
+                detail: detail.to_string(),
+            };
+            let mut matching = queue.iter().filter(|q| q.job.job_id == a.job_id);
+            let q = matching
+                .next()
+                .ok_or_else(|| mismatch(None, "job not in queue"))?;
+            if matching.next().is_some() {
+                return Err(mismatch(None, "job id not unique in queue"));
+            }
+            if !seen.insert(a.job_id.as_str()) {
+                return Err(mismatch(None, "job assigned more than once"));
+            }
+            if a.slot < proj.slot {
+                return Err(mismatch(None, "assignments out of slot order"));
+            }
+            match slot_window(q, slot_s, slots) {
+                Some((first, last)) if (first..=last).contains(&a.slot) => {}
+                _ => return Err(mismatch(None, "outside release/deadline window")),
+            }
+            if a.start_s != u64::from(a.slot) * slot_s || a.end_s != u64::from(a.slot + 1) * slot_s
+            {
+                return Err(mismatch(None, "start_s/end_s do not match slot"));
+            }
+            let grant = ctx
+                .grants
+                .iter()
+                .find(|g| g.id == q.job.autonomy_grant_id)
+                .ok_or_else(|| mismatch(None, "unknown autonomy grant"))?;
+
+            proj.advance_to(a.slot);
+            if proj.slot_jobs >= ml.max_concurrent_jobs {
+                return Err(mismatch(None, "slot over max_concurrent_jobs"));
+            }
+            let decision = proj.decide(self, &q.job, grant).outcome;
+            if decision != Decision::Approved {
+                return Err(mismatch(
+                    Some(decision),
+                    "one-shot decision rejects projected state",
+                ));
+            }
+            proj.admit(&q.job);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ALNComplianceParticle, AutonomyLevel};
+
+    fn job(id: &str, grant: &str, compliant: bool) -> NeuromorphJob {
+        NeuromorphJob {
+            job_id: id.to_string(),
+            kernel_id: "k".to_string(),
+            energy_cost_nj: 100.0,
+            protein_cost_mg: 5.0,
+            sbio_cost: 0.1,
+            duty_cost: 0.15,
+            delta_t_c: 0.4,
+            autonomy_grant_id: grant.to_string(),
+            aln_compliance: ALNComplianceParticle {
+                neurorights_mental_privacy: true,
+                neurorights_reversibility: compliant,
+                neurorights_no_finance: true,
+                neurorights_no_expropriation: true,
+            },
+        }
+    }
+
+    fn queued(id: &str, priority: u32) -> QueuedJob {
+        QueuedJob {
+            job: job(id, "g", true),
+            priority,
+            release_s: 0,
+            deadline_s: None,
+        }
+    }
+
+    struct Fixture {
+        host: OrganicCpuSnapshot,
+        budget: HostBudget,
+        thermo: ThermodynamicEnvelope,
+        ml: MlPassSchedule,
+        grants: Vec<AutonomyGrant>,
+        scheduler: OrganicCpuScheduler,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            Self {
+                host: OrganicCpuSnapshot {
+                    hrv_ms: 60.0,
+                    eeg_load_norm: 0.2,
+                    core_temp_c: 37.0,
+                    local_temp_c: 33.0,
+                    duty_fraction: 0.1,
+                    il6_proxy: 0.1,
+                },
+                budget: HostBudget {
+                    energy_nj_headroom: 450.0,
+                    protein_headroom_mg: 50.0,
+                    duty_fraction_max: 0.6,
+                },
+                thermo: ThermodynamicEnvelope {
+                    delta_t_c_max: 1.0,
+                    il6_index_max: 0.5,
+                    thermo_class: "C".to_string(),
+                },
+                ml: MlPassSchedule {
+                    passes_per_day: 4,
+                    max_concurrent_jobs: 2,
+                },
+                grants: vec![AutonomyGrant {
+                    id: "g".to_string(),
+                    level: AutonomyLevel::LabOnly,
+                    required_blood: 1.0,
+                    min_chat_factor: 0.0,
+                    jurisdiction: "lab".to_string(),
+                    permitted_backends: vec!["OrganicCPU".to_string()],
+                }],
+                scheduler: OrganicCpuScheduler {
+                    u_safe_duty: 0.5,
+                    max_energy_nj: 200.0,
+                    max_protein_mg: 20.0,
+                    max_sbio: 1.0,
+                },
+            }
+        }
+
+        fn ctx(&self) -> PlanningContext<'_> {
+            PlanningContext {
+                host: &self.host,
+                budget: &self.budget,
+                thermo: &self.thermo,
+                ml: &self.ml,
+                grants: &self.grants,
+            }
+        }
+    }
+
+    fn queue() -> Vec<QueuedJob> {
+        vec![
+            queued("e", 1),
+            queued("d", 5),
+            queued("c", 5),
+            queued("a", 9),
+            queued("b", 8),
+            QueuedJob {
+                job: job("f", "g", false),
+                ..queued("f", 9)
+            },
+            QueuedJob {
+                deadline_s: Some(10),
+                ..queued("g", 9)
+            },
+            QueuedJob {
+                job: job("h", "missing", true),
+                ..queued("h", 9)
+            },
+        ]
+    }
+
+    #[test]
+    fn plans_by_priority_under_projected_heat_and_budget() {
+        let fx = Fixture::new();
+        let cfg = HorizonConfig::default();
+        let schedule = fx.scheduler.plan_horizon(fx.ctx(), &queue(), &cfg);
+        assert_eq!(
+            schedule,
+            fx.scheduler.plan_horizon(fx.ctx(), &queue(), &cfg)
+        );
+
+        let placed: Vec<(&str, u32)> = schedule
+            .assignments
+            .iter()
+            .map(|a| (a.job_id.as_str(), a.slot))
+            .collect();
+        // d does not fit slot 1: c's heat plus the decayed carry leaves 0.2 °C.
+        assert_eq!(placed, vec![("a", 0), ("b", 0), ("c", 1), ("d", 2)]);
+
+        let reasons: Vec<(&str, &DeferralReason)> = schedule
+            .deferrals
+            .iter()
+            .map(|d| (d.job_id.as_str(), &d.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                ("g", &DeferralReason::NoSlotInWindow),
+                ("f", &DeferralReason::NeurorightsViolation),
+                (
+                    "h",
+                    &DeferralReason::UnknownAutonomyGrant("missing".to_string())
+                ),
+                ("e", &DeferralReason::EnergyBudgetExhausted),
+            ]
+        );
+        assert_eq!(
+            fx.scheduler
+                .verify_horizon(fx.ctx(), &queue(), &cfg, &schedule),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn verify_rejects_slots_the_one_shot_decision_denies() {
+        let fx = Fixture::new();
+        let cfg = HorizonConfig::default();
+        let mut schedule = fx.scheduler.plan_horizon(fx.ctx(), &queue(), &cfg);
+        let moved = &mut schedule.assignments[3];
+        moved.slot = 1;
+        moved.start_s = schedule.slot_s;
+        moved.end_s = 2 * schedule.slot_s;
+
+        let err = fx
+            .scheduler
+            .verify_horizon(fx.ctx(), &queue(), &cfg, &schedule)
+            .unwrap_err();
+        assert_eq!(err.job_id, "d");
+        assert_eq!(err.decision, Some(Decision::Denied));
+
+        let mut twice = fx.scheduler.plan_horizon(fx.ctx(), &queue(), &cfg);
+        twice.assignments[3].job_id = "c".to_string();
+        let err = fx
+            .scheduler
+            .verify_horizon(fx.ctx(), &queue(), &cfg, &twice)
+            .unwrap_err();
+        assert_eq!((err.job_id.as_str(), err.slot), ("c", 2));
+        assert_eq!(err.detail, "job assigned more than once");
+
+        let out_of_class = MlPassSchedule {
+            passes_per_day: 256,
+            ..fx.ml.clone()
+        };
+        let ctx = PlanningContext {
+            ml: &out_of_class,
+            ..fx.ctx()
+        };
+        let none = fx.scheduler.plan_horizon(ctx, &queue(), &cfg);
+        assert!(none.assignments.is_empty());
+        assert!(none
+            .deferrals
+            .iter()
+            .all(|d| d.reason == DeferralReason::MlScheduleOutOfClass));
+    }
+
+    #[test]
+    fn duplicate_job_ids_are_deferred_not_planned() {
+        let fx = Fixture::new();
+        let cfg = HorizonConfig::default();
+        let queue = vec![queued("a", 9), queued("x", 8), queued("a", 1), queued("b", 5)];
+        let schedule = fx.scheduler.plan_horizon(fx.ctx(), &queue, &cfg);
+
+        let placed: Vec<&str> = schedule.assignments.iter().map(|a| a.job_id.as_str()).collect();
+        assert_eq!(placed, vec!["x", "b"]);
+        assert_eq!(
+            schedule.deferrals,
+            vec![
+                JobDeferral { job_id: "a".to_string(), reason: DeferralReason::DuplicateJobId },
+                JobDeferral { job_id: "a".to_string(), reason: DeferralReason::DuplicateJobId },
+            ]
+        );
+        assert_eq!(fx.scheduler.verify_horizon(fx.ctx(), &queue, &cfg, &schedule), Ok(()));
+
+        let mut forged = schedule.clone();
+        forged.assignments[1].job_id = "a".to_string();
+        let err = fx
+            .scheduler
+            .verify_horizon(fx.ctx(), &queue, &cfg, &forged)
+            .unwrap_err();
+        assert_eq!(err.detail, "job id not unique in queue");
+    }
+
+    #[test]
+    fn verify_recomputes_the_slot_layout_from_config() {
+        let fx = Fixture::new();
+        let cfg = HorizonConfig::default();
+        let schedule = fx.scheduler.plan_horizon(fx.ctx(), &queue(), &cfg);
+
+        // Stretching the slots would move "g" inside its 10 s deadline.
+        let mut stretched = schedule.clone();
+        stretched.slot_s = 5;
+        stretched.slots = 17_280;
+        let err = fx
+            .scheduler
+            .verify_horizon(fx.ctx(), &queue(), &cfg, &stretched)
+            .unwrap_err();
+        assert_eq!((err.job_id.as_str(), err.decision), ("", None));
+
+        let mut shifted = schedule.clone();
+        shifted.assignments[0].end_s += 1;
+        let err = fx
+            .scheduler
+            .verify_horizon(fx.ctx(), &queue(), &cfg, &shifted)
+            .unwrap_err();
+        assert_eq!(err.job_id, schedule.assignments[0].job_id);
+        assert_eq!(err.detail, "start_s/end_s do not match slot");
+    }
+
+    #[test]
+    fn deferrals_carry_the_last_reasons_and_hard_failures_are_not_retried() {
+        let fx = Fixture::new();
+        let cfg = HorizonConfig::default();
+        let slot_s = slot_len(&cfg, &fx.ml);
+        let mut hot = job("hot", "g", true);
+        hot.delta_t_c = 0.9;
+        let mut heavy = job("heavy", "g", true);
+        heavy.sbio_cost = 2.0;
+        let queue = vec![
+            queued("a", 10),
+            // Blocked by a's heat in slot 0 and its decayed carry in slot 1.
+            QueuedJob {
+                job: hot,
+                deadline_s: Some(2 * slot_s),
+                ..queued("hot", 9)
+            },
+            QueuedJob {
+                job: heavy,
+                ..queued("heavy", 8)
+            },
+        ];
+        let schedule = fx.scheduler.plan_horizon(fx.ctx(), &queue, &cfg);
+
+        let placed: Vec<&str> = schedule.assignments.iter().map(|a| a.job_id.as_str()).collect();
+        assert_eq!(placed, vec!["a"]);
+        assert_eq!(
+            schedule.deferrals,
+            vec![
+                JobDeferral {
+                    job_id: "hot".to_string(),
+                    reason: DeferralReason::DeadlineMissed {
+                        last_decision: Some(Decision::Denied),
+                        last_reasons: vec![DecisionReason::DeltaTCeiling],
+                    },
+                },
+                JobDeferral {
+                    job_id: "heavy".to_string(),
+                    reason: DeferralReason::Rejected {
+                        slot: 0,
+                        decision: Decision::Denied,
+                        reasons: vec![DecisionReason::SbioEnvelope],
+                    },
+                },
+            ]
+        );
+    }
+}
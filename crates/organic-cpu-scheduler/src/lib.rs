@@ -1,6 +1,18 @@
 use serde::{Deserialize, Serialize};
 
+pub mod budget;
+pub mod decision;
+pub mod horizon;
+pub mod model;
+pub mod safety;
+pub mod snapshot;
+
+pub use decision::{DecisionInput, DecisionReason, SchedulingDecision};
+pub use horizon::{HorizonConfig, HorizonSchedule, PlanningContext, QueuedJob};
+pub use model::{HostState, JobRequest, ResourceBudget, SafetyEnvelope};
+
 /// Snapshot of an organic host, parallel to BciHostSnapshot / ChatHostSnapshot.
+/// Converts into the canonical [`HostState`].
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct OrganicCpuSnapshot {
     pub hrv_ms: f32,
@@ -33,7 +45,7 @@ pub struct MlPassSchedule {
 }
 
 /// ALNComplianceParticle with neurorights flags.
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ALNComplianceParticle {
     pub neurorights_mental_privacy: bool,
     pub neurorights_reversibility: bool,
@@ -41,6 +53,15 @@ pub struct ALNComplianceParticle {
     pub neurorights_no_expropriation: bool,
 }
 
+impl ALNComplianceParticle {
+    pub fn neurorights_intact(&self) -> bool {
+        self.neurorights_mental_privacy
+            && self.neurorights_reversibility
+            && self.neurorights_no_finance
+            && self.neurorights_no_expropriation
+    }
+}
+
 /// AutonomyGrant mirror.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct AutonomyGrant {
@@ -81,7 +102,10 @@ pub enum Decision {
     Denied,
 }
 
-/// BioVirtualScheduler / OrganicCpuScheduler surface.
+/// BioVirtualScheduler / OrganicCpuScheduler surface. Returns the outcome of
+/// `OrganicCpuScheduler::decide`, which also reports every failed check,
+/// except that the budget and duty reasons in `LEGACY_CORRIDOR_DENIALS` deny
+/// here as they always have.
 pub trait BioVirtualScheduler {
     fn schedule_neuromorph_job(
         &self,
@@ -109,72 +133,6 @@ impl OrganicCpuScheduler {
         let diff = duty - self.u_safe_duty;
         diff * diff
     }
-
-    /// Check corridor polytope over (E, Mprot, Sbio, duty, ΔT).
-    fn corridor_ok(
-        &self,
-        host: &OrganicCpuSnapshot,
-        budget: &HostBudget,
-        thermo: &ThermodynamicEnvelope,
-        job: &NeuromorphJob,
-    ) -> bool {
-        // Energy: require headroom after job.
-        let e_after = budget.energy_nj_headroom - job.energy_cost_nj;
-        if e_after < 0.0 || job.energy_cost_nj > self.max_energy_nj {
-            return false;
-        }
-
-        // Protein headroom.
-        let mprot_after = budget.protein_headroom_mg - job.protein_cost_mg;
-        if mprot_after < 0.0 || job.protein_cost_mg > self.max_protein_mg {
-            return false;
-        }
-
-        // Bioscale cost.
-        if job.sbio_cost > self.max_sbio {
-            return false;
-        }
-
-        // Duty: current + cost must be within host & scheduler envelope.
-        let duty_after = host.duty_fraction + job.duty_cost;
-        if duty_after > budget.duty_fraction_max {
-            return false;
-        }
-
-        // Thermal polytope: requested ΔT within envelope.
-        if job.delta_t_c > thermo.delta_t_c_max {
-            return false;
-        }
-
-        true
-    }
-
-    /// Neurorights & ALNCompliance check.
-    fn neurorights_ok(&self, job: &NeuromorphJob) -> bool {
-        let c = &job.aln_compliance;
-        c.neurorights_mental_privacy
-            && c.neurorights_reversibility
-            && c.neurorights_no_finance
-            && c.neurorights_no_expropriation
-    }
-
-    /// AutonomyGrant vs job autonomy sanity.
-    fn autonomy_ok(&self, grant: &AutonomyGrant, job: &NeuromorphJob) -> bool {
-        // Require that backend is permitted for OrganicCPU.
-        if !grant
-            .permitted_backends
-            .iter()
-            .any(|b| b == "OrganicCPU")
-        {
-            return false;
-        }
-        // Energy/protein headroom thresholds enforced by caller via HostBudget.
-        if job.energy_cost_nj > grant.required_blood * 1_000.0 {
-            // Bridge blood requirement to energy envelope in a deterministic way.
-            return false;
-        }
-        true
-    }
 }
 
 impl BioVirtualScheduler for OrganicCpuScheduler {
@@ -187,44 +145,19 @@ impl BioVirtualScheduler for OrganicCpuScheduler {
         job: &NeuromorphJob,
         autonomy_grant: &AutonomyGrant,
     ) -> Decision {
-        // 1. Lyapunov duty control: require V(u_after) <= V(u_current).
-        let duty_after = host.duty_fraction + job.duty_cost;
-        let v_before = self.lyapunov(host.duty_fraction);
-        let v_after = self.lyapunov(duty_after);
-        let lyapunov_ok = v_after <= v_before;
-
-        if !lyapunov_ok {
-            return Decision::Deferred;
-        }
-
-        // 2. Corridor polytope.
-        if !self.corridor_ok(host, budget, thermo, job) {
-            return Decision::Denied;
-        }
-
-        // 3. ML schedule ceilings (Class‑C).
-        if ml.max_concurrent_jobs > 8 || ml.passes_per_day > 128 {
-            return Decision::Denied;
-        }
-
-        // 4. Neurorights and ALN compliance.
-        if !self.neurorights_ok(job) {
-            return Decision::Denied;
-        }
-
-        // 5. Autonomy grant scope.
-        if !self.autonomy_ok(autonomy_grant, job) {
+        let decision = self.decide(DecisionInput {
+            host: &host.into(),
+            budget: &budget.into(),
+            envelope: &thermo.into(),
+            ml,
+            job: &job.into(),
+            grant: autonomy_grant,
+        });
+        if decision.outcome == Decision::Deferred
+            && decision.reasons.iter().any(|r| decision::LEGACY_CORRIDOR_DENIALS.contains(r))
+        {
             return Decision::Denied;
         }
-
-        // 6. Organic safety band heuristics: IL‑6 & EEG load.
-        if host.il6_proxy > thermo.il6_index_max {
-            return Decision::Deferred;
-        }
-        if host.eeg_load_norm > 0.85 {
-            return Decision::Deferred;
-        }
-
-        Decision::Approved
+        decision.outcome
     }
 }
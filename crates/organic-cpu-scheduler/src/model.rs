@@ -0,0 +1,188 @@
+//! Canonical host, budget, envelope and job model.
+//!
+//! The crate grew two incompatible shapes: the scheduler types at the crate
+//! root (`OrganicCpuSnapshot`, `HostBudget`, `ThermodynamicEnvelope`,
+//! `NeuromorphJob`) and the `snapshot` / `budget` / `safety` shapes used by
+//! `decide_organic_job`. Both convert into the types here via `From`.
+//!
+//! Energy is held in nanojoules as `f64`: an `f32` widened and scaled by 1e9
+//! is exact, so converted values order exactly as the originals did. Fields
+//! only one legacy shape carries are `Option`; `None` means "not measured" and
+//! leaves the corresponding check out.
+
+use serde::{Deserialize, Serialize};
+
+use crate::budget::HostBudget as LegacyBudget;
+use crate::safety::{NeuromorphJobCost, ThermodynamicEnvelope as LegacyEnvelope};
+use crate::snapshot::OrganicCpuSnapshot as LegacySnapshot;
+use crate::{
+    ALNComplianceParticle, HostBudget, NeuromorphJob, OrganicCpuSnapshot, ThermodynamicEnvelope,
+};
+
+const NJ_PER_JOULE: f64 = 1e9;
+
+fn joules_to_nj(j: f32) -> f64 {
+    f64::from(j) * NJ_PER_JOULE
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HostState {
+    pub hrv_ms: f32,
+    pub core_temp_c: f32,
+    pub local_temp_c: f32,
+    /// IL-6 proxy or lab inflammation index, 0..1.
+    pub inflammation_index: f32,
+    pub duty_neuromorph: f32,
+    pub duty_chat: Option<f32>,
+    pub eeg_load_norm: Option<f32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ResourceBudget {
+    pub energy_nj_remaining: f64,
+    pub protein_mg_remaining: Option<f32>,
+    pub max_neuromorph_duty: f32,
+    pub max_chat_duty: Option<f32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SafetyEnvelope {
+    pub max_delta_t_c: f32,
+    pub max_core_temp_c: Option<f32>,
+    pub max_inflammation_index: f32,
+    /// Per-job energy ceiling, on top of the scheduler's own `max_energy_nj`.
+    pub max_job_energy_nj: Option<f64>,
+    pub max_neuromorph_duty: Option<f32>,
+    pub max_chat_duty: Option<f32>,
+    pub thermo_class: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct JobRequest {
+    pub job_id: String,
+    pub energy_nj: f64,
+    pub protein_mg: f32,
+    pub sbio: f32,
+    pub duty: f32,
+    pub delta_t_c: f32,
+    pub autonomy_grant_id: String,
+    pub compliance: ALNComplianceParticle,
+}
+
+impl JobRequest {
+    /// `NeuromorphJobCost` carries no identity or compliance; the caller
+    /// supplies them. Protein and bioscale cost are not estimated on that
+    /// path and count as zero.
+    pub fn from_cost(
+        job_id: impl Into<String>,
+        cost: &NeuromorphJobCost,
+        autonomy_grant_id: impl Into<String>,
+        compliance: ALNComplianceParticle,
+    ) -> Self {
+        Self {
+            job_id: job_id.into(),
+            energy_nj: joules_to_nj(cost.estimated_energy_joules),
+            protein_mg: 0.0,
+            sbio: 0.0,
+            duty: cost.estimated_duty_neuromorph,
+            delta_t_c: cost.estimated_delta_core_c,
+            autonomy_grant_id: autonomy_grant_id.into(),
+            compliance,
+        }
+    }
+}
+
+impl From<&OrganicCpuSnapshot> for HostState {
+    fn from(s: &OrganicCpuSnapshot) -> Self {
+        Self {
+            hrv_ms: s.hrv_ms,
+            core_temp_c: s.core_temp_c,
+            local_temp_c: s.local_temp_c,
+            inflammation_index: s.il6_proxy,
+            duty_neuromorph: s.duty_fraction,
+            duty_chat: None,
+            eeg_load_norm: Some(s.eeg_load_norm),
+        }
+    }
+}
+
+impl From<&LegacySnapshot> for HostState {
+    fn from(s: &LegacySnapshot) -> Self {
+        Self {
+            hrv_ms: s.hrv_ms,
+            core_temp_c: s.core_temp_c,
+            local_temp_c: s.skin_temp_c,
+            inflammation_index: s.inflammation_index,
+            duty_neuromorph: s.duty_fraction_neuromorph,
+            duty_chat: Some(s.duty_fraction_chat),
+            // Perceived load is self-report, not an EEG measurement.
+            eeg_load_norm: None,
+        }
+    }
+}
+
+impl From<&HostBudget> for ResourceBudget {
+    fn from(b: &HostBudget) -> Self {
+        Self {
+            energy_nj_remaining: f64::from(b.energy_nj_headroom),
+            protein_mg_remaining: Some(b.protein_headroom_mg),
+            max_neuromorph_duty: b.duty_fraction_max,
+            max_chat_duty: None,
+        }
+    }
+}
+
+impl From<&LegacyBudget> for ResourceBudget {
+    fn from(b: &LegacyBudget) -> Self {
+        Self {
+            energy_nj_remaining: joules_to_nj(b.energy_joules_remaining),
+            // A 0..1 fraction has no mg equivalent.
+            protein_mg_remaining: None,
+            max_neuromorph_duty: b.max_neuromorph_duty_fraction,
+            max_chat_duty: Some(b.max_chat_duty_fraction),
+        }
+    }
+}
+
+impl From<&ThermodynamicEnvelope> for SafetyEnvelope {
+    fn from(t: &ThermodynamicEnvelope) -> Self {
+        Self {
+            max_delta_t_c: t.delta_t_c_max,
+            max_core_temp_c: None,
+            max_inflammation_index: t.il6_index_max,
+            max_job_energy_nj: None,
+            max_neuromorph_duty: None,
+            max_chat_duty: None,
+            thermo_class: Some(t.thermo_class.clone()),
+        }
+    }
+}
+
+impl From<&LegacyEnvelope> for SafetyEnvelope {
+    fn from(t: &LegacyEnvelope) -> Self {
+        Self {
+            max_delta_t_c: t.max_delta_core_c,
+            max_core_temp_c: Some(t.max_core_c),
+            max_inflammation_index: t.max_inflammation_index,
+            max_job_energy_nj: Some(joules_to_nj(t.max_energy_joules)),
+            max_neuromorph_duty: Some(t.max_neuromorph_duty),
+            max_chat_duty: Some(t.max_chat_duty),
+            thermo_class: None,
+        }
+    }
+}
+
+impl From<&NeuromorphJob> for JobRequest {
+    fn from(j: &NeuromorphJob) -> Self {
+        Self {
+            job_id: j.job_id.clone(),
+            energy_nj: f64::from(j.energy_cost_nj),
+            protein_mg: j.protein_cost_mg,
+            sbio: j.sbio_cost,
+            duty: j.duty_cost,
+            delta_t_c: j.delta_t_c,
+            autonomy_grant_id: j.autonomy_grant_id.clone(),
+            compliance: j.aln_compliance.clone(),
+        }
+    }
+}
@@ -1,7 +1,13 @@
 use crate::snapshot::OrganicCpuSnapshot;
 use crate::budget::HostBudget;
+use crate::decision::DecisionInput;
+use crate::model::{HostState, JobRequest};
+use crate::{ALNComplianceParticle, AutonomyGrant, AutonomyLevel, MlPassSchedule, OrganicCpuScheduler};
 use serde::{Deserialize, Serialize};
 
+/// Job and grant id for the pass-through inputs `decide_organic_job` builds.
+const LEGACY_GRANT_ID: &str = "legacy";
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub enum JobDecision {
     Permit,
@@ -26,43 +32,51 @@ pub struct NeuromorphJobCost {
     pub estimated_delta_core_c: f32,
 }
 
+/// Legacy check over the `snapshot` / `budget` shapes, as an adapter over
+/// `OrganicCpuScheduler::decide`. Any deny-class failure denies, with the
+/// code of the first such reason; otherwise the first defer-class reason
+/// defers. This path has no scheduler,
+/// ML schedule, grant or compliance, so those checks are given inputs that
+/// always pass; the envelope's duty ceilings are now enforced alongside the
+/// budget's. Prefer `decide`, which reports every failed check.
 pub fn decide_organic_job(
     snap: &OrganicCpuSnapshot,
     budget: &HostBudget,
     envelope: &ThermodynamicEnvelope,
     cost: &NeuromorphJobCost,
 ) -> JobDecision {
-    // Thermal hard stops
-    if snap.core_temp_c + cost.estimated_delta_core_c > envelope.max_core_c {
-        return JobDecision::Deny("core_temp_ceiling".into());
-    }
-    if cost.estimated_delta_core_c > envelope.max_delta_core_c {
-        return JobDecision::Deny("delta_core_c_ceiling".into());
-    }
-
-    // Energy budget
-    if cost.estimated_energy_joules > budget.energy_joules_remaining {
-        return JobDecision::Defer("insufficient_energy_budget".into());
-    }
-    if cost.estimated_energy_joules > envelope.max_energy_joules {
-        return JobDecision::Deny("thermo_envelope_energy_violation".into());
-    }
-
-    // Duty cycles
-    if snap.duty_fraction_neuromorph + cost.estimated_duty_neuromorph
-        > budget.max_neuromorph_duty_fraction
-    {
-        return JobDecision::Defer("neuromorph_duty_ceiling".into());
-    }
-
-    if snap.duty_fraction_chat > budget.max_chat_duty_fraction {
-        return JobDecision::Defer("chat_duty_ceiling".into());
-    }
-
-    // Inflammation / recovery
-    if snap.inflammation_index > envelope.max_inflammation_index {
-        return JobDecision::Defer("inflammation_high".into());
-    }
-
-    JobDecision::Permit
+    let host = HostState::from(snap);
+    // u_safe at the post-job duty, so the Lyapunov check never fires.
+    let scheduler = OrganicCpuScheduler {
+        u_safe_duty: host.duty_neuromorph + cost.estimated_duty_neuromorph,
+        max_energy_nj: f32::INFINITY,
+        max_protein_mg: f32::INFINITY,
+        max_sbio: f32::INFINITY,
+    };
+    let grant = AutonomyGrant {
+        id: LEGACY_GRANT_ID.to_string(),
+        level: AutonomyLevel::LabOnly,
+        required_blood: f32::INFINITY,
+        min_chat_factor: 0.0,
+        jurisdiction: String::new(),
+        permitted_backends: vec!["OrganicCPU".to_string()],
+    };
+    let compliance = ALNComplianceParticle {
+        neurorights_mental_privacy: true,
+        neurorights_reversibility: true,
+        neurorights_no_finance: true,
+        neurorights_no_expropriation: true,
+    };
+    let decision = scheduler.decide(DecisionInput {
+        host: &host,
+        budget: &budget.into(),
+        envelope: &envelope.into(),
+        ml: &MlPassSchedule {
+            passes_per_day: 0,
+            max_concurrent_jobs: 0,
+        },
+        job: &JobRequest::from_cost(LEGACY_GRANT_ID, cost, LEGACY_GRANT_ID, compliance),
+        grant: &grant,
+    });
+    JobDecision::from(&decision)
 }
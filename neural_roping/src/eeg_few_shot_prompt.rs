@@ -1,6 +1,6 @@
 use augdoctor_policies::shot_level_policy::{ShotLevel, ShotLevelDecision, ShotLevelSignal};
 use augdoctor_policies::neural_rope_prompt_selector::{
-    NeuralRopePromptSelector, PromptSelectionRequest,
+    NeuralRopePromptSelector, PromptSelectionRequest, RetrievalConfig,
 };
 use bioscale_upgrade_service::neural_rope::NeuralRope;
 
@@ -41,7 +41,7 @@ pub fn build_eeg_few_shot_prompt(
         shot_level: decision.chosen_level.clone(),
         max_examples: decision.max_examples,
     };
-    let selection_res = selector.select_examples(&selection_req);
+    let selection_res = selector.select_relevant(&selection_req, intent_label, &RetrievalConfig::allow_same_plane_only());
 
     let base_instruction = format!(
         "You are an EEG-driven control assistant. \
@@ -1,4 +1,4 @@
-use bioscale_upgrade_service::neural_rope::NeuralRope;
+use bioscale_upgrade_service::neural_rope::{NeuralRope, TraceSink};
 
 /// Minimal EEG feature summary attached to each trace (non-identity-bearing).
 #[derive(Clone, Debug)]
@@ -11,12 +11,14 @@ pub struct EegFeatureSummary {
     pub erp_latency_ms: u16,
 }
 
-pub struct EegTraceRecorder<'a> {
-    rope: &'a mut NeuralRope,
+/// Records into any `TraceSink`: a plain `NeuralRope`, or a
+/// `DurableNeuralRope` when traces must survive restarts.
+pub struct EegTraceRecorder<'a, S: TraceSink = NeuralRope> {
+    rope: &'a mut S,
 }
 
-impl<'a> EegTraceRecorder<'a> {
-    pub fn new(rope: &'a mut NeuralRope) -> Self {
+impl<'a, S: TraceSink> EegTraceRecorder<'a, S> {
+    pub fn new(rope: &'a mut S) -> Self {
         EegTraceRecorder { rope }
     }
 
@@ -30,7 +32,7 @@ impl<'a> EegTraceRecorder<'a> {
         feature: &EegFeatureSummary,
         reward_score: f32,
         safety_decision: &str,
-    ) {
+    ) -> std::io::Result<()> {
         let trace_text = format!(
             "EEG_INTENT env={} upgrade={} intent={} alpha={:.3} beta={:.3} csp={:.3} erp_latency_ms={}",
             environment_id,
@@ -42,12 +44,12 @@ impl<'a> EegTraceRecorder<'a> {
             feature.erp_latency_ms
         );
 
-        self.rope.append_trace(
+        self.rope.record_trace(
             &trace_text,
             "bci/hci/eeg",
             Some(upgrade_id.to_string()),
             reward_score,
             safety_decision,
-        );
+        )
     }
 }
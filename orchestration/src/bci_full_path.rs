@@ -2,16 +2,23 @@ use augdoctor_policies::shot_level_policy::{
     ShotLevel, ShotLevelDecision, ShotLevelPolicy, ShotLevelPolicyConfig, ShotLevelSignal,
 };
 use augdoctor_policies::neural_rope_prompt_selector::{
-    NeuralRopePromptSelector, PromptSelectionRequest,
+    NeuralRopePromptSelector, PromptSelectionRequest, RetrievalConfig,
 };
 use augdoctor_policies::neuro_handshake_orchestrator::{
-    HandshakePhase, NeuroHandshakeOrchestrator, NeuroHandshakeState,
+    unix_millis, HandshakeEvent, HandshakePhase, HandshakeRejection, NeuroHandshakeOrchestrator,
+    NeuroHandshakeState,
+};
+use bioscale_upgrade_service::neural_rope::TraceSink;
+use bioscale_upgrade_service::neural_rope_store::{
+    DurableNeuralRope, NeuralRopeStoreConfig, NeuralRopeStoreError,
 };
-use bioscale_upgrade_service::neural_rope::NeuralRope;
 use bioscale_upgrade_store::{
-    BioscaleUpgradeStore, BioscaleStoreConfig, UpgradeApplicationResult,
+    BioscaleAwarenessProfile, BioscaleStoreConfig, BioscaleStoreError, BioscaleUpgradeAsset,
+    BioscaleUpgradeStore, ConsciousnessComplianceLevel, EnvironmentProfile, HardwareBindingProfile,
+    UpgradeApplicationResult,
 };
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 /// Simplified model client for AI-Chats (HTTP to LLM gateway).
 #[derive(Clone)]
@@ -51,11 +58,39 @@ pub struct BciPathResult {
     pub final_prompt: String,
     pub model_output: String,
     pub bioscale_result: Option<UpgradeApplicationResult>,
+    /// Why the bioscale step was refused, when it was.
+    #[serde(default)]
+    pub bioscale_error: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum BciPathError {
+    /// The handshake refused the event; carries the (possibly reset) state.
+    #[error("handshake rejected: {rejection}")]
+    Handshake {
+        rejection: HandshakeRejection,
+        handshake: Box<NeuroHandshakeState>,
+    },
+    #[error("model call failed: {0}")]
+    Model(Box<dyn std::error::Error + Send + Sync>),
+    /// The trace could not be persisted to the neural-rope store.
+    #[error("neural-rope trace not recorded: {0}")]
+    Trace(#[from] std::io::Error),
+}
+
+impl BciPathError {
+    /// Handshake state to persist after a failed call, if the failure touched it.
+    pub fn handshake(&self) -> Option<&NeuroHandshakeState> {
+        match self {
+            BciPathError::Handshake { handshake, .. } => Some(handshake),
+            BciPathError::Model(_) | BciPathError::Trace(_) => None,
+        }
+    }
 }
 
 pub struct BciOrchestrator<'a> {
     pub store: &'a mut BioscaleUpgradeStore,
-    pub rope: &'a mut NeuralRope,
+    pub rope: &'a mut DurableNeuralRope,
     pub model_client: ModelClient,
     pub shot_policy: ShotLevelPolicy,
 }
@@ -63,7 +98,7 @@ pub struct BciOrchestrator<'a> {
 impl<'a> BciOrchestrator<'a> {
     pub fn new(
         store: &'a mut BioscaleUpgradeStore,
-        rope: &'a mut NeuralRope,
+        rope: &'a mut DurableNeuralRope,
         model_client: ModelClient,
         shot_policy: ShotLevelPolicy,
     ) -> Self {
@@ -84,21 +119,33 @@ impl<'a> BciOrchestrator<'a> {
         upgrade_id: &str,
         environment_hardware: Vec<String>,
         environment_tags: Vec<String>,
-    ) -> Result<(BciPathResult, NeuroHandshakeState), Box<dyn std::error::Error + Send + Sync>> {
+    ) -> Result<(BciPathResult, NeuroHandshakeState), BciPathError> {
         // 1) Neuro-handshake progression
         let actions = augdoctor_policies::neuro_handshake_orchestrator::NeuroHandshakeOrchestrator::next_actions(&handshake_state);
 
-        if handshake_state.phase == HandshakePhase::Safety {
+        let now_ms = unix_millis();
+        let step = match handshake_state.phase {
             // In a real system, you'd show consent and safety UI here.
-            // For this orchestrator, we simulate consent on first BCI event.
-            handshake_state =
-                NeuroHandshakeOrchestrator::apply_event(handshake_state, "user_consented");
-        }
-
-        if handshake_state.phase == HandshakePhase::Calibration {
+            // For this orchestrator, we simulate consent on the BCI event unless
+            // the user revoked it; a revoked session waits for explicit consent.
+            HandshakePhase::Safety if NeuroHandshakeOrchestrator::may_auto_consent(&handshake_state) => {
+                Some(HandshakeEvent::UserConsented)
+            }
+            HandshakePhase::Safety => None,
             // Simulate that one calibration sample has been recorded per event.
-            handshake_state =
-                NeuroHandshakeOrchestrator::apply_event(handshake_state, "calibration_sample_recorded");
+            HandshakePhase::Calibration => Some(HandshakeEvent::CalibrationSampleRecorded {
+                channel: Some(event.channel.clone()),
+            }),
+            HandshakePhase::Operation => None,
+        };
+        // A timeout resets the session to Safety; hand that state back with the
+        // rejection so the caller persists the reset instead of the stale phase.
+        let rejection = match step {
+            Some(step) => NeuroHandshakeOrchestrator::apply(&mut handshake_state, &step, now_ms).err(),
+            None => NeuroHandshakeOrchestrator::expire(&mut handshake_state, now_ms),
+        };
+        if let Some(rejection) = rejection {
+            return Err(BciPathError::Handshake { rejection, handshake: Box::new(handshake_state) });
         }
 
         // If still not in Operation, we do not route to model or bioscale upgrade yet.
@@ -111,11 +158,12 @@ impl<'a> BciOrchestrator<'a> {
 
             let path_result = BciPathResult {
                 session_id: event.session_id,
-                handshake_phase: handshake_state.phase.clone(),
+                handshake_phase: handshake_state.phase,
                 shot_level_decision: dummy_decision,
                 final_prompt: String::from(""),
                 model_output: String::from(""),
                 bioscale_result: None,
+                bioscale_error: None,
             };
             return Ok((path_result, handshake_state));
         }
@@ -134,14 +182,14 @@ impl<'a> BciOrchestrator<'a> {
         let shot_decision = self.shot_policy.decide(&signal);
 
         // 3) Select neural-rope examples if few-shot
-        let selector = NeuralRopePromptSelector::new(self.rope);
+        let selector = NeuralRopePromptSelector::new(&self.rope.rope);
         let selection_req = PromptSelectionRequest {
             task_id: signal.task_id.clone(),
             plane_label: signal.plane_label.clone(),
             shot_level: shot_decision.chosen_level.clone(),
             max_examples: shot_decision.max_examples,
         };
-        let selection_res = selector.select_examples(&selection_req);
+        let selection_res = selector.select_relevant(&selection_req, &format!("{} {}", event.intent_label, event.channel), &RetrievalConfig::allow_same_plane_only());
 
         // Build final prompt text for the model
         let base_instruction = format!(
@@ -168,55 +216,108 @@ impl<'a> BciOrchestrator<'a> {
         };
 
         // 4) Model call
-        let model_output = self.model_client.call_model(final_prompt.clone()).await?;
-
-        // 5) Apply bioscale upgrade / guard
-        let bioscale_result = self
-            .store
-            .apply_upgrade_to_environment(
-                upgrade_id,
-                &event.environment_id,
-                environment_hardware.clone(),
-                environment_tags.clone(),
-            )
-            .ok();
-
-        // 6) Append to neural-rope (assisted learning)
+        let model_output = self
+            .model_client
+            .call_model(final_prompt.clone())
+            .await
+            .map_err(BciPathError::Model)?;
+
+        // 5) Apply bioscale upgrade / guard, persisting the trace to the
+        // neural-rope (assisted learning) before the application is recorded.
         let plane_label = String::from("bci/hci/eeg");
-        let safety_decision = bioscale_result
-            .as_ref()
-            .map(|r| r.guard_decision.clone())
-            .unwrap_or_else(|| String::from("GuardDeniedOrError"));
-
-        self.rope.append_trace(
-            &format!(
-                "bci_event session={} env={} intent={} model_output={}",
-                event.session_id, event.environment_id, event.intent_label, model_output
-            ),
-            &plane_label,
-            Some(upgrade_id.to_string()),
-            1.0, // reward stub; you can plug real RL signal here
-            &safety_decision,
+        let trace_text = format!(
+            "bci_event session={} env={} intent={} model_output={}",
+            event.session_id, event.environment_id, event.intent_label, model_output
+        );
+        let rope = &mut *self.rope;
+        let mut trace_error = None;
+        let applied = self.store.apply_upgrade_to_environment_with(
+            upgrade_id,
+            &event.environment_id,
+            environment_hardware.clone(),
+            environment_tags.clone(),
+            |r| {
+                rope.record_trace(
+                    &trace_text,
+                    &plane_label,
+                    Some(upgrade_id.to_string()),
+                    1.0, // reward stub; you can plug real RL signal here
+                    &r.guard_decision,
+                )
+                .map_err(|e| {
+                    let msg = e.to_string();
+                    trace_error = Some(e);
+                    BioscaleStoreError::StorageError(msg)
+                })
+            },
         );
+        if let Some(e) = trace_error {
+            return Err(BciPathError::Trace(e));
+        }
+        let (bioscale_result, bioscale_error) = match applied {
+            Ok(r) => (Some(r), None),
+            Err(e) => {
+                // Nothing was applied; the denial is still worth a trace.
+                rope.record_trace(
+                    &trace_text,
+                    &plane_label,
+                    Some(upgrade_id.to_string()),
+                    1.0,
+                    "GuardDeniedOrError",
+                )?;
+                (None, Some(e.to_string()))
+            }
+        };
 
         let path_result = BciPathResult {
             session_id: event.session_id,
-            handshake_phase: handshake_state.phase.clone(),
+            handshake_phase: handshake_state.phase,
             shot_level_decision: shot_decision,
             final_prompt,
             model_output,
             bioscale_result,
+            bioscale_error,
         };
 
         Ok((path_result, handshake_state))
     }
 }
 
-/// Example initialization function (e.g., from main.rs)
+/// Environment the default orchestrator's store is configured for.
+pub const DEFAULT_BCI_ENVIRONMENT_ID: &str = "bci-default";
+/// Upgrade the default orchestrator's store holds as Active.
+pub const DEFAULT_BCI_UPGRADE_ID: &str = "bci-default-control";
+
+fn default_bci_upgrade() -> BioscaleUpgradeAsset {
+    let mut asset = BioscaleUpgradeAsset::new(
+        "bci-default-control",
+        BioscaleAwarenessProfile {
+            involves_living_organism: true,
+            tissue_interface: vec![],
+            organ_targets: vec![],
+            biosignal_channels: vec![String::from("eeg"), String::from("emg")],
+        },
+        ConsciousnessComplianceLevel::NoConsciousSubstrate,
+        HardwareBindingProfile {
+            allowed_hardware_ids: vec![],
+            required_safety_modules: vec![],
+            bioscale_resolution_microns: 10,
+        },
+        vec![String::from("emg")],
+        "0xbci-default-control",
+    );
+    asset.id = DEFAULT_BCI_UPGRADE_ID.to_string();
+    asset
+}
+
+/// Example initialization function (e.g., from main.rs). The store comes
+/// with `DEFAULT_BCI_ENVIRONMENT_ID` registered and `DEFAULT_BCI_UPGRADE_ID`
+/// Active, so events for that pair reach the guard. Traces go to a durable
+/// rope under `BCI_NEURAL_ROPE_DIR` (default `data/bci-neural-rope`).
 pub fn create_default_bci_orchestrator(
-) -> (BciOrchestrator<'static>, NeuroHandshakeState) {
+) -> Result<(BciOrchestrator<'static>, NeuroHandshakeState), NeuralRopeStoreError> {
     static mut STORE: Option<BioscaleUpgradeStore> = None;
-    static mut ROPE: Option<NeuralRope> = None;
+    static mut ROPE: Option<DurableNeuralRope> = None;
 
     unsafe {
         STORE.get_or_insert_with(|| {
@@ -228,14 +329,27 @@ pub fn create_default_bci_orchestrator(
                     String::from("KYC"),
                 ],
                 max_upgrade_assets: 1024,
+                legacy_environment_fallback: false,
+                legacy_auto_activate: false,
             };
-            BioscaleUpgradeStore::new(cfg)
+            let mut store = BioscaleUpgradeStore::new(cfg);
+            store
+                .register_environment(EnvironmentProfile::legacy(DEFAULT_BCI_ENVIRONMENT_ID))
+                .expect("in-memory store accepts the default environment");
+            store
+                .register_active_upgrade(default_bci_upgrade())
+                .expect("in-memory store accepts the default upgrade");
+            store
         });
 
-        ROPE.get_or_insert_with(NeuralRope::new);
+        if ROPE.is_none() {
+            let rope_dir = std::env::var("BCI_NEURAL_ROPE_DIR")
+                .unwrap_or_else(|_| String::from("data/bci-neural-rope"));
+            ROPE = Some(DurableNeuralRope::open(&rope_dir, NeuralRopeStoreConfig::default())?);
+        }
 
         let store_ref: &mut BioscaleUpgradeStore = STORE.as_mut().unwrap();
-        let rope_ref: &mut NeuralRope = ROPE.as_mut().unwrap();
+        let rope_ref: &mut DurableNeuralRope = ROPE.as_mut().unwrap();
 
         let model_client = ModelClient {
             endpoint: String::from("https://llm-gateway.example.com/v1/chat"),
@@ -253,6 +367,56 @@ pub fn create_default_bci_orchestrator(
 
         let handshake_state = NeuroHandshakeOrchestrator::initial("session-1", 3);
 
-        (orchestrator, handshake_state)
+        Ok((orchestrator, handshake_state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn default_orchestrator_reaches_the_guard() {
+        let rope_dir = std::env::temp_dir().join(format!("bci-neural-rope-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&rope_dir);
+        std::env::set_var("BCI_NEURAL_ROPE_DIR", &rope_dir);
+        let (mut orchestrator, mut handshake) = create_default_bci_orchestrator().unwrap();
+        let event = BciEvent {
+            session_id: String::from("session-1"),
+            environment_id: String::from(DEFAULT_BCI_ENVIRONMENT_ID),
+            channel: String::from("emg"),
+            intent_label: String::from("grab"),
+            risk_score: 0.1,
+            latency_budget_ms: 200,
+            token_budget: 256,
+        };
+
+        // Consent plus three calibration samples reach Operation.
+        let mut result = None;
+        for _ in 0..4 {
+            let (r, next) = orchestrator
+                .handle_bci_event(event.clone(), handshake, DEFAULT_BCI_UPGRADE_ID, vec![], vec![])
+                .await
+                .unwrap();
+            handshake = next;
+            result = Some(r);
+        }
+        let result = result.unwrap();
+        assert_eq!(result.handshake_phase, HandshakePhase::Operation);
+        assert_eq!(result.bioscale_error, None);
+        assert!(result.bioscale_result.is_some());
+        assert_ne!(orchestrator.rope.rope.export_snapshot(1).len(), 0);
+        assert!(!orchestrator.rope.store.is_empty());
+
+        // An expired Operation session comes back reset, not dropped.
+        handshake.phase_entered_at_ms = Some(0);
+        let err = orchestrator
+            .handle_bci_event(event, handshake, DEFAULT_BCI_UPGRADE_ID, vec![], vec![])
+            .await
+            .unwrap_err();
+        let reset = err.handshake().unwrap();
+        assert_eq!(reset.phase, HandshakePhase::Safety);
+        assert_eq!(reset.history.last().unwrap().cause, "timeout");
+        let _ = std::fs::remove_dir_all(&rope_dir);
     }
 }
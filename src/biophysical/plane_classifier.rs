@@ -1,7 +1,11 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::path::Path;
 
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum EnvironmentPlane {
     Cybernetics,
     Augmented,
@@ -20,7 +24,7 @@ pub enum EnvironmentPlane {
     Unknown,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConsciousnessState {
     None,
     InactiveSticky,
@@ -120,15 +124,63 @@ pub struct PlaneClassifierConfig {
 #[derive(Clone, Debug)]
 pub struct PlaneClassifier {
     pub config: PlaneClassifierConfig,
-    decision_log: Vec<String>,
+    rules: PlaneRuleTable,
+    rules_hash: String,
+    decision_log: Vec<DecisionRecord>,
 }
 
 impl PlaneClassifier {
+    /// Classifier over the built-in rule table.
     pub fn new(config: PlaneClassifierConfig) -> Self {
-        PlaneClassifier {
+        Self::with_rule_table(config, PlaneRuleTable::builtin())
+            .expect("built-in plane rule table is valid")
+    }
+
+    /// Classifier over `rules`, which must pass `PlaneRuleTable::validate`.
+    pub fn with_rule_table(
+        config: PlaneClassifierConfig,
+        rules: PlaneRuleTable,
+    ) -> Result<Self, RuleTableError> {
+        rules.check()?;
+        let rules_hash = rules.hash();
+        Ok(PlaneClassifier {
             config,
+            rules,
+            rules_hash,
             decision_log: Vec::with_capacity(256),
+        })
+    }
+
+    pub fn rule_table(&self) -> &PlaneRuleTable {
+        &self.rules
+    }
+
+    pub fn rule_table_hash(&self) -> &str {
+        &self.rules_hash
+    }
+
+    fn record(
+        &mut self,
+        check: &str,
+        rule_id: &str,
+        inputs: serde_json::Value,
+        outcome: String,
+        contributing_rules: Vec<String>,
+    ) {
+        if !self.config.log_all_decisions {
+            return;
         }
+        self.decision_log.push(DecisionRecord {
+            seq: self.decision_log.len() as u64,
+            check: check.to_string(),
+            rule_table_id: self.rules.table_id.clone(),
+            rule_table_version: self.rules.version.clone(),
+            rule_table_hash: self.rules_hash.clone(),
+            rule_id: rule_id.to_string(),
+            inputs,
+            outcome,
+            contributing_rules,
+        });
     }
 
     pub fn awareness_check(&mut self, involves_living_organism: bool) -> AwarenessFlag {
@@ -137,12 +189,13 @@ impl PlaneClassifier {
         } else {
             AwarenessFlag::NoOrganism
         };
-        if self.config.log_all_decisions {
-            self.decision_log.push(format!(
-                "[awareness-check] involves_living_organism={} -> {:?}",
-                involves_living_organism, flag
-            ));
-        }
+        self.record(
+            "awareness-check",
+            "builtin:awareness",
+            serde_json::json!({ "involves_living_organism": involves_living_organism }),
+            format!("{:?}", flag),
+            Vec::new(),
+        );
         flag
     }
 
@@ -150,36 +203,30 @@ impl PlaneClassifier {
         &mut self,
         requested_state: ConsciousnessState,
     ) -> ConsciousnessState {
-        match requested_state {
-            ConsciousnessState::ActiveImmutable | ConsciousnessState::InactiveImmutable => {
-                if self.config.prohibit_consciousness_mutation {
-                    if self.config.log_all_decisions {
-                        self.decision_log.push(format!(
-                            "[consciousness-state] immutable-state requested {:?} -> preserved",
-                            requested_state
-                        ));
-                    }
-                    requested_state
-                } else {
-                    if self.config.log_all_decisions {
-                        self.decision_log.push(format!(
-                            "[consciousness-state] immutable-state allowed {:?}",
-                            requested_state
-                        ));
-                    }
-                    requested_state
-                }
-            }
-            other => {
-                if self.config.log_all_decisions {
-                    self.decision_log.push(format!(
-                        "[consciousness-state] non-immutable state {:?}",
-                        other
-                    ));
-                }
-                other
-            }
-        }
+        let prohibit = self.config.prohibit_consciousness_mutation;
+        let (rule_id, state) = {
+            let rule = self
+                .rules
+                .consciousness_rule_for(&requested_state, prohibit)
+                .expect("validated rule table covers every consciousness state");
+            (
+                rule.id.clone(),
+                rule.result
+                    .clone()
+                    .unwrap_or_else(|| requested_state.clone()),
+            )
+        };
+        self.record(
+            "consciousness-state",
+            &rule_id,
+            serde_json::json!({
+                "requested_state": requested_state,
+                "prohibit_mutation": prohibit,
+            }),
+            format!("{:?}", state),
+            vec![rule_id.clone()],
+        );
+        state
     }
 
     pub fn oculus_check(
@@ -205,12 +252,13 @@ impl PlaneClassifier {
             FeedbackSensorFlag::NoFeedback
         };
 
-        if self.config.log_all_decisions {
-            self.decision_log.push(format!(
-                "[oculus-check] has_oculus={}, has_remote_feed={} -> {:?}/{:?}",
-                has_oculus, has_remote_feed, oculus_flag, feedback_flag
-            ));
-        }
+        self.record(
+            "oculus-check",
+            "builtin:oculus",
+            serde_json::json!({ "has_oculus": has_oculus, "has_remote_feed": has_remote_feed }),
+            format!("{:?}/{:?}", oculus_flag, feedback_flag),
+            Vec::new(),
+        );
 
         (oculus_flag, feedback_flag)
     }
@@ -223,26 +271,36 @@ impl PlaneClassifier {
         controller_contract: &str,
         hardware_dependencies: Vec<String>,
     ) -> BrainTokenState {
+        let inputs = serde_json::json!({
+            "net_weight": net_weight,
+            "circulating_supply": circulating_supply,
+            "frozen": frozen,
+            "controller_contract": controller_contract,
+        });
+
         if frozen && self.config.prohibit_brain_token_freeze {
             let state = BrainTokenState::FrozenDisallowed {
                 reason: String::from("Brain-tokens must not be frozen for any cybernetic hardware or augmented citizen."),
                 last_holder_id: String::from("unknown-holder"),
             };
-            if self.config.log_all_decisions {
-                self.decision_log.push(format!(
-                    "[brain-tokens] frozen=TRUE -> {:?}",
-                    state
-                ));
-            }
+            self.record(
+                "brain-tokens",
+                "builtin:brain-tokens.frozen",
+                inputs,
+                format!("{:?}", state),
+                Vec::new(),
+            );
             return state;
         }
 
         if net_weight <= 0.0 || circulating_supply <= 0.0 {
-            if self.config.log_all_decisions {
-                self.decision_log.push(String::from(
-                    "[brain-tokens] no net_weight or circulating_supply -> None",
-                ));
-            }
+            self.record(
+                "brain-tokens",
+                "builtin:brain-tokens.empty",
+                inputs,
+                String::from("None"),
+                Vec::new(),
+            );
             return BrainTokenState::None;
         }
 
@@ -253,12 +311,13 @@ impl PlaneClassifier {
             hardware_dependencies,
         };
 
-        if self.config.log_all_decisions {
-            self.decision_log.push(format!(
-                "[brain-tokens] circulating net_weight={}, circulating_supply={}, controller={}",
-                net_weight, circulating_supply, controller_contract
-            ));
-        }
+        self.record(
+            "brain-tokens",
+            "builtin:brain-tokens.circulating",
+            inputs,
+            String::from("Circulating"),
+            Vec::new(),
+        );
 
         state
     }
@@ -274,12 +333,16 @@ impl PlaneClassifier {
             CloningPolicy::ClonableNonConscious
         };
 
-        if self.config.log_all_decisions {
-            self.decision_log.push(format!(
-                "[cloning-policy] conscious_pattern={}, identity_descriptors={} -> {:?}",
-                involves_conscious_pattern, contains_identity_descriptors, policy
-            ));
-        }
+        self.record(
+            "cloning-policy",
+            "builtin:cloning-policy",
+            serde_json::json!({
+                "conscious_pattern": involves_conscious_pattern,
+                "identity_descriptors": contains_identity_descriptors,
+            }),
+            format!("{:?}", policy),
+            Vec::new(),
+        );
 
         policy
     }
@@ -289,79 +352,15 @@ impl PlaneClassifier {
         input_tags: &[String],
         hardware_profile: &[String],
     ) -> EnvironmentPlane {
-        let mut score: HashMap<EnvironmentPlane, i32> = HashMap::new();
-
-        fn inc(map: &mut HashMap<EnvironmentPlane, i32>, plane: EnvironmentPlane, v: i32) {
-            let entry = map.entry(plane).or_insert(0);
-            *entry += v;
-        }
-
-        for tag in input_tags {
-            let t = tag.to_lowercase();
-            if t.contains("bci") || t.contains("eeg") {
-                inc(&mut score, EnvironmentPlane::BciHciEeg, 3);
-                inc(&mut score, EnvironmentPlane::Biophysics, 1);
-            }
-            if t.contains("emg") || t.contains("ecg") {
-                inc(&mut score, EnvironmentPlane::Biophysics, 2);
-                inc(&mut score, EnvironmentPlane::Bioscale, 1);
-            }
-            if t.contains("avatar") || t.contains("ar/vr") || t.contains("oculus") {
-                inc(&mut score, EnvironmentPlane::Augmented, 3);
-                inc(&mut score, EnvironmentPlane::RealityOs, 1);
-            }
-            if t.contains("wetware") {
-                inc(&mut score, EnvironmentPlane::Wetware, 4);
-            }
-            if t.contains("bioware") {
-                inc(&mut score, EnvironmentPlane::Bioware, 4);
-            }
-            if t.contains("neural-net") || t.contains("ml-model") {
-                inc(&mut score, EnvironmentPlane::NeuralNetwork, 3);
-                inc(&mut score, EnvironmentPlane::SoftwareOnly, 1);
-            }
-            if t.contains("quantum") {
-                inc(&mut score, EnvironmentPlane::Cybernetics, 1);
-                inc(&mut score, EnvironmentPlane::NeuralNetwork, 1);
-            }
-            if t.contains("consciousness-net") || t.contains("soul-guard") {
-                inc(&mut score, EnvironmentPlane::ConsciousnessNetwork, 4);
-            }
-        }
-
-        for hw in hardware_profile {
-            let h = hw.to_lowercase();
-            if h.contains("openbci") || h.contains("eeg-headset") {
-                inc(&mut score, EnvironmentPlane::BciHciEeg, 5);
-                inc(&mut score, EnvironmentPlane::Biophysics, 2);
-            }
-            if h.contains("implant") || h.contains("neural-link") {
-                inc(&mut score, EnvironmentPlane::Transhuman, 4);
-                inc(&mut score, EnvironmentPlane::Cybernetics, 2);
-            }
-            if h.contains("robotic-limb") || h.contains("exoskeleton") {
-                inc(&mut score, EnvironmentPlane::Cybernetics, 4);
-                inc(&mut score, EnvironmentPlane::Augmented, 2);
-            }
-        }
-
-        let mut best_plane = EnvironmentPlane::Unknown;
-        let mut best_score = i32::MIN;
-        for (plane, val) in score.iter() {
-            if *val > best_score {
-                best_score = *val;
-                best_plane = plane.clone();
-            }
-        }
-
-        if self.config.log_all_decisions {
-            self.decision_log.push(format!(
-                "[plane-classifier] tags={:?}, hardware={:?} -> {}",
-                input_tags, hardware_profile, best_plane
-            ));
-        }
-
-        best_plane
+        let decision = self.rules.classify(input_tags, hardware_profile);
+        self.record(
+            "plane-classifier",
+            &decision.rule_id,
+            serde_json::json!({ "tags": input_tags, "hardware": hardware_profile }),
+            decision.plane.to_string(),
+            decision.contributing_rules,
+        );
+        decision.plane
     }
 
     pub fn build_environment_metadata(
@@ -411,7 +410,678 @@ impl PlaneClassifier {
         }
     }
 
-    pub fn decision_log(&self) -> &[String] {
+    pub fn decision_log(&self) -> &[DecisionRecord] {
         &self.decision_log
     }
 }
+
+// ---------------------------------------------------------------------------
+// Rule tables
+// ---------------------------------------------------------------------------
+
+pub const PLANE_RULE_TABLE_SCHEMA: u32 = 1;
+
+/// Rule id recorded when no plane rule contributed any score.
+pub const FALLBACK_PLANE_RULE_ID: &str = "fallback:unknown";
+
+/// Which classifier input a plane rule inspects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleSource {
+    Tag,
+    Hardware,
+}
+
+/// Adds `scores` once per input entry (lower-cased) that contains any of the
+/// `any_contains` needles.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlaneScoreRule {
+    pub id: String,
+    pub source: RuleSource,
+    pub any_contains: Vec<String>,
+    pub scores: Vec<(EnvironmentPlane, i32)>,
+}
+
+/// Maps a requested consciousness state to the granted one. `prohibit_mutation`
+/// of `None` matches either config setting; `result` of `None` grants the
+/// requested state unchanged.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConsciousnessRule {
+    pub id: String,
+    pub states: Vec<ConsciousnessState>,
+    #[serde(default)]
+    pub prohibit_mutation: Option<bool>,
+    #[serde(default)]
+    pub result: Option<ConsciousnessState>,
+    #[serde(default)]
+    pub note: String,
+}
+
+/// Versioned classification rules. The table is identified in decision
+/// records by `hash()`, so any edit, including reordering, yields a new id.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlaneRuleTable {
+    pub schema: u32,
+    pub table_id: String,
+    pub version: String,
+    /// Tie-break order when two planes reach the same score; earlier wins.
+    pub plane_priority: Vec<EnvironmentPlane>,
+    pub plane_rules: Vec<PlaneScoreRule>,
+    pub consciousness_rules: Vec<ConsciousnessRule>,
+}
+
+/// Result of running the plane rules over one set of inputs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlaneDecision {
+    pub plane: EnvironmentPlane,
+    pub score: i32,
+    /// Rule that contributed most to `plane` (first in table order on a tie).
+    pub rule_id: String,
+    /// Every rule that fired for `plane`, in table order.
+    pub contributing_rules: Vec<String>,
+}
+
+/// One classifier decision, with enough context to replay it against the
+/// rule table identified by `rule_table_hash`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    pub seq: u64,
+    pub check: String,
+    pub rule_table_id: String,
+    pub rule_table_version: String,
+    pub rule_table_hash: String,
+    pub rule_id: String,
+    pub inputs: serde_json::Value,
+    pub outcome: String,
+    #[serde(default)]
+    pub contributing_rules: Vec<String>,
+}
+
+impl Display for DecisionRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "[{}] {} -> {} (rule {} @ {})",
+            self.check,
+            self.inputs,
+            self.outcome,
+            self.rule_id,
+            &self.rule_table_hash[..self.rule_table_hash.len().min(12)]
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuleTableIssue {
+    UnsupportedSchema(u32),
+    DuplicateRuleId(String),
+    DuplicatePriority(EnvironmentPlane),
+    MissingPriority {
+        rule_id: String,
+        plane: EnvironmentPlane,
+    },
+    Unreachable {
+        rule_id: String,
+        reason: String,
+    },
+    Overlap {
+        first: String,
+        second: String,
+        detail: String,
+    },
+    Gap {
+        state: ConsciousnessState,
+        prohibit_mutation: bool,
+    },
+}
+
+impl Display for RuleTableIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            RuleTableIssue::UnsupportedSchema(v) => write!(f, "unsupported schema {}", v),
+            RuleTableIssue::DuplicateRuleId(id) => write!(f, "duplicate rule id {}", id),
+            RuleTableIssue::DuplicatePriority(p) => {
+                write!(f, "plane {} listed twice in plane_priority", p)
+            }
+            RuleTableIssue::MissingPriority { rule_id, plane } => {
+                write!(
+                    f,
+                    "rule {} scores plane {} which is not in plane_priority",
+                    rule_id, plane
+                )
+            }
+            RuleTableIssue::Unreachable { rule_id, reason } => {
+                write!(f, "rule {} is unreachable: {}", rule_id, reason)
+            }
+            RuleTableIssue::Overlap {
+                first,
+                second,
+                detail,
+            } => {
+                write!(f, "rules {} and {} overlap: {}", first, second, detail)
+            }
+            RuleTableIssue::Gap {
+                state,
+                prohibit_mutation,
+            } => write!(
+                f,
+                "no consciousness rule for {:?} with prohibit_mutation={}",
+                state, prohibit_mutation
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RuleTableError {
+    Io(std::io::Error),
+    Parse(serde_json::Error),
+    Invalid(Vec<RuleTableIssue>),
+}
+
+impl Display for RuleTableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            RuleTableError::Io(e) => write!(f, "rule table io error: {}", e),
+            RuleTableError::Parse(e) => write!(f, "rule table parse error: {}", e),
+            RuleTableError::Invalid(issues) => {
+                write!(f, "rule table rejected:")?;
+                for issue in issues {
+                    write!(f, " {};", issue)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for RuleTableError {}
+
+const ALL_CONSCIOUSNESS_STATES: [ConsciousnessState; 5] = [
+    ConsciousnessState::None,
+    ConsciousnessState::InactiveSticky,
+    ConsciousnessState::InactiveImmutable,
+    ConsciousnessState::ActiveSticky,
+    ConsciousnessState::ActiveImmutable,
+];
+
+impl PlaneRuleTable {
+    /// The mappings the classifier shipped with before rules moved to data;
+    /// mirrored in `config/plane-classifier-rules.v1.json`.
+    pub fn builtin() -> Self {
+        use EnvironmentPlane::*;
+        fn plane(
+            id: &str,
+            source: RuleSource,
+            needles: &[&str],
+            scores: &[(EnvironmentPlane, i32)],
+        ) -> PlaneScoreRule {
+            PlaneScoreRule {
+                id: id.to_string(),
+                source,
+                any_contains: needles.iter().map(|n| n.to_string()).collect(),
+                scores: scores.to_vec(),
+            }
+        }
+        let immutable = vec![
+            ConsciousnessState::ActiveImmutable,
+            ConsciousnessState::InactiveImmutable,
+        ];
+        PlaneRuleTable {
+            schema: PLANE_RULE_TABLE_SCHEMA,
+            table_id: "plane-classifier".to_string(),
+            version: "1.0.0".to_string(),
+            plane_priority: vec![
+                Cybernetics,
+                Augmented,
+                Transhuman,
+                Evolution,
+                Bioscale,
+                Biophysics,
+                Organic,
+                BciHciEeg,
+                RealityOs,
+                ConsciousnessNetwork,
+                NeuralNetwork,
+                Wetware,
+                Bioware,
+                SoftwareOnly,
+            ],
+            plane_rules: vec![
+                plane(
+                    "tag.bci-eeg",
+                    RuleSource::Tag,
+                    &["bci", "eeg"],
+                    &[(BciHciEeg, 3), (Biophysics, 1)],
+                ),
+                plane(
+                    "tag.emg-ecg",
+                    RuleSource::Tag,
+                    &["emg", "ecg"],
+                    &[(Biophysics, 2), (Bioscale, 1)],
+                ),
+                plane(
+                    "tag.immersive",
+                    RuleSource::Tag,
+                    &["avatar", "ar/vr", "oculus"],
+                    &[(Augmented, 3), (RealityOs, 1)],
+                ),
+                plane(
+                    "tag.wetware",
+                    RuleSource::Tag,
+                    &["wetware"],
+                    &[(Wetware, 4)],
+                ),
+                plane(
+                    "tag.bioware",
+                    RuleSource::Tag,
+                    &["bioware"],
+                    &[(Bioware, 4)],
+                ),
+                plane(
+                    "tag.neural-net",
+                    RuleSource::Tag,
+                    &["neural-net", "ml-model"],
+                    &[(NeuralNetwork, 3), (SoftwareOnly, 1)],
+                ),
+                plane(
+                    "tag.quantum",
+                    RuleSource::Tag,
+                    &["quantum"],
+                    &[(Cybernetics, 1), (NeuralNetwork, 1)],
+                ),
+                plane(
+                    "tag.consciousness-net",
+                    RuleSource::Tag,
+                    &["consciousness-net", "soul-guard"],
+                    &[(ConsciousnessNetwork, 4)],
+                ),
+                plane(
+                    "hw.eeg-headset",
+                    RuleSource::Hardware,
+                    &["openbci", "eeg-headset"],
+                    &[(BciHciEeg, 5), (Biophysics, 2)],
+                ),
+                plane(
+                    "hw.implant",
+                    RuleSource::Hardware,
+                    &["implant", "neural-link"],
+                    &[(Transhuman, 4), (Cybernetics, 2)],
+                ),
+                plane(
+                    "hw.exoskeleton",
+                    RuleSource::Hardware,
+                    &["robotic-limb", "exoskeleton"],
+                    &[(Cybernetics, 4), (Augmented, 2)],
+                ),
+            ],
+            consciousness_rules: vec![
+                ConsciousnessRule {
+                    id: "cs.immutable.preserved".to_string(),
+                    states: immutable.clone(),
+                    prohibit_mutation: Some(true),
+                    result: None,
+                    note: "immutable-state requested -> preserved".to_string(),
+                },
+                ConsciousnessRule {
+                    id: "cs.immutable.allowed".to_string(),
+                    states: immutable,
+                    prohibit_mutation: Some(false),
+                    result: None,
+                    note: "immutable-state allowed".to_string(),
+                },
+                ConsciousnessRule {
+                    id: "cs.non-immutable".to_string(),
+                    states: vec![
+                        ConsciousnessState::None,
+                        ConsciousnessState::InactiveSticky,
+                        ConsciousnessState::ActiveSticky,
+                    ],
+                    prohibit_mutation: None,
+                    result: None,
+                    note: "non-immutable state".to_string(),
+                },
+            ],
+        }
+    }
+
+    pub fn from_json(raw: &str) -> Result<Self, RuleTableError> {
+        let table: PlaneRuleTable = serde_json::from_str(raw).map_err(RuleTableError::Parse)?;
+        table.check()?;
+        Ok(table)
+    }
+
+    /// Load and validate a table; rejected tables never reach a classifier.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RuleTableError> {
+        let raw = std::fs::read_to_string(path).map_err(RuleTableError::Io)?;
+        Self::from_json(&raw)
+    }
+
+    /// Hex SHA-256 over the canonical JSON encoding of the table.
+    pub fn hash(&self) -> String {
+        let bytes = serde_json::to_vec(self).expect("rule table serializes");
+        hex::encode(Sha256::digest(&bytes))
+    }
+
+    fn check(&self) -> Result<(), RuleTableError> {
+        let issues = self.validate();
+        if issues.is_empty() {
+            Ok(())
+        } else {
+            Err(RuleTableError::Invalid(issues))
+        }
+    }
+
+    /// Every structural problem in the table. Plane rules overlap when a needle
+    /// of one is contained in a needle of another rule on the same source,
+    /// since the broader rule then fires on every input the narrower one does.
+    /// Consciousness rules must match each (state, prohibit_mutation) pair
+    /// exactly once.
+    pub fn validate(&self) -> Vec<RuleTableIssue> {
+        let mut issues = Vec::new();
+        if self.schema != PLANE_RULE_TABLE_SCHEMA {
+            issues.push(RuleTableIssue::UnsupportedSchema(self.schema));
+        }
+
+        let mut seen_ids = HashSet::new();
+        let ids = self
+            .plane_rules
+            .iter()
+            .map(|r| &r.id)
+            .chain(self.consciousness_rules.iter().map(|r| &r.id));
+        for id in ids {
+            if !seen_ids.insert(id.as_str()) {
+                issues.push(RuleTableIssue::DuplicateRuleId(id.clone()));
+            }
+        }
+
+        let mut seen_planes = HashSet::new();
+        for p in &self.plane_priority {
+            if !seen_planes.insert(p) {
+                issues.push(RuleTableIssue::DuplicatePriority(p.clone()));
+            }
+        }
+
+        for rule in &self.plane_rules {
+            let unreachable = |reason: &str| RuleTableIssue::Unreachable {
+                rule_id: rule.id.clone(),
+                reason: reason.to_string(),
+            };
+            if rule.any_contains.is_empty() {
+                issues.push(unreachable("no needles"));
+            }
+            if rule.any_contains.iter().any(|n| n.is_empty()) {
+                issues.push(unreachable("empty needle"));
+            }
+            if rule.any_contains.iter().any(|n| *n != n.to_lowercase()) {
+                issues.push(unreachable(
+                    "needles must be lower-case; inputs are lower-cased",
+                ));
+            }
+            if !rule.scores.iter().any(|(_, v)| *v > 0) {
+                issues.push(unreachable("no positive score"));
+            }
+            for (p, _) in &rule.scores {
+                if !self.plane_priority.contains(p) {
+                    issues.push(RuleTableIssue::MissingPriority {
+                        rule_id: rule.id.clone(),
+                        plane: p.clone(),
+                    });
+                }
+            }
+        }
+
+        for (i, a) in self.plane_rules.iter().enumerate() {
+            for b in &self.plane_rules[i + 1..] {
+                if a.source != b.source {
+                    continue;
+                }
+                let hit = a.any_contains.iter().find_map(|na| {
+                    b.any_contains
+                        .iter()
+                        .find(|nb| {
+                            !na.is_empty()
+                                && !nb.is_empty()
+                                && (na.contains(nb.as_str()) || nb.contains(na.as_str()))
+                        })
+                        .map(|nb| (na, nb))
+                });
+                if let Some((na, nb)) = hit {
+                    issues.push(RuleTableIssue::Overlap {
+                        first: a.id.clone(),
+                        second: b.id.clone(),
+                        detail: format!("needles {:?} and {:?}", na, nb),
+                    });
+                }
+            }
+        }
+
+        for rule in &self.consciousness_rules {
+            if rule.states.is_empty() {
+                issues.push(RuleTableIssue::Unreachable {
+                    rule_id: rule.id.clone(),
+                    reason: "no states".to_string(),
+                });
+            }
+        }
+        for state in ALL_CONSCIOUSNESS_STATES.iter() {
+            for prohibit in [true, false] {
+                let matching: Vec<&ConsciousnessRule> = self
+                    .consciousness_rules
+                    .iter()
+                    .filter(|r| r.matches(state, prohibit))
+                    .collect();
+                match matching.as_slice() {
+                    [] => issues.push(RuleTableIssue::Gap {
+                        state: state.clone(),
+                        prohibit_mutation: prohibit,
+                    }),
+                    [_] => {}
+                    [first, second, ..] => issues.push(RuleTableIssue::Overlap {
+                        first: first.id.clone(),
+                        second: second.id.clone(),
+                        detail: format!("{:?} with prohibit_mutation={}", state, prohibit),
+                    }),
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// Score every plane and pick the highest, breaking ties by
+    /// `plane_priority`. With no score at all the result is `Unknown`.
+    pub fn classify(&self, input_tags: &[String], hardware_profile: &[String]) -> PlaneDecision {
+        let mut score: HashMap<EnvironmentPlane, i32> = HashMap::new();
+        // Per plane, per rule id: how much each rule contributed.
+        let mut contributions: HashMap<EnvironmentPlane, BTreeMap<usize, i32>> = HashMap::new();
+
+        for (idx, rule) in self.plane_rules.iter().enumerate() {
+            let inputs = match rule.source {
+                RuleSource::Tag => input_tags,
+                RuleSource::Hardware => hardware_profile,
+            };
+            for input in inputs {
+                let lowered = input.to_lowercase();
+                if !rule
+                    .any_contains
+                    .iter()
+                    .any(|n| lowered.contains(n.as_str()))
+                {
+                    continue;
+                }
+                for (plane, v) in &rule.scores {
+                    *score.entry(plane.clone()).or_insert(0) += v;
+                    *contributions
+                        .entry(plane.clone())
+                        .or_default()
+                        .entry(idx)
+                        .or_insert(0) += v;
+                }
+            }
+        }
+
+        let best = self
+            .plane_priority
+            .iter()
+            .filter_map(|p| score.get(p).map(|v| (p, *v)))
+            .fold(
+                None::<(&EnvironmentPlane, i32)>,
+                |best, (p, v)| match best {
+                    Some((_, bv)) if bv >= v => best,
+                    _ => Some((p, v)),
+                },
+            );
+
+        match best {
+            Some((plane, value)) => {
+                let by_rule = &contributions[plane];
+                let top = by_rule
+                    .iter()
+                    .fold(None::<(usize, i32)>, |top, (idx, v)| match top {
+                        Some((_, tv)) if tv >= *v => top,
+                        _ => Some((*idx, *v)),
+                    })
+                    .map(|(idx, _)| idx)
+                    .expect("winning plane has at least one contribution");
+                PlaneDecision {
+                    plane: plane.clone(),
+                    score: value,
+                    rule_id: self.plane_rules[top].id.clone(),
+                    contributing_rules: by_rule
+                        .keys()
+                        .map(|idx| self.plane_rules[*idx].id.clone())
+                        .collect(),
+                }
+            }
+            None => PlaneDecision {
+                plane: EnvironmentPlane::Unknown,
+                score: 0,
+                rule_id: FALLBACK_PLANE_RULE_ID.to_string(),
+                contributing_rules: Vec::new(),
+            },
+        }
+    }
+
+    pub fn consciousness_rule_for(
+        &self,
+        state: &ConsciousnessState,
+        prohibit_mutation: bool,
+    ) -> Option<&ConsciousnessRule> {
+        self.consciousness_rules
+            .iter()
+            .find(|r| r.matches(state, prohibit_mutation))
+    }
+}
+
+impl ConsciousnessRule {
+    fn matches(&self, state: &ConsciousnessState, prohibit_mutation: bool) -> bool {
+        self.states.contains(state)
+            && self
+                .prohibit_mutation
+                .is_none_or(|p| p == prohibit_mutation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PlaneClassifierConfig {
+        PlaneClassifierConfig {
+            prohibit_consciousness_mutation: true,
+            prohibit_brain_token_freeze: true,
+            log_all_decisions: true,
+            allowed_hardware_ids: Vec::new(),
+        }
+    }
+
+    fn strings(v: &[&str]) -> Vec<String> {
+        v.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn shipped_table_matches_builtin_and_validates() {
+        let shipped = PlaneRuleTable::load(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/config/plane-classifier-rules.v1.json"
+        ))
+        .unwrap();
+        assert_eq!(shipped, PlaneRuleTable::builtin());
+        assert!(PlaneRuleTable::builtin().validate().is_empty());
+    }
+
+    #[test]
+    fn rejects_overlapping_unreachable_and_gapped_rules() {
+        let mut table = PlaneRuleTable::builtin();
+        table.plane_rules.push(PlaneScoreRule {
+            id: "tag.eeg-cap".to_string(),
+            source: RuleSource::Tag,
+            any_contains: strings(&["eeg-cap"]),
+            scores: vec![(EnvironmentPlane::BciHciEeg, 1)],
+        });
+        table.plane_rules.push(PlaneScoreRule {
+            id: "tag.silent".to_string(),
+            source: RuleSource::Tag,
+            any_contains: strings(&["organoid"]),
+            scores: vec![(EnvironmentPlane::Organic, 0)],
+        });
+        table
+            .consciousness_rules
+            .retain(|r| r.id != "cs.immutable.allowed");
+
+        let issues = table.validate();
+        assert!(issues.contains(&RuleTableIssue::Overlap {
+            first: "tag.bci-eeg".to_string(),
+            second: "tag.eeg-cap".to_string(),
+            detail: "needles \"eeg\" and \"eeg-cap\"".to_string(),
+        }));
+        assert!(issues.iter().any(|i| matches!(
+            i,
+            RuleTableIssue::Unreachable { rule_id, .. } if rule_id == "tag.silent"
+        )));
+        assert!(issues.contains(&RuleTableIssue::Gap {
+            state: ConsciousnessState::ActiveImmutable,
+            prohibit_mutation: false,
+        }));
+        assert!(matches!(
+            PlaneClassifier::with_rule_table(config(), table),
+            Err(RuleTableError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn decision_records_carry_table_hash_and_replay() {
+        let mut classifier = PlaneClassifier::new(config());
+        let plane = classifier.classify_plane(
+            &strings(&["EEG-session", "quantum"]),
+            &strings(&["OpenBCI-Ultracortex"]),
+        );
+        assert_eq!(plane, EnvironmentPlane::BciHciEeg);
+        classifier.consciousness_state_check(ConsciousnessState::ActiveImmutable);
+        // Equal Cybernetics/NeuralNetwork scores resolve by priority order.
+        assert_eq!(
+            classifier.classify_plane(&strings(&["quantum"]), &[]),
+            EnvironmentPlane::Cybernetics
+        );
+
+        let log = classifier.decision_log();
+        let hash = PlaneRuleTable::builtin().hash();
+        assert!(log.iter().all(|r| r.rule_table_hash == hash));
+        assert_eq!(log[0].rule_id, "hw.eeg-headset");
+        assert_eq!(
+            log[0].contributing_rules,
+            strings(&["tag.bci-eeg", "hw.eeg-headset"])
+        );
+        assert_eq!(log[1].rule_id, "cs.immutable.preserved");
+
+        // An auditor holding the table and the record reproduces the outcome.
+        let record: DecisionRecord =
+            serde_json::from_str(&serde_json::to_string(&log[0]).unwrap()).unwrap();
+        let tags: Vec<String> = serde_json::from_value(record.inputs["tags"].clone()).unwrap();
+        let hw: Vec<String> = serde_json::from_value(record.inputs["hardware"].clone()).unwrap();
+        let replayed = PlaneRuleTable::builtin().classify(&tags, &hw);
+        assert_eq!(replayed.plane.to_string(), record.outcome);
+        assert_eq!(replayed.rule_id, record.rule_id);
+    }
+}
@@ -15,6 +15,8 @@
 use core::fmt;
 use core::time::Duration;
 
+use serde::{Deserialize, Serialize};
+
 // ---------------------- External trait interfaces -------------------------
 
 pub mod quantumhash {
@@ -55,13 +57,15 @@ pub mod quantumhash {
 }
 
 pub mod alndid {
-    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
     pub struct ALNDID {
         pub id: String,
         pub shard: String,
     }
 
-    #[derive(Clone, Debug, PartialEq, Eq)]
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
     pub enum RoleClass {
         Host,
         EthicalOperator,
@@ -71,14 +75,14 @@ pub mod alndid {
         Observer,
     }
 
-    #[derive(Clone, Debug)]
+    #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct AccessEnvelope {
         pub did: ALNDID,
         pub roles: Vec<RoleClass>,
         pub min_biophysics_knowledge_score: f64,
     }
 
-    #[derive(Clone, Debug)]
+    #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct ConsentProof {
         pub did: ALNDID,
         pub evolution_event_id: String,
@@ -183,7 +187,7 @@ pub mod biospectreconsensus {
 // ------------------------ Core time type ----------------------------------
 
 /// Lorentz-consistent timestamp: (proper_time_ns, frame_offset_ps).
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LorentzTimestamp(pub i128, pub i64);
 
 // ---------------------- Biophysical token state ---------------------------
@@ -192,7 +196,7 @@ use alndid::{AccessEnvelope, ALNDID, ConsentProof, DIDDirectory};
 use lifeforcesafety::LifeforceSafety;
 use quantumhash::{LorentzSafeHasher, QuantumHash, QuantumHasher};
 
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct BioTokenState {
     pub brain: f64,
     pub wave: f64,
@@ -275,21 +279,21 @@ impl BioTokenState {
 
 // -------------------- Host frame and runtime events -----------------------
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ALNHostFrame {
     pub host_id: ALNDID,
     pub access: AccessEnvelope,
     pub lorentz_ts: LorentzTimestamp,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum RuntimeEventKind {
     EvolutionUpgrade { evolution_id: String },
     WaveLoad { task_id: String, requested_wave: f64 },
     SmartAutonomy { agent_id: String, requested_smart: f64 },
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RuntimeEvent {
     pub kind: RuntimeEventKind,
     pub initiator: ALNDID,
@@ -308,6 +312,15 @@ pub enum RuntimeError {
 
 pub type RuntimeResult<T> = Result<T, RuntimeError>;
 
+/// An accepted event: the attesting frame plus the state it attests, so
+/// callers can chain the next execution off `post_state`.
+#[derive(Clone, Debug)]
+pub struct ExecutionOutcome {
+    pub frame: biospectreconsensus::ConsensusFrame,
+    pub post_state: BioTokenState,
+    pub state_hash: QuantumHash,
+}
+
 // --------------------------- Runtime config -------------------------------
 
 #[derive(Clone, Debug)]
@@ -470,7 +483,7 @@ where
         previous_frame: Option<ConsensusFrame>,
         host_frame: ALNHostFrame,
         event: RuntimeEvent,
-    ) -> RuntimeResult<ConsensusFrame> {
+    ) -> RuntimeResult<ExecutionOutcome> {
         self.ensure_same_host(&state, &host_frame)?;
 
         let access = self.authenticate_initiator(event.initiator.clone())?;
@@ -530,7 +543,7 @@ where
 
         let frame = ConsensusFrame {
             host_frame: host_frame.clone(),
-            state_hash,
+            state_hash: state_hash.clone(),
             prev_state_hash: previous_frame.as_ref().map(|f| f.state_hash.clone()),
             seq_no,
         };
@@ -539,7 +552,11 @@ where
             .validate_state_step(previous_frame, &frame, &state)
             .map_err(RuntimeError::ConsensusViolation)?;
 
-        Ok(frame)
+        Ok(ExecutionOutcome {
+            frame,
+            post_state: state,
+            state_hash,
+        })
     }
 }
 
@@ -0,0 +1,516 @@
+//! Multi-peer gossip for host nodes.
+//!
+//! Each host signs the `GossipFrame`s it produces with its own Ed25519 key;
+//! peers verify the signature against the public keys in the static peer set,
+//! check the hash link to neighbouring frames they already hold for that host,
+//! drop duplicates and keep both frames as fork evidence when a host announces
+//! two different states for the same sequence number. Accepted frames (and the second frame
+//! of a fork) are relayed to every other peer, so a frame reaches the whole
+//! set as long as it stays connected.
+//!
+//! Transport is newline-delimited JSON over TCP, one connection per send.
+//! Only the local host holds a secret key, so no peer can sign frames (or
+//! fabricate fork evidence) in another host's name. Swap in PQ signatures
+//! alongside the real `QuantumHasher`.
+//!
+//! Memory is bounded: each host keeps a window of `retain_per_host` frames,
+//! frames older than the window are rejected as stale, and at most
+//! `max_fork_evidence` fork records are kept (oldest dropped first).
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::chain::host_node::GossipFrame;
+
+/// Frames retained per host for link checks and duplicate suppression.
+pub const DEFAULT_RETAIN_PER_HOST: usize = 1024;
+/// Fork records retained per node.
+pub const DEFAULT_MAX_FORK_EVIDENCE: usize = 256;
+
+// ------------------ Signing --------------------------------------------------
+
+impl GossipFrame {
+    /// Canonical bytes covered by the signature (everything but `signature`).
+    pub fn signing_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(160);
+        for field in [&self.host_id, &self.shard] {
+            buf.extend_from_slice(&(field.len() as u32).to_be_bytes());
+            buf.extend_from_slice(field.as_bytes());
+        }
+        buf.extend_from_slice(&self.seq_no.to_be_bytes());
+        buf.extend_from_slice(self.state_hash.as_bytes());
+        buf.push(b'|');
+        if let Some(prev) = &self.prev_state_hash {
+            buf.extend_from_slice(prev.as_bytes());
+        }
+        buf
+    }
+
+    pub fn sign(&mut self, key: &SigningKey) {
+        self.signature = hex::encode(key.sign(&self.signing_bytes()).to_bytes());
+    }
+
+    pub fn verify(&self, key: &VerifyingKey) -> bool {
+        let Some(sig) = hex::decode(&self.signature).ok().and_then(|b| Signature::from_slice(&b).ok()) else {
+            return false;
+        };
+        key.verify(&self.signing_bytes(), &sig).is_ok()
+    }
+}
+
+// ------------------ Static peer set ------------------------------------------
+
+/// A peer host: where to reach it and the public key its frames verify with.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GossipPeer {
+    pub host_id: String,
+    pub addr: SocketAddr,
+    /// Hex-encoded Ed25519 public key.
+    pub public_key: String,
+}
+
+/// On-disk form of a peer set. The only secret is the local host's own key.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PeerSetConfig {
+    pub local_host_id: String,
+    /// Hex-encoded 32-byte Ed25519 secret key of this host.
+    pub local_secret_key: String,
+    #[serde(default)]
+    pub peers: Vec<GossipPeer>,
+    #[serde(default)]
+    pub retain_per_host: Option<usize>,
+    #[serde(default)]
+    pub max_fork_evidence: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct PeerSet {
+    local_host_id: String,
+    local_key: SigningKey,
+    peers: Vec<GossipPeer>,
+    keys: HashMap<String, VerifyingKey>,
+    retain_per_host: usize,
+    max_fork_evidence: usize,
+}
+
+fn decode_key<const N: usize>(who: &str, hex_key: &str) -> Result<[u8; N], String> {
+    hex::decode(hex_key)
+        .map_err(|e| format!("invalid key for {who}: {e}"))?
+        .try_into()
+        .map_err(|_| format!("invalid key for {who}: expected {N} bytes"))
+}
+
+impl PeerSet {
+    pub fn new(local_host_id: &str, local_key: SigningKey) -> Self {
+        let mut keys = HashMap::new();
+        keys.insert(local_host_id.to_string(), local_key.verifying_key());
+        Self {
+            local_host_id: local_host_id.to_string(),
+            local_key,
+            peers: Vec::new(),
+            keys,
+            retain_per_host: DEFAULT_RETAIN_PER_HOST,
+            max_fork_evidence: DEFAULT_MAX_FORK_EVIDENCE,
+        }
+    }
+
+    pub fn with_peer(mut self, host_id: &str, addr: SocketAddr, public_key: VerifyingKey) -> Self {
+        self.keys.insert(host_id.to_string(), public_key);
+        self.peers.push(GossipPeer {
+            host_id: host_id.to_string(),
+            addr,
+            public_key: hex::encode(public_key.as_bytes()),
+        });
+        self
+    }
+
+    pub fn from_config(cfg: PeerSetConfig) -> Result<Self, String> {
+        let secret = decode_key::<32>(&cfg.local_host_id, &cfg.local_secret_key)?;
+        let mut set = Self::new(&cfg.local_host_id, SigningKey::from_bytes(&secret));
+        for peer in cfg.peers {
+            if peer.host_id == set.local_host_id {
+                return Err(format!("peer list contains the local host {}", peer.host_id));
+            }
+            let public = decode_key::<32>(&peer.host_id, &peer.public_key)?;
+            let key = VerifyingKey::from_bytes(&public)
+                .map_err(|e| format!("invalid key for {}: {e}", peer.host_id))?;
+            set = set.with_peer(&peer.host_id, peer.addr, key);
+        }
+        if let Some(n) = cfg.retain_per_host {
+            set.retain_per_host = n.max(1);
+        }
+        if let Some(n) = cfg.max_fork_evidence {
+            set.max_fork_evidence = n.max(1);
+        }
+        Ok(set)
+    }
+
+    /// Load a `PeerSetConfig` JSON file.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        let raw = std::fs::read_to_string(path)?;
+        let cfg: PeerSetConfig = serde_json::from_str(&raw)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        Self::from_config(cfg).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    pub fn local_host_id(&self) -> &str {
+        &self.local_host_id
+    }
+
+    pub fn peers(&self) -> &[GossipPeer] {
+        &self.peers
+    }
+
+    pub fn local_public_key(&self) -> VerifyingKey {
+        self.local_key.verifying_key()
+    }
+
+    pub fn key_for(&self, host_id: &str) -> Option<&VerifyingKey> {
+        self.keys.get(host_id)
+    }
+}
+
+// ------------------ Verification & fork detection -----------------------------
+
+/// Two validly signed frames from one host for the same sequence number.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ForkEvidence {
+    pub host_id: String,
+    pub seq_no: u64,
+    pub first: GossipFrame,
+    pub second: GossipFrame,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum GossipVerdict {
+    Accepted,
+    Duplicate,
+    Fork,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum GossipRejection {
+    UnknownHost(String),
+    BadSignature { host_id: String, seq_no: u64 },
+    BrokenLink { host_id: String, seq_no: u64, reason: &'static str },
+    /// Older than the retained window, so it can no longer be link-checked.
+    Stale { host_id: String, seq_no: u64 },
+}
+
+impl fmt::Display for GossipRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownHost(h) => write!(f, "frame from unknown host {h}"),
+            Self::BadSignature { host_id, seq_no } => write!(f, "bad signature on {host_id}#{seq_no}"),
+            Self::BrokenLink { host_id, seq_no, reason } => write!(f, "broken hash link at {host_id}#{seq_no}: {reason}"),
+            Self::Stale { host_id, seq_no } => write!(f, "stale frame {host_id}#{seq_no} is below the retained window"),
+        }
+    }
+}
+
+/// Retained frames of one host and the lowest sequence number still accepted.
+#[derive(Debug, Default)]
+struct HostChain {
+    frames: BTreeMap<u64, GossipFrame>,
+    floor: u64,
+}
+
+/// What this node has accepted from every host, keyed by sequence number.
+#[derive(Debug, Default)]
+pub struct GossipView {
+    chains: HashMap<String, HostChain>,
+    evidence: VecDeque<ForkEvidence>,
+    reported: HashSet<(String, u64, String)>,
+    retain_per_host: usize,
+    max_fork_evidence: usize,
+}
+
+impl GossipView {
+    pub fn new(retain_per_host: usize, max_fork_evidence: usize) -> Self {
+        Self {
+            retain_per_host: retain_per_host.max(1),
+            max_fork_evidence: max_fork_evidence.max(1),
+            ..Default::default()
+        }
+    }
+
+    pub fn observe(&mut self, peers: &PeerSet, frame: &GossipFrame) -> Result<GossipVerdict, GossipRejection> {
+        let host_id = frame.host_id.clone();
+        let seq_no = frame.seq_no;
+        let key = peers.key_for(&host_id).ok_or_else(|| GossipRejection::UnknownHost(host_id.clone()))?;
+        if !frame.verify(key) {
+            return Err(GossipRejection::BadSignature { host_id, seq_no });
+        }
+        let broken = |reason| GossipRejection::BrokenLink { host_id: frame.host_id.clone(), seq_no, reason };
+        match (seq_no, &frame.prev_state_hash) {
+            (0, Some(_)) => return Err(broken("genesis frame carries prev_state_hash")),
+            (n, None) if n > 0 => return Err(broken("missing prev_state_hash")),
+            _ => {}
+        }
+
+        let chain = self.chains.entry(host_id.clone()).or_default();
+        if seq_no < chain.floor {
+            return Err(GossipRejection::Stale { host_id, seq_no });
+        }
+        if let Some(existing) = chain.frames.get(&seq_no) {
+            if existing.state_hash == frame.state_hash && existing.prev_state_hash == frame.prev_state_hash {
+                return Ok(GossipVerdict::Duplicate);
+            }
+            if !self.reported.insert((host_id.clone(), seq_no, frame.state_hash.clone())) {
+                return Ok(GossipVerdict::Duplicate);
+            }
+            self.evidence.push_back(ForkEvidence { host_id, seq_no, first: existing.clone(), second: frame.clone() });
+            while self.evidence.len() > self.max_fork_evidence {
+                if let Some(old) = self.evidence.pop_front() {
+                    self.reported.remove(&(old.host_id, old.seq_no, old.second.state_hash));
+                }
+            }
+            return Ok(GossipVerdict::Fork);
+        }
+        if let Some(prev) = seq_no.checked_sub(1).and_then(|p| chain.frames.get(&p)) {
+            if frame.prev_state_hash.as_ref() != Some(&prev.state_hash) {
+                return Err(broken("prev_state_hash does not match the previous frame"));
+            }
+        }
+        if let Some(next) = chain.frames.get(&(seq_no + 1)) {
+            if next.prev_state_hash.as_ref() != Some(&frame.state_hash) {
+                return Err(broken("state_hash does not match the next frame's prev_state_hash"));
+            }
+        }
+
+        chain.frames.insert(seq_no, frame.clone());
+        while chain.frames.len() > self.retain_per_host {
+            if let Some((evicted, _)) = chain.frames.pop_first() {
+                chain.floor = evicted + 1;
+            }
+        }
+        Ok(GossipVerdict::Accepted)
+    }
+
+    /// Highest accepted `(seq_no, state_hash)` for a host.
+    pub fn head(&self, host_id: &str) -> Option<(u64, String)> {
+        self.chains.get(host_id)?.frames.last_key_value().map(|(seq, f)| (*seq, f.state_hash.clone()))
+    }
+
+    pub fn fork_evidence(&self) -> impl Iterator<Item = &ForkEvidence> {
+        self.evidence.iter()
+    }
+}
+
+// ------------------ Network node ---------------------------------------------
+
+/// Gossip endpoint for one host: verifies inbound frames and relays them.
+#[derive(Clone)]
+pub struct GossipNode {
+    peers: Arc<PeerSet>,
+    view: Arc<Mutex<GossipView>>,
+}
+
+impl GossipNode {
+    pub fn new(peers: PeerSet) -> Self {
+        let view = GossipView::new(peers.retain_per_host, peers.max_fork_evidence);
+        Self { peers: Arc::new(peers), view: Arc::new(Mutex::new(view)) }
+    }
+
+    pub fn peers(&self) -> &PeerSet {
+        &self.peers
+    }
+
+    pub fn head(&self, host_id: &str) -> Option<(u64, String)> {
+        self.view.lock().unwrap().head(host_id)
+    }
+
+    pub fn fork_evidence(&self) -> Vec<ForkEvidence> {
+        self.view.lock().unwrap().fork_evidence().cloned().collect()
+    }
+
+    /// Sign a locally produced frame, record it and send it to every peer.
+    pub async fn publish(&self, mut frame: GossipFrame) -> Result<GossipVerdict, GossipRejection> {
+        frame.host_id = self.peers.local_host_id.clone();
+        frame.sign(&self.peers.local_key);
+        self.receive(frame).await
+    }
+
+    /// Verify a frame and relay it if it was new (accepted or fork evidence).
+    pub async fn receive(&self, frame: GossipFrame) -> Result<GossipVerdict, GossipRejection> {
+        let verdict = self.view.lock().unwrap().observe(&self.peers, &frame)?;
+        if verdict == GossipVerdict::Fork {
+            eprintln!("[HostGossip] fork evidence: {} announced two states at seq {}", frame.host_id, frame.seq_no);
+        }
+        if verdict != GossipVerdict::Duplicate {
+            self.broadcast(&frame).await;
+        }
+        Ok(verdict)
+    }
+
+    async fn broadcast(&self, frame: &GossipFrame) {
+        let mut line = match serde_json::to_vec(frame) {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        line.push(b'\n');
+        for peer in self.peers.peers() {
+            if let Err(e) = send_line(peer.addr, &line).await {
+                eprintln!("[HostGossip] send to {} ({}) failed: {e}", peer.host_id, peer.addr);
+            }
+        }
+    }
+
+    /// Accept inbound gossip connections on `listener`.
+    pub fn serve(&self, listener: TcpListener) -> JoinHandle<()> {
+        let node = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = match listener.accept().await {
+                    Ok(v) => v,
+                    Err(_) => continue,
+                };
+                let node = node.clone();
+                tokio::spawn(async move {
+                    let mut lines = BufReader::new(socket).lines();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let Ok(frame) = serde_json::from_str::<GossipFrame>(line.trim()) else {
+                            continue;
+                        };
+                        if let Err(e) = node.receive(frame).await {
+                            eprintln!("[HostGossip] frame rejected: {e}");
+                        }
+                    }
+                });
+            }
+        })
+    }
+
+    /// Publish every frame the local `HostNode` produces.
+    pub fn forward_local(&self, mut rx: mpsc::Receiver<GossipFrame>) -> JoinHandle<()> {
+        let node = self.clone();
+        tokio::spawn(async move {
+            while let Some(frame) = rx.recv().await {
+                if let Err(e) = node.publish(frame).await {
+                    eprintln!("[HostGossip] local frame rejected: {e}");
+                }
+            }
+        })
+    }
+}
+
+async fn send_line(addr: SocketAddr, line: &[u8]) -> std::io::Result<()> {
+    let mut stream = TcpStream::connect(addr).await?;
+    stream.write_all(line).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(seq_no: u64, state: &str, prev: Option<&str>) -> GossipFrame {
+        GossipFrame {
+            host_id: String::new(),
+            shard: "phx-main".to_string(),
+            seq_no,
+            state_hash: state.to_string(),
+            prev_state_hash: prev.map(str::to_string),
+            signature: String::new(),
+        }
+    }
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    fn signed(host: &str, key: &SigningKey, mut f: GossipFrame) -> GossipFrame {
+        f.host_id = host.to_string();
+        f.sign(key);
+        f
+    }
+
+    fn peers() -> PeerSet {
+        PeerSet::new("local", key(1)).with_peer("host-a", "127.0.0.1:1".parse().unwrap(), key(2).verifying_key())
+    }
+
+    #[test]
+    fn view_checks_signature_links_duplicates_and_forks() {
+        let peers = peers();
+        let mut view = GossipView::new(16, 16);
+        let f0 = signed("host-a", &key(2), frame(0, "h0", None));
+        let f1 = signed("host-a", &key(2), frame(1, "h1", Some("h0")));
+
+        assert_eq!(view.observe(&peers, &f0), Ok(GossipVerdict::Accepted));
+        assert_eq!(view.observe(&peers, &f0), Ok(GossipVerdict::Duplicate));
+        // The local host's key cannot speak for host-a.
+        assert!(matches!(
+            view.observe(&peers, &signed("host-a", &key(1), frame(1, "h1", Some("h0")))),
+            Err(GossipRejection::BadSignature { .. })
+        ));
+        assert!(matches!(
+            view.observe(&peers, &signed("host-a", &key(2), frame(1, "h1", Some("zz")))),
+            Err(GossipRejection::BrokenLink { .. })
+        ));
+        assert!(matches!(
+            view.observe(&peers, &signed("host-x", &key(2), frame(0, "h0", None))),
+            Err(GossipRejection::UnknownHost(_))
+        ));
+        assert_eq!(view.observe(&peers, &f1), Ok(GossipVerdict::Accepted));
+
+        let conflicting = signed("host-a", &key(2), frame(1, "h1-other", Some("h0")));
+        assert_eq!(view.observe(&peers, &conflicting), Ok(GossipVerdict::Fork));
+        assert_eq!(view.observe(&peers, &conflicting), Ok(GossipVerdict::Duplicate));
+        assert_eq!(view.fork_evidence().count(), 1);
+        assert_eq!(view.head("host-a"), Some((1, "h1".to_string())));
+    }
+
+    #[test]
+    fn evicted_frames_stay_rejected_and_evidence_is_capped() {
+        let peers = peers();
+        let mut view = GossipView::new(2, 2);
+        let mut prev: Option<String> = None;
+        for seq in 0..4u64 {
+            let hash = format!("h{seq}");
+            let f = signed("host-a", &key(2), frame(seq, &hash, prev.as_deref()));
+            assert_eq!(view.observe(&peers, &f), Ok(GossipVerdict::Accepted));
+            prev = Some(hash);
+        }
+        // seq 0 and 1 were evicted; replaying them must not re-enter the window.
+        let replayed = signed("host-a", &key(2), frame(0, "h0", None));
+        assert_eq!(
+            view.observe(&peers, &replayed),
+            Err(GossipRejection::Stale { host_id: "host-a".into(), seq_no: 0 })
+        );
+
+        for (i, seq) in [2u64, 3, 2].iter().enumerate() {
+            let prev = format!("h{}", seq - 1);
+            let f = signed("host-a", &key(2), frame(*seq, &format!("fork-{i}"), Some(&prev)));
+            assert_eq!(view.observe(&peers, &f), Ok(GossipVerdict::Fork));
+        }
+        let kept: Vec<_> = view.fork_evidence().map(|e| e.second.state_hash.clone()).collect();
+        assert_eq!(kept, vec!["fork-1".to_string(), "fork-2".to_string()]);
+    }
+
+    #[test]
+    fn config_holds_only_peer_public_keys() {
+        let cfg: PeerSetConfig = serde_json::from_value(serde_json::json!({
+            "local_host_id": "local",
+            "local_secret_key": hex::encode([1u8; 32]),
+            "peers": [{
+                "host_id": "host-a",
+                "addr": "127.0.0.1:1",
+                "public_key": hex::encode(key(2).verifying_key().as_bytes()),
+            }],
+        }))
+        .unwrap();
+        let set = PeerSet::from_config(cfg).unwrap();
+        assert_eq!(set.key_for("host-a"), Some(&key(2).verifying_key()));
+        assert_eq!(set.local_public_key(), key(1).verifying_key());
+    }
+}
@@ -0,0 +1,336 @@
+//! Bounded in-process history for the host JSON-RPC node.
+//!
+//! Keeps the most recent consensus frames and runtime-event outcomes so
+//! operators can page through what happened after a submission, and fans new
+//! frames out to `host.subscribeFrames` clients. Nothing here is durable: the
+//! append-only `consensus/frames.log` remains the record of truth, this is
+//! only the queryable window over it, reseeded from that log on start.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::chain::host_node::ConsensusFrame;
+
+pub const DEFAULT_HISTORY_CAPACITY: usize = 4096;
+pub const DEFAULT_PAGE_LIMIT: usize = 100;
+pub const MAX_PAGE_LIMIT: usize = 500;
+const SUBSCRIBER_BUFFER: usize = 256;
+/// `kind` recorded for events submitted through `host.submitRuntimeEvent`.
+pub const BCI_EVENT_KIND: &str = "bci_event";
+
+/// A consensus frame as retained by the history store.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FrameRecord {
+    pub seq: u64,
+    pub at_ms: i64,
+    pub frame: ConsensusFrame,
+}
+
+/// Outcome of one `host.submitRuntimeEvent` call, accepted or not.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RuntimeEventRecord {
+    pub seq: u64,
+    pub at_ms: i64,
+    pub hostid: String,
+    /// Runtime event kind; `BCI_EVENT_KIND` for `host.submitRuntimeEvent`.
+    pub kind: String,
+    /// Intent label of a BCI event ("grab", "scroll", ...).
+    #[serde(default)]
+    pub intent: Option<String>,
+    pub timestamp_utc: String,
+    pub accepted: bool,
+    pub error_code: Option<i32>,
+    pub error: Option<String>,
+    pub civic_class: Option<String>,
+    pub consensus_frame_id: Option<String>,
+}
+
+/// Filter and cursor for the paged history methods. `kind` and `intent` only
+/// apply to runtime events; time bounds are inclusive unix milliseconds.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default)]
+    pub hostid: Option<String>,
+    #[serde(default)]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub intent: Option<String>,
+    #[serde(default)]
+    pub from_ms: Option<i64>,
+    #[serde(default)]
+    pub to_ms: Option<i64>,
+    /// Return records with `seq` strictly greater than this.
+    #[serde(default)]
+    pub after_seq: Option<u64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl HistoryQuery {
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT)
+    }
+
+    fn in_window(&self, seq: u64, at_ms: i64) -> bool {
+        self.after_seq.is_none_or(|after| seq > after)
+            && self.from_ms.is_none_or(|from| at_ms >= from)
+            && self.to_ms.is_none_or(|to| at_ms <= to)
+    }
+}
+
+/// One page of results. `next_cursor` is the `after_seq` to pass for the next
+/// page, or `None` once the window is exhausted. `oldest_retained_seq` lets a
+/// client notice that records it has not seen yet were already evicted.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HistoryPage<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<u64>,
+    pub oldest_retained_seq: Option<u64>,
+}
+
+struct HistoryInner {
+    frames: VecDeque<FrameRecord>,
+    events: VecDeque<RuntimeEventRecord>,
+    next_frame_seq: u64,
+    next_event_seq: u64,
+}
+
+pub struct HostHistory {
+    capacity: usize,
+    inner: Mutex<HistoryInner>,
+    frames_tx: broadcast::Sender<FrameRecord>,
+}
+
+impl HostHistory {
+    /// Retains at most `capacity` frames and `capacity` events; the oldest
+    /// record is evicted first.
+    pub fn new(capacity: usize) -> Self {
+        let (frames_tx, _) = broadcast::channel(SUBSCRIBER_BUFFER);
+        Self {
+            capacity: capacity.max(1),
+            inner: Mutex::new(HistoryInner {
+                frames: VecDeque::new(),
+                events: VecDeque::new(),
+                next_frame_seq: 0,
+                next_event_seq: 0,
+            }),
+            frames_tx,
+        }
+    }
+
+    /// Capacity from `HOST_HISTORY_CAPACITY`, falling back to the default.
+    pub fn from_env() -> Self {
+        let capacity = std::env::var("HOST_HISTORY_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_HISTORY_CAPACITY);
+        Self::new(capacity)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Retain a frame and publish it to subscribers.
+    pub fn record_frame(&self, frame: &ConsensusFrame) -> FrameRecord {
+        let record = {
+            let mut inner = self.inner.lock().unwrap();
+            let record = FrameRecord {
+                seq: inner.next_frame_seq,
+                at_ms: timestamp_ms(&frame.timestamp_utc),
+                frame: frame.clone(),
+            };
+            inner.next_frame_seq += 1;
+            if inner.frames.len() == self.capacity {
+                inner.frames.pop_front();
+            }
+            inner.frames.push_back(record.clone());
+            record
+        };
+        // No receivers is not an error: nobody is subscribed right now.
+        let _ = self.frames_tx.send(record.clone());
+        record
+    }
+
+    /// Retain frames read back from the consensus log without publishing
+    /// them; only the newest `capacity` are kept.
+    pub fn seed_frames(&self, frames: impl IntoIterator<Item = ConsensusFrame>) {
+        let mut inner = self.inner.lock().unwrap();
+        for frame in frames {
+            let record = FrameRecord {
+                seq: inner.next_frame_seq,
+                at_ms: timestamp_ms(&frame.timestamp_utc),
+                frame,
+            };
+            inner.next_frame_seq += 1;
+            if inner.frames.len() == self.capacity {
+                inner.frames.pop_front();
+            }
+            inner.frames.push_back(record);
+        }
+    }
+
+    /// Retain a runtime-event outcome; `seq` and `at_ms` are assigned here.
+    pub fn record_event(&self, mut record: RuntimeEventRecord) -> RuntimeEventRecord {
+        let mut inner = self.inner.lock().unwrap();
+        record.seq = inner.next_event_seq;
+        record.at_ms = timestamp_ms(&record.timestamp_utc);
+        inner.next_event_seq += 1;
+        if inner.events.len() == self.capacity {
+            inner.events.pop_front();
+        }
+        inner.events.push_back(record.clone());
+        record
+    }
+
+    pub fn frames(&self, q: &HistoryQuery) -> HistoryPage<FrameRecord> {
+        let inner = self.inner.lock().unwrap();
+        let matching = inner.frames.iter().filter(|r| {
+            q.in_window(r.seq, r.at_ms) && q.hostid.as_ref().is_none_or(|h| *h == r.frame.hostid)
+        });
+        page(matching, q.limit(), |r| r.seq, inner.frames.front().map(|r| r.seq))
+    }
+
+    pub fn events(&self, q: &HistoryQuery) -> HistoryPage<RuntimeEventRecord> {
+        let inner = self.inner.lock().unwrap();
+        let matching = inner.events.iter().filter(|r| {
+            q.in_window(r.seq, r.at_ms)
+                && q.hostid.as_ref().is_none_or(|h| *h == r.hostid)
+                && q.kind.as_ref().is_none_or(|k| *k == r.kind)
+                && q.intent.as_ref().is_none_or(|i| r.intent.as_ref() == Some(i))
+        });
+        page(matching, q.limit(), |r| r.seq, inner.events.front().map(|r| r.seq))
+    }
+
+    /// Receiver for frames recorded from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<FrameRecord> {
+        self.frames_tx.subscribe()
+    }
+}
+
+fn page<'a, T: Clone + 'a>(
+    matching: impl Iterator<Item = &'a T>,
+    limit: usize,
+    seq_of: impl Fn(&T) -> u64,
+    oldest_retained_seq: Option<u64>,
+) -> HistoryPage<T> {
+    let mut items: Vec<T> = matching.take(limit + 1).cloned().collect();
+    let next_cursor = if items.len() > limit {
+        items.truncate(limit);
+        items.last().map(&seq_of)
+    } else {
+        None
+    };
+    HistoryPage { items, next_cursor, oldest_retained_seq }
+}
+
+/// RFC 3339 timestamps are indexed by their instant; anything unparseable
+/// falls back to the time it was recorded.
+fn timestamp_ms(timestamp_utc: &str) -> i64 {
+    chrono::DateTime::parse_from_rfc3339(timestamp_utc)
+        .map(|t| t.timestamp_millis())
+        .unwrap_or_else(|_| chrono::Utc::now().timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(hostid: &str, minute: u32) -> ConsensusFrame {
+        ConsensusFrame {
+            frame_id: format!("0xFRM-{hostid}-{minute}"),
+            prev_frame_id: "0xGENESIS".to_string(),
+            timestamp_utc: format!("2026-01-01T00:{minute:02}:00Z"),
+            hostid: hostid.to_string(),
+            event_hash: format!("0xEVT-{minute}"),
+            lifeforce_ok: true,
+            eco_cost: 0.1,
+            eco_band: "low".to_string(),
+        }
+    }
+
+    fn event(kind: &str, intent: &str, accepted: bool) -> RuntimeEventRecord {
+        RuntimeEventRecord {
+            seq: 0,
+            at_ms: 0,
+            hostid: "host-a".to_string(),
+            kind: kind.to_string(),
+            intent: Some(intent.to_string()),
+            timestamp_utc: "2026-01-01T00:00:00Z".to_string(),
+            accepted,
+            error_code: None,
+            error: None,
+            civic_class: None,
+            consensus_frame_id: None,
+        }
+    }
+
+    #[test]
+    fn pages_frames_by_host_and_time_range() {
+        let h = HostHistory::new(64);
+        for m in 0..10 {
+            h.record_frame(&frame(if m % 2 == 0 { "host-a" } else { "host-b" }, m));
+        }
+        let from = timestamp_ms("2026-01-01T00:02:00Z");
+        let mut q = HistoryQuery {
+            hostid: Some("host-a".into()),
+            from_ms: Some(from),
+            limit: Some(2),
+            ..Default::default()
+        };
+        let first = h.frames(&q);
+        assert_eq!(first.items.iter().map(|r| r.seq).collect::<Vec<_>>(), vec![2, 4]);
+        assert_eq!(first.next_cursor, Some(4));
+
+        q.after_seq = first.next_cursor;
+        let second = h.frames(&q);
+        assert_eq!(second.items.iter().map(|r| r.seq).collect::<Vec<_>>(), vec![6, 8]);
+        assert_eq!(second.next_cursor, None);
+    }
+
+    #[test]
+    fn evicts_oldest_and_filters_events_by_kind() {
+        let h = HostHistory::new(3);
+        let submitted = [("bci_event", "grab"), ("wave_load", "scroll"), ("bci_event", "grab"), ("bci_event", "scroll")];
+        for (i, (kind, intent)) in submitted.iter().enumerate() {
+            h.record_event(event(kind, intent, i != 1));
+        }
+        let all = h.events(&HistoryQuery::default());
+        assert_eq!(all.items.len(), 3);
+        assert_eq!(all.oldest_retained_seq, Some(1));
+        let bci = h.events(&HistoryQuery { kind: Some("bci_event".into()), ..Default::default() });
+        assert_eq!(bci.items.iter().map(|r| r.seq).collect::<Vec<_>>(), vec![2, 3]);
+        let grabs = h.events(&HistoryQuery {
+            kind: Some("bci_event".into()),
+            intent: Some("grab".into()),
+            ..Default::default()
+        });
+        assert_eq!(grabs.items.iter().map(|r| r.seq).collect::<Vec<_>>(), vec![2]);
+    }
+
+    #[test]
+    fn subscribers_receive_frames_recorded_after_subscribing() {
+        let h = HostHistory::new(8);
+        h.record_frame(&frame("host-a", 0));
+        let mut rx = h.subscribe();
+        h.record_frame(&frame("host-a", 1));
+        let got = rx.try_recv().unwrap();
+        assert_eq!(got.seq, 1);
+        assert_eq!(got.frame.frame_id, "0xFRM-host-a-1");
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn seeded_frames_keep_the_newest_and_do_not_publish() {
+        let h = HostHistory::new(2);
+        let mut rx = h.subscribe();
+        h.seed_frames((0..3).map(|m| frame("host-a", m)));
+        assert!(rx.try_recv().is_err());
+        let page = h.frames(&HistoryQuery::default());
+        assert_eq!(page.items.iter().map(|r| r.seq).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(h.record_frame(&frame("host-a", 3)).seq, 3);
+    }
+}
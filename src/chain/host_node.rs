@@ -18,7 +18,7 @@ use std::sync::{Arc, RwLock};
 use serde::{Deserialize, Serialize};
 use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tokio::task::JoinHandle;
 
 use crate::chain::biophysical_runtime::{
@@ -31,6 +31,7 @@ use crate::chain::biophysical_runtime::{
     LorentzTimeSource,
     LorentzTimestamp,
     RuntimeEvent as CoreRuntimeEvent,
+    ExecutionOutcome,
     RuntimeEventKind,
     RuntimeResult,
     RuntimeConfig,
@@ -38,11 +39,15 @@ use crate::chain::biophysical_runtime::{
     SystemLorentzClock,
 };
 
-use crate::security::{AuthEnvelope, CivicClass};
-use crate::civic_profile::CivicRewardProfile;
+use crate::chain::host_gossip::{GossipNode, PeerSet};
+use crate::chain::host_history::{HistoryQuery, HostHistory, RuntimeEventRecord, BCI_EVENT_KIND};
+use crate::chain::runtime_event_log::{self, ReplayError, ReplayReport, RuntimeEventLog};
+use crate::security::AuthEnvelope;
+use civic_policy::{CivicClass, CivicPolicy};
 use crate::civic_audit::{CivicAuditEntry, append_civic_audit_entry, eco_band_label};
 use augdoctorpolicies::neurohandshakeorchestrator::{
-    HandshakePhase, NeuroHandshakeOrchestrator, NeuroHandshakeState,
+    unix_millis, HandshakeEvent, HandshakePhase, NeuroHandshakeOrchestrator,
+    NeuroHandshakeState,
 };
 use augdoctorpolicies::shotlevelpolicy::{
     ShotLevel, ShotLevelDecision, ShotLevelPolicy, ShotLevelPolicyConfig,
@@ -69,11 +74,15 @@ struct InnerStore {
 
 impl HostStorage {
     fn new(initial_state: BioTokenState) -> Self {
+        Self::restore(initial_state, None)
+    }
+
+    fn restore(
+        state: BioTokenState,
+        last_frame: Option<biospectre_consensus::ConsensusFrame>,
+    ) -> Self {
         Self {
-            inner: Arc::new(RwLock::new(InnerStore {
-                state: initial_state,
-                last_frame: None,
-            })),
+            inner: Arc::new(RwLock::new(InnerStore { state, last_frame })),
         }
     }
 
@@ -191,13 +200,17 @@ fn default_lifeforce_state() -> lifeforce_safety::LifeforceState {
 
 // ------------------ Gossip messages -----------------------------------------
 
-#[derive(Debug, Serialize, Deserialize)]
-struct GossipFrame {
-    host_id: String,
-    shard: String,
-    seq_no: u64,
-    state_hash: String,
-    prev_state_hash: Option<String>,
+/// Frame announced to peers after each applied event. `signature` is filled
+/// in by the gossip layer (`host_gossip`) with the origin host's Ed25519 key.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GossipFrame {
+    pub host_id: String,
+    pub shard: String,
+    pub seq_no: u64,
+    pub state_hash: String,
+    pub prev_state_hash: Option<String>,
+    #[serde(default)]
+    pub signature: String,
 }
 
 // ------------------ Outer JSON-RPC for AI-Chat ------------------------------
@@ -305,7 +318,7 @@ pub struct HostNodePaths {
     pub state_file: PathBuf,
     pub consensus_log: PathBuf,
     pub civic_audit_log: PathBuf,
-    pub civic_profile_json: PathBuf,
+    pub civic_policy_json: PathBuf,
 }
 
 impl HostNodePaths {
@@ -315,7 +328,7 @@ impl HostNodePaths {
             state_file: base_dir.join("state/bio_token_state.json"),
             consensus_log: base_dir.join("consensus/frames.log"),
             civic_audit_log: base_dir.join("audit/civic-audit-log.jsonl"),
-            civic_profile_json: base_dir.join("profiles/civic-reward-profile.json"),
+            civic_policy_json: base_dir.join("profiles/civic-policy.v1.json"),
             base_dir,
         }
     }
@@ -330,7 +343,7 @@ impl HostNodePaths {
         if let Some(parent) = self.civic_audit_log.parent() {
             fs::create_dir_all(parent)?;
         }
-        if let Some(parent) = self.civic_profile_json.parent() {
+        if let Some(parent) = self.civic_policy_json.parent() {
             fs::create_dir_all(parent)?;
         }
         Ok(())
@@ -485,12 +498,15 @@ pub struct HostNodeState {
     pub hostid: String,
     pub ledger: Arc<Mutex<InnerLedger>>,
     pub rope: Arc<Mutex<NeuralRope>>,
-    pub civic_profile: Arc<CivicRewardProfile>,
+    pub civic_policy: Arc<CivicPolicy>,
     pub did_directory: Arc<DidDirectory>,
     pub consent_verifier: Arc<ConsentVerifier>,
     pub consensus: Arc<Mutex<HostConsensus>>,
     pub handshake_state: Arc<Mutex<NeuroHandshakeState>>,
     pub shot_policy: Arc<ShotLevelPolicy>,
+    pub history: Arc<HostHistory>,
+    /// Gossip endpoint when the host has joined a peer set.
+    pub gossip: Option<GossipNode>,
     pub paths: HostNodePaths,
 }
 
@@ -512,23 +528,14 @@ impl HostNodeState {
             InnerLedger::new_default(hostid.to_string())
         };
 
-        // Civic profile from JSON (or default).
-        let civic_profile = CivicRewardProfile::load_from_json(&paths.civic_profile_json)
-            .unwrap_or_else(|_| CivicRewardProfile {
-                multiplier_min: 0.0,
-                multiplier_max: 4.0,
-                default_multiplier: 1.0,
-                required_knowledge_factor: 0.60,
-                heroic_tags: std::collections::HashSet::new(),
-                heroic_multiplier: 3.0,
-                good_tags: std::collections::HashSet::new(),
-                good_multiplier: 1.5,
-                neutral_multiplier: 1.0,
-                disallowed_tags: std::collections::HashSet::new(),
-                eco_bonus_enabled: false,
-                eco_low_threshold: 0.0,
-                eco_low_bonus: 1.0,
-            });
+        // Civic policy from JSON; a missing or invalid file falls back to the
+        // built-in policy so every host classifies the same way.
+        let civic_policy = CivicPolicy::load(&paths.civic_policy_json).unwrap_or_else(|e| {
+            if paths.civic_policy_json.exists() {
+                eprintln!("host_node: {e}; using built-in civic policy");
+            }
+            CivicPolicy::builtin()
+        });
 
         let did_directory = DidDirectory::new();
         let consent_verifier = ConsentVerifier::new();
@@ -544,20 +551,32 @@ impl HostNodeState {
             mintokenbudgetforfewshot: 512,
         });
 
+        // History outlives restarts only as far as the frame log does.
+        let history = HostHistory::from_env();
+        history.seed_frames(read_consensus_frames(&paths.consensus_log)?);
+
         Ok(Self {
             hostid: hostid.to_string(),
             ledger: Arc::new(Mutex::new(ledger)),
             rope: Arc::new(Mutex::new(NeuralRope::new())),
-            civic_profile: Arc::new(civic_profile),
+            civic_policy: Arc::new(civic_policy),
             did_directory: Arc::new(did_directory),
             consent_verifier: Arc::new(consent_verifier),
             consensus: Arc::new(Mutex::new(consensus)),
             handshake_state: Arc::new(Mutex::new(handshake_state)),
             shot_policy: Arc::new(shot_policy),
+            history: Arc::new(history),
+            gossip: None,
             paths,
         })
     }
 
+    /// Expose `gossip` (and its fork evidence) over JSON-RPC.
+    pub fn with_gossip(mut self, gossip: GossipNode) -> Self {
+        self.gossip = Some(gossip);
+        self
+    }
+
     pub fn persist_state(&self) -> std::io::Result<()> {
         let ledger = self.ledger.lock().unwrap();
         let state: BioTokenState = ledger.to_state();
@@ -586,6 +605,30 @@ impl HostNodeState {
     }
 }
 
+/// Frames from `consensus/frames.log`, oldest first. A torn last line (crash
+/// mid-append) is skipped; a missing log is empty.
+fn read_consensus_frames(path: &Path) -> std::io::Result<Vec<ConsensusFrame>> {
+    let file = match fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut frames = Vec::new();
+    let mut lines = StdBufReader::new(file).lines().peekable();
+    while let Some(line) = lines.next() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(frame) => frames.push(frame),
+            Err(_) if lines.peek().is_none() => break,
+            Err(e) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        }
+    }
+    Ok(frames)
+}
+
 // ------------------ Runtime event pipeline (AI-Chat → inner ledger) ---------
 
 async fn handle_runtime_event(
@@ -604,15 +647,19 @@ async fn handle_runtime_event(
     }
 
     // 2. Civic classification and knowledge floor.
-    let profile = state.civic_profile.as_ref();
-    let civic_class = profile.classify(&event.civic_tags);
+    let policy = state.civic_policy.as_ref();
+    let civic = policy.classify(&event.civic_tags);
+    let civic_class = civic.class;
     if civic_class == CivicClass::Disallowed {
         return Err(JsonRpcError {
             code: -32002,
-            message: "disallowed civic class".to_string(),
+            message: format!(
+                "disallowed civic class (policy {}@{}, rule {:?})",
+                civic.policy_id, civic.policy_version, civic.fired
+            ),
         });
     }
-    if event.auth.knowledge_factor < profile.required_knowledge_factor {
+    if event.auth.knowledge_factor < policy.required_knowledge_factor() {
         return Err(JsonRpcError {
             code: -32003,
             message: "knowledge factor below required threshold".to_string(),
@@ -622,11 +669,28 @@ async fn handle_runtime_event(
     // 3. Neuro‑handshake progression (host‑local Phoenix test).
     let mut handshake_state = state.handshake_state.lock().unwrap().clone();
     if handshake_state.phase != HandshakePhase::Operation {
-        handshake_state =
-            NeuroHandshakeOrchestrator::apply_event(handshake_state, "userconsented");
-        handshake_state =
-            NeuroHandshakeOrchestrator::apply_event(handshake_state, "calibrationsamplerecorded");
+        // Consent is implied unless the user revoked it (a timeout reset does
+        // not count); a revoked session waits for the user to consent again.
+        let step = match handshake_state.phase {
+            HandshakePhase::Safety if NeuroHandshakeOrchestrator::may_auto_consent(&handshake_state) => {
+                Some(HandshakeEvent::UserConsented)
+            }
+            HandshakePhase::Safety => None,
+            _ => Some(HandshakeEvent::CalibrationSampleRecorded {
+                channel: Some("eeg".to_string()),
+            }),
+        };
+        let applied = match step {
+            Some(step) => NeuroHandshakeOrchestrator::apply(&mut handshake_state, &step, unix_millis()).err(),
+            None => NeuroHandshakeOrchestrator::expire(&mut handshake_state, unix_millis()),
+        };
         *state.handshake_state.lock().unwrap() = handshake_state.clone();
+        if let Some(rejection) = applied {
+            return Err(JsonRpcError {
+                code: -32004,
+                message: format!("handshake rejected: {rejection}"),
+            });
+        }
         if handshake_state.phase != HandshakePhase::Operation {
             return Err(JsonRpcError {
                 code: -32004,
@@ -641,7 +705,7 @@ async fn handle_runtime_event(
     // 4. Eco‑aware multiplier.
     let eco_cost = event.bci_event.eco_cost_estimate;
     let multiplier =
-        profile.eco_adjusted_multiplier(civic_class.clone(), eco_cost);
+        policy.eco_adjusted_multiplier(civic_class, eco_cost);
     let eco_band = eco_band_label(eco_cost);
 
     // 5. Shot‑level policy (observability only).
@@ -674,13 +738,16 @@ async fn handle_runtime_event(
         &adjusted_event,
         handshake_copy,
         &id_header,
-        profile.required_knowledge_factor,
+        policy.required_knowledge_factor(),
         &event.timestamp_utc,
     );
 
     let (ledger_result, new_handshake, _le, shot_level) = match res {
         Ok(v) => v,
         Err(e) => {
+            if let Some(h) = e.handshake() {
+                *state.handshake_state.lock().unwrap() = h.clone();
+            }
             return Err(JsonRpcError {
                 code: -32005,
                 message: format!("ledger_orchestrator_error:{e}"),
@@ -707,7 +774,7 @@ async fn handle_runtime_event(
     let audit_entry = CivicAuditEntry {
         timestamp_utc: event.timestamp_utc.clone(),
         civic_tags: event.civic_tags.clone(),
-        civic_class: civic_class.clone(),
+        civic_class,
         reward_multiplier: multiplier,
         eco_cost,
         eco_band: eco_band.clone(),
@@ -738,6 +805,7 @@ async fn handle_runtime_event(
     );
     drop(consensus);
     let _ = state.append_consensus_frame(&frame);
+    state.history.record_frame(&frame);
 
     // 9. Persist inner state.
     let _ = state.persist_state();
@@ -746,6 +814,8 @@ async fn handle_runtime_event(
     let result = serde_json::json!({
         "hostid": state.hostid,
         "civic_class": format!("{:?}", civic_class),
+        "civic_rule": civic.fired,
+        "civic_policy_version": civic.policy_version,
         "reward_multiplier": multiplier,
         "eco_band": eco_band,
         "lifeforce_ok": lifeforce_ok,
@@ -779,14 +849,38 @@ async fn handle_jsonrpc(
             let parsed: Result<RuntimeEvent, _> =
                 serde_json::from_value(req.params.clone());
             match parsed {
-                Ok(ev) => match handle_runtime_event(state, ev).await {
-                    Ok(val) => {
-                        response.result = Some(val);
-                    }
-                    Err(err) => {
-                        response.error = Some(err);
+                Ok(ev) => {
+                    let history = state.history.clone();
+                    let mut record = RuntimeEventRecord {
+                        seq: 0,
+                        at_ms: 0,
+                        hostid: state.hostid.clone(),
+                        kind: BCI_EVENT_KIND.to_string(),
+                        intent: Some(ev.bci_event.intent_label.clone()),
+                        timestamp_utc: ev.timestamp_utc.clone(),
+                        accepted: false,
+                        error_code: None,
+                        error: None,
+                        civic_class: None,
+                        consensus_frame_id: None,
+                    };
+                    match handle_runtime_event(state, ev).await {
+                        Ok(val) => {
+                            record.accepted = true;
+                            record.civic_class =
+                                val["civic_class"].as_str().map(str::to_string);
+                            record.consensus_frame_id =
+                                val["consensus_frame_id"].as_str().map(str::to_string);
+                            response.result = Some(val);
+                        }
+                        Err(err) => {
+                            record.error_code = Some(err.code);
+                            record.error = Some(err.message.clone());
+                            response.error = Some(err);
+                        }
                     }
-                },
+                    history.record_event(record);
+                }
                 Err(e) => {
                     response.error = Some(JsonRpcError {
                         code: -32602,
@@ -798,12 +892,64 @@ async fn handle_jsonrpc(
         "host.getHandshakePhase" => {
             let phase = {
                 let hs = state.handshake_state.lock().unwrap();
-                hs.phase.clone()
+                hs.phase
             };
             response.result = Some(serde_json::json!({
                 "phase": format!("{:?}", phase)
             }));
         }
+        "host.getFrames" | "host.getRuntimeEvents" => {
+            let query: Result<HistoryQuery, _> = if req.params.is_null() {
+                Ok(HistoryQuery::default())
+            } else {
+                serde_json::from_value(req.params.clone())
+            };
+            match query {
+                Ok(q) => {
+                    let page = if req.method == "host.getFrames" {
+                        serde_json::to_value(state.history.frames(&q))
+                    } else {
+                        serde_json::to_value(state.history.events(&q))
+                    };
+                    match page {
+                        Ok(v) => response.result = Some(v),
+                        Err(e) => {
+                            response.error = Some(JsonRpcError {
+                                code: -32603,
+                                message: format!("internal error: {e}"),
+                            });
+                        }
+                    }
+                }
+                Err(e) => {
+                    response.error = Some(JsonRpcError {
+                        code: -32602,
+                        message: format!("invalid params: {e}"),
+                    });
+                }
+            }
+        }
+        "host.getForkEvidence" => {
+            let items = state.gossip.as_ref().map(GossipNode::fork_evidence).unwrap_or_default();
+            response.result = Some(serde_json::json!({
+                "gossip_enabled": state.gossip.is_some(),
+                "items": items,
+            }));
+        }
+        "host.getBioTokenState" => {
+            let bio_state = state.ledger.lock().unwrap().to_state();
+            response.result = Some(serde_json::json!({
+                "hostid": state.hostid,
+                "state": bio_state,
+            }));
+        }
+        "host.subscribeFrames" => {
+            // Only reachable without a socket to stream on.
+            response.error = Some(JsonRpcError {
+                code: -32601,
+                message: "host.subscribeFrames requires a streaming connection".to_string(),
+            });
+        }
         "host.getHostInfo" => {
             response.result = Some(serde_json::json!({
                 "hostid": state.hostid,
@@ -824,21 +970,68 @@ async fn handle_jsonrpc(
     response
 }
 
-/// Start a single‑host JSON‑RPC server for AI‑Chat platforms.
+/// Stream frames to a `host.subscribeFrames` client until it disconnects.
 ///
-/// Example call:
-///   { "jsonrpc":"2.0", "id":1, "method":"host.submitRuntimeEvent", "params":{...} }
-pub async fn run_host_node(
-    hostid: &str,
-    base_dir: impl AsRef<Path>,
-    bind_addr: &str,
-) -> std::io::Result<JoinHandle<()>> {
-    let state = HostNodeState::load_or_init(hostid, base_dir)?;
-    let listener = TcpListener::bind(bind_addr).await
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::AddrInUse, e))?;
-    let shared = Arc::new(state);
+/// The first line is the JSON-RPC response carrying the subscription id; each
+/// later line is a `host.frame` notification (or `host.framesLagged` when the
+/// client fell too far behind and frames were skipped). `params.hostid`
+/// optionally restricts the stream to one host.
+async fn stream_frames(
+    state: &HostNodeState,
+    req: JsonRpcRequest,
+    socket: &mut TcpStream,
+) -> std::io::Result<()> {
+    let hostid = req.params.get("hostid").and_then(|v| v.as_str()).map(str::to_string);
+    let subscription = format!("sub-{}", Uuid::new_v4());
+    let mut rx = state.history.subscribe();
 
-    let handle = tokio::spawn(async move {
+    let ack = JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id: req.id,
+        result: Some(serde_json::json!({ "subscription": subscription })),
+        error: None,
+    };
+    write_line(socket, &serde_json::to_value(&ack)?).await?;
+
+    loop {
+        let notification = match rx.recv().await {
+            Ok(record) => {
+                if hostid.as_ref().is_some_and(|h| *h != record.frame.hostid) {
+                    continue;
+                }
+                serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "method": "host.frame",
+                    "params": { "subscription": subscription, "record": record },
+                })
+            }
+            Err(broadcast::error::RecvError::Lagged(missed)) => serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "host.framesLagged",
+                "params": { "subscription": subscription, "missed": missed },
+            }),
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        };
+        // A write error means the client went away; that ends the subscription.
+        write_line(socket, &notification).await?;
+    }
+}
+
+async fn write_line(socket: &mut TcpStream, value: &serde_json::Value) -> std::io::Result<()> {
+    let mut raw = serde_json::to_vec(value)?;
+    raw.push(b'\n');
+    socket.write_all(&raw).await?;
+    socket.flush().await
+}
+
+/// Serve JSON-RPC on an already-bound listener.
+///
+/// One request per connection: the client writes the request and shuts down
+/// its write half, then reads the response. `host.subscribeFrames` keeps the
+/// connection open and streams newline-delimited notifications instead.
+pub fn serve_jsonrpc(listener: TcpListener, state: HostNodeState) -> JoinHandle<()> {
+    let shared = Arc::new(state);
+    tokio::spawn(async move {
         loop {
             let (mut socket, _) = match listener.accept().await {
                 Ok(v) => v,
@@ -853,6 +1046,10 @@ pub async fn run_host_node(
                 let req: Result<JsonRpcRequest, _> =
                     serde_json::from_slice(&buf);
                 let resp = match req {
+                    Ok(r) if r.method == "host.subscribeFrames" => {
+                        let _ = stream_frames(&state, r, &mut socket).await;
+                        return;
+                    }
                     Ok(r) => handle_jsonrpc((*state).clone(), r).await,
                     Err(e) => JsonRpcResponse {
                         jsonrpc: "2.0".to_string(),
@@ -868,9 +1065,28 @@ pub async fn run_host_node(
                 let _ = socket.write_all(&raw).await;
             });
         }
-    });
+    })
+}
 
-    Ok(handle)
+/// Start a single‑host JSON‑RPC server for AI‑Chat platforms.
+///
+/// Example call:
+///   { "jsonrpc":"2.0", "id":1, "method":"host.submitRuntimeEvent", "params":{...} }
+///
+/// History and state methods:
+///   host.getFrames / host.getRuntimeEvents  params: HistoryQuery
+///   host.getBioTokenState                   params: null
+///   host.getForkEvidence                    params: null
+///   host.subscribeFrames                    params: { "hostid"?: "..." }
+pub async fn run_host_node(
+    hostid: &str,
+    base_dir: impl AsRef<Path>,
+    bind_addr: &str,
+) -> std::io::Result<JoinHandle<()>> {
+    let state = HostNodeState::load_or_init(hostid, base_dir)?;
+    let listener = TcpListener::bind(bind_addr).await
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::AddrInUse, e))?;
+    Ok(serve_jsonrpc(listener, state))
 }
 
 // ------------------ Inner HostNode (Lorentz + BiophysicalRuntime) -----------
@@ -886,6 +1102,7 @@ where
     storage: HostStorage,
     clock: Arc<dyn LorentzTimeSource + Send + Sync>,
     gossip_tx: mpsc::Sender<GossipFrame>,
+    event_log: Option<Arc<Mutex<RuntimeEventLog>>>,
 }
 
 impl<D, C, HC> HostNode<D, C, HC>
@@ -907,10 +1124,18 @@ where
             storage,
             clock: Arc::new(SystemLorentzClock),
             gossip_tx,
+            event_log: None,
         };
         (node, gossip_rx)
     }
 
+    /// Resume from a replayed event log and keep appending accepted events to it.
+    pub fn with_event_log(mut self, log: RuntimeEventLog, replayed: ReplayReport) -> Self {
+        self.storage = HostStorage::restore(replayed.state, replayed.last_frame);
+        self.event_log = Some(Arc::new(Mutex::new(log)));
+        self
+    }
+
     fn build_host_frame(&self) -> ALNHostFrame {
         ALNHostFrame {
             host_id: self.host_id.clone(),
@@ -990,18 +1215,34 @@ where
 
                 let runtime_event = self.convert_rpc_event(event);
                 let previous = self.storage.read_last_frame();
-                let previous_ref = previous.as_ref();
 
-                let result: RuntimeResult<biospectre_consensus::ConsensusFrame> =
-                    self.runtime
-                        .execute_event(&mut state, previous_ref, &host_frame, &runtime_event);
+                let result: RuntimeResult<ExecutionOutcome> = self.runtime.execute_event(
+                    state,
+                    previous,
+                    host_frame.clone(),
+                    runtime_event.clone(),
+                );
 
                 match result {
-                    Ok(frame) => {
-                        let state_hash_str = hex::encode(frame.state_hash.0);
+                    Ok(outcome) => {
+                        if let Some(log) = &self.event_log {
+                            // Not durable means not applied: replay must see every
+                            // state this node has attested.
+                            if let Err(e) = log.lock().unwrap().append(
+                                &host_frame,
+                                &runtime_event,
+                                &outcome,
+                            ) {
+                                return RpcResponse::Error {
+                                    error: format!("event log append failed: {e}"),
+                                };
+                            }
+                        }
+                        let frame = outcome.frame;
+                        let state_hash_str = hex::encode(outcome.state_hash.0);
                         let prev_state_hash_str =
                             frame.prev_state_hash.as_ref().map(|h| hex::encode(h.0));
-                        self.storage.apply_state_and_frame(state.clone(), frame.clone());
+                        self.storage.apply_state_and_frame(outcome.post_state, frame.clone());
 
                         let _ = self
                             .gossip_tx
@@ -1011,6 +1252,7 @@ where
                                 seq_no: frame.seq_no,
                                 state_hash: state_hash_str.clone(),
                                 prev_state_hash: prev_state_hash_str,
+                                signature: String::new(),
                             })
                             .await;
 
@@ -1086,16 +1328,16 @@ where
             storage: self.storage.clone(),
             clock: self.clock.clone(),
             gossip_tx: self.gossip_tx.clone(),
+            event_log: self.event_log.clone(),
         }
     }
 }
 
 // ------------------ Bootstrap helper ----------------------------------------
 
-pub async fn bootstrap_single_host_node(
-    host_id: aln_did::ALNDID,
-    bind: SocketAddr,
-) -> anyhow::Result<(JoinHandle<()>, mpsc::Receiver<GossipFrame>)> {
+fn default_host_runtime(
+    host_id: &aln_did::ALNDID,
+) -> BiophysicalRuntime<InMemoryDIDDirectory, SimpleConsentVerifier, LocalHostConsensus> {
     let did_dir = InMemoryDIDDirectory::new();
     did_dir.insert(aln_did::AccessEnvelope {
         did: host_id.clone(),
@@ -1103,15 +1345,17 @@ pub async fn bootstrap_single_host_node(
         min_biophysics_knowledge_score: 1.0,
     });
 
-    let runtime = BiophysicalRuntime::new(
+    BiophysicalRuntime::new(
         RuntimeConfig::default(),
         default_lifeforce_state(),
         did_dir,
         SimpleConsentVerifier,
         LocalHostConsensus,
-    );
+    )
+}
 
-    let initial_state = BioTokenState {
+fn default_genesis_state(host_id: &aln_did::ALNDID) -> BioTokenState {
+    BioTokenState {
         brain: 1.0,
         wave: 0.0,
         blood: 1.0,
@@ -1120,9 +1364,40 @@ pub async fn bootstrap_single_host_node(
         smart: 0.0,
         host_id: host_id.clone(),
         lorentz_ts: LorentzTimestamp(0, 0),
-    };
+    }
+}
 
-    let (node, gossip_rx) = HostNode::new(host_id, runtime, initial_state);
+/// Directory of the durable runtime event log (`HOST_RUNTIME_LOG_DIR`).
+pub fn runtime_log_dir() -> PathBuf {
+    std::env::var("HOST_RUNTIME_LOG_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| PathBuf::from("data/runtime-log"))
+}
+
+/// Rebuild a host's state from its event log with the same runtime
+/// configuration `bootstrap_single_host_node` serves with.
+pub fn replay_host_event_log(
+    host_id: &aln_did::ALNDID,
+    dir: impl AsRef<Path>,
+) -> Result<ReplayReport, ReplayError> {
+    runtime_event_log::replay(&default_host_runtime(host_id), dir)
+}
+
+pub async fn bootstrap_single_host_node(
+    host_id: aln_did::ALNDID,
+    bind: SocketAddr,
+) -> anyhow::Result<(JoinHandle<()>, mpsc::Receiver<GossipFrame>)> {
+    let runtime = default_host_runtime(&host_id);
+
+    // Resume from the event log; a log that no longer replays is an incident
+    // to investigate, not something to serve on top of.
+    let log_dir = runtime_log_dir();
+    let log = RuntimeEventLog::open(&log_dir, &default_genesis_state(&host_id))?;
+    let replayed = runtime_event_log::replay(&runtime, &log_dir)
+        .map_err(|e| anyhow::anyhow!("runtime event log {}: {e}", log_dir.display()))?;
+
+    let (node, gossip_rx) = HostNode::new(host_id, runtime, replayed.state.clone());
+    let node = node.with_event_log(log, replayed);
     let handle = tokio::spawn(async move {
         if let Err(e) = node.serve(bind).await {
             eprintln!("[HostNode] Server failed: {:?}", e);
@@ -1131,3 +1406,225 @@ pub async fn bootstrap_single_host_node(
 
     Ok((handle, gossip_rx))
 }
+
+/// Like `bootstrap_single_host_node`, but joins the static peer set in
+/// `peers`: local frames are signed and sent to every peer, and inbound gossip
+/// on `gossip_bind` is verified and relayed.
+pub async fn bootstrap_host_node_with_gossip(
+    host_id: aln_did::ALNDID,
+    bind: SocketAddr,
+    gossip_bind: SocketAddr,
+    peers: PeerSet,
+) -> anyhow::Result<(JoinHandle<()>, GossipNode)> {
+    if peers.local_host_id() != host_id.id {
+        anyhow::bail!(
+            "peer set is for {}, not {}",
+            peers.local_host_id(),
+            host_id.id
+        );
+    }
+    let (handle, gossip_rx) = bootstrap_single_host_node(host_id, bind).await?;
+    let gossip = GossipNode::new(peers);
+    gossip.serve(TcpListener::bind(gossip_bind).await?);
+    gossip.forward_local(gossip_rx);
+    Ok((handle, gossip))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::host_gossip::{GossipRejection, GossipVerdict};
+
+    async fn rpc(addr: SocketAddr, method: &str, params: serde_json::Value) -> serde_json::Value {
+        let mut socket = TcpStream::connect(addr).await.unwrap();
+        let req = serde_json::json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params });
+        socket.write_all(&serde_json::to_vec(&req).unwrap()).await.unwrap();
+        socket.shutdown().await.unwrap();
+        let mut buf = Vec::new();
+        socket.read_to_end(&mut buf).await.unwrap();
+        serde_json::from_slice(&buf).unwrap()
+    }
+
+    #[tokio::test]
+    async fn history_queries_and_frame_subscription_over_loopback() {
+        let dir = std::env::temp_dir().join(format!("host-node-rpc-{}", std::process::id()));
+        let state = HostNodeState::load_or_init("host-loopback", &dir).unwrap();
+        let history = state.history.clone();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let _server = serve_jsonrpc(listener, state);
+
+        let mut sub = TcpStream::connect(addr).await.unwrap();
+        sub.write_all(br#"{"jsonrpc":"2.0","id":1,"method":"host.subscribeFrames","params":{}}"#)
+            .await
+            .unwrap();
+        sub.shutdown().await.unwrap();
+        let mut lines = BufReader::new(sub).lines();
+        let ack: serde_json::Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert!(ack["result"]["subscription"].is_string());
+
+        let frame = HostConsensus::new("host-loopback").build_frame(
+            "2026-01-01T00:00:00Z",
+            "0xEVT-loopback",
+            true,
+            0.1,
+            "low",
+        );
+        history.record_frame(&frame);
+
+        let note: serde_json::Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(note["method"], "host.frame");
+        assert_eq!(note["params"]["record"]["frame"]["frame_id"], frame.frame_id.as_str());
+
+        let page = rpc(addr, "host.getFrames", serde_json::json!({ "hostid": "host-loopback" })).await;
+        assert_eq!(page["result"]["items"].as_array().unwrap().len(), 1);
+        let other = rpc(addr, "host.getFrames", serde_json::json!({ "hostid": "host-other" })).await;
+        assert!(other["result"]["items"].as_array().unwrap().is_empty());
+
+        let bio = rpc(addr, "host.getBioTokenState", serde_json::Value::Null).await;
+        assert_eq!(bio["result"]["hostid"], "host-loopback");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn history_is_reseeded_from_the_frame_log() {
+        let dir = std::env::temp_dir().join(format!("host-node-reseed-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let state = HostNodeState::load_or_init("host-reseed", &dir).unwrap();
+        let mut consensus = HostConsensus::new("host-reseed");
+        for i in 0..2 {
+            let frame = consensus.build_frame("2026-01-01T00:00:00Z", &format!("0xEVT-{i}"), true, 0.1, "low");
+            state.append_consensus_frame(&frame).unwrap();
+        }
+        drop(state);
+
+        let state = HostNodeState::load_or_init("host-reseed", &dir).unwrap();
+        let page = state.history.frames(&HistoryQuery::default());
+        assert_eq!(
+            page.items.iter().map(|r| r.frame.event_hash.as_str()).collect::<Vec<_>>(),
+            vec!["0xEVT-0", "0xEVT-1"]
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// A real `HostNode` (default runtime, no event log) joined to gossip.
+    struct GossipHost {
+        node: HostNode<InMemoryDIDDirectory, SimpleConsentVerifier, LocalHostConsensus>,
+        gossip: GossipNode,
+    }
+
+    fn gossip_key(i: usize) -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&[i as u8 + 1; 32])
+    }
+
+    async fn gossip_cluster(n: usize) -> Vec<GossipHost> {
+        let mut listeners = Vec::new();
+        for _ in 0..n {
+            listeners.push(TcpListener::bind("127.0.0.1:0").await.unwrap());
+        }
+        let addrs: Vec<SocketAddr> = listeners.iter().map(|l| l.local_addr().unwrap()).collect();
+        let mut hosts = Vec::new();
+        for (i, listener) in listeners.into_iter().enumerate() {
+            let host_id = aln_did::ALNDID { id: format!("did:host-{i}"), shard: "phx-main".to_string() };
+            let mut peers = PeerSet::new(&host_id.id, gossip_key(i));
+            for (j, addr) in addrs.iter().enumerate().filter(|(j, _)| *j != i) {
+                peers = peers.with_peer(&format!("did:host-{j}"), *addr, gossip_key(j).verifying_key());
+            }
+            let runtime = default_host_runtime(&host_id);
+            let genesis = default_genesis_state(&host_id);
+            let (node, gossip_rx) = HostNode::new(host_id, runtime, genesis);
+            let gossip = GossipNode::new(peers);
+            gossip.serve(listener);
+            gossip.forward_local(gossip_rx);
+            hosts.push(GossipHost { node, gossip });
+        }
+        hosts
+    }
+
+    async fn submit_wave(host: &GossipHost, task: &str) -> String {
+        let req = RpcRequest::SubmitEvent {
+            header: RpcSecurityHeader {
+                issuer_did: host.node.host_id.id.clone(),
+                subject_role: "system_daemon".to_string(),
+                network_tier: "core".to_string(),
+                biophysical_chain_allowed: true,
+            },
+            event: RpcEvent {
+                initiator_did: host.node.host_id.id.clone(),
+                initiator_shard: host.node.host_id.shard.clone(),
+                consent: None,
+                event_kind: RpcEventKind::WaveLoad { task_id: task.to_string(), requested_wave: 0.05 },
+            },
+        };
+        match host.node.handle_rpc(req).await {
+            RpcResponse::OkEventApplied { state_hash, .. } => state_hash,
+            other => panic!("event rejected: {other:?}"),
+        }
+    }
+
+    async fn eventually(mut check: impl FnMut() -> bool) -> bool {
+        for _ in 0..200 {
+            if check() {
+                return true;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn host_nodes_converge_over_gossip_then_flag_equivocation() {
+        let hosts = gossip_cluster(3).await;
+        let mut hashes0 = Vec::new();
+        for task in ["t0", "t1", "t2"] {
+            hashes0.push(submit_wave(&hosts[0], task).await);
+        }
+        let head1 = submit_wave(&hosts[1], "u0").await;
+
+        let converged = eventually(|| {
+            hosts.iter().all(|h| {
+                h.gossip.head("did:host-0") == Some((2, hashes0[2].clone()))
+                    && h.gossip.head("did:host-1") == Some((0, head1.clone()))
+            })
+        })
+        .await;
+        assert!(converged, "host nodes did not converge");
+        assert!(hosts.iter().all(|h| h.gossip.fork_evidence().is_empty()));
+
+        // host-0 equivocates: a second, validly signed state for seq 2 sent only to host-2.
+        let mut fork = GossipFrame {
+            host_id: "did:host-0".to_string(),
+            shard: "phx-main".to_string(),
+            seq_no: 2,
+            state_hash: "forked".to_string(),
+            prev_state_hash: Some(hashes0[1].clone()),
+            signature: String::new(),
+        };
+        fork.sign(&gossip_key(0));
+        assert_eq!(hosts[2].gossip.receive(fork.clone()).await, Ok(GossipVerdict::Fork));
+        let flagged = eventually(|| hosts.iter().all(|h| h.gossip.fork_evidence().len() == 1)).await;
+        assert!(flagged, "fork evidence did not reach every host");
+
+        let dir = std::env::temp_dir().join(format!("host-node-forks-{}", std::process::id()));
+        let state = HostNodeState::load_or_init("did:host-2", &dir).unwrap().with_gossip(hosts[2].gossip.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let _server = serve_jsonrpc(listener, state);
+        let evidence = rpc(addr, "host.getForkEvidence", serde_json::Value::Null).await;
+        assert_eq!(evidence["result"]["gossip_enabled"], true);
+        assert_eq!(evidence["result"]["items"][0]["host_id"], "did:host-0");
+        assert_eq!(evidence["result"]["items"][0]["seq_no"], 2);
+        let _ = fs::remove_dir_all(&dir);
+
+        // A peer cannot frame host-0: the same fork signed with host-1's key is refused.
+        fork.state_hash = "framed".to_string();
+        fork.sign(&gossip_key(1));
+        assert!(matches!(
+            hosts[2].gossip.receive(fork).await,
+            Err(GossipRejection::BadSignature { .. })
+        ));
+    }
+}
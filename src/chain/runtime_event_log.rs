@@ -0,0 +1,464 @@
+//! Durable log of accepted runtime events and deterministic replay.
+//!
+//! Layout under the log directory:
+//!
+//! ```text
+//! genesis.json    starting BioTokenState and its attestation hash
+//! events.jsonl    one RuntimeLogEntry per accepted event, in seq_no order
+//! ```
+//!
+//! Only events the runtime accepted are logged, each with the host frame and
+//! `LorentzTimestamp` it executed under and the state hash it produced.
+//! `replay` feeds them back through a `BiophysicalRuntime` from genesis and
+//! fails at the first entry whose recomputed hash differs from the recorded
+//! one, which is what incident reconstruction needs to trust the result.
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::chain::biophysical_runtime::{
+    alndid::{ConsentVerifier, DIDDirectory},
+    biospectreconsensus::{ConsensusFrame, HostConsensus},
+    quantumhash::QuantumHash,
+    ALNHostFrame, BioTokenState, BiophysicalRuntime, ExecutionOutcome, LorentzTimestamp,
+    RuntimeEvent,
+};
+
+pub fn hash_hex(hash: &QuantumHash) -> String {
+    hex::encode(hash.0)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RuntimeGenesis {
+    pub state: BioTokenState,
+    pub state_hash: String,
+}
+
+/// One accepted event and the frame it produced.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RuntimeLogEntry {
+    pub seq_no: u64,
+    pub lorentz_ts: LorentzTimestamp,
+    pub host_frame: ALNHostFrame,
+    pub event: RuntimeEvent,
+    pub state_hash: String,
+    pub prev_state_hash: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    Parse { line: usize, error: String },
+    MissingGenesis(PathBuf),
+    GenesisHashMismatch { recorded: String, computed: String },
+    SequenceGap { expected: u64, found: u64 },
+    Rejected { seq_no: u64, error: String },
+    HashMismatch { seq_no: u64, recorded: String, replayed: String },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "io error: {e}"),
+            Self::Parse { line, error } => write!(f, "events.jsonl line {line}: {error}"),
+            Self::MissingGenesis(p) => write!(f, "no genesis at {}", p.display()),
+            Self::GenesisHashMismatch { recorded, computed } => {
+                write!(f, "genesis hash {recorded} does not match state ({computed})")
+            }
+            Self::SequenceGap { expected, found } => write!(f, "expected seq_no {expected}, found {found}"),
+            Self::Rejected { seq_no, error } => write!(f, "seq_no {seq_no} rejected on replay: {error}"),
+            Self::HashMismatch { seq_no, recorded, replayed } => {
+                write!(f, "seq_no {seq_no}: recorded hash {recorded}, replayed {replayed}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// State rebuilt by `replay`.
+#[derive(Clone, Debug)]
+pub struct ReplayReport {
+    pub entries: u64,
+    pub state: BioTokenState,
+    pub state_hash: String,
+    pub last_frame: Option<ConsensusFrame>,
+}
+
+pub struct RuntimeEventLog {
+    dir: PathBuf,
+    next_seq: u64,
+}
+
+impl RuntimeEventLog {
+    /// Open the log in `dir`, writing `genesis` if the log is new. An existing
+    /// genesis is kept; the caller should `replay` to recover its state.
+    pub fn open(dir: impl AsRef<Path>, genesis: &BioTokenState) -> std::io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        let genesis_path = dir.join("genesis.json");
+        if !genesis_path.exists() {
+            let g = RuntimeGenesis {
+                state: genesis.clone(),
+                state_hash: hash_hex(&genesis.consensus_attest()),
+            };
+            let tmp = genesis_path.with_extension("tmp");
+            {
+                let mut f = File::create(&tmp)?;
+                f.write_all(&serde_json::to_vec_pretty(&g)?)?;
+                f.sync_all()?;
+            }
+            fs::rename(tmp, &genesis_path)?;
+        }
+        let events_path = dir.join("events.jsonl");
+        let scan = read_entries(&events_path)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+        if events_path.exists() {
+            let mut f = OpenOptions::new().append(true).open(&events_path)?;
+            if f.metadata()?.len() > scan.intact_len {
+                // Torn final append from a crash: drop it.
+                f.set_len(scan.intact_len)?;
+                f.sync_data()?;
+            }
+            if scan.needs_newline {
+                // Complete entry missing only its newline.
+                f.write_all(b"\n")?;
+                f.sync_data()?;
+            }
+        }
+        let next_seq = scan.entries.last().map(|e| e.seq_no + 1).unwrap_or(0);
+        Ok(Self { dir, next_seq })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Append an accepted execution. `seq_no` must follow the last entry.
+    pub fn append(
+        &mut self,
+        host_frame: &ALNHostFrame,
+        event: &RuntimeEvent,
+        outcome: &ExecutionOutcome,
+    ) -> std::io::Result<()> {
+        if outcome.frame.seq_no != self.next_seq {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("expected seq_no {}, got {}", self.next_seq, outcome.frame.seq_no),
+            ));
+        }
+        let entry = RuntimeLogEntry {
+            seq_no: outcome.frame.seq_no,
+            lorentz_ts: host_frame.lorentz_ts,
+            host_frame: host_frame.clone(),
+            event: event.clone(),
+            state_hash: hash_hex(&outcome.state_hash),
+            prev_state_hash: outcome.frame.prev_state_hash.as_ref().map(hash_hex),
+        };
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+        let mut f = OpenOptions::new().create(true).append(true).open(self.dir.join("events.jsonl"))?;
+        let len = f.metadata()?.len();
+        if let Err(e) = f.write_all(&line).and_then(|_| f.sync_data()) {
+            // Leave no partial line for the next append or replay to run into.
+            let _ = f.set_len(len);
+            return Err(e);
+        }
+        self.next_seq += 1;
+        Ok(())
+    }
+}
+
+/// Entries in `events.jsonl` and how much of the file they cover.
+struct ScannedLog {
+    entries: Vec<RuntimeLogEntry>,
+    /// Length of the prefix holding `entries`; anything after it is a torn
+    /// final append.
+    intact_len: u64,
+    /// The last entry is complete but its newline was never written.
+    needs_newline: bool,
+}
+
+/// Read every entry, treating an unterminated final line that does not parse
+/// as a torn append rather than corruption.
+fn read_entries(path: &Path) -> Result<ScannedLog, ReplayError> {
+    let mut scan = ScannedLog { entries: Vec::new(), intact_len: 0, needs_newline: false };
+    if !path.exists() {
+        return Ok(scan);
+    }
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut line = Vec::new();
+    let mut line_no = 0;
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line)?;
+        if read == 0 {
+            break;
+        }
+        line_no += 1;
+        let terminated = line.ends_with(b"\n");
+        if line.iter().all(u8::is_ascii_whitespace) {
+            if terminated {
+                scan.intact_len += read as u64;
+            }
+            continue;
+        }
+        match serde_json::from_slice(&line) {
+            Ok(entry) => {
+                scan.entries.push(entry);
+                scan.intact_len += read as u64;
+                scan.needs_newline = !terminated;
+            }
+            Err(_) if !terminated => break,
+            Err(e) => return Err(ReplayError::Parse { line: line_no, error: e.to_string() }),
+        }
+    }
+    Ok(scan)
+}
+
+/// Rebuild state from genesis by re-executing every logged event on
+/// `runtime`, checking each step against the recorded hashes.
+///
+/// Each event runs on the previous post-state stamped with its host frame's
+/// `LorentzTimestamp`, exactly as `HostNode` does before `execute_event`.
+pub fn replay<D, C, HC>(runtime: &BiophysicalRuntime<D, C, HC>, dir: impl AsRef<Path>) -> Result<ReplayReport, ReplayError>
+where
+    D: DIDDirectory,
+    C: ConsentVerifier,
+    HC: HostConsensus,
+{
+    let dir = dir.as_ref();
+    let genesis_path = dir.join("genesis.json");
+    if !genesis_path.exists() {
+        return Err(ReplayError::MissingGenesis(genesis_path));
+    }
+    let genesis: RuntimeGenesis = serde_json::from_str(&fs::read_to_string(&genesis_path)?)
+        .map_err(|e| ReplayError::Parse { line: 0, error: e.to_string() })?;
+    let computed = hash_hex(&genesis.state.consensus_attest());
+    if computed != genesis.state_hash {
+        return Err(ReplayError::GenesisHashMismatch { recorded: genesis.state_hash, computed });
+    }
+
+    let mut state = genesis.state;
+    let mut state_hash = genesis.state_hash;
+    let mut last_frame: Option<ConsensusFrame> = None;
+    let entries = read_entries(&dir.join("events.jsonl"))?.entries;
+    for (expected, entry) in (0u64..).zip(&entries) {
+        if entry.seq_no != expected {
+            return Err(ReplayError::SequenceGap { expected, found: entry.seq_no });
+        }
+        let mut pre = state.clone();
+        pre.lorentz_ts = entry.lorentz_ts;
+        let outcome = runtime
+            .execute_event(pre, last_frame.clone(), entry.host_frame.clone(), entry.event.clone())
+            .map_err(|e| ReplayError::Rejected { seq_no: entry.seq_no, error: format!("{e:?}") })?;
+        let replayed = hash_hex(&outcome.state_hash);
+        if replayed != entry.state_hash {
+            return Err(ReplayError::HashMismatch { seq_no: entry.seq_no, recorded: entry.state_hash.clone(), replayed });
+        }
+        state = outcome.post_state;
+        state_hash = replayed;
+        last_frame = Some(outcome.frame);
+    }
+
+    Ok(ReplayReport { entries: entries.len() as u64, state, state_hash, last_frame })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::biophysical_runtime::{
+        alndid::{AccessEnvelope, ConsentProof, RoleClass, ALNDID},
+        lifeforcesafety::{DraculaWaveCurve, LifeforceState, MetabolicBands, NanoEnvelope},
+        RuntimeConfig, RuntimeEventKind,
+    };
+
+    struct Directory;
+    impl DIDDirectory for Directory {
+        fn resolve_access(&self, did: ALNDID) -> Option<AccessEnvelope> {
+            Some(AccessEnvelope { did, roles: vec![RoleClass::Host], min_biophysics_knowledge_score: 1.0 })
+        }
+        fn is_ethical_operator(&self, _: ALNDID) -> bool {
+            false
+        }
+    }
+
+    struct Consent;
+    impl ConsentVerifier for Consent {
+        fn verify_self_consent(&self, proof: ConsentProof) -> bool {
+            !proof.zk_sig.is_empty()
+        }
+    }
+
+    struct Chain;
+    impl HostConsensus for Chain {
+        fn validate_state_step(&self, previous: Option<ConsensusFrame>, next: &ConsensusFrame, _: &BioTokenState) -> Result<(), &'static str> {
+            match previous {
+                Some(p) if next.seq_no != p.seq_no + 1 => Err("sequence mismatch"),
+                None if next.seq_no != 0 => Err("genesis must be seq 0"),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    fn runtime() -> BiophysicalRuntime<Directory, Consent, Chain> {
+        let lifeforce = LifeforceState {
+            bands: MetabolicBands { blood_min: 0.25, blood_soft_floor: 0.35, oxygen_min: 0.9, oxygen_soft_floor: 0.94 },
+            wave_curve: DraculaWaveCurve { max_wave_factor: 0.6, decay_coefficient: 0.01 },
+            nano_envelope: NanoEnvelope { max_concurrent_workload: 1.0, eco_penalty_factor: 0.5 },
+        };
+        BiophysicalRuntime::new(RuntimeConfig::default(), lifeforce, Directory, Consent, Chain)
+    }
+
+    fn host() -> ALNDID {
+        ALNDID { id: "bostrom-test-host".into(), shard: "phx-test".into() }
+    }
+
+    fn genesis() -> BioTokenState {
+        BioTokenState {
+            brain: 1.0,
+            wave: 0.0,
+            blood: 1.0,
+            oxygen: 0.98,
+            nano: 0.0,
+            smart: 0.0,
+            host_id: host(),
+            lorentz_ts: LorentzTimestamp(0, 0),
+        }
+    }
+
+    fn kinds() -> Vec<RuntimeEventKind> {
+        vec![
+            RuntimeEventKind::WaveLoad { task_id: "t1".into(), requested_wave: 0.3 },
+            RuntimeEventKind::SmartAutonomy { agent_id: "a1".into(), requested_smart: 0.2 },
+            RuntimeEventKind::EvolutionUpgrade { evolution_id: "evo-1".into() },
+        ]
+    }
+
+    /// Execute `kinds()` like `HostNode` does, logging each accepted event.
+    fn record(dir: &Path) -> String {
+        let rt = runtime();
+        let mut log = RuntimeEventLog::open(dir, &genesis()).unwrap();
+        let mut state = genesis();
+        let mut last: Option<ConsensusFrame> = None;
+        let mut hash = String::new();
+        for (i, kind) in kinds().into_iter().enumerate() {
+            let ts = LorentzTimestamp(1_000 * (i as i128 + 1), 0);
+            let host_frame = ALNHostFrame {
+                host_id: host(),
+                access: AccessEnvelope { did: host(), roles: vec![RoleClass::Host], min_biophysics_knowledge_score: 1.0 },
+                lorentz_ts: ts,
+            };
+            let event = RuntimeEvent {
+                kind,
+                initiator: host(),
+                consent: Some(ConsentProof { did: host(), evolution_event_id: format!("ev-{i}"), timestamp_ms_utc: 0, zk_sig: vec![1] }),
+                lorentz_ts: ts,
+            };
+            state.lorentz_ts = ts;
+            let outcome = rt.execute_event(state, last, host_frame.clone(), event.clone()).unwrap();
+            log.append(&host_frame, &event, &outcome).unwrap();
+            hash = hash_hex(&outcome.state_hash);
+            state = outcome.post_state;
+            last = Some(outcome.frame);
+        }
+        hash
+    }
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("runtime-event-log-{tag}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn execution_returns_post_state_that_chains() {
+        let rt = runtime();
+        let host_frame = ALNHostFrame {
+            host_id: host(),
+            access: AccessEnvelope { did: host(), roles: vec![RoleClass::Host], min_biophysics_knowledge_score: 1.0 },
+            lorentz_ts: LorentzTimestamp(5, 0),
+        };
+        let event = RuntimeEvent {
+            kind: RuntimeEventKind::WaveLoad { task_id: "t".into(), requested_wave: 0.4 },
+            initiator: host(),
+            consent: None,
+            lorentz_ts: LorentzTimestamp(5, 0),
+        };
+        let out = rt.execute_event(genesis(), None, host_frame, event).unwrap();
+        assert!(out.post_state.wave > 0.0);
+        assert_eq!(out.state_hash, out.post_state.consensus_attest());
+        assert_eq!(out.frame.state_hash, out.state_hash);
+    }
+
+    #[test]
+    fn replay_rebuilds_state_and_matches_recorded_hashes() {
+        let dir = temp_dir("replay");
+        let head = record(&dir);
+        let report = replay(&runtime(), &dir).unwrap();
+        assert_eq!(report.entries, 3);
+        assert_eq!(report.state_hash, head);
+        assert_eq!(report.last_frame.unwrap().seq_no, 2);
+        assert!(report.state.smart > 0.0);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn logged_floats_parse_back_to_the_hashed_bits() {
+        let mut seed = 0x9e37_79b9_7f4a_7c15u64;
+        for _ in 0..10_000 {
+            seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+            let mut state = genesis();
+            state.wave = (seed >> 11) as f64 / (1u64 << 53) as f64;
+            state.nano = f64::from_bits(0x3f00_0000_0000_0000 | (seed >> 12));
+            let parsed: BioTokenState = serde_json::from_slice(&serde_json::to_vec(&state).unwrap()).unwrap();
+            assert_eq!(parsed.consensus_attest(), state.consensus_attest(), "wave {:e} nano {:e}", state.wave, state.nano);
+        }
+    }
+
+    #[test]
+    fn torn_final_append_is_dropped_on_open() {
+        let dir = temp_dir("torn");
+        let head = record(&dir);
+        let path = dir.join("events.jsonl");
+        let intact = fs::read(&path).unwrap();
+        let mut torn = intact.clone();
+        torn.extend_from_slice(br#"{"seq_no":3,"lorentz_ts":"#);
+        fs::write(&path, &torn).unwrap();
+
+        assert_eq!(replay(&runtime(), &dir).unwrap().entries, 3);
+        RuntimeEventLog::open(&dir, &genesis()).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), intact);
+        assert_eq!(replay(&runtime(), &dir).unwrap().state_hash, head);
+
+        // A complete last entry that only lost its newline is kept.
+        fs::write(&path, &intact[..intact.len() - 1]).unwrap();
+        RuntimeEventLog::open(&dir, &genesis()).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), intact);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn replay_flags_tampered_entry() {
+        let dir = temp_dir("tamper");
+        record(&dir);
+        let path = dir.join("events.jsonl");
+        let raw = fs::read_to_string(&path).unwrap();
+        fs::write(&path, raw.replacen("\"requested_wave\":0.3", "\"requested_wave\":0.5", 1)).unwrap();
+        match replay(&runtime(), &dir) {
+            Err(ReplayError::HashMismatch { seq_no, .. }) => assert_eq!(seq_no, 0),
+            other => panic!("expected hash mismatch, got {other:?}"),
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
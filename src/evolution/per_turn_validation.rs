@@ -1,6 +1,10 @@
 #![forbid(unsafe_code)]
 
 use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
 
 use crate::biophysical_chain_neuroautomationpipeline::{
     EvolutionProposal, Reversibility,
@@ -14,8 +18,8 @@ use crate::governance::deep_domain_rights::{
     DeepDomainRightsProfile, DeepDomainRightsStatus,
 };
 use crate::governance::evolution_emergency_rights::{
-    validate_emergency_override_turn, EmergencyEmergencyValidationKind as EmergencyValidationKind,
-    EmergencyTurnContext, EvolutionEmergencyRightsProfile,
+    validate_emergency_override_turn, EmergencyTurnContext, EmergencyValidationKind,
+    EvolutionEmergencyRightsProfile,
 };
 use crate::governance::irreversible_token::IrreversibleToken;
 use crate::organichainconsensus::EvolutionIntervalState;
@@ -26,7 +30,9 @@ use crate::telemetry::outer_attestation::OuterAttestationRequest;
 /// Enumerate the per-turn automation actions.
 ///
 /// NOTE: EmergencyOverrideUse is treated as action 0 so it always runs.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// `Custom` actions exist only in a loaded rule policy and are composed from
+/// the same rule primitives as the built-in ones.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum AutomationActionId {
     EmergencyOverrideUse,
     IntentDetection,
@@ -39,20 +45,34 @@ pub enum AutomationActionId {
     Traceability,
     MetaGovernance,
     OuterAttestation,
+    Custom(String),
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ValidationResultKind {
     Passed,
     Failed,
     Skipped, // not applicable this turn (e.g., no irreversible patterns)
 }
 
-#[derive(Clone, Debug)]
+/// Explain report for one action: the overall verdict plus every rule that
+/// was evaluated to reach it, in order. Evaluation stops at the first rule
+/// that fails or marks the action not applicable.
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ValidationResult {
     pub action: AutomationActionId,
     pub kind: ValidationResultKind,
     pub messages: Vec<String>,
+    pub rules: Vec<RuleEvaluation>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RuleEvaluation {
+    pub rule: String,
+    /// The context values the rule looked at, rendered for humans.
+    pub inputs: BTreeMap<String, String>,
+    pub outcome: ValidationResultKind,
+    pub messages: Vec<String>,
 }
 
 /// High-level view of everything a single evolution turn might touch.
@@ -81,484 +101,1078 @@ pub struct PerTurnContext<'a> {
     pub emergencies_used_today: u32,
 }
 
-/// Central entrypoint: validate all actions for this turn.
-pub fn validate_per_turn(
-    context: &PerTurnContext<'_>,
-) -> BTreeMap<AutomationActionId, ValidationResult> {
-    use AutomationActionId::*;
-
-    let mut results = BTreeMap::new();
-
-    // 0. Emergency override invariants
-    results.insert(
-        EmergencyOverrideUse,
-        validate_emergency_override_use(context),
-    );
-
-    // 1–10. Existing actions
-    results.insert(IntentDetection, validate_intent_detection(context));
-    results.insert(TurnValidation, validate_turn_validation(context));
-    results.insert(EvolutionInterval, validate_evolution_interval(context));
-    results.insert(EcoAndEvolveBudgets, validate_eco_and_evolve_budgets(context));
-    results.insert(ReversibleActuation, validate_reversible_actuation(context));
-    results.insert(IrreversibleTurn, validate_irreversible_turn(context));
-    results.insert(
-        DeepDomainExcavation,
-        validate_deep_domain_excavation(context),
-    );
-    results.insert(Traceability, validate_traceability(context));
-    results.insert(MetaGovernance, validate_meta_governance(context));
-    results.insert(OuterAttestation, validate_outer_attestation(context));
-
-    results
+// ------------------------------ Rule primitives ------------------------------
+
+/// One check over the turn context. A policy file lists these per action;
+/// `message` parameters are what the action reports when the rule finds the
+/// action not applicable this turn.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum RuleSpec {
+    /// Delegates to `validate_emergency_override_turn`.
+    EmergencyOverride,
+    RequireProposal { message: String },
+    IntentIdNonEmpty,
+    IntentIdPrefix { prefix: String },
+    ConstraintsProfileSet,
+    PrevalidatedByDefaultValidator,
+    RequireIntervalState { message: String },
+    IntervalPermitsStep,
+    IntervalStepsWithinDailyMax,
+    RequireBudgetWindows { message: String },
+    EcoWithinDailyBudget,
+    BrainTokenCapacity,
+    DraculaWaveCapacity,
+    RequirePatterns { message: String },
+    /// Not applicable when any pattern is irreversible or partially reversible.
+    RequireOnlyReversiblePatterns { message: String },
+    /// Not applicable unless some pattern is irreversible or partially reversible.
+    RequireNonReversiblePatterns { message: String },
+    IrreversibleTokenAttached,
+    IrreversibleTokenMatchesTranscript,
+    IrreversibleTokenNotRevoked,
+    RequireDeepDomainBindings { message: String },
+    TranscriptHashPresent,
+    HumanExplanationMinWords { min_words: usize },
+    DeepQpuDatashardNote,
+    AugmentationRightsSafe,
+    DeepDomainRightsSafe,
+    RequireOuterAttestation { message: String },
+    OuterAttestationHashesOnly,
+    OuterAttestationNoInnerBalances,
+    HostRoleIn { roles: Vec<String> },
 }
 
-/// 0. EmergencyOverrideUse – host-only rollback guard
-fn validate_emergency_override_use(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::EmergencyOverrideUse;
+struct RuleVerdict {
+    outcome: ValidationResultKind,
+    inputs: BTreeMap<String, String>,
+    messages: Vec<String>,
+}
 
-    if let (Some(erights), Some(ectx)) = (&ctx.emergency_rights, &ctx.emergency) {
-        let res = validate_emergency_override_turn(erights, ectx, ctx.emergencies_used_today);
-        let kind = match res.kind {
-            EmergencyValidationKind::Passed => ValidationResultKind::Passed,
-            EmergencyValidationKind::Failed => ValidationResultKind::Failed,
-            EmergencyValidationKind::Skipped => ValidationResultKind::Skipped,
-        };
-        ValidationResult {
-            action: EmergencyOverrideUse,
-            kind,
-            messages: res.messages,
+impl RuleVerdict {
+    fn new(outcome: ValidationResultKind, inputs: &[(&str, String)], messages: Vec<String>) -> Self {
+        Self {
+            outcome,
+            inputs: inputs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+            messages,
         }
-    } else {
-        ValidationResult {
-            action: EmergencyOverrideUse,
-            kind: ValidationResultKind::Skipped,
-            messages: vec!["no emergency override bound to this turn".into()],
+    }
+
+    fn pass(inputs: &[(&str, String)]) -> Self {
+        Self::new(ValidationResultKind::Passed, inputs, Vec::new())
+    }
+
+    fn note(inputs: &[(&str, String)], msg: impl Into<String>) -> Self {
+        Self::new(ValidationResultKind::Passed, inputs, vec![msg.into()])
+    }
+
+    fn fail(inputs: &[(&str, String)], msg: impl Into<String>) -> Self {
+        Self::new(ValidationResultKind::Failed, inputs, vec![msg.into()])
+    }
+
+    fn skip(inputs: &[(&str, String)], msg: impl Into<String>) -> Self {
+        Self::new(ValidationResultKind::Skipped, inputs, vec![msg.into()])
+    }
+
+    /// Pass when `ok`, otherwise fail with `msg`.
+    fn check(ok: bool, inputs: &[(&str, String)], msg: &str) -> Self {
+        if ok {
+            Self::pass(inputs)
+        } else {
+            Self::fail(inputs, msg)
         }
     }
 }
 
-/// 1. Intent detection + proposal shaping
-fn validate_intent_detection(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::IntentDetection;
-    let mut msgs = Vec::new();
-
-    if let Some(proposal) = ctx.proposal {
-        if proposal.intentid.is_empty() {
-            msgs.push("proposal.intentid must be non-empty and part of typed vocabulary".into());
-            return ValidationResult {
-                action: IntentDetection,
-                kind: ValidationResultKind::Failed,
-                messages: msgs,
-            };
-        }
-        if !proposal.intentid.starts_with("INTENT_") {
-            msgs.push(format!(
-                "intentid '{}' does not use INTENT_ prefix",
-                proposal.intentid
-            ));
-            return ValidationResult {
-                action: IntentDetection,
-                kind: ValidationResultKind::Failed,
-                messages: msgs,
-            };
+fn present<T>(v: Option<T>) -> String {
+    if v.is_some() { "present" } else { "absent" }.to_string()
+}
+
+/// The parts of a `PerTurnContext` the rules read, flattened to plain values.
+/// Rules evaluate against this view only, so every rule path can be pinned by
+/// golden tests without building the governance and telemetry types.
+#[derive(Clone, Debug, Default)]
+struct TurnFacts {
+    emergency: Option<EmergencyTurnContext>,
+    emergency_rights: Option<EvolutionEmergencyRightsProfile>,
+    emergencies_used_today: u32,
+    proposal: Option<ProposalFacts>,
+    interval: Option<IntervalFacts>,
+    /// `within_daily_budget()` of the eco window, when bound.
+    eco_within_budget: Option<bool>,
+    /// `has_sufficient_capacity()` of the BrainToken window, when bound.
+    brain_capacity: Option<bool>,
+    /// `has_sufficient_capacity()` of the DraculaWave window, when bound.
+    dw_capacity: Option<bool>,
+    deep_epochs: bool,
+    lifeforce: bool,
+    irreversible_token: Option<TokenFacts>,
+    transcripthash: Option<String>,
+    human_explanation: Option<String>,
+    wrote_deep_qpudatashard: bool,
+    /// `verify_rights_safe()` of each rights profile: `Err` carries the
+    /// rendered invariant violation.
+    aug_rights: Option<Result<(), String>>,
+    deep_rights: Option<Result<(), String>>,
+    outer_attestation: Option<OuterAttestationFacts>,
+    host_role: String,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ProposalFacts {
+    intentid: String,
+    constraints_profile_id: String,
+    prevalidated_by_default_validator: bool,
+    patterns: usize,
+    /// Some pattern is irreversible or partially reversible.
+    has_non_reversible: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+struct IntervalFacts {
+    permits_new_step: bool,
+    steps_taken_today: String,
+    max_steps_per_day: String,
+    steps_within_daily_max: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+struct TokenFacts {
+    transcripthash: String,
+    revoked: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+struct OuterAttestationFacts {
+    payload_is_hashes_only: bool,
+    includes_inner_token_balances: bool,
+}
+
+impl TurnFacts {
+    fn from_context(ctx: &PerTurnContext<'_>) -> Self {
+        Self {
+            emergency: ctx.emergency.clone(),
+            emergency_rights: ctx.emergency_rights.clone(),
+            emergencies_used_today: ctx.emergencies_used_today,
+            proposal: ctx.proposal.map(|p| ProposalFacts {
+                intentid: p.intentid.clone(),
+                constraints_profile_id: p.constraints_profile_id.clone(),
+                prevalidated_by_default_validator: p.prevalidated_by_default_validator,
+                patterns: p.patterns.len(),
+                has_non_reversible: p.patterns.iter().any(|pat| {
+                    matches!(
+                        pat.reversibility,
+                        Reversibility::Irreversible | Reversibility::PartiallyReversible
+                    )
+                }),
+            }),
+            interval: ctx.interval_state.map(|i| IntervalFacts {
+                permits_new_step: i.permits_new_step,
+                steps_taken_today: i.steps_taken_today.to_string(),
+                max_steps_per_day: i.max_steps_per_day.to_string(),
+                steps_within_daily_max: i.steps_taken_today <= i.max_steps_per_day,
+            }),
+            eco_within_budget: ctx.eco_window.map(|e| e.within_daily_budget()),
+            brain_capacity: ctx.brain_window.map(|b| b.has_sufficient_capacity()),
+            dw_capacity: ctx.dw_window.map(|d| d.has_sufficient_capacity()),
+            deep_epochs: ctx.deep_epochs.is_some(),
+            lifeforce: ctx.lifeforce.is_some(),
+            irreversible_token: ctx.irreversible_token.map(|t| TokenFacts {
+                transcripthash: t.transcripthash.clone(),
+                revoked: t.revoked,
+            }),
+            transcripthash: ctx.transcripthash.clone(),
+            human_explanation: ctx.human_explanation.clone(),
+            wrote_deep_qpudatashard: ctx.wrote_deep_qpudatashard,
+            aug_rights: ctx.aug_rights.map(|a| match a.verify_rights_safe() {
+                AugmentationRightStatus::RightsSafe => Ok(()),
+                AugmentationRightStatus::ViolatesInvariant(e) => Err(format!("{:?}", e)),
+            }),
+            deep_rights: ctx.deep_rights.map(|d| match d.verify_rights_safe() {
+                DeepDomainRightsStatus::RightsSafe => Ok(()),
+                DeepDomainRightsStatus::ViolatesInvariant(e) => Err(format!("{:?}", e)),
+            }),
+            outer_attestation: ctx.outer_attestation.map(|a| OuterAttestationFacts {
+                payload_is_hashes_only: a.payload_is_hashes_only,
+                includes_inner_token_balances: a.includes_inner_token_balances,
+            }),
+            host_role: ctx.host_role.clone(),
         }
-    } else {
-        msgs.push("no proposal; nothing to validate for intent-detection".into());
-        return ValidationResult {
-            action: IntentDetection,
-            kind: ValidationResultKind::Skipped,
-            messages: msgs,
-        };
     }
 
-    ValidationResult {
-        action: IntentDetection,
-        kind: ValidationResultKind::Passed,
-        messages: vec!["intent-detection invariants satisfied".into()],
+    fn has_non_reversible(&self) -> bool {
+        self.proposal.as_ref().is_some_and(|p| p.has_non_reversible)
     }
 }
 
-/// 2. DefaultProposalValidator
-fn validate_turn_validation(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::TurnValidation;
-    let mut msgs = Vec::new();
-
-    if let Some(proposal) = ctx.proposal {
-        if proposal.constraints_profile_id.is_empty() {
-            msgs.push("constraints_profile_id must be set on EvolutionProposal".into());
-            return ValidationResult {
-                action: TurnValidation,
-                kind: ValidationResultKind::Failed,
-                messages: msgs,
-            };
-        }
-        if !proposal.prevalidated_by_default_validator {
-            msgs.push("DefaultProposalValidator did not confirm this proposal".into());
-            return ValidationResult {
-                action: TurnValidation,
-                kind: ValidationResultKind::Failed,
-                messages: msgs,
-            };
+impl RuleSpec {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::EmergencyOverride => "emergency_override",
+            Self::RequireProposal { .. } => "require_proposal",
+            Self::IntentIdNonEmpty => "intent_id_non_empty",
+            Self::IntentIdPrefix { .. } => "intent_id_prefix",
+            Self::ConstraintsProfileSet => "constraints_profile_set",
+            Self::PrevalidatedByDefaultValidator => "prevalidated_by_default_validator",
+            Self::RequireIntervalState { .. } => "require_interval_state",
+            Self::IntervalPermitsStep => "interval_permits_step",
+            Self::IntervalStepsWithinDailyMax => "interval_steps_within_daily_max",
+            Self::RequireBudgetWindows { .. } => "require_budget_windows",
+            Self::EcoWithinDailyBudget => "eco_within_daily_budget",
+            Self::BrainTokenCapacity => "brain_token_capacity",
+            Self::DraculaWaveCapacity => "dracula_wave_capacity",
+            Self::RequirePatterns { .. } => "require_patterns",
+            Self::RequireOnlyReversiblePatterns { .. } => "require_only_reversible_patterns",
+            Self::RequireNonReversiblePatterns { .. } => "require_non_reversible_patterns",
+            Self::IrreversibleTokenAttached => "irreversible_token_attached",
+            Self::IrreversibleTokenMatchesTranscript => "irreversible_token_matches_transcript",
+            Self::IrreversibleTokenNotRevoked => "irreversible_token_not_revoked",
+            Self::RequireDeepDomainBindings { .. } => "require_deep_domain_bindings",
+            Self::TranscriptHashPresent => "transcript_hash_present",
+            Self::HumanExplanationMinWords { .. } => "human_explanation_min_words",
+            Self::DeepQpuDatashardNote => "deep_qpudatashard_note",
+            Self::AugmentationRightsSafe => "augmentation_rights_safe",
+            Self::DeepDomainRightsSafe => "deep_domain_rights_safe",
+            Self::RequireOuterAttestation { .. } => "require_outer_attestation",
+            Self::OuterAttestationHashesOnly => "outer_attestation_hashes_only",
+            Self::OuterAttestationNoInnerBalances => "outer_attestation_no_inner_balances",
+            Self::HostRoleIn { .. } => "host_role_in",
         }
+    }
 
-        ValidationResult {
-            action: TurnValidation,
-            kind: ValidationResultKind::Passed,
-            messages: vec!["proposal satisfied all biophysical constraints".into()],
-        }
-    } else {
-        ValidationResult {
-            action: TurnValidation,
-            kind: ValidationResultKind::Skipped,
-            messages: vec!["no proposal present for this turn".into()],
+    fn evaluate(&self, ctx: &TurnFacts) -> RuleVerdict {
+        match self {
+            Self::EmergencyOverride => match (&ctx.emergency_rights, &ctx.emergency) {
+                (Some(erights), Some(ectx)) => {
+                    let res = validate_emergency_override_turn(erights, ectx, ctx.emergencies_used_today);
+                    let outcome = match res.kind {
+                        EmergencyValidationKind::Passed => ValidationResultKind::Passed,
+                        EmergencyValidationKind::Failed => ValidationResultKind::Failed,
+                        EmergencyValidationKind::Skipped => ValidationResultKind::Skipped,
+                    };
+                    let inputs = [
+                        ("turn_id", ectx.turn_id.clone()),
+                        ("emergencies_used_today", ctx.emergencies_used_today.to_string()),
+                        ("max_emergencies_per_day", erights.max_emergencies_per_day.to_string()),
+                    ];
+                    RuleVerdict::new(outcome, &inputs, res.messages)
+                }
+                _ => RuleVerdict::skip(
+                    &[("emergency", present(ctx.emergency.as_ref())), ("emergency_rights", present(ctx.emergency_rights.as_ref()))],
+                    "no emergency override bound to this turn",
+                ),
+            },
+
+            Self::RequireProposal { message } => {
+                let inputs = [("proposal", present(ctx.proposal.as_ref()))];
+                if ctx.proposal.is_some() {
+                    RuleVerdict::pass(&inputs)
+                } else {
+                    RuleVerdict::skip(&inputs, message.clone())
+                }
+            }
+            Self::IntentIdNonEmpty => {
+                let id = ctx.proposal.as_ref().map(|p| p.intentid.clone()).unwrap_or_default();
+                RuleVerdict::check(
+                    !id.is_empty(),
+                    &[("intentid", id.clone())],
+                    "proposal.intentid must be non-empty and part of typed vocabulary",
+                )
+            }
+            Self::IntentIdPrefix { prefix } => {
+                let id = ctx.proposal.as_ref().map(|p| p.intentid.clone()).unwrap_or_default();
+                let inputs = [("intentid", id.clone()), ("prefix", prefix.clone())];
+                if id.starts_with(prefix.as_str()) {
+                    RuleVerdict::pass(&inputs)
+                } else {
+                    RuleVerdict::fail(&inputs, format!("intentid '{}' does not use {} prefix", id, prefix))
+                }
+            }
+            Self::ConstraintsProfileSet => {
+                let id = ctx.proposal.as_ref().map(|p| p.constraints_profile_id.clone()).unwrap_or_default();
+                RuleVerdict::check(
+                    !id.is_empty(),
+                    &[("constraints_profile_id", id.clone())],
+                    "constraints_profile_id must be set on EvolutionProposal",
+                )
+            }
+            Self::PrevalidatedByDefaultValidator => {
+                let ok = ctx.proposal.as_ref().is_some_and(|p| p.prevalidated_by_default_validator);
+                RuleVerdict::check(
+                    ok,
+                    &[("prevalidated_by_default_validator", ok.to_string())],
+                    "DefaultProposalValidator did not confirm this proposal",
+                )
+            }
+
+            Self::RequireIntervalState { message } => {
+                let inputs = [("interval_state", present(ctx.interval.as_ref()))];
+                if ctx.interval.is_some() {
+                    RuleVerdict::pass(&inputs)
+                } else {
+                    RuleVerdict::skip(&inputs, message.clone())
+                }
+            }
+            Self::IntervalPermitsStep => {
+                let ok = ctx.interval.as_ref().is_some_and(|i| i.permits_new_step);
+                RuleVerdict::check(ok, &[("permits_new_step", ok.to_string())], "cantakeevolutionstep() denied this turn")
+            }
+            Self::IntervalStepsWithinDailyMax => {
+                let (taken, max, ok) = match &ctx.interval {
+                    Some(i) => (i.steps_taken_today.clone(), i.max_steps_per_day.clone(), i.steps_within_daily_max),
+                    None => ("0".to_string(), "0".to_string(), true),
+                };
+                RuleVerdict::check(
+                    ok,
+                    &[("steps_taken_today", taken), ("max_steps_per_day", max)],
+                    "steps_taken_today exceeded max_steps_per_day",
+                )
+            }
+
+            Self::RequireBudgetWindows { message } => {
+                let inputs = [
+                    ("eco_window", present(ctx.eco_within_budget)),
+                    ("brain_window", present(ctx.brain_capacity)),
+                    ("dw_window", present(ctx.dw_capacity)),
+                ];
+                if ctx.eco_within_budget.is_some() && ctx.brain_capacity.is_some() && ctx.dw_capacity.is_some() {
+                    RuleVerdict::pass(&inputs)
+                } else {
+                    RuleVerdict::skip(&inputs, message.clone())
+                }
+            }
+            Self::EcoWithinDailyBudget => {
+                let ok = ctx.eco_within_budget == Some(true);
+                RuleVerdict::check(ok, &[("within_daily_budget", ok.to_string())], "eco-governor daily budget exhausted")
+            }
+            Self::BrainTokenCapacity => {
+                let ok = ctx.brain_capacity == Some(true);
+                RuleVerdict::check(ok, &[("has_sufficient_capacity", ok.to_string())], "insufficient BrainTokens for this turn")
+            }
+            Self::DraculaWaveCapacity => {
+                let ok = ctx.dw_capacity == Some(true);
+                RuleVerdict::check(ok, &[("has_sufficient_capacity", ok.to_string())], "insufficient DraculaWave for this turn")
+            }
+
+            Self::RequirePatterns { message } => {
+                let n = ctx.proposal.as_ref().map(|p| p.patterns).unwrap_or(0);
+                let inputs = [("patterns", n.to_string())];
+                if n > 0 {
+                    RuleVerdict::pass(&inputs)
+                } else {
+                    RuleVerdict::skip(&inputs, message.clone())
+                }
+            }
+            Self::RequireOnlyReversiblePatterns { message } => {
+                let non_rev = ctx.has_non_reversible();
+                let inputs = [("has_non_reversible_patterns", non_rev.to_string())];
+                if non_rev {
+                    RuleVerdict::skip(&inputs, message.clone())
+                } else {
+                    RuleVerdict::note(&inputs, "all actuation patterns in this turn are FullyReversible")
+                }
+            }
+            Self::RequireNonReversiblePatterns { message } => {
+                let non_rev = ctx.has_non_reversible();
+                let inputs = [("has_non_reversible_patterns", non_rev.to_string())];
+                if non_rev {
+                    RuleVerdict::pass(&inputs)
+                } else {
+                    RuleVerdict::skip(&inputs, message.clone())
+                }
+            }
+            Self::IrreversibleTokenAttached => RuleVerdict::check(
+                ctx.irreversible_token.is_some(),
+                &[("irreversible_token", present(ctx.irreversible_token.as_ref()))],
+                "irreversible patterns present but no IrreversibleToken attached",
+            ),
+            Self::IrreversibleTokenMatchesTranscript => {
+                let token_hash = ctx.irreversible_token.as_ref().map(|t| t.transcripthash.clone());
+                let inputs = [
+                    ("token_transcripthash", token_hash.clone().unwrap_or_default()),
+                    ("turn_transcripthash", ctx.transcripthash.clone().unwrap_or_default()),
+                ];
+                // Only comparable when the turn carries a transcript hash.
+                match (&token_hash, &ctx.transcripthash) {
+                    (Some(t), Some(turn)) if t != turn => {
+                        RuleVerdict::fail(&inputs, "IrreversibleToken.transcripthash != turn.transcripthash")
+                    }
+                    _ => RuleVerdict::pass(&inputs),
+                }
+            }
+            Self::IrreversibleTokenNotRevoked => {
+                let revoked = ctx.irreversible_token.as_ref().is_some_and(|t| t.revoked);
+                RuleVerdict::check(!revoked, &[("revoked", revoked.to_string())], "IrreversibleToken is revoked")
+            }
+
+            Self::RequireDeepDomainBindings { message } => {
+                let inputs = [
+                    ("deep_epochs", present(ctx.deep_epochs.then_some(()))),
+                    ("deep_rights", present(ctx.deep_rights.as_ref())),
+                    ("lifeforce", present(ctx.lifeforce.then_some(()))),
+                ];
+                if ctx.deep_epochs && ctx.deep_rights.is_some() && ctx.lifeforce {
+                    RuleVerdict::pass(&inputs)
+                } else {
+                    RuleVerdict::skip(&inputs, message.clone())
+                }
+            }
+
+            Self::TranscriptHashPresent => {
+                let hash = ctx.transcripthash.clone().unwrap_or_default();
+                RuleVerdict::check(
+                    !hash.is_empty(),
+                    &[("transcripthash", hash.clone())],
+                    "transcripthash must be non-empty for all evolution turns",
+                )
+            }
+            Self::HumanExplanationMinWords { min_words } => match &ctx.human_explanation {
+                Some(exp) => {
+                    let words = exp.split_whitespace().count();
+                    let inputs = [("word_count", words.to_string()), ("min_words", min_words.to_string())];
+                    if words < *min_words {
+                        RuleVerdict::fail(&inputs, format!("human_explanation too short: {} words (min {})", words, min_words))
+                    } else {
+                        RuleVerdict::pass(&inputs)
+                    }
+                }
+                None => RuleVerdict::fail(&[("human_explanation", present::<()>(None))], "missing human_explanation for this turn"),
+            },
+            Self::DeepQpuDatashardNote => {
+                let inputs = [("wrote_deep_qpudatashard", ctx.wrote_deep_qpudatashard.to_string())];
+                if ctx.wrote_deep_qpudatashard {
+                    RuleVerdict::note(&inputs, "qpudatashard for deep-object excavation was written")
+                } else {
+                    RuleVerdict::note(&inputs, "no deep qpudatashard recorded (ok if no B3/B4 used)")
+                }
+            }
+
+            Self::AugmentationRightsSafe => match &ctx.aug_rights {
+                Some(Ok(())) => {
+                    RuleVerdict::note(&[("aug_rights", "RightsSafe".into())], "AugmentationRight profile is RightsSafe")
+                }
+                Some(Err(e)) => RuleVerdict::fail(
+                    &[("aug_rights", "ViolatesInvariant".into())],
+                    format!("AugmentationRight invariant violation: {}", e),
+                ),
+                None => RuleVerdict::note(
+                    &[("aug_rights", present::<()>(None))],
+                    "no AugmentationRight profile in context; assuming pre-validated at startup",
+                ),
+            },
+            Self::DeepDomainRightsSafe => match &ctx.deep_rights {
+                Some(Ok(())) => {
+                    RuleVerdict::note(&[("deep_rights", "RightsSafe".into())], "DeepDomainRightsProfile is RightsSafe")
+                }
+                Some(Err(e)) => RuleVerdict::fail(
+                    &[("deep_rights", "ViolatesInvariant".into())],
+                    format!("DeepDomainRights invariant violation: {}", e),
+                ),
+                None => RuleVerdict::note(
+                    &[("deep_rights", present::<()>(None))],
+                    "no DeepDomainRightsProfile bound; deep-domain excavation should be disabled",
+                ),
+            },
+
+            Self::RequireOuterAttestation { message } => {
+                let inputs = [("outer_attestation", present(ctx.outer_attestation.as_ref()))];
+                if ctx.outer_attestation.is_some() {
+                    RuleVerdict::pass(&inputs)
+                } else {
+                    RuleVerdict::skip(&inputs, message.clone())
+                }
+            }
+            Self::OuterAttestationHashesOnly => {
+                let ok = ctx.outer_attestation.as_ref().is_some_and(|a| a.payload_is_hashes_only);
+                RuleVerdict::check(
+                    ok,
+                    &[("payload_is_hashes_only", ok.to_string())],
+                    "outer attestation must contain only hashes / proof-ids",
+                )
+            }
+            Self::OuterAttestationNoInnerBalances => {
+                let leaks = ctx.outer_attestation.as_ref().is_some_and(|a| a.includes_inner_token_balances);
+                RuleVerdict::check(
+                    !leaks,
+                    &[("includes_inner_token_balances", leaks.to_string())],
+                    "outer attestation may not leak inner token balances",
+                )
+            }
+
+            Self::HostRoleIn { roles } => {
+                let inputs = [("host_role", ctx.host_role.clone()), ("allowed", roles.join(","))];
+                if roles.contains(&ctx.host_role) {
+                    RuleVerdict::pass(&inputs)
+                } else {
+                    RuleVerdict::fail(&inputs, format!("host_role '{}' is not one of [{}]", ctx.host_role, roles.join(", ")))
+                }
+            }
         }
     }
 }
 
-/// 3. Evolution interval gating
-fn validate_evolution_interval(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::EvolutionInterval;
-
-    if let Some(interval) = ctx.interval_state {
-        if !interval.permits_new_step {
-            return ValidationResult {
-                action: EvolutionInterval,
-                kind: ValidationResultKind::Failed,
-                messages: vec!["cantakeevolutionstep() denied this turn".into()],
-            };
-        }
-        if interval.steps_taken_today > interval.max_steps_per_day {
-            return ValidationResult {
-                action: EvolutionInterval,
-                kind: ValidationResultKind::Failed,
-                messages: vec!["steps_taken_today exceeded max_steps_per_day".into()],
-            };
-        }
+// ------------------------------ Registry -------------------------------------
 
-        ValidationResult {
-            action: EvolutionInterval,
-            kind: ValidationResultKind::Passed,
-            messages: vec!["evolution interval invariants satisfied".into()],
-        }
-    } else {
-        ValidationResult {
-            action: EvolutionInterval,
-            kind: ValidationResultKind::Skipped,
-            messages: vec!["no interval_state; likely a dry-run or sandbox turn".into()],
+/// Rules for one action, evaluated in order. `passed_message` is appended to
+/// the notes of passing rules when every rule passes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActionRules {
+    pub action: AutomationActionId,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    pub rules: Vec<RuleSpec>,
+    #[serde(default)]
+    pub passed_message: Option<String>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl ActionRules {
+    pub fn new(action: AutomationActionId, rules: Vec<RuleSpec>, passed_message: Option<&str>) -> Self {
+        Self { action, enabled: true, rules, passed_message: passed_message.map(str::to_string) }
+    }
+
+    pub fn evaluate(&self, ctx: &PerTurnContext<'_>) -> ValidationResult {
+        self.evaluate_facts(&TurnFacts::from_context(ctx))
+    }
+
+    fn evaluate_facts(&self, ctx: &TurnFacts) -> ValidationResult {
+        let mut evaluated = Vec::new();
+        let mut notes = Vec::new();
+        for rule in &self.rules {
+            let verdict = rule.evaluate(ctx);
+            let outcome = verdict.outcome.clone();
+            let messages = verdict.messages.clone();
+            evaluated.push(RuleEvaluation {
+                rule: rule.name().to_string(),
+                inputs: verdict.inputs,
+                outcome: verdict.outcome,
+                messages: verdict.messages,
+            });
+            if outcome != ValidationResultKind::Passed {
+                return ValidationResult { action: self.action.clone(), kind: outcome, messages, rules: evaluated };
+            }
+            notes.extend(messages);
         }
+        notes.extend(self.passed_message.clone());
+        ValidationResult { action: self.action.clone(), kind: ValidationResultKind::Passed, messages: notes, rules: evaluated }
     }
 }
 
-/// 4. Eco + evolution budgets
-fn validate_eco_and_evolve_budgets(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::EcoAndEvolveBudgets;
-
-    if let (Some(eco), Some(brain), Some(dw)) = (ctx.eco_window, ctx.brain_window, ctx.dw_window) {
-        if !eco.within_daily_budget() {
-            return ValidationResult {
-                action: EcoAndEvolveBudgets,
-                kind: ValidationResultKind::Failed,
-                messages: vec!["eco-governor daily budget exhausted".into()],
-            };
-        }
-        if !brain.has_sufficient_capacity() {
-            return ValidationResult {
-                action: EcoAndEvolveBudgets,
-                kind: ValidationResultKind::Failed,
-                messages: vec!["insufficient BrainTokens for this turn".into()],
-            };
-        }
-        if !dw.has_sufficient_capacity() {
-            return ValidationResult {
-                action: EcoAndEvolveBudgets,
-                kind: ValidationResultKind::Failed,
-                messages: vec!["insufficient DraculaWave for this turn".into()],
-            };
-        }
+/// Declarative rule policy. With `extends_default` the listed actions replace
+/// (or, with `enabled: false`, remove) the matching default-bundle entries;
+/// otherwise the file is the complete rule set.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PerTurnRulePolicy {
+    pub version: u32,
+    #[serde(default = "enabled_by_default")]
+    pub extends_default: bool,
+    pub actions: Vec<ActionRules>,
+}
 
-        ValidationResult {
-            action: EcoAndEvolveBudgets,
-            kind: ValidationResultKind::Passed,
-            messages: vec!["eco and evolution budgets respected".into()],
-        }
-    } else {
-        ValidationResult {
-            action: EcoAndEvolveBudgets,
-            kind: ValidationResultKind::Skipped,
-            messages: vec!["missing eco or token windows; likely non-actuating turn".into()],
+pub const PER_TURN_RULE_POLICY_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum RulePolicyError {
+    Io(String),
+    Parse(String),
+    UnsupportedVersion(u32),
+    DuplicateAction(AutomationActionId),
+    EmptyRules(AutomationActionId),
+}
+
+impl fmt::Display for RulePolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "cannot read rule policy: {e}"),
+            Self::Parse(e) => write!(f, "invalid rule policy: {e}"),
+            Self::UnsupportedVersion(v) => write!(f, "rule policy version {v} is not supported"),
+            Self::DuplicateAction(a) => write!(f, "action {a:?} listed more than once"),
+            Self::EmptyRules(a) => write!(f, "enabled action {a:?} has no rules"),
         }
     }
 }
 
-/// 5. Reversible per-turn actuation
-fn validate_reversible_actuation(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::ReversibleActuation;
-
-    if let Some(proposal) = ctx.proposal {
-        if proposal.patterns.is_empty() {
-            return ValidationResult {
-                action: ReversibleActuation,
-                kind: ValidationResultKind::Skipped,
-                messages: vec!["no BiophysicalPatterns in proposal".into()],
-            };
-        }
+impl std::error::Error for RulePolicyError {}
 
-        let mut has_irreversible = false;
-        for p in &proposal.patterns {
-            if matches!(
-                p.reversibility,
-                Reversibility::Irreversible | Reversibility::PartiallyReversible
-            ) {
-                has_irreversible = true;
-                break;
-            }
-        }
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerTurnRuleRegistry {
+    actions: BTreeMap<AutomationActionId, ActionRules>,
+}
+
+impl Default for PerTurnRuleRegistry {
+    fn default() -> Self {
+        Self::default_bundle()
+    }
+}
 
-        if has_irreversible {
-            return ValidationResult {
-                action: ReversibleActuation,
-                kind: ValidationResultKind::Skipped,
-                messages: vec![
-                    "proposal contains non-reversible patterns; handled by irreversible-turn validator"
-                        .into(),
+impl PerTurnRuleRegistry {
+    /// The built-in rule set; equivalent to the checks this module always ran.
+    pub fn default_bundle() -> Self {
+        use AutomationActionId::*;
+        use RuleSpec::*;
+        let msg = |s: &str| s.to_string();
+        let bundle = vec![
+            ActionRules::new(EmergencyOverrideUse, vec![EmergencyOverride], None),
+            ActionRules::new(
+                IntentDetection,
+                vec![
+                    RequireProposal { message: msg("no proposal; nothing to validate for intent-detection") },
+                    IntentIdNonEmpty,
+                    IntentIdPrefix { prefix: msg("INTENT_") },
                 ],
-            };
-        }
+                Some("intent-detection invariants satisfied"),
+            ),
+            ActionRules::new(
+                TurnValidation,
+                vec![
+                    RequireProposal { message: msg("no proposal present for this turn") },
+                    ConstraintsProfileSet,
+                    PrevalidatedByDefaultValidator,
+                ],
+                Some("proposal satisfied all biophysical constraints"),
+            ),
+            ActionRules::new(
+                EvolutionInterval,
+                vec![
+                    RequireIntervalState { message: msg("no interval_state; likely a dry-run or sandbox turn") },
+                    IntervalPermitsStep,
+                    IntervalStepsWithinDailyMax,
+                ],
+                Some("evolution interval invariants satisfied"),
+            ),
+            ActionRules::new(
+                EcoAndEvolveBudgets,
+                vec![
+                    RequireBudgetWindows { message: msg("missing eco or token windows; likely non-actuating turn") },
+                    EcoWithinDailyBudget,
+                    BrainTokenCapacity,
+                    DraculaWaveCapacity,
+                ],
+                Some("eco and evolution budgets respected"),
+            ),
+            ActionRules::new(
+                ReversibleActuation,
+                vec![
+                    RequireProposal { message: msg("no proposal for this turn") },
+                    RequirePatterns { message: msg("no BiophysicalPatterns in proposal") },
+                    RequireOnlyReversiblePatterns {
+                        message: msg("proposal contains non-reversible patterns; handled by irreversible-turn validator"),
+                    },
+                ],
+                None,
+            ),
+            ActionRules::new(
+                IrreversibleTurn,
+                vec![
+                    RequireNonReversiblePatterns { message: msg("no irreversible or partially-reversible patterns") },
+                    IrreversibleTokenAttached,
+                    IrreversibleTokenMatchesTranscript,
+                    IrreversibleTokenNotRevoked,
+                ],
+                Some("irreversible turn carries valid consent token"),
+            ),
+            ActionRules::new(
+                DeepDomainExcavation,
+                vec![RequireDeepDomainBindings { message: msg("no deep-domain epochs or rights bound to this turn") }],
+                Some("deep-domain excavation delegated to governed_select_deep_epochs and rights profile"),
+            ),
+            ActionRules::new(
+                Traceability,
+                vec![TranscriptHashPresent, HumanExplanationMinWords { min_words: 25 }, DeepQpuDatashardNote],
+                None,
+            ),
+            ActionRules::new(MetaGovernance, vec![AugmentationRightsSafe, DeepDomainRightsSafe], None),
+            ActionRules::new(
+                OuterAttestation,
+                vec![
+                    RequireOuterAttestation { message: msg("no outer attestation for this turn") },
+                    OuterAttestationHashesOnly,
+                    OuterAttestationNoInnerBalances,
+                ],
+                Some("outer attestation respects inner/outer firewall"),
+            ),
+        ];
+        Self { actions: bundle.into_iter().map(|a| (a.action.clone(), a)).collect() }
+    }
 
-        ValidationResult {
-            action: ReversibleActuation,
-            kind: ValidationResultKind::Passed,
-            messages: vec!["all actuation patterns in this turn are FullyReversible".into()],
+    pub fn from_policy(policy: PerTurnRulePolicy) -> Result<Self, RulePolicyError> {
+        if policy.version > PER_TURN_RULE_POLICY_VERSION {
+            return Err(RulePolicyError::UnsupportedVersion(policy.version));
         }
-    } else {
-        ValidationResult {
-            action: ReversibleActuation,
-            kind: ValidationResultKind::Skipped,
-            messages: vec!["no proposal for this turn".into()],
+        let mut registry = if policy.extends_default { Self::default_bundle() } else { Self { actions: BTreeMap::new() } };
+        let mut seen = std::collections::BTreeSet::new();
+        for entry in policy.actions {
+            if !seen.insert(entry.action.clone()) {
+                return Err(RulePolicyError::DuplicateAction(entry.action));
+            }
+            if !entry.enabled {
+                registry.actions.remove(&entry.action);
+                continue;
+            }
+            if entry.rules.is_empty() {
+                return Err(RulePolicyError::EmptyRules(entry.action));
+            }
+            registry.actions.insert(entry.action.clone(), entry);
         }
+        Ok(registry)
     }
-}
 
-/// 6. Irreversible + partially reversible turns
-fn validate_irreversible_turn(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::IrreversibleTurn;
-
-    let mut needs_irrev = false;
-    if let Some(proposal) = ctx.proposal {
-        for p in &proposal.patterns {
-            if matches!(
-                p.reversibility,
-                Reversibility::Irreversible | Reversibility::PartiallyReversible
-            ) {
-                needs_irrev = true;
-                break;
-            }
-        }
+    pub fn from_json(raw: &str) -> Result<Self, RulePolicyError> {
+        let policy: PerTurnRulePolicy = serde_json::from_str(raw).map_err(|e| RulePolicyError::Parse(e.to_string()))?;
+        Self::from_policy(policy)
     }
 
-    if !needs_irrev {
-        return ValidationResult {
-            action: IrreversibleTurn,
-            kind: ValidationResultKind::Skipped,
-            messages: vec!["no irreversible or partially-reversible patterns".into()],
-        };
+    pub fn load(path: &Path) -> Result<Self, RulePolicyError> {
+        let raw = std::fs::read_to_string(path).map_err(|e| RulePolicyError::Io(format!("{}: {e}", path.display())))?;
+        Self::from_json(&raw)
     }
 
-    let token = match ctx.irreversible_token {
-        Some(t) => t,
-        None => {
-            return ValidationResult {
-                action: IrreversibleTurn,
-                kind: ValidationResultKind::Failed,
-                messages: vec![
-                    "irreversible patterns present but no IrreversibleToken attached".into(),
-                ],
-            }
-        }
-    };
-
-    if let Some(thash) = &ctx.transcripthash {
-        if &token.transcripthash != thash {
-            return ValidationResult {
-                action: IrreversibleTurn,
-                kind: ValidationResultKind::Failed,
-                messages: vec!["IrreversibleToken.transcripthash != turn.transcripthash".into()],
-            };
+    /// The registry as a standalone policy document.
+    pub fn to_policy(&self) -> PerTurnRulePolicy {
+        PerTurnRulePolicy {
+            version: PER_TURN_RULE_POLICY_VERSION,
+            extends_default: false,
+            actions: self.actions.values().cloned().collect(),
         }
     }
 
-    if token.revoked {
-        return ValidationResult {
-            action: IrreversibleTurn,
-            kind: ValidationResultKind::Failed,
-            messages: vec!["IrreversibleToken is revoked".into()],
-        };
+    /// Add or replace the rules for an action.
+    pub fn register(&mut self, rules: ActionRules) {
+        self.actions.insert(rules.action.clone(), rules);
+    }
+
+    pub fn actions(&self) -> impl Iterator<Item = &ActionRules> {
+        self.actions.values()
+    }
+
+    pub fn evaluate(&self, context: &PerTurnContext<'_>) -> BTreeMap<AutomationActionId, ValidationResult> {
+        self.evaluate_facts(&TurnFacts::from_context(context))
     }
 
-    ValidationResult {
-        action: IrreversibleTurn,
-        kind: ValidationResultKind::Passed,
-        messages: vec!["irreversible turn carries valid consent token".into()],
+    fn evaluate_facts(&self, facts: &TurnFacts) -> BTreeMap<AutomationActionId, ValidationResult> {
+        self.actions.iter().map(|(id, rules)| (id.clone(), rules.evaluate_facts(facts))).collect()
     }
 }
 
-/// 7. Deep-domain excavation (B1–B4, ?/!)
-fn validate_deep_domain_excavation(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::DeepDomainExcavation;
+/// Central entrypoint: validate all actions for this turn with the default
+/// rule bundle.
+pub fn validate_per_turn(
+    context: &PerTurnContext<'_>,
+) -> BTreeMap<AutomationActionId, ValidationResult> {
+    PerTurnRuleRegistry::default_bundle().evaluate(context)
+}
 
-    if ctx.deep_epochs.is_none() || ctx.deep_rights.is_none() || ctx.lifeforce.is_none() {
-        return ValidationResult {
-            action: DeepDomainExcavation,
-            kind: ValidationResultKind::Skipped,
-            messages: vec!["no deep-domain epochs or rights bound to this turn".into()],
-        };
+/// Validate with an explicit registry, e.g. one loaded from a policy file.
+pub fn validate_per_turn_with(
+    registry: &PerTurnRuleRegistry,
+    context: &PerTurnContext<'_>,
+) -> BTreeMap<AutomationActionId, ValidationResult> {
+    registry.evaluate(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> PerTurnContext<'static> {
+        PerTurnContext {
+            proposal: None,
+            interval_state: None,
+            eco_window: None,
+            brain_window: None,
+            dw_window: None,
+            lifeforce: None,
+            deep_epochs: None,
+            deep_rights: None,
+            aug_rights: None,
+            transcripthash: None,
+            human_explanation: None,
+            wrote_deep_qpudatashard: false,
+            irreversible_token: None,
+            outer_attestation: None,
+            host_role: "host".into(),
+            ai_platform_label: "test".into(),
+            emergency: None,
+            emergency_rights: None,
+            emergencies_used_today: 0,
+        }
     }
 
-    ValidationResult {
-        action: DeepDomainExcavation,
-        kind: ValidationResultKind::Passed,
-        messages: vec![
-            "deep-domain excavation delegated to governed_select_deep_epochs and rights profile"
-                .into(),
-        ],
+    fn golden(results: &BTreeMap<AutomationActionId, ValidationResult>) -> String {
+        results
+            .values()
+            .map(|r| format!("{:?}|{:?}|{}\n", r.action, r.kind, r.messages.join(" / ")))
+            .collect()
     }
-}
 
-/// 8. Traceability
-fn validate_traceability(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::Traceability;
-    let mut msgs = Vec::new();
-
-    if ctx
-        .transcripthash
-        .as_ref()
-        .map(|s| s.is_empty())
-        .unwrap_or(true)
-    {
-        msgs.push("transcripthash must be non-empty for all evolution turns".into());
-        return ValidationResult {
-            action: Traceability,
-            kind: ValidationResultKind::Failed,
-            messages: msgs,
-        };
+    #[test]
+    fn golden_empty_turn() {
+        let expected = "\
+EmergencyOverrideUse|Skipped|no emergency override bound to this turn
+IntentDetection|Skipped|no proposal; nothing to validate for intent-detection
+TurnValidation|Skipped|no proposal present for this turn
+EvolutionInterval|Skipped|no interval_state; likely a dry-run or sandbox turn
+EcoAndEvolveBudgets|Skipped|missing eco or token windows; likely non-actuating turn
+ReversibleActuation|Skipped|no proposal for this turn
+IrreversibleTurn|Skipped|no irreversible or partially-reversible patterns
+DeepDomainExcavation|Skipped|no deep-domain epochs or rights bound to this turn
+Traceability|Failed|transcripthash must be non-empty for all evolution turns
+MetaGovernance|Passed|no AugmentationRight profile in context; assuming pre-validated at startup / no DeepDomainRightsProfile bound; deep-domain excavation should be disabled
+OuterAttestation|Skipped|no outer attestation for this turn
+";
+        assert_eq!(golden(&validate_per_turn(&ctx())), expected);
+    }
+
+    #[test]
+    fn golden_traceability_and_explain_report() {
+        let mut c = ctx();
+        c.transcripthash = Some("0xabc".into());
+        c.human_explanation = Some("too short to count".into());
+        let r = &validate_per_turn(&c)[&AutomationActionId::Traceability];
+        assert_eq!(r.kind, ValidationResultKind::Failed);
+        assert_eq!(r.messages, vec!["human_explanation too short: 4 words (min 25)".to_string()]);
+        let names: Vec<&str> = r.rules.iter().map(|e| e.rule.as_str()).collect();
+        assert_eq!(names, vec!["transcript_hash_present", "human_explanation_min_words"]);
+        assert_eq!(r.rules[1].inputs["word_count"], "4");
+        assert_eq!(r.rules[1].inputs["min_words"], "25");
+
+        c.human_explanation = Some(vec!["word"; 25].join(" "));
+        c.wrote_deep_qpudatashard = true;
+        let r = &validate_per_turn(&c)[&AutomationActionId::Traceability];
+        assert_eq!(r.kind, ValidationResultKind::Passed);
+        assert_eq!(r.messages, vec!["qpudatashard for deep-object excavation was written".to_string()]);
+        assert_eq!(r.rules.len(), 3);
     }
 
-    if let Some(exp) = &ctx.human_explanation {
-        let word_count = exp.split_whitespace().count();
-        if word_count < 25 {
-            msgs.push(format!(
-                "human_explanation too short: {} words (min 25)",
-                word_count
-            ));
-            return ValidationResult {
-                action: Traceability,
-                kind: ValidationResultKind::Failed,
-                messages: msgs,
-            };
+    /// `kind|rule=outcome,...|messages` for one action under the default
+    /// bundle, so each golden line pins the rule path that was taken.
+    fn trail(facts: &TurnFacts, action: AutomationActionId) -> String {
+        let r = &PerTurnRuleRegistry::default_bundle().evaluate_facts(facts)[&action];
+        let rules: Vec<String> = r.rules.iter().map(|e| format!("{}={:?}", e.rule, e.outcome)).collect();
+        format!("{:?}|{}|{}\n", r.kind, rules.join(","), r.messages.join(" / "))
+    }
+
+    fn proposal(intentid: &str, non_reversible: bool) -> ProposalFacts {
+        ProposalFacts {
+            intentid: intentid.into(),
+            constraints_profile_id: "cp-default".into(),
+            prevalidated_by_default_validator: true,
+            patterns: 2,
+            has_non_reversible: non_reversible,
         }
-    } else {
-        msgs.push("missing human_explanation for this turn".into());
-        return ValidationResult {
-            action: Traceability,
-            kind: ValidationResultKind::Failed,
-            messages: msgs,
-        };
     }
 
-    if ctx.wrote_deep_qpudatashard {
-        msgs.push("qpudatashard for deep-object excavation was written".into());
-    } else {
-        msgs.push("no deep qpudatashard recorded (ok if no B3/B4 used)".into());
+    fn with(f: impl FnOnce(&mut TurnFacts)) -> TurnFacts {
+        let mut facts = TurnFacts { host_role: "host".into(), ..TurnFacts::default() };
+        f(&mut facts);
+        facts
     }
 
-    ValidationResult {
-        action: Traceability,
-        kind: ValidationResultKind::Passed,
-        messages: msgs,
+    #[test]
+    fn golden_proposal_paths() {
+        let cases = [
+            with(|f| f.proposal = Some(proposal("", false))),
+            with(|f| f.proposal = Some(proposal("MOVE_ARM", false))),
+            with(|f| f.proposal = Some(proposal("INTENT_MOVE_ARM", false))),
+        ];
+        let intent: String = cases.iter().map(|f| trail(f, AutomationActionId::IntentDetection)).collect();
+        assert_eq!(intent, "\
+Failed|require_proposal=Passed,intent_id_non_empty=Failed|proposal.intentid must be non-empty and part of typed vocabulary
+Failed|require_proposal=Passed,intent_id_non_empty=Passed,intent_id_prefix=Failed|intentid 'MOVE_ARM' does not use INTENT_ prefix
+Passed|require_proposal=Passed,intent_id_non_empty=Passed,intent_id_prefix=Passed|intent-detection invariants satisfied
+");
+
+        let cases = [
+            with(|f| f.proposal = Some(ProposalFacts { constraints_profile_id: String::new(), ..proposal("INTENT_A", false) })),
+            with(|f| f.proposal = Some(ProposalFacts { prevalidated_by_default_validator: false, ..proposal("INTENT_A", false) })),
+            with(|f| f.proposal = Some(proposal("INTENT_A", false))),
+        ];
+        let turn: String = cases.iter().map(|f| trail(f, AutomationActionId::TurnValidation)).collect();
+        assert_eq!(turn, "\
+Failed|require_proposal=Passed,constraints_profile_set=Failed|constraints_profile_id must be set on EvolutionProposal
+Failed|require_proposal=Passed,constraints_profile_set=Passed,prevalidated_by_default_validator=Failed|DefaultProposalValidator did not confirm this proposal
+Passed|require_proposal=Passed,constraints_profile_set=Passed,prevalidated_by_default_validator=Passed|proposal satisfied all biophysical constraints
+");
     }
-}
 
-/// 9. Meta-governance (AugmentationRight + DeepDomainRights)
-fn validate_meta_governance(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::MetaGovernance;
-    let mut msgs = Vec::new();
+    #[test]
+    fn golden_interval_paths() {
+        let interval = |permits: bool, taken: u32, max: u32| IntervalFacts {
+            permits_new_step: permits,
+            steps_taken_today: taken.to_string(),
+            max_steps_per_day: max.to_string(),
+            steps_within_daily_max: taken <= max,
+        };
+        let cases = [
+            with(|f| f.interval = Some(interval(false, 0, 3))),
+            with(|f| f.interval = Some(interval(true, 4, 3))),
+            with(|f| f.interval = Some(interval(true, 3, 3))),
+        ];
+        let got: String = cases.iter().map(|f| trail(f, AutomationActionId::EvolutionInterval)).collect();
+        assert_eq!(got, "\
+Failed|require_interval_state=Passed,interval_permits_step=Failed|cantakeevolutionstep() denied this turn
+Failed|require_interval_state=Passed,interval_permits_step=Passed,interval_steps_within_daily_max=Failed|steps_taken_today exceeded max_steps_per_day
+Passed|require_interval_state=Passed,interval_permits_step=Passed,interval_steps_within_daily_max=Passed|evolution interval invariants satisfied
+");
+    }
 
-    if let Some(aug) = ctx.aug_rights {
-        match aug.verify_rights_safe() {
-            AugmentationRightStatus::RightsSafe => {
-                msgs.push("AugmentationRight profile is RightsSafe".into())
-            }
-            AugmentationRightStatus::ViolatesInvariant(e) => {
-                return ValidationResult {
-                    action: MetaGovernance,
-                    kind: ValidationResultKind::Failed,
-                    messages: vec![format!("AugmentationRight invariant violation: {:?}", e)],
-                };
-            }
-        }
-    } else {
-        msgs.push(
-            "no AugmentationRight profile in context; assuming pre-validated at startup".into(),
-        );
+    #[test]
+    fn golden_budget_paths() {
+        let windows = |eco: bool, brain: bool, dw: bool| {
+            with(move |f| {
+                f.eco_within_budget = Some(eco);
+                f.brain_capacity = Some(brain);
+                f.dw_capacity = Some(dw);
+            })
+        };
+        let cases = [
+            with(|f| {
+                f.eco_within_budget = Some(true);
+                f.brain_capacity = Some(true);
+            }),
+            windows(false, true, true),
+            windows(true, false, true),
+            windows(true, true, false),
+            windows(true, true, true),
+        ];
+        let got: String = cases.iter().map(|f| trail(f, AutomationActionId::EcoAndEvolveBudgets)).collect();
+        assert_eq!(got, "\
+Skipped|require_budget_windows=Skipped|missing eco or token windows; likely non-actuating turn
+Failed|require_budget_windows=Passed,eco_within_daily_budget=Failed|eco-governor daily budget exhausted
+Failed|require_budget_windows=Passed,eco_within_daily_budget=Passed,brain_token_capacity=Failed|insufficient BrainTokens for this turn
+Failed|require_budget_windows=Passed,eco_within_daily_budget=Passed,brain_token_capacity=Passed,dracula_wave_capacity=Failed|insufficient DraculaWave for this turn
+Passed|require_budget_windows=Passed,eco_within_daily_budget=Passed,brain_token_capacity=Passed,dracula_wave_capacity=Passed|eco and evolution budgets respected
+");
     }
 
-    if let Some(dd) = ctx.deep_rights {
-        match dd.verify_rights_safe() {
-            DeepDomainRightsStatus::RightsSafe => {
-                msgs.push("DeepDomainRightsProfile is RightsSafe".into())
-            }
-            DeepDomainRightsStatus::ViolatesInvariant(e) => {
-                return ValidationResult {
-                    action: MetaGovernance,
-                    kind: ValidationResultKind::Failed,
-                    messages: vec![format!("DeepDomainRights invariant violation: {:?}", e)],
-                };
-            }
-        }
-    } else {
-        msgs.push(
-            "no DeepDomainRightsProfile bound; deep-domain excavation should be disabled".into(),
-        );
+    #[test]
+    fn golden_reversible_and_irreversible_paths() {
+        let token = |hash: &str, revoked: bool| Some(TokenFacts { transcripthash: hash.into(), revoked });
+        let cases = [
+            with(|f| f.proposal = Some(ProposalFacts { patterns: 0, ..proposal("INTENT_A", false) })),
+            with(|f| f.proposal = Some(proposal("INTENT_A", false))),
+            with(|f| f.proposal = Some(proposal("INTENT_A", true))),
+            with(|f| {
+                f.proposal = Some(proposal("INTENT_A", true));
+                f.transcripthash = Some("0xturn".into());
+            }),
+            with(|f| {
+                f.proposal = Some(proposal("INTENT_A", true));
+                f.transcripthash = Some("0xturn".into());
+                f.irreversible_token = token("0xother", false);
+            }),
+            with(|f| {
+                f.proposal = Some(proposal("INTENT_A", true));
+                f.transcripthash = Some("0xturn".into());
+                f.irreversible_token = token("0xturn", true);
+            }),
+            with(|f| {
+                f.proposal = Some(proposal("INTENT_A", true));
+                f.transcripthash = Some("0xturn".into());
+                f.irreversible_token = token("0xturn", false);
+            }),
+            // Without a turn transcript hash there is nothing to compare.
+            with(|f| {
+                f.proposal = Some(proposal("INTENT_A", true));
+                f.irreversible_token = token("0xother", false);
+            }),
+        ];
+        let got: String = cases
+            .iter()
+            .map(|f| {
+                trail(f, AutomationActionId::ReversibleActuation) + &trail(f, AutomationActionId::IrreversibleTurn)
+            })
+            .collect();
+        assert_eq!(got, "\
+Skipped|require_proposal=Passed,require_patterns=Skipped|no BiophysicalPatterns in proposal
+Skipped|require_non_reversible_patterns=Skipped|no irreversible or partially-reversible patterns
+Passed|require_proposal=Passed,require_patterns=Passed,require_only_reversible_patterns=Passed|all actuation patterns in this turn are FullyReversible
+Skipped|require_non_reversible_patterns=Skipped|no irreversible or partially-reversible patterns
+Skipped|require_proposal=Passed,require_patterns=Passed,require_only_reversible_patterns=Skipped|proposal contains non-reversible patterns; handled by irreversible-turn validator
+Failed|require_non_reversible_patterns=Passed,irreversible_token_attached=Failed|irreversible patterns present but no IrreversibleToken attached
+Skipped|require_proposal=Passed,require_patterns=Passed,require_only_reversible_patterns=Skipped|proposal contains non-reversible patterns; handled by irreversible-turn validator
+Failed|require_non_reversible_patterns=Passed,irreversible_token_attached=Failed|irreversible patterns present but no IrreversibleToken attached
+Skipped|require_proposal=Passed,require_patterns=Passed,require_only_reversible_patterns=Skipped|proposal contains non-reversible patterns; handled by irreversible-turn validator
+Failed|require_non_reversible_patterns=Passed,irreversible_token_attached=Passed,irreversible_token_matches_transcript=Failed|IrreversibleToken.transcripthash != turn.transcripthash
+Skipped|require_proposal=Passed,require_patterns=Passed,require_only_reversible_patterns=Skipped|proposal contains non-reversible patterns; handled by irreversible-turn validator
+Failed|require_non_reversible_patterns=Passed,irreversible_token_attached=Passed,irreversible_token_matches_transcript=Passed,irreversible_token_not_revoked=Failed|IrreversibleToken is revoked
+Skipped|require_proposal=Passed,require_patterns=Passed,require_only_reversible_patterns=Skipped|proposal contains non-reversible patterns; handled by irreversible-turn validator
+Passed|require_non_reversible_patterns=Passed,irreversible_token_attached=Passed,irreversible_token_matches_transcript=Passed,irreversible_token_not_revoked=Passed|irreversible turn carries valid consent token
+Skipped|require_proposal=Passed,require_patterns=Passed,require_only_reversible_patterns=Skipped|proposal contains non-reversible patterns; handled by irreversible-turn validator
+Passed|require_non_reversible_patterns=Passed,irreversible_token_attached=Passed,irreversible_token_matches_transcript=Passed,irreversible_token_not_revoked=Passed|irreversible turn carries valid consent token
+");
     }
 
-    ValidationResult {
-        action: MetaGovernance,
-        kind: ValidationResultKind::Passed,
-        messages: msgs,
+    #[test]
+    fn golden_meta_governance_paths() {
+        let cases = [
+            with(|f| f.aug_rights = Some(Err("[\"host_id\"]".into()))),
+            with(|f| f.aug_rights = Some(Ok(()))),
+            with(|f| {
+                f.aug_rights = Some(Ok(()));
+                f.deep_rights = Some(Err("[\"scope\"]".into()));
+            }),
+            with(|f| {
+                f.aug_rights = Some(Ok(()));
+                f.deep_rights = Some(Ok(()));
+            }),
+        ];
+        let got: String = cases.iter().map(|f| trail(f, AutomationActionId::MetaGovernance)).collect();
+        assert_eq!(got, "\
+Failed|augmentation_rights_safe=Failed|AugmentationRight invariant violation: [\"host_id\"]
+Passed|augmentation_rights_safe=Passed,deep_domain_rights_safe=Passed|AugmentationRight profile is RightsSafe / no DeepDomainRightsProfile bound; deep-domain excavation should be disabled
+Failed|augmentation_rights_safe=Passed,deep_domain_rights_safe=Failed|DeepDomainRights invariant violation: [\"scope\"]
+Passed|augmentation_rights_safe=Passed,deep_domain_rights_safe=Passed|AugmentationRight profile is RightsSafe / DeepDomainRightsProfile is RightsSafe
+");
     }
-}
 
-/// 10. Outer-world attestation
-fn validate_outer_attestation(ctx: &PerTurnContext<'_>) -> ValidationResult {
-    use AutomationActionId::OuterAttestation;
-
-    if let Some(att) = ctx.outer_attestation {
-        if !att.payload_is_hashes_only {
-            return ValidationResult {
-                action: OuterAttestation,
-                kind: ValidationResultKind::Failed,
-                messages: vec!["outer attestation must contain only hashes / proof-ids".into()],
-            };
-        }
-        if att.includes_inner_token_balances {
-            return ValidationResult {
-                action: OuterAttestation,
-                kind: ValidationResultKind::Failed,
-                messages: vec!["outer attestation may not leak inner token balances".into()],
-            };
-        }
+    #[test]
+    fn golden_outer_attestation_paths() {
+        let attestation = |hashes_only: bool, leaks: bool| {
+            with(move |f| {
+                f.outer_attestation = Some(OuterAttestationFacts {
+                    payload_is_hashes_only: hashes_only,
+                    includes_inner_token_balances: leaks,
+                })
+            })
+        };
+        let cases = [attestation(false, false), attestation(true, true), attestation(true, false)];
+        let got: String = cases.iter().map(|f| trail(f, AutomationActionId::OuterAttestation)).collect();
+        assert_eq!(got, "\
+Failed|require_outer_attestation=Passed,outer_attestation_hashes_only=Failed|outer attestation must contain only hashes / proof-ids
+Failed|require_outer_attestation=Passed,outer_attestation_hashes_only=Passed,outer_attestation_no_inner_balances=Failed|outer attestation may not leak inner token balances
+Passed|require_outer_attestation=Passed,outer_attestation_hashes_only=Passed,outer_attestation_no_inner_balances=Passed|outer attestation respects inner/outer firewall
+");
+    }
 
-        ValidationResult {
-            action: OuterAttestation,
-            kind: ValidationResultKind::Passed,
-            messages: vec!["outer attestation respects inner/outer firewall".into()],
-        }
-    } else {
-        ValidationResult {
-            action: OuterAttestation,
-            kind: ValidationResultKind::Skipped,
-            messages: vec!["no outer attestation for this turn".into()],
-        }
+    #[test]
+    fn policy_file_overlays_default_bundle_and_ships_it() {
+        let shipped = include_str!("../../policies/per-turn-validation.default.json");
+        assert_eq!(PerTurnRuleRegistry::from_json(shipped).unwrap(), PerTurnRuleRegistry::default_bundle());
+
+        let overlay = r#"{
+            "version": 1,
+            "actions": [
+                { "action": "OuterAttestation", "enabled": false, "rules": [] },
+                { "action": { "Custom": "HostRole" },
+                  "rules": [ { "rule": "host_role_in", "roles": ["host", "ethical_operator"] } ],
+                  "passed_message": "host role allowed" }
+            ]
+        }"#;
+        let registry = PerTurnRuleRegistry::from_json(overlay).unwrap();
+        let results = validate_per_turn_with(&registry, &ctx());
+        assert!(!results.contains_key(&AutomationActionId::OuterAttestation));
+        let custom = &results[&AutomationActionId::Custom("HostRole".into())];
+        assert_eq!(custom.kind, ValidationResultKind::Passed);
+        assert_eq!(custom.messages, vec!["host role allowed".to_string()]);
+        assert_eq!(results.len(), 11);
+
+        let dup = r#"{ "version": 1, "actions": [
+            { "action": "Traceability", "rules": [ { "rule": "transcript_hash_present" } ] },
+            { "action": "Traceability", "rules": [ { "rule": "transcript_hash_present" } ] } ] }"#;
+        assert!(matches!(PerTurnRuleRegistry::from_json(dup), Err(RulePolicyError::DuplicateAction(_))));
     }
 }
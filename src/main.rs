@@ -1,11 +1,19 @@
 mod chain {
     pub mod biophysical_runtime;
+    pub mod host_gossip;
+    pub mod host_history;
     pub mod host_node;
+    pub mod runtime_event_log;
 }
 
 use chain::biophysical_runtime::aln_did::ALNDID;
-use chain::host_node::bootstrap_single_host_node;
+use chain::host_gossip::PeerSet;
+use chain::host_node::{
+    bootstrap_host_node_with_gossip, bootstrap_single_host_node, replay_host_event_log,
+    runtime_log_dir, serve_jsonrpc, HostNodeState,
+};
 use std::net::SocketAddr;
+use tokio::net::TcpListener;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -14,7 +22,47 @@ async fn main() -> anyhow::Result<()> {
         shard: "phx-main".to_string(),
     };
 
+    // `replay [log_dir]`: rebuild state from genesis, verify every recorded
+    // frame hash and exit instead of serving.
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("replay") {
+        let dir = args.get(2).map(std::path::PathBuf::from).unwrap_or_else(runtime_log_dir);
+        let report = replay_host_event_log(&host_id, &dir)?;
+        println!(
+            "[REPLAY] {} events verified from {}; state_hash {}",
+            report.entries,
+            dir.display(),
+            report.state_hash
+        );
+        println!("[REPLAY] final state {:?}", report.state);
+        return Ok(());
+    }
+
     let bind: SocketAddr = "127.0.0.1:7070".parse().unwrap();
+
+    // Join a static peer set when one is configured; otherwise run alone and
+    // just log the frames this host would gossip.
+    if let Ok(cfg) = std::env::var("HOST_GOSSIP_CONFIG") {
+        let peers = PeerSet::load(std::path::Path::new(&cfg))?;
+        let gossip_bind: SocketAddr = std::env::var("HOST_GOSSIP_BIND")
+            .unwrap_or_else(|_| "127.0.0.1:7071".to_string())
+            .parse()?;
+        let rpc_bind: SocketAddr = std::env::var("HOST_RPC_BIND")
+            .unwrap_or_else(|_| "127.0.0.1:7072".to_string())
+            .parse()?;
+        let node_dir = std::env::var("HOST_NODE_DIR").unwrap_or_else(|_| "data/host-node".to_string());
+        let hostid = host_id.id.clone();
+        let (_handle, gossip) =
+            bootstrap_host_node_with_gossip(host_id, bind, gossip_bind, peers).await?;
+
+        // Serve history and fork evidence so operators can see divergence.
+        let state = HostNodeState::load_or_init(&hostid, &node_dir)?.with_gossip(gossip);
+        let _rpc = serve_jsonrpc(TcpListener::bind(rpc_bind).await?, state);
+        println!("[HostNode] JSON-RPC (host.getForkEvidence, host.getFrames) on {}", rpc_bind);
+        futures::future::pending::<()>().await;
+        return Ok(());
+    }
+
     let (_handle, mut gossip_rx) = bootstrap_single_host_node(host_id, bind).await?;
 
     tokio::spawn(async move {
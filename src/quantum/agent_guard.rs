@@ -1,6 +1,6 @@
 use crate::biophysical::plane_classifier::{
-    BrainTokenState, CloningPolicy, ConsciousnessState, EnvironmentMetadata, EnvironmentPlane,
-    PlaneClassifier, PlaneClassifierConfig,
+    BrainTokenState, CloningPolicy, ConsciousnessState, DecisionRecord, EnvironmentMetadata,
+    EnvironmentPlane, PlaneClassifier, PlaneClassifierConfig,
 };
 
 #[derive(Clone, Debug)]
@@ -99,7 +99,7 @@ impl QuantumLearningGuard {
         input_tags: Vec<String>,
         hardware_profile: Vec<String>,
         regulatory_labels: Vec<String>,
-    ) -> (EnvironmentMetadata, GuardDecision, Vec<String>) {
+    ) -> (EnvironmentMetadata, GuardDecision, Vec<DecisionRecord>) {
         let meta = self.classifier.build_environment_metadata(
             id,
             involves_living_organism,
@@ -40,7 +40,7 @@ fn happy_path_claim_and_spend() -> Result<()> {
     let energy_router_label = "energy_router";
 
     // Instantiate energy router
-    let router_msg = energy_router::InstantiateMsg { owner: "owner".to_string() };
+    let router_msg = energy_router::InstantiateMsg { owner: "owner".to_string(), bridge_contract: None };
     let router_addr = app.instantiate_contract(router_code, Addr::unchecked("creator"), &router_msg, &[], energy_router_label, None)?;
 
     // Instantiate AU.ET with bridge having initial balances
@@ -80,11 +80,12 @@ fn happy_path_claim_and_spend() -> Result<()> {
     // Register and approve asset via governance. Build a snapshot entry for the user and make merkle_root == H_i for single-leaf tree
     let s_user = aln_bridge::SnapshotEntry { chain_id: "kaiyo-1".to_string(), height: 123, denom: "ibc/xxx".to_string(), address: user.to_string(), balance: "100".to_string() };
     let h_user = compute_snapshot_hash(&s_user);
-    let asset = aln_registry::RegisteredAsset { id: "a1".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 123, merkle_root: h_user.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true };
+    let asset = aln_registry::RegisteredAsset { id: "a1".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 123, merkle_root: h_user.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     let reg_msg = aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() };
     app.execute_contract(gov.clone(), reg_addr.clone(), &reg_msg, &[])?;
     let approve_msg = aln_registry::ExecuteMsg::ApproveSanitized { id: "a1".to_string(), ubs_report_hash: "h1".to_string() };
     app.execute_contract(gov.clone(), reg_addr.clone(), &approve_msg, &[])?;
+    app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ActivateAsset { id: "a1".to_string() }, &[])?;
 
     // Instantiate bridge with references and governance
     let bridge_instantiate_msg = aln_bridge::InstantiateMsg { auet_contract: auet_addr.to_string(), csp_contract: Some(csp_addr.to_string()), registry_contract: reg_addr.to_string(), governance_addr: gov.to_string() };
@@ -155,7 +156,7 @@ fn claim_before_sanitized_rejected() -> Result<()> {
     // register asset but do not approve sanitized
     let s2 = aln_bridge::SnapshotEntry { chain_id: "kaiyo-1".to_string(), height: 0, denom: "ibc/yyy".to_string(), address: "user".to_string(), balance: "1".to_string() };
     let h2 = compute_snapshot_hash(&s2);
-    let asset = aln_registry::RegisteredAsset { id: "a2".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/yyy".to_string(), snapshot_height: 0, merkle_root: h2.clone(), ubs_report_hash: None, scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: false };
+    let asset = aln_registry::RegisteredAsset { id: "a2".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/yyy".to_string(), snapshot_height: 0, merkle_root: h2.clone(), ubs_report_hash: None, scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: false, state: Default::default(), merkle_root_version: 0 };
     let reg_msg2 = aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() };
     app.execute_contract(gov.clone(), reg_addr.clone(), &reg_msg2, &[])?;
 
@@ -200,9 +201,10 @@ fn double_claim_rejected() -> Result<()> {
     let reg_addr = app.instantiate_contract(reg_code, Addr::unchecked("creator"), &reg_msg, &[], "REG", None)?;
     let s3 = aln_bridge::SnapshotEntry { chain_id: "kaiyo-1".to_string(), height: 0, denom: "ibc/zzz".to_string(), address: "user".to_string(), balance: "1".to_string() };
     let h3 = compute_snapshot_hash(&s3);
-    let asset = aln_registry::RegisteredAsset { id: "a3".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/zzz".to_string(), snapshot_height: 0, merkle_root: h3.clone(), ubs_report_hash: Some("h3".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true };
+    let asset = aln_registry::RegisteredAsset { id: "a3".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/zzz".to_string(), snapshot_height: 0, merkle_root: h3.clone(), ubs_report_hash: Some("h3".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() }, &[])?;
     app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ApproveSanitized { id: "a3".to_string(), ubs_report_hash: "h3".to_string() }, &[])?;
+    app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ActivateAsset { id: "a3".to_string() }, &[])?;
     let bridge_msg = aln_bridge::InstantiateMsg { auet_contract: auet_addr.to_string(), csp_contract: None, registry_contract: reg_addr.to_string(), governance_addr: gov.to_string() };
     let bridge_addr_inst = app.instantiate_contract(bridge_code, Addr::unchecked("creator"), &bridge_msg, &[], "BRIDGE", None)?;
 
@@ -352,9 +354,10 @@ fn claim_with_valid_merkle_proof_succeeds() -> Result<()> {
     let gov = Addr::unchecked("gov");
     let reg_instantiate_msg = aln_registry::InstantiateMsg { governance_addr: gov.to_string(), allow_missing_ubs: Some(true) };
     let reg_addr = app.instantiate_contract(reg_code, Addr::unchecked("creator"), &reg_instantiate_msg, &[], "REG", None)?;
-    let asset = aln_registry::RegisteredAsset { id: "m1".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 100, merkle_root: root.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true };
+    let asset = aln_registry::RegisteredAsset { id: "m1".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 100, merkle_root: root.clone(), ubs_report_hash: Some("h1".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() }, &[])?;
     app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ApproveSanitized { id: "m1".to_string(), ubs_report_hash: "h1".to_string() }, &[])?;
+    app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ActivateAsset { id: "m1".to_string() }, &[])?;
 
     // Instantiate AU.ET with the bridge address to hold initial funds
     let bridge_addr = Addr::unchecked("bridge");
@@ -411,9 +414,10 @@ fn claim_with_invalid_merkle_proof_fails() -> Result<()> {
     let gov = Addr::unchecked("gov");
     let reg_instantiate_msg = aln_registry::InstantiateMsg { governance_addr: gov.to_string(), allow_missing_ubs: Some(true) };
     let reg_addr = app.instantiate_contract(reg_code, Addr::unchecked("creator"), &reg_instantiate_msg, &[], "REG", None)?;
-    let asset = aln_registry::RegisteredAsset { id: "m2".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 100, merkle_root: root.clone(), ubs_report_hash: Some("h2".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true };
+    let asset = aln_registry::RegisteredAsset { id: "m2".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 100, merkle_root: root.clone(), ubs_report_hash: Some("h2".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() }, &[])?;
     app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ApproveSanitized { id: "m2".to_string(), ubs_report_hash: "h2".to_string() }, &[])?;
+    app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ActivateAsset { id: "m2".to_string() }, &[])?;
     let bridge_addr = Addr::unchecked("bridge");
     let auet_instantiate_msg = aln_auet::InstantiateMsg { cw20: Cw20InstantiateMsg { name: "AU.ET".to_string(), symbol: "AUET".to_string(), decimals: 6, initial_balances: vec![Cw20Coin { address: bridge_addr.to_string(), amount: Uint128::new(1_000_000) }], mint: None, marketing: None }, snapshot: aln_auet::SnapshotMeta { chain_id: "kaiyo-1".to_string(), height: 100, merkle_root: root.clone() }, allowed_modules: Some(vec![]) };
     let auet_addr = app.instantiate_contract(auet_code, Addr::unchecked("creator"), &auet_instantiate_msg, &[], "AUET", None)?;
@@ -424,8 +428,8 @@ fn claim_with_invalid_merkle_proof_fails() -> Result<()> {
     wrong_proof[0].0[0] ^= 0xff; // flip a byte
     let proof_steps: Vec<aln_bridge::ProofStep> = wrong_proof.iter().map(|(b, is_left)| aln_bridge::ProofStep { sibling: Binary(b.clone()), is_left: *is_left }).collect();
     let claim_msg = aln_bridge::ExecuteMsg::Claim { asset_id: "m2".to_string(), snapshot: s1.clone(), snapshot_hash: h1.clone(), merkle_proof: proof_steps, amount_auet: Uint128::new(100), amount_csp: None };
-    let res = app.execute_contract(Addr::unchecked("user"), bridge_addr_inst.clone(), &claim_msg, &[]);
-    assert!(res.is_err());
+    let err = app.execute_contract(Addr::unchecked("user"), bridge_addr_inst.clone(), &claim_msg, &[]).unwrap_err();
+    assert!(err.root_cause().to_string().contains("invalid merkle proof"), "{err:?}");
     Ok(())
 }
 
@@ -447,9 +451,10 @@ fn claim_with_modified_snapshot_entry_fails() -> Result<()> {
     let gov = Addr::unchecked("gov");
     let reg_instantiate_msg = aln_registry::InstantiateMsg { governance_addr: gov.to_string(), allow_missing_ubs: Some(true) };
     let reg_addr = app.instantiate_contract(reg_code, Addr::unchecked("creator"), &reg_instantiate_msg, &[], "REG", None)?;
-    let asset = aln_registry::RegisteredAsset { id: "m3".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 100, merkle_root: root.clone(), ubs_report_hash: Some("h3".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true };
+    let asset = aln_registry::RegisteredAsset { id: "m3".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 100, merkle_root: root.clone(), ubs_report_hash: Some("h3".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 0, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() }, &[])?;
     app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ApproveSanitized { id: "m3".to_string(), ubs_report_hash: "h3".to_string() }, &[])?;
+    app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ActivateAsset { id: "m3".to_string() }, &[])?;
     let bridge_addr = Addr::unchecked("bridge");
     let auet_instantiate_msg = aln_auet::InstantiateMsg { cw20: Cw20InstantiateMsg { name: "AU.ET".to_string(), symbol: "AUET".to_string(), decimals: 6, initial_balances: vec![Cw20Coin { address: bridge_addr.to_string(), amount: Uint128::new(1_000_000) }], mint: None, marketing: None }, snapshot: aln_auet::SnapshotMeta { chain_id: "kaiyo-1".to_string(), height: 100, merkle_root: root.clone() }, allowed_modules: Some(vec![]) };
     let auet_addr = app.instantiate_contract(auet_code, Addr::unchecked("creator"), &auet_instantiate_msg, &[], "AUET", None)?;
@@ -460,9 +465,11 @@ fn claim_with_modified_snapshot_entry_fails() -> Result<()> {
     let proof_steps: Vec<aln_bridge::ProofStep> = proof_bytes.iter().map(|(b, is_left)| aln_bridge::ProofStep { sibling: Binary(b.clone()), is_left: *is_left }).collect();
     let mut tampered = s1.clone();
     tampered.balance = "1001".to_string();
-    let claim_msg = aln_bridge::ExecuteMsg::Claim { asset_id: "m3".to_string(), snapshot: tampered, snapshot_hash: h1.clone(), merkle_proof: proof_steps, amount_auet: Uint128::new(10), amount_csp: None };
-    let res = app.execute_contract(Addr::unchecked("user2"), bridge_addr_inst.clone(), &claim_msg, &[]);
-    assert!(res.is_err());
+    // hash the tampered entry itself so the claim gets past the hash check and is rejected by the proof
+    let tampered_hash = compute_snapshot_hash(&tampered);
+    let claim_msg = aln_bridge::ExecuteMsg::Claim { asset_id: "m3".to_string(), snapshot: tampered, snapshot_hash: tampered_hash, merkle_proof: proof_steps, amount_auet: Uint128::new(10), amount_csp: None };
+    let err = app.execute_contract(Addr::unchecked("user2"), bridge_addr_inst.clone(), &claim_msg, &[]).unwrap_err();
+    assert!(err.root_cause().to_string().contains("invalid merkle proof"), "{err:?}");
     Ok(())
 }
 
@@ -485,7 +492,7 @@ fn claim_before_activation_height_fails() -> Result<()> {
     let gov = Addr::unchecked("gov");
     let reg_instantiate_msg = aln_registry::InstantiateMsg { governance_addr: gov.to_string(), allow_missing_ubs: Some(true) };
     let reg_addr = app.instantiate_contract(reg_code, Addr::unchecked("creator"), &reg_instantiate_msg, &[], "REG", None)?;
-    let asset = aln_registry::RegisteredAsset { id: "d1".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 100, merkle_root: root.clone(), ubs_report_hash: Some("h3".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 1000, sanitized_approved: true };
+    let asset = aln_registry::RegisteredAsset { id: "d1".to_string(), source_chain: "kaiyo-1".to_string(), source_denom: "ibc/xxx".to_string(), snapshot_height: 100, merkle_root: root.clone(), ubs_report_hash: Some("h3".to_string()), scaling_profile_id: "malicious_cleanup".to_string(), activation_height: 1000, sanitized_approved: true, state: Default::default(), merkle_root_version: 0 };
     app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::RegisterAsset { asset: asset.clone() }, &[])?;
     app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ApproveSanitized { id: "d1".to_string(), ubs_report_hash: "h3".to_string() }, &[])?;
 
@@ -506,6 +513,7 @@ fn claim_before_activation_height_fails() -> Result<()> {
     
     // now set height to after activation
     app.update_block(|b| b.height = 2000);
+    app.execute_contract(gov.clone(), reg_addr.clone(), &aln_registry::ExecuteMsg::ActivateAsset { id: "d1".to_string() }, &[])?;
     let res2 = app.execute_contract(Addr::unchecked("del_user"), bridge_addr_inst.clone(), &claim_msg, &[])?;
     assert!(res2.attributes.iter().any(|a| a.key == "action" && a.value == "claim"));
     Ok(())
@@ -1,3 +1,6 @@
+mod scaling;
+
+use scaling::compress_balance;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
@@ -17,12 +20,59 @@ struct DenomTracesResponse {
     pub pagination: Value,
 }
 
+#[derive(Debug, Deserialize)]
+struct Coin {
+    pub amount: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SupplyResponse {
+    pub amount: Coin,
+}
+
 #[derive(Debug, Serialize)]
 struct OrphanRecord {
     pub ibc_denom: String,
     pub path: String,
     pub base_denom: String,
     pub reason: String,
+    /// On-chain supply in minimal units, as a decimal string.
+    pub supply: Option<String>,
+    /// Supply compressed at `C_E` / `C_S`, assuming 6 source decimals.
+    pub au_et: Option<String>,
+    pub csp: Option<String>,
+}
+
+/// Source decimals assumed for orphans; their metadata is not registered.
+const ORPHAN_SRC_DECIMALS: u8 = 6;
+const ALN_DECIMALS: u8 = 6;
+
+fn fetch_supply(ibc_denom: &str) -> anyhow::Result<u128> {
+    let url = format!(
+        "https://kujira-api.polkachu.com/cosmos/bank/v1beta1/supply/by_denom?denom={}",
+        urlencoding::encode(ibc_denom)
+    );
+    let resp: SupplyResponse = reqwest::blocking::get(&url)?.json()?;
+    Ok(resp.amount.amount.parse()?)
+}
+
+/// Supply plus its exact AU.ET / CSP compression; a failed lookup or
+/// compression leaves the fields empty instead of recording zero.
+fn compressed_supply(ibc_denom: &str) -> (Option<String>, Option<String>, Option<String>) {
+    let supply = match fetch_supply(ibc_denom) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("supply lookup for {} failed: {}", ibc_denom, e);
+            return (None, None, None);
+        }
+    };
+    let compress = |c| {
+        compress_balance(supply, ORPHAN_SRC_DECIMALS, ALN_DECIMALS, c)
+            .map_err(|e| eprintln!("compressing {} failed: {:?}", ibc_denom, e))
+            .ok()
+            .map(|v| v.to_string())
+    };
+    (Some(supply.to_string()), compress(aln_energy::C_E), compress(aln_energy::C_S))
 }
 
 fn main() -> anyhow::Result<()> {
@@ -61,11 +111,15 @@ fn main() -> anyhow::Result<()> {
             let ibc_denom = format!("ibc/{}", hex::encode(hash));
 
             if !known_bases.contains(&trace.base_denom) {
+                let (supply, au_et, csp) = compressed_supply(&ibc_denom);
                 orphan_records.push(OrphanRecord {
                     ibc_denom,
                     path: trace.path.clone(),
                     base_denom: trace.base_denom.clone(),
                     reason: "base_denom_not_in_registry".to_string(),
+                    supply,
+                    au_et,
+                    csp,
                 });
             }
         }
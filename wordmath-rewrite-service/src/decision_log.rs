@@ -0,0 +1,449 @@
+//! Append-only, hash-chained log of prompt gating decisions.
+//!
+//! One JSON line per decision. Each entry commits to the previous entry's
+//! hash, so editing or dropping a line breaks verification from that point on.
+//!
+//! Only a per-entry index (file offset and interned DID) is held in memory;
+//! entries are read back from disk when a page is queried. A final line
+//! left torn by a crash mid-append is cut off on open, since it was never
+//! acknowledged; an unreadable line anywhere else is an error.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use augdoctor_wordmath_core::PromptRightsHeader;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+pub const DEFAULT_PAGE_LIMIT: usize = 50;
+pub const MAX_PAGE_LIMIT: usize = 500;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DecisionEntry {
+    pub seq: u64,
+    pub at_ms: u64,
+    pub did: String,
+    /// "admit", "rewrite", "block", or "reject" for inflated metric claims.
+    pub action: String,
+    pub thresholds_version: String,
+    pub header: PromptRightsHeader,
+    pub prev_hash: String,
+    #[serde(default)]
+    pub hash: String,
+}
+
+impl DecisionEntry {
+    /// SHA-256 over the entry serialized with an empty `hash` field.
+    pub fn compute_hash(&self) -> String {
+        let mut unsealed = self.clone();
+        unsealed.hash = String::new();
+        let bytes = serde_json::to_vec(&unsealed).expect("decision entry serializes");
+        hex::encode(Sha256::digest(&bytes))
+    }
+}
+
+#[derive(Debug)]
+pub enum DecisionLogError {
+    Io(std::io::Error),
+    Parse {
+        line: usize,
+        error: serde_json::Error,
+    },
+    BrokenChain {
+        seq: u64,
+        reason: String,
+    },
+}
+
+impl fmt::Display for DecisionLogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecisionLogError::Io(e) => write!(f, "decision log io error: {}", e),
+            DecisionLogError::Parse { line, error } => {
+                write!(f, "decision log line {} unreadable: {}", line, error)
+            }
+            DecisionLogError::BrokenChain { seq, reason } => {
+                write!(f, "decision log chain broken at seq {}: {}", seq, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecisionLogError {}
+
+impl From<std::io::Error> for DecisionLogError {
+    fn from(e: std::io::Error) -> Self {
+        DecisionLogError::Io(e)
+    }
+}
+
+/// Filter and cursor for `GET /wordmath/decisions`.
+#[derive(Clone, Debug, Default)]
+pub struct DecisionQuery {
+    pub did: Option<String>,
+    pub after_seq: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct DecisionPage {
+    pub items: Vec<DecisionEntry>,
+    /// `after_seq` for the next page, `None` once exhausted.
+    pub next_cursor: Option<u64>,
+    pub head_hash: String,
+}
+
+/// In-memory record of one entry; its seq is its position in the index.
+struct IndexEntry {
+    offset: u64,
+    did: u32,
+}
+
+struct LogInner {
+    file: File,
+    /// File length, i.e. where the next entry starts.
+    len: u64,
+    index: Vec<IndexEntry>,
+    dids: Vec<String>,
+    did_ids: HashMap<String, u32>,
+    head_hash: String,
+}
+
+impl LogInner {
+    fn push(&mut self, offset: u64, did: &str, hash: &str) {
+        let did = match self.did_ids.get(did) {
+            Some(&id) => id,
+            None => {
+                let id = self.dids.len() as u32;
+                self.dids.push(did.to_string());
+                self.did_ids.insert(did.to_string(), id);
+                id
+            }
+        };
+        self.index.push(IndexEntry { offset, did });
+        self.head_hash = hash.to_string();
+    }
+}
+
+pub struct DecisionLog {
+    path: PathBuf,
+    inner: Mutex<LogInner>,
+}
+
+impl DecisionLog {
+    /// Open (or create) the log at `path`, verifying the whole chain.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, DecisionLogError> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+        let mut inner = LogInner {
+            file,
+            len: 0,
+            index: Vec::new(),
+            dids: Vec::new(),
+            did_ids: HashMap::new(),
+            head_hash: GENESIS_HASH.to_string(),
+        };
+
+        let mut reader = BufReader::new(File::open(&path)?);
+        let mut line = Vec::new();
+        let mut line_no = 0;
+        loop {
+            line.clear();
+            let read = reader.read_until(b'\n', &mut line)?;
+            if read == 0 {
+                break;
+            }
+            line_no += 1;
+            let offset = inner.len;
+            let terminated = line.ends_with(b"\n");
+            if line.iter().all(u8::is_ascii_whitespace) {
+                inner.len += read as u64;
+                continue;
+            }
+            let entry: DecisionEntry = match serde_json::from_slice(&line) {
+                Ok(entry) => entry,
+                Err(_) if !terminated => {
+                    // Torn final append: drop it.
+                    inner.file.set_len(offset)?;
+                    inner.file.sync_data()?;
+                    break;
+                }
+                Err(error) => {
+                    return Err(DecisionLogError::Parse {
+                        line: line_no,
+                        error,
+                    })
+                }
+            };
+            check_link(&entry, inner.index.len() as u64, &inner.head_hash)?;
+            inner.push(offset, &entry.did, &entry.hash);
+            inner.len += read as u64;
+            if !terminated {
+                // Complete entry missing only its newline.
+                inner.file.write_all(b"\n")?;
+                inner.file.sync_data()?;
+                inner.len += 1;
+            }
+        }
+        Ok(Self {
+            path,
+            inner: Mutex::new(inner),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(
+        &self,
+        action: &str,
+        thresholds_version: &str,
+        header: &PromptRightsHeader,
+    ) -> Result<DecisionEntry, DecisionLogError> {
+        let mut inner = self.inner.lock().unwrap();
+        let mut entry = DecisionEntry {
+            seq: inner.index.len() as u64,
+            at_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            did: header.did.clone(),
+            action: action.to_string(),
+            thresholds_version: thresholds_version.to_string(),
+            header: header.clone(),
+            prev_hash: inner.head_hash.clone(),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        let mut line = serde_json::to_vec(&entry).expect("decision entry serializes");
+        line.push(b'\n');
+        let offset = inner.len;
+        if let Err(e) = inner
+            .file
+            .write_all(&line)
+            .and_then(|()| inner.file.sync_data())
+        {
+            // Leave no partial line for the next append to run into.
+            let _ = inner.file.set_len(offset);
+            return Err(e.into());
+        }
+        inner.len += line.len() as u64;
+        inner.push(offset, &entry.did, &entry.hash);
+        Ok(entry)
+    }
+
+    pub fn query(&self, q: &DecisionQuery) -> Result<DecisionPage, DecisionLogError> {
+        let inner = self.inner.lock().unwrap();
+        let limit = q
+            .limit
+            .unwrap_or(DEFAULT_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT);
+        let did = match &q.did {
+            Some(d) => match inner.did_ids.get(d) {
+                Some(&id) => Some(id),
+                None => {
+                    return Ok(DecisionPage {
+                        items: Vec::new(),
+                        next_cursor: None,
+                        head_hash: inner.head_hash.clone(),
+                    })
+                }
+            },
+            None => None,
+        };
+        let start = q.after_seq.map_or(0, |after| after.saturating_add(1));
+        let selected: Vec<(u64, u64)> = inner
+            .index
+            .iter()
+            .enumerate()
+            .skip(usize::try_from(start).unwrap_or(usize::MAX))
+            .filter(|(_, e)| did.is_none_or(|d| e.did == d))
+            .take(limit + 1)
+            .map(|(seq, e)| (seq as u64, e.offset))
+            .collect();
+
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let mut items = Vec::with_capacity(selected.len().min(limit));
+        let mut line = String::new();
+        for &(seq, offset) in selected.iter().take(limit) {
+            reader.seek(SeekFrom::Start(offset))?;
+            line.clear();
+            reader.read_line(&mut line)?;
+            let entry: DecisionEntry =
+                serde_json::from_str(&line).map_err(|e| DecisionLogError::BrokenChain {
+                    seq,
+                    reason: format!("entry changed on disk: {}", e),
+                })?;
+            items.push(entry);
+        }
+        let next_cursor = if selected.len() > limit {
+            items.last().map(|e| e.seq)
+        } else {
+            None
+        };
+        Ok(DecisionPage {
+            items,
+            next_cursor,
+            head_hash: inner.head_hash.clone(),
+        })
+    }
+}
+
+/// Check one entry's seq, back-link and hash against the chain so far.
+fn check_link(entry: &DecisionEntry, seq: u64, prev: &str) -> Result<(), DecisionLogError> {
+    let broken = |reason: String| DecisionLogError::BrokenChain {
+        seq: entry.seq,
+        reason,
+    };
+    if entry.seq != seq {
+        return Err(broken(format!("expected seq {}", seq)));
+    }
+    if entry.prev_hash != prev {
+        return Err(broken(
+            "prev_hash does not match previous entry".to_string(),
+        ));
+    }
+    if entry.compute_hash() != entry.hash {
+        return Err(broken("entry hash mismatch".to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use augdoctor_wordmath_core::{
+        classify_prompt, NeurorightsFlags, PromptBandThresholds, PromptMetrics, SocialImpactVector,
+    };
+
+    fn header(did: &str) -> PromptRightsHeader {
+        PromptRightsHeader {
+            did: did.to_string(),
+            phoenix_profile: "phoenix_santan".to_string(),
+            neurorights: NeurorightsFlags {
+                no_exclusion_basic_services: true,
+                no_neuro_coercion: true,
+                no_score_from_inner_state: true,
+                augmentation_continuity: true,
+            },
+            impact: SocialImpactVector {
+                s_antistigma: 0.9,
+                s_nonexclusion: 0.9,
+                s_peacekeeping: 0.9,
+                s_eco: 0.9,
+            },
+            wordmath: classify_prompt(
+                PromptMetrics {
+                    repetition_y: 0.0,
+                    drift_z: 0.0,
+                    toxicity_t: 0.0,
+                    kindness_k: 1.0,
+                    evidentiality_e: 1.0,
+                },
+                &PromptBandThresholds::default(),
+            ),
+        }
+    }
+
+    fn temp_log(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("wordmath-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("decisions.jsonl")
+    }
+
+    #[test]
+    fn pages_by_did_and_survives_reopen() {
+        let path = temp_log("page");
+        let log = DecisionLog::open(&path).unwrap();
+        for i in 0..5 {
+            let did = if i % 2 == 0 { "did:aln:a" } else { "did:aln:b" };
+            log.append("admit", "v1", &header(did)).unwrap();
+        }
+        drop(log);
+
+        let log = DecisionLog::open(&path).unwrap();
+        let first = log
+            .query(&DecisionQuery {
+                did: Some("did:aln:a".to_string()),
+                limit: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            first.items.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+        let second = log
+            .query(&DecisionQuery {
+                did: Some("did:aln:a".to_string()),
+                after_seq: first.next_cursor,
+                limit: Some(2),
+            })
+            .unwrap();
+        assert_eq!(
+            second.items.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![4]
+        );
+        assert_eq!(second.next_cursor, None);
+        assert_eq!(
+            log.append("block", "v1", &header("did:aln:c")).unwrap().seq,
+            5
+        );
+    }
+
+    #[test]
+    fn tampered_entry_is_rejected_on_open() {
+        let path = temp_log("tamper");
+        let log = DecisionLog::open(&path).unwrap();
+        log.append("block", "v1", &header("did:aln:a")).unwrap();
+        log.append("rewrite", "v1", &header("did:aln:a")).unwrap();
+        drop(log);
+
+        let raw = fs::read_to_string(&path).unwrap();
+        fs::write(&path, raw.replacen("\"block\"", "\"admit\"", 1)).unwrap();
+        assert!(matches!(
+            DecisionLog::open(&path),
+            Err(DecisionLogError::BrokenChain { seq: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn torn_final_line_is_dropped_on_open() {
+        let path = temp_log("torn");
+        let log = DecisionLog::open(&path).unwrap();
+        log.append("admit", "v1", &header("did:aln:a")).unwrap();
+        log.append("rewrite", "v1", &header("did:aln:b")).unwrap();
+        drop(log);
+
+        let raw = fs::read(&path).unwrap();
+        let second = raw[..raw.len() - 1].iter().rposition(|b| *b == b'\n').unwrap() + 1;
+        fs::write(&path, &raw[..second + 20]).unwrap();
+
+        let log = DecisionLog::open(&path).unwrap();
+        assert_eq!(fs::read(&path).unwrap(), raw[..second]);
+        let entry = log.append("block", "v1", &header("did:aln:c")).unwrap();
+        assert_eq!(entry.seq, 1);
+        drop(log);
+        let log = DecisionLog::open(&path).unwrap();
+        let page = log.query(&DecisionQuery::default()).unwrap();
+        assert_eq!(
+            page.items.iter().map(|e| e.action.as_str()).collect::<Vec<_>>(),
+            vec!["admit", "block"]
+        );
+        assert_eq!(page.head_hash, entry.hash);
+    }
+}
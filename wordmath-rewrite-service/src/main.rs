@@ -1,9 +1,30 @@
-use augdoctor_wordmath_core::{classify_prompt, PromptBand, PromptBandThresholds, PromptMetrics, PromptRightsHeader};
+mod decision_log;
+
+use augdoctor_wordmath_core::rewrite::{AppliedTransform, ScorePoint};
+use augdoctor_wordmath_core::{
+    claim_inflates_band, classify_prompt, compare_metrics, compute_metrics, MetricDisagreement,
+    MetricLexicon, NeurorightsFlags, PromptBand, PromptMetrics, PromptRightsHeader,
+    RewriteEngine, SocialImpactVector, TaskTriad, ThresholdProfiles,
+};
+use decision_log::{DecisionLog, DecisionLogError, DecisionQuery};
 use hyper::{Body, Request, Response, StatusCode};
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
 use tokio::net::TcpListener;
 
+const DEFAULT_BIND: &str = "127.0.0.1:8088";
+const DEFAULT_THRESHOLDS_PATH: &str = "config/wordmath-thresholds.v1.json";
+const DEFAULT_DECISION_LOG_PATH: &str = "data/wordmath/decisions.jsonl";
+/// Request bodies larger than this are refused before parsing.
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+struct ServiceState {
+    thresholds: ThresholdProfiles,
+    log: DecisionLog,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 struct IncomingPromptRequest {
     pub did: String,
@@ -11,13 +32,27 @@ struct IncomingPromptRequest {
     pub neurorights: NeurorightsFlags,
     pub impact: SocialImpactVector,
     pub text: String,
-    pub metrics: PromptMetrics, // computed by a front-end model
+    /// Optional client claim; classification always uses locally computed
+    /// metrics and only compares against this.
+    #[serde(default)]
+    pub metrics: Option<PromptMetrics>,
+    #[serde(default)]
+    pub task_triad: Option<TaskTriad>,
 }
 
+/// Largest per-metric gap between a client claim and the computed value that
+/// is not reported.
+const METRIC_TOLERANCE: f32 = 0.15;
+
 #[derive(Clone, Debug, Serialize)]
 struct RewriteSuggestion {
     pub explanation: String,
     pub suggested_text: String,
+    /// Score after each rewrite step, starting with the submitted text.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub trajectory: Vec<ScorePoint>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transforms: Vec<AppliedTransform>,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -25,17 +60,129 @@ struct WordMathResponse {
     pub header: PromptRightsHeader,
     pub action: String, // "admit", "rewrite", "block"
     pub rewrite: Option<RewriteSuggestion>,
+    /// Claimed metrics that disagree with the computed ones.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub metric_disagreements: Vec<MetricDisagreement>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct MetricRejection {
+    pub error: String,
+    pub computed: PromptMetrics,
+    pub metric_disagreements: Vec<MetricDisagreement>,
+}
+
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .body(Body::from(serde_json::to_vec(value).unwrap()))
+        .unwrap()
+}
+
+/// Collect the body, or `None` once it exceeds `limit` bytes.
+async fn read_body_limited(mut body: Body, limit: usize) -> Result<Option<Vec<u8>>, hyper::Error> {
+    use hyper::body::HttpBody;
+    let mut out = Vec::new();
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if out.len() + chunk.len() > limit {
+            return Ok(None);
+        }
+        out.extend_from_slice(&chunk);
+    }
+    Ok(Some(out))
+}
+
+fn percent_decode(raw: &str) -> String {
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3])
+                    .ok()
+                    .and_then(|h| u8::from_str_radix(h, 16).ok());
+                match hex {
+                    Some(b) => {
+                        out.push(b);
+                        i += 3;
+                        continue;
+                    }
+                    None => out.push(b'%'),
+                }
+            }
+            b'+' => out.push(b' '),
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn log_unavailable(e: &DecisionLogError) -> Response<Body> {
+    eprintln!("decision log failed: {}", e);
+    Response::builder()
+        .status(StatusCode::INTERNAL_SERVER_ERROR)
+        .body(Body::from("decision log unavailable"))
+        .unwrap()
+}
+
+/// Run `f` against the decision log on the blocking pool; appends write and
+/// fsync the file, which must not stall the reactor.
+async fn on_log<T, F>(state: &Arc<ServiceState>, f: F) -> Result<T, DecisionLogError>
+where
+    T: Send + 'static,
+    F: FnOnce(&ServiceState) -> Result<T, DecisionLogError> + Send + 'static,
+{
+    let state = state.clone();
+    tokio::task::spawn_blocking(move || f(&state))
+        .await
+        .unwrap_or_else(|e| Err(DecisionLogError::Io(std::io::Error::other(e))))
+}
+
+fn parse_decision_query(query: Option<&str>) -> DecisionQuery {
+    let mut q = DecisionQuery::default();
+    for pair in query.unwrap_or("").split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value);
+        match key {
+            "did" => q.did = Some(value),
+            "after_seq" => q.after_seq = value.parse().ok(),
+            "limit" => q.limit = value.parse().ok(),
+            _ => {}
+        }
+    }
+    q
 }
 
-async fn handle(req: Request<Body>) -> Result<Response<Body>, hyper::Error> {
+async fn handle(
+    req: Request<Body>,
+    state: Arc<ServiceState>,
+) -> Result<Response<Body>, hyper::Error> {
+    // Read-only: the log is only ever appended to by POST /wordmath.
+    if req.method() == hyper::Method::GET && req.uri().path() == "/wordmath/decisions" {
+        let q = parse_decision_query(req.uri().query());
+        return Ok(match on_log(&state, move |s| s.log.query(&q)).await {
+            Ok(page) => json_response(StatusCode::OK, &page),
+            Err(e) => log_unavailable(&e),
+        });
+    }
     if req.method() != hyper::Method::POST {
         return Ok(Response::builder()
             .status(StatusCode::METHOD_NOT_ALLOWED)
-            .body(Body::from("use POST /wordmath"))
+            .body(Body::from(
+                "use POST /wordmath or GET /wordmath/decisions?did=&after_seq=&limit=",
+            ))
             .unwrap());
     }
 
-    let whole = hyper::body::to_bytes(req.into_body()).await?;
+    let Some(whole) = read_body_limited(req.into_body(), MAX_BODY_BYTES).await? else {
+        return Ok(Response::builder()
+            .status(StatusCode::PAYLOAD_TOO_LARGE)
+            .body(Body::from(format!("body exceeds {} bytes", MAX_BODY_BYTES)))
+            .unwrap());
+    };
     let incoming: IncomingPromptRequest = match serde_json::from_slice(&whole) {
         Ok(v) => v,
         Err(_) => {
@@ -46,8 +193,43 @@ async fn handle(req: Request<Body>) -> Result<Response<Body>, hyper::Error> {
         }
     };
 
-    let thresholds = PromptBandThresholds::default();
-    let score = classify_prompt(incoming.metrics.clone(), &thresholds);
+    let thresholds = state.thresholds.for_profile(&incoming.phoenix_profile).clone();
+    let triad = incoming.task_triad.clone().unwrap_or_default();
+    let computed = compute_metrics(&incoming.text, &triad, &MetricLexicon::default());
+
+    let mut metric_disagreements = Vec::new();
+    if let Some(claimed) = &incoming.metrics {
+        metric_disagreements = compare_metrics(claimed, &computed, METRIC_TOLERANCE);
+        // A claim that would buy a better band than the text earns is refused
+        // outright; smaller disagreements are only reported.
+        if !metric_disagreements.is_empty() && claim_inflates_band(claimed, &computed, &thresholds) {
+            // Recorded at the band the text actually earns.
+            let header = PromptRightsHeader {
+                did: incoming.did,
+                phoenix_profile: incoming.phoenix_profile,
+                neurorights: incoming.neurorights,
+                impact: incoming.impact,
+                wordmath: classify_prompt(computed.clone(), &thresholds),
+            };
+            if let Err(e) = on_log(&state, move |s| {
+                s.log.append("reject", &s.thresholds.version, &header)
+            })
+            .await
+            {
+                return Ok(log_unavailable(&e));
+            }
+            return Ok(json_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                &MetricRejection {
+                    error: "claimed metrics disagree with computed metrics".to_string(),
+                    computed,
+                    metric_disagreements,
+                },
+            ));
+        }
+    }
+
+    let score = classify_prompt(computed, &thresholds);
 
     let header = PromptRightsHeader {
         did: incoming.did,
@@ -60,13 +242,41 @@ async fn handle(req: Request<Body>) -> Result<Response<Body>, hyper::Error> {
     let (action, rewrite) = match header.wordmath.band {
         PromptBand::GreenAdmit => ("admit".to_string(), None),
         PromptBand::AmberRewrite => {
+            let engine = RewriteEngine {
+                lexicon: state.thresholds.rewrite_lexicon.clone(),
+                thresholds: thresholds.clone(),
+                ..RewriteEngine::default()
+            };
+            // The requester's own DID is not evidence for the prompt, so the
+            // engine only ever inserts the non-scoring placeholder here.
+            let text = incoming.text.clone();
+            let outcome = match tokio::task::spawn_blocking(move || {
+                engine.rewrite(&text, &triad, None)
+            })
+            .await
+            {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    eprintln!("rewrite task failed: {}", e);
+                    return Ok(Response::builder()
+                        .status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .body(Body::from("rewrite failed"))
+                        .unwrap());
+                }
+            };
+            let explanation = if outcome.improved {
+                format!(
+                    "Rewrite reaches {:?} (f={:.2}).",
+                    outcome.final_score.band, outcome.final_score.f
+                )
+            } else {
+                "Reduce repetition, center on declared task triad, soften adversarial language, and add one DID-bound evidential hook.".to_string()
+            };
             let suggestion = RewriteSuggestion {
-                explanation: "Reduce repetition, center on declared task triad, soften adversarial language, and add one DID-bound evidential hook.".to_string(),
-                suggested_text: incoming
-                    .text
-                    .replace("!", ".")
-                    .replace("must", "should")
-                    .to_string(),
+                explanation,
+                suggested_text: outcome.text,
+                trajectory: outcome.trajectory,
+                transforms: outcome.transforms,
             };
             ("rewrite".to_string(), Some(suggestion))
         }
@@ -75,22 +285,66 @@ async fn handle(req: Request<Body>) -> Result<Response<Body>, hyper::Error> {
                 explanation: "Prompt is too off-topic, repetitive, or toxic for safe quantum-learning. Remove hostile framing, narrow to cybernetics/biophysical-blockchain/quantum-learning, and specify non-financial, DID-bound constraints."
                     .to_string(),
                 suggested_text: "".to_string(),
+                trajectory: Vec::new(),
+                transforms: Vec::new(),
             };
             ("block".to_string(), Some(suggestion))
         }
     };
 
-    let resp = WordMathResponse { header, action, rewrite };
-    let body = serde_json::to_vec(&resp).unwrap();
-    Ok(Response::builder()
-        .status(StatusCode::OK)
-        .body(Body::from(body))
-        .unwrap())
+    let (logged_action, logged_header) = (action.clone(), header.clone());
+    if let Err(e) = on_log(&state, move |s| {
+        s.log.append(&logged_action, &s.thresholds.version, &logged_header)
+    })
+    .await
+    {
+        return Ok(log_unavailable(&e));
+    }
+
+    let resp = WordMathResponse {
+        header,
+        action,
+        rewrite,
+        metric_disagreements,
+    };
+    Ok(json_response(StatusCode::OK, &resp))
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let addr: SocketAddr = "127.0.0.1:8088".parse()?;
+    let addr: SocketAddr = std::env::var("WORDMATH_BIND")
+        .unwrap_or_else(|_| DEFAULT_BIND.to_string())
+        .parse()?;
+    let thresholds_path = std::env::var("WORDMATH_THRESHOLDS")
+        .unwrap_or_else(|_| DEFAULT_THRESHOLDS_PATH.to_string());
+    let log_path = std::env::var("WORDMATH_DECISION_LOG")
+        .unwrap_or_else(|_| DEFAULT_DECISION_LOG_PATH.to_string());
+
+    // An explicit WORDMATH_THRESHOLDS must load; the relative default is
+    // optional and falls back to the built-in thresholds when absent.
+    let thresholds = match std::env::var("WORDMATH_THRESHOLDS") {
+        Err(_) if !Path::new(&thresholds_path).exists() => {
+            println!("{} not found, using built-in thresholds", thresholds_path);
+            ThresholdProfiles::default()
+        }
+        _ => ThresholdProfiles::load(&thresholds_path)?,
+    };
+    // GET /wordmath/decisions is unauthenticated, so the default bind keeps
+    // it on loopback; anything wider should sit behind an authenticating proxy.
+    if !addr.ip().is_loopback() {
+        println!("{} is not loopback: the decision log is readable by any client that can reach it", addr);
+    }
+    let state = Arc::new(ServiceState {
+        thresholds,
+        log: DecisionLog::open(&log_path)?,
+    });
+    println!(
+        "thresholds {} from {}, decision log {}",
+        state.thresholds.version,
+        thresholds_path,
+        state.log.path().display()
+    );
+
     let listener = TcpListener::bind(addr).await?;
     println!("wordmath-rewrite-service listening on {}", addr);
 
@@ -98,7 +352,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         let (stream, _) = listener.accept().await?;
         let io = hyper::server::conn::http1::Builder::new().serve_connection(
             stream,
-            hyper::service::service_fn(handle),
+            hyper::service::service_fn({
+                let state = state.clone();
+                move |req| handle(req, state.clone())
+            }),
         );
         tokio::task::spawn(async move {
             if let Err(e) = io.await {